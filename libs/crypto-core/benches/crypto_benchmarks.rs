@@ -4,8 +4,10 @@ use crypto_core::{
     envelope::{CryptoEnvelope, CryptoEnvelopeBuilder},
     aad::AADValidator,
     memory::SecureBuffer,
+    multi_recipient::{KeyWrapBatch, MultiRecipientEnvelope, RecipientKeyPair},
 };
 use std::time::Duration;
+use zeroize::Zeroizing;
 
 fn benchmark_key_generation(c: &mut Criterion) {
     let mut group = c.benchmark_group("key_generation");
@@ -293,6 +295,41 @@ fn benchmark_concurrent_operations(c: &mut Criterion) {
     group.finish();
 }
 
+fn benchmark_sync_package_wrapping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sync_package_wrapping");
+    
+    // Typical multi-device rotation: 10 devices x 6 purposes = 60 wraps
+    let devices: Vec<RecipientKeyPair> = (0..10).map(|_| RecipientKeyPair::generate()).collect();
+    let recipients: Vec<[u8; 32]> = devices
+        .iter()
+        .map(|device| device.public_key().try_into().unwrap())
+        .collect();
+    let keys: Vec<(String, Zeroizing<[u8; 32]>)> = (0..6)
+        .map(|purpose| (format!("purpose-{}", purpose), Zeroizing::new([purpose as u8; 32])))
+        .collect();
+    
+    group.throughput(Throughput::Elements((recipients.len() * keys.len()) as u64));
+    
+    // Baseline: one sealed envelope per purpose, each wrapped to every device and serialized
+    group.bench_function("individual_wraps_10x6", |b| {
+        b.iter(|| {
+            for (label, key) in &keys {
+                let envelope = MultiRecipientEnvelope::seal_to(key.as_ref(), label.as_bytes(), black_box(&recipients)).unwrap();
+                black_box(envelope.to_json().unwrap());
+            }
+        })
+    });
+    
+    group.bench_function("batch_wrap_10x6", |b| {
+        b.iter(|| {
+            let batch = KeyWrapBatch::wrap_all(black_box(&keys), black_box(&recipients)).unwrap();
+            black_box(batch.to_json().unwrap());
+        })
+    });
+    
+    group.finish();
+}
+
 fn benchmark_cross_platform_consistency(c: &mut Criterion) {
     let mut group = c.benchmark_group("cross_platform");
     
@@ -342,6 +379,7 @@ criterion_group! {
         benchmark_memory_operations,
         benchmark_target_validation,
         benchmark_concurrent_operations,
+        benchmark_sync_package_wrapping,
         benchmark_cross_platform_consistency
 }
 
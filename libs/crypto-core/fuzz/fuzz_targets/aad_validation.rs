@@ -1,7 +1,7 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
 use arbitrary::{Arbitrary, Unstructured};
-use crypto_core::{encrypt_data, decrypt_data, generate_key, validate_aad};
+use crypto_core::{encrypt_data, decrypt_category_data, DataCategory, generate_key, validate_aad};
 
 #[derive(Arbitrary, Debug)]
 struct AadFuzzInput {
@@ -28,7 +28,7 @@ fuzz_target!(|input: AadFuzzInput| {
         // Test encryption with first AAD
         if let Ok(encrypted1) = encrypt_data(&input.data, &key, &input.aad1, &input.device_id) {
             // Test decryption with same AAD (should succeed)
-            if let Ok(decrypted1) = decrypt_data(&encrypted1.encrypted_data, &encrypted1.envelope, &key) {
+            if let Ok(decrypted1) = decrypt_category_data(&encrypted1.encrypted_data, &encrypted1.envelope, &key, DataCategory::CycleData) {
                 assert_eq!(input.data, decrypted1);
             }
             
@@ -37,7 +37,7 @@ fuzz_target!(|input: AadFuzzInput| {
                 // This should fail due to AAD mismatch - test that it fails gracefully
                 let mut modified_envelope = encrypted1.envelope.clone();
                 // Note: This is testing the robustness of error handling, not bypassing security
-                let _ = decrypt_data(&encrypted1.encrypted_data, &modified_envelope, &key);
+                let _ = decrypt_category_data(&encrypted1.encrypted_data, &modified_envelope, &key, DataCategory::CycleData);
             }
         }
         
@@ -54,7 +54,7 @@ fuzz_target!(|input: AadFuzzInput| {
             let _ = validate_aad(aad, &input.device_id);
             
             if let Ok(encrypted) = encrypt_data(&input.data, &key, aad, &input.device_id) {
-                let _ = decrypt_data(&encrypted.encrypted_data, &encrypted.envelope, &key);
+                let _ = decrypt_category_data(&encrypted.encrypted_data, &encrypted.envelope, &key, DataCategory::CycleData);
             }
         }
         
@@ -71,7 +71,7 @@ fuzz_target!(|input: AadFuzzInput| {
                 let _ = validate_aad(&input.aad1, device_id);
                 
                 if let Ok(encrypted) = encrypt_data(&input.data, &key, &input.aad1, device_id) {
-                    let _ = decrypt_data(&encrypted.encrypted_data, &encrypted.envelope, &key);
+                    let _ = decrypt_category_data(&encrypted.encrypted_data, &encrypted.envelope, &key, DataCategory::CycleData);
                 }
             }
         }
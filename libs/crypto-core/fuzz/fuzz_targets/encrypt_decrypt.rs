@@ -1,7 +1,7 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
 use arbitrary::{Arbitrary, Unstructured};
-use crypto_core::{encrypt_data, decrypt_data, generate_key};
+use crypto_core::{encrypt_data, decrypt_category_data, DataCategory, generate_key};
 
 #[derive(Arbitrary, Debug)]
 struct FuzzInput {
@@ -21,7 +21,7 @@ fuzz_target!(|input: FuzzInput| {
         // Test encryption
         if let Ok(encrypted) = encrypt_data(&input.data, &key, &input.aad, &input.device_id) {
             // Test decryption of valid encrypted data
-            if let Ok(decrypted) = decrypt_data(&encrypted.encrypted_data, &encrypted.envelope, &key) {
+            if let Ok(decrypted) = decrypt_category_data(&encrypted.encrypted_data, &encrypted.envelope, &key, DataCategory::CycleData) {
                 // Verify round-trip correctness
                 assert_eq!(input.data, decrypted);
             }
@@ -30,12 +30,12 @@ fuzz_target!(|input: FuzzInput| {
             let mut corrupted_data = encrypted.encrypted_data.clone();
             if !corrupted_data.is_empty() {
                 corrupted_data[0] = corrupted_data[0].wrapping_add(1);
-                let _ = decrypt_data(&corrupted_data, &encrypted.envelope, &key);
+                let _ = decrypt_category_data(&corrupted_data, &encrypted.envelope, &key, DataCategory::CycleData);
             }
             
             // Test decryption with wrong key (should fail gracefully)
             if let Ok(wrong_key) = generate_key() {
-                let _ = decrypt_data(&encrypted.encrypted_data, &encrypted.envelope, &wrong_key);
+                let _ = decrypt_category_data(&encrypted.encrypted_data, &encrypted.envelope, &wrong_key, DataCategory::CycleData);
             }
         }
     }
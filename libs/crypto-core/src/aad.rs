@@ -1,6 +1,11 @@
 use wasm_bindgen::prelude::*;
 use crate::security::{constant_time_compare, SideChannelProtection, AuditTrail};
 use sha2::{Sha256, Digest};
+use hmac::{Hmac, Mac};
+use std::collections::VecDeque;
+use crate::scope::{scoped_error, ErrorScope};
+use std::sync::Mutex;
+use crate::platform;
 
 // Additional Authenticated Data (AAD) validation logic with security hardening
 #[wasm_bindgen]
@@ -8,6 +13,8 @@ pub struct AADValidator {
     context: String,
     user_id: Option<String>,
     timestamp: Option<u64>,
+    schema_version: Option<u32>,
+    user_scope_tag: Option<Vec<u8>>,
     audit_trail: AuditTrail,
     hash_cache: Option<Vec<u8>>,
 }
@@ -21,6 +28,8 @@ impl AADValidator {
             context,
             user_id: None,
             timestamp: None,
+            schema_version: None,
+            user_scope_tag: None,
             audit_trail: AuditTrail::new(100),
             hash_cache: None,
         }
@@ -36,6 +45,24 @@ impl AADValidator {
         self.timestamp = Some(timestamp);
     }
 
+    // Bind the data-layer record schema version into the AAD
+    #[wasm_bindgen]
+    pub fn set_schema_version(&mut self, schema_version: u32) {
+        self.schema_version = Some(schema_version);
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn schema_version(&self) -> Option<u32> {
+        self.schema_version
+    }
+
+    // Bind the keyed tag of the owning user into the AAD
+    #[wasm_bindgen]
+    pub fn set_user_scope(&mut self, scope: &UserScope) {
+        self.user_scope_tag = Some(scope.tag());
+    }
+
     // Generate AAD for cryptographic operations with security hardening
     #[wasm_bindgen]
     #[must_use]
@@ -60,6 +87,20 @@ impl AADValidator {
             aad.extend_from_slice(&timestamp.to_le_bytes());
         }
         
+        // Schema version only appended when set, so existing AAD stays unchanged
+        if let Some(schema_version) = self.schema_version {
+            aad.push(0); // Separator
+            aad.extend_from_slice(b"schema:");
+            aad.extend_from_slice(&schema_version.to_le_bytes());
+        }
+        
+        // User scope tag follows the same rule as the schema version
+        if let Some(ref scope_tag) = self.user_scope_tag {
+            aad.push(0); // Separator
+            aad.extend_from_slice(b"scope:");
+            aad.extend_from_slice(scope_tag);
+        }
+        
         // Compute and cache hash for integrity
         let mut hasher = Sha256::new();
         hasher.update(&aad);
@@ -127,4 +168,209 @@ pub fn create_healthcare_share_aad(user_id: String, share_token: &str) -> Vec<u8
     let mut aad = validator.generate_aad();
     aad.extend_from_slice(share_token.as_bytes());
     aad
-}
\ No newline at end of file
+}
+
+// Typed error raised when a record was sealed under a different data-layer schema
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    expected: u32,
+    found: Option<u32>,
+    scope: ErrorScope,
+}
+
+#[wasm_bindgen]
+impl SchemaMismatch {
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn expected(&self) -> u32 {
+        self.expected
+    }
+
+    // None when the record predates schema binding
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn found(&self) -> Option<u32> {
+        self.found
+    }
+}
+
+impl SchemaMismatch {
+    #[must_use]
+    pub fn new(expected: u32, found: Option<u32>) -> SchemaMismatch {
+        SchemaMismatch { expected, found, scope: ErrorScope::capture() }
+    }
+}
+
+impl std::fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.found {
+            Some(found) => write!(f, "Schema mismatch: expected version {}, record has {}", self.expected, found)?,
+            None => write!(f, "Schema mismatch: expected version {}, record has no schema version", self.expected)?,
+        }
+        write!(f, "{}", self.scope)
+    }
+}
+
+scoped_error!(SchemaMismatch);
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub(crate) const USER_SCOPE_DOMAIN: &[u8] = b"aura-user-scope-v1";
+pub(crate) const USER_SCOPE_TAG_LENGTH: usize = 16;
+const MAX_SCOPE_VIOLATION_EVENTS: usize = 50;
+
+static ACTIVE_USER_SCOPE: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+static SCOPE_VIOLATION_EVENTS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+// Keyed tag identifying which user an envelope belongs to. The tag is an HMAC of the
+// user id, so the server cannot link envelopes to user ids, but every device of the
+// same user computes the same tag.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserScope {
+    tag: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl UserScope {
+    #[wasm_bindgen(constructor)]
+    pub fn new(scope_key: &[u8], user_id: &str) -> Result<UserScope, JsValue> {
+        Self::derive(scope_key, user_id).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn tag(&self) -> Vec<u8> {
+        self.tag.clone()
+    }
+}
+
+impl UserScope {
+    pub(crate) fn derive(scope_key: &[u8], user_id: &str) -> Result<UserScope, String> {
+        if scope_key.len() < 32 {
+            return Err("User scope key must be at least 32 bytes".to_string());
+        }
+        if user_id.is_empty() {
+            return Err("User id must not be empty".to_string());
+        }
+
+        let mut mac = HmacSha256::new_from_slice(scope_key)
+            .map_err(|_| "User scope key is invalid".to_string())?;
+        mac.update(USER_SCOPE_DOMAIN);
+        mac.update(user_id.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        Ok(UserScope { tag: digest[..USER_SCOPE_TAG_LENGTH].to_vec() })
+    }
+}
+
+// Set the scope of the signed-in user; envelopes are checked against it before decryption
+#[wasm_bindgen]
+pub fn set_active_user_scope(scope: &UserScope) {
+    if let Ok(mut active) = ACTIVE_USER_SCOPE.lock() {
+        *active = Some(scope.tag());
+    }
+}
+
+// Clear on sign-out so no envelope can be opened until the next user is set
+#[wasm_bindgen]
+pub fn clear_active_user_scope() {
+    if let Ok(mut active) = ACTIVE_USER_SCOPE.lock() {
+        *active = None;
+    }
+}
+
+pub(crate) fn active_user_scope_tag() -> Option<Vec<u8>> {
+    ACTIVE_USER_SCOPE.lock().ok().and_then(|active| active.clone())
+}
+
+// Typed error raised when an envelope belongs to a different user than the active one
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeViolation {
+    envelope_tagged: bool,
+    scope_active: bool,
+    scope: ErrorScope,
+}
+
+#[wasm_bindgen]
+impl ScopeViolation {
+    // False for envelopes written before scope tags existed
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn envelope_tagged(&self) -> bool {
+        self.envelope_tagged
+    }
+
+    // False when no user scope had been set
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn scope_active(&self) -> bool {
+        self.scope_active
+    }
+}
+
+impl ScopeViolation {
+    pub fn new(envelope_tagged: bool, scope_active: bool) -> ScopeViolation {
+        ScopeViolation { envelope_tagged, scope_active, scope: ErrorScope::capture() }
+    }
+
+    // Record the violation as a security event; tags are never included
+    pub(crate) fn report(&self) {
+        let event = serde_json::json!({
+            "type": "scope_violation",
+            "timestamp": platform::now_ms(),
+            "envelope_tagged": self.envelope_tagged,
+            "scope_active": self.scope_active,
+        })
+        .to_string();
+
+        if let Ok(mut events) = SCOPE_VIOLATION_EVENTS.lock() {
+            events.push_back(event);
+            if events.len() > MAX_SCOPE_VIOLATION_EVENTS {
+                events.pop_front();
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ScopeViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.scope_active {
+            write!(f, "Scope violation: no active user scope")?;
+        } else if !self.envelope_tagged {
+            write!(f, "Scope violation: envelope has no user scope tag")?;
+        } else {
+            write!(f, "Scope violation: envelope belongs to a different user")?;
+        }
+        write!(f, "{}", self.scope)
+    }
+}
+
+scoped_error!(ScopeViolation);
+
+// Drain recorded scope violation events as a JSON array
+#[wasm_bindgen]
+#[must_use]
+pub fn take_scope_violation_events() -> String {
+    format!("[{}]", drain_scope_violation_events().join(","))
+}
+
+pub(crate) fn drain_scope_violation_events() -> Vec<String> {
+    SCOPE_VIOLATION_EVENTS
+        .lock()
+        .map(|mut events| events.drain(..).collect())
+        .unwrap_or_default()
+}
+
+// Put drained events back ahead of newer ones, e.g. after a failed flush
+pub(crate) fn requeue_scope_violation_events(drained: Vec<String>) {
+    if let Ok(mut events) = SCOPE_VIOLATION_EVENTS.lock() {
+        for event in drained.into_iter().rev() {
+            if events.len() == MAX_SCOPE_VIOLATION_EVENTS {
+                break;
+            }
+            events.push_front(event);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use wasm_bindgen::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use crate::derivation::DataCategory;
+use crate::platform;
+use crate::scope::{scoped_error, ErrorScope};
+
+// Recent-authentication requirements
+// Some categories may only be decrypted shortly after the user authenticated
+// (biometric unlock, passkey, PIN). The host reports each successful
+// authentication and the decrypt path checks the configured maximum age for the
+// category, so screens no longer re-implement the rule individually.
+
+struct AuthFreshnessState {
+    policy: AuthFreshnessPolicy,
+    last_authenticated_ms: Option<u64>,
+}
+
+static AUTH_FRESHNESS: Mutex<AuthFreshnessState> = Mutex::new(AuthFreshnessState {
+    policy: AuthFreshnessPolicy { max_age_ms: BTreeMap::new() },
+    last_authenticated_ms: None,
+});
+
+/// Maximum time since the last authentication, per data category
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthFreshnessPolicy {
+    max_age_ms: BTreeMap<String, u64>,
+}
+
+#[wasm_bindgen]
+impl AuthFreshnessPolicy {
+    /// Empty policy: no category requires recent authentication
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> AuthFreshnessPolicy {
+        Self::default()
+    }
+
+    /// Require authentication within `max_age_ms` before decrypting `category`
+    #[wasm_bindgen]
+    pub fn require(&mut self, category: DataCategory, max_age_ms: u64) {
+        self.max_age_ms.insert(category.to_string(), max_age_ms);
+    }
+
+    #[wasm_bindgen]
+    pub fn remove(&mut self, category: DataCategory) {
+        self.max_age_ms.remove(&category.to_string());
+    }
+
+    #[wasm_bindgen]
+    pub fn max_age_ms(&self, category: DataCategory) -> Option<u64> {
+        self.max_age_ms.get(&category.to_string()).copied()
+    }
+}
+
+impl AuthFreshnessPolicy {
+    pub(crate) fn check(
+        &self,
+        category: &DataCategory,
+        last_authenticated_ms: Option<u64>,
+        now: u64,
+    ) -> Result<(), ReauthRequired> {
+        let Some(max_age_ms) = self.max_age_ms(category.clone()) else {
+            return Ok(());
+        };
+        let elapsed_ms = last_authenticated_ms.map(|at| now.saturating_sub(at));
+        match elapsed_ms {
+            Some(elapsed) if elapsed <= max_age_ms => Ok(()),
+            _ => Err(ReauthRequired {
+                category: category.clone(),
+                max_age_ms,
+                elapsed_ms,
+                scope: ErrorScope::capture(),
+            }),
+        }
+    }
+}
+
+// Typed error raised when a category needs a more recent authentication
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReauthRequired {
+    category: DataCategory,
+    max_age_ms: u64,
+    elapsed_ms: Option<u64>,
+    scope: ErrorScope,
+}
+
+#[wasm_bindgen]
+impl ReauthRequired {
+    #[wasm_bindgen(getter)]
+    pub fn category(&self) -> DataCategory {
+        self.category.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_age_ms(&self) -> u64 {
+        self.max_age_ms
+    }
+
+    // None when no authentication has been reported yet
+    #[wasm_bindgen(getter)]
+    pub fn elapsed_ms(&self) -> Option<u64> {
+        self.elapsed_ms
+    }
+}
+
+impl std::fmt::Display for ReauthRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.elapsed_ms {
+            Some(elapsed) => write!(
+                f,
+                "Reauthentication required for {}: last authentication {} ms ago, limit {} ms",
+                self.category.to_string(), elapsed, self.max_age_ms
+            )?,
+            None => write!(
+                f,
+                "Reauthentication required for {}: no authentication reported",
+                self.category.to_string()
+            )?,
+        }
+        write!(f, "{}", self.scope)
+    }
+}
+
+scoped_error!(ReauthRequired);
+
+/// Install the process-wide freshness policy
+#[wasm_bindgen]
+pub fn set_auth_freshness_policy(policy: &AuthFreshnessPolicy) {
+    if let Ok(mut state) = AUTH_FRESHNESS.lock() {
+        state.policy = policy.clone();
+    }
+}
+
+/// Host hook: the user authenticated at `authenticated_at_ms` (future times are clamped to now)
+#[wasm_bindgen]
+pub fn report_authentication(authenticated_at_ms: u64) {
+    let authenticated_at_ms = authenticated_at_ms.min(platform::now_ms());
+    if let Ok(mut state) = AUTH_FRESHNESS.lock() {
+        // Out-of-order reports must not move the last authentication backwards
+        let latest = state.last_authenticated_ms.map_or(authenticated_at_ms, |last| last.max(authenticated_at_ms));
+        state.last_authenticated_ms = Some(latest);
+    }
+}
+
+/// Forget the last authentication, e.g. on lock or sign-out
+#[wasm_bindgen]
+pub fn clear_authentication() {
+    if let Ok(mut state) = AUTH_FRESHNESS.lock() {
+        state.last_authenticated_ms = None;
+    }
+}
+
+/// Last authentication reported through `report_authentication`, if any
+pub(crate) fn last_reported_authentication() -> Option<u64> {
+    AUTH_FRESHNESS.lock().ok().and_then(|state| state.last_authenticated_ms)
+}
+
+/// Check the freshness requirement for `category` without decrypting anything
+#[wasm_bindgen]
+pub fn check_auth_freshness(category: DataCategory) -> Result<(), ReauthRequired> {
+    enforce_auth_freshness(&category, platform::now_ms())
+}
+
+pub(crate) fn enforce_auth_freshness(category: &DataCategory, now: u64) -> Result<(), ReauthRequired> {
+    match AUTH_FRESHNESS.lock() {
+        Ok(state) => state.policy.check(category, state.last_authenticated_ms, now),
+        // Fail closed: a poisoned lock must not bypass the requirement
+        Err(_) => Err(ReauthRequired {
+            category: category.clone(),
+            max_age_ms: 0,
+            elapsed_ms: None,
+            scope: ErrorScope::capture(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIVE_MINUTES: u64 = 5 * 60 * 1000;
+
+    #[test]
+    fn test_policy_requires_recent_authentication() {
+        let mut policy = AuthFreshnessPolicy::new();
+        policy.require(DataCategory::HealthcareSharing, FIVE_MINUTES);
+
+        assert!(policy.check(&DataCategory::CycleData, None, 10_000).is_ok());
+        assert!(policy.check(&DataCategory::HealthcareSharing, Some(10_000), 10_000 + FIVE_MINUTES).is_ok());
+
+        let stale = policy.check(&DataCategory::HealthcareSharing, Some(10_000), 10_001 + FIVE_MINUTES).unwrap_err();
+        assert_eq!(stale.elapsed_ms(), Some(FIVE_MINUTES + 1));
+        let never = policy.check(&DataCategory::HealthcareSharing, None, 10_000).unwrap_err();
+        assert!(never.to_string().contains("no authentication reported"));
+
+        policy.remove(DataCategory::HealthcareSharing);
+        assert!(policy.check(&DataCategory::HealthcareSharing, None, 10_000).is_ok());
+    }
+}
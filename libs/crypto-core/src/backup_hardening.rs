@@ -0,0 +1,363 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+use crate::key_rotation::KeyRotationManager;
+use crate::oprf::OprfBlind;
+use crate::platform;
+use crate::recovery::RecoveryPhrase;
+use crate::security::constant_time_compare;
+
+// Server-assisted backup hardening
+// The password path of a backup is keyed by an OPRF (`oprf`) evaluation of the
+// client KDF output under a key only the server holds. The client sends a blinded
+// element, the server evaluates it after rate-limited authentication, and the client
+// unblinds the result, so a stolen backup blob cannot be brute-forced offline: every
+// password guess needs one online evaluation, and the server never sees the KDF
+// output or anything it could test guesses against. The recovery phrase path is
+// keyed by the phrase seed alone and keeps working without the server.
+//
+// Version 1 blobs were keyed by a static random share the server released after
+// authentication; once released, the share allowed unlimited offline guesses. They
+// can still be opened with the share and should be resealed under the OPRF.
+//
+// Each blob also carries a salted HMAC commitment to the master key it encrypts, so
+// the app can confirm a backup still matches the live key hierarchy without opening
+// it. This catches a backup of a wrong or stale key while the user can still redo it.
+// The salt keeps backups of the same key unlinkable.
+
+const HARDENED_BACKUP_VERSION: u8 = 2;
+const LEGACY_SHARE_VERSION: u8 = 1;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const SERVER_SHARE_LENGTH: usize = 32;
+const MIN_KDF_OUTPUT_LENGTH: usize = 32;
+pub(crate) const PASSWORD_PATH_SALT: &[u8] = b"aura-server-assisted-backup-v1";
+pub(crate) const PASSWORD_PATH_INFO: &[u8] = b"password-path";
+pub(crate) const PHRASE_PATH_INFO: &[u8] = b"aura-backup-phrase-path-v1";
+pub(crate) const SHARE_COMMITMENT_DOMAIN: &[u8] = b"aura-server-share-commitment-v1";
+pub(crate) const KEY_COMMITMENT_DOMAIN: &[u8] = b"aura-backup-key-commitment-v1";
+const KEY_COMMITMENT_SALT_LENGTH: usize = 16;
+
+/// Backup blob with a server-assisted password path and an offline phrase path
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HardenedBackup {
+    version: u8,
+    // Only on version 1 blobs, sealed under a static server share
+    #[serde(default)]
+    share_commitment: Vec<u8>,
+    password_nonce: Vec<u8>,
+    password_ciphertext: Vec<u8>,
+    phrase_nonce: Vec<u8>,
+    phrase_ciphertext: Vec<u8>,
+    // Both empty on blobs sealed before key attestation was added
+    #[serde(default)]
+    key_commitment_salt: Vec<u8>,
+    #[serde(default)]
+    key_commitment: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl HardenedBackup {
+    /// Blind the password KDF output; send `blinded_element` to the server's
+    /// rate-limited OPRF endpoint and keep the blind for `seal` or `open_with_oprf`
+    #[wasm_bindgen]
+    pub fn blind_password(kdf_output: &[u8]) -> Result<OprfBlind, JsValue> {
+        Self::blind_kdf_output(kdf_output).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Seal `master_key` under the OPRF output for the password and, independently,
+    /// under the recovery phrase
+    #[wasm_bindgen]
+    pub fn seal(
+        master_key: &[u8],
+        password_blind: &OprfBlind,
+        evaluated_element: &[u8],
+        recovery_phrase: &RecoveryPhrase,
+    ) -> Result<HardenedBackup, JsValue> {
+        let seed = Zeroizing::new(recovery_phrase.to_seed("")?);
+        Self::seal_with(master_key, password_blind, evaluated_element, &seed).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Password path: needs the server's evaluation of a fresh blind of the password
+    #[wasm_bindgen]
+    pub fn open_with_oprf(&self, password_blind: &OprfBlind, evaluated_element: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.open_password_path(password_blind, evaluated_element).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Password path of version 1 blobs: needs the share the server released
+    #[wasm_bindgen]
+    pub fn open_with_server_share(&self, kdf_output: &[u8], server_share: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.open_legacy_share_path(kdf_output, server_share).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Phrase path: fully client-side, no server involvement
+    #[wasm_bindgen]
+    pub fn open_with_phrase(&self, recovery_phrase: &RecoveryPhrase) -> Result<Vec<u8>, JsValue> {
+        let seed = Zeroizing::new(recovery_phrase.to_seed("")?);
+        self.open_phrase_path(&seed).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Check, without decrypting, that this backup encrypts the master key of the
+    /// manager's live hierarchy
+    #[wasm_bindgen]
+    pub fn verify_backup_matches_active_keys(&self, manager: &KeyRotationManager) -> Result<bool, JsValue> {
+        let master_key = manager.master_key_bytes()
+            .ok_or_else(|| JsValue::from_str("Key hierarchy has no master key"))?;
+        self.matches_master_key(&master_key).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize backup: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<HardenedBackup, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid hardened backup: {}", e)))
+    }
+}
+
+impl HardenedBackup {
+    pub(crate) fn blind_kdf_output(kdf_output: &[u8]) -> Result<OprfBlind, String> {
+        if kdf_output.len() < MIN_KDF_OUTPUT_LENGTH {
+            return Err("KDF output must be at least 32 bytes".to_string());
+        }
+        Ok(OprfBlind::new(kdf_output))
+    }
+
+    pub(crate) fn seal_with(
+        master_key: &[u8],
+        password_blind: &OprfBlind,
+        evaluated_element: &[u8],
+        phrase_seed: &[u8],
+    ) -> Result<HardenedBackup, String> {
+        let oprf_output = password_blind.finalize(evaluated_element)?;
+        let password_key = password_path_key(oprf_output.as_ref())?;
+        let phrase_key = phrase_path_key(phrase_seed)?;
+        let (password_nonce, password_ciphertext) = wrap(&password_key, master_key, HARDENED_BACKUP_VERSION)?;
+        let (phrase_nonce, phrase_ciphertext) = wrap(&phrase_key, master_key, HARDENED_BACKUP_VERSION)?;
+        let mut key_commitment_salt = vec![0u8; KEY_COMMITMENT_SALT_LENGTH];
+        platform::fill_random(&mut key_commitment_salt);
+
+        Ok(HardenedBackup {
+            version: HARDENED_BACKUP_VERSION,
+            share_commitment: Vec::new(),
+            password_nonce,
+            password_ciphertext,
+            phrase_nonce,
+            phrase_ciphertext,
+            key_commitment: key_commitment(master_key, &key_commitment_salt),
+            key_commitment_salt,
+        })
+    }
+
+    pub(crate) fn open_password_path(&self, password_blind: &OprfBlind, evaluated_element: &[u8]) -> Result<Vec<u8>, String> {
+        self.check_version()?;
+        if self.version == LEGACY_SHARE_VERSION {
+            return Err("Version 1 backups are opened with the server share".to_string());
+        }
+        let oprf_output = password_blind.finalize(evaluated_element)?;
+        let key = password_path_key(oprf_output.as_ref())?;
+        unwrap(&key, &self.password_nonce, &self.password_ciphertext, self.version)
+    }
+
+    pub(crate) fn open_legacy_share_path(&self, kdf_output: &[u8], server_share: &[u8]) -> Result<Vec<u8>, String> {
+        self.check_version()?;
+        if self.version != LEGACY_SHARE_VERSION {
+            return Err("Backup is sealed under the OPRF, not a server share".to_string());
+        }
+        if server_share.len() != SERVER_SHARE_LENGTH {
+            return Err("Server share must be 32 bytes".to_string());
+        }
+        // A wrong or substituted share is reported as such, not as a wrong password
+        if !constant_time_compare(&share_commitment(server_share), &self.share_commitment) {
+            return Err("Server share does not match this backup".to_string());
+        }
+        let mut ikm = Zeroizing::new(Vec::with_capacity(kdf_output.len() + server_share.len()));
+        ikm.extend_from_slice(kdf_output);
+        ikm.extend_from_slice(server_share);
+        let key = password_path_key(&ikm)?;
+        unwrap(&key, &self.password_nonce, &self.password_ciphertext, self.version)
+    }
+
+    pub(crate) fn open_phrase_path(&self, phrase_seed: &[u8]) -> Result<Vec<u8>, String> {
+        self.check_version()?;
+        let key = phrase_path_key(phrase_seed)?;
+        unwrap(&key, &self.phrase_nonce, &self.phrase_ciphertext, self.version)
+    }
+
+    pub(crate) fn matches_master_key(&self, master_key: &[u8]) -> Result<bool, String> {
+        self.check_version()?;
+        if self.key_commitment.is_empty() || self.key_commitment_salt.len() != KEY_COMMITMENT_SALT_LENGTH {
+            return Err("Backup carries no key commitment".to_string());
+        }
+        Ok(constant_time_compare(&key_commitment(master_key, &self.key_commitment_salt), &self.key_commitment))
+    }
+
+    fn check_version(&self) -> Result<(), String> {
+        if self.version != HARDENED_BACKUP_VERSION && self.version != LEGACY_SHARE_VERSION {
+            return Err(format!("Unsupported hardened backup version: {}", self.version));
+        }
+        Ok(())
+    }
+}
+
+fn password_path_key(ikm: &[u8]) -> Result<Zeroizing<[u8; KEY_LENGTH]>, String> {
+    let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+    Hkdf::<Sha256>::new(Some(PASSWORD_PATH_SALT), ikm)
+        .expand(PASSWORD_PATH_INFO, key.as_mut())
+        .map_err(|_| "Password path key derivation failed".to_string())?;
+    Ok(key)
+}
+
+fn phrase_path_key(phrase_seed: &[u8]) -> Result<Zeroizing<[u8; KEY_LENGTH]>, String> {
+    let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+    Hkdf::<Sha256>::new(None, phrase_seed)
+        .expand(PHRASE_PATH_INFO, key.as_mut())
+        .map_err(|_| "Phrase path key derivation failed".to_string())?;
+    Ok(key)
+}
+
+fn share_commitment(server_share: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(SHARE_COMMITMENT_DOMAIN);
+    hasher.update(server_share);
+    hasher.finalize().to_vec()
+}
+
+fn key_commitment(master_key: &[u8], salt: &[u8]) -> Vec<u8> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(master_key).expect("HMAC accepts any key length");
+    mac.update(KEY_COMMITMENT_DOMAIN);
+    mac.update(salt);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn wrap(key: &[u8; KEY_LENGTH], master_key: &[u8], version: u8) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let mut nonce = vec![0u8; NONCE_LENGTH];
+    platform::fill_random(&mut nonce);
+    let ciphertext = Aes256Gcm::new_from_slice(key)
+        .map_err(|_| "Invalid wrapping key".to_string())?
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: master_key, aad: &[version] })
+        .map_err(|_| "Backup encryption failed".to_string())?;
+    Ok((nonce, ciphertext))
+}
+
+fn unwrap(key: &[u8; KEY_LENGTH], nonce: &[u8], ciphertext: &[u8], version: u8) -> Result<Vec<u8>, String> {
+    if nonce.len() != NONCE_LENGTH {
+        return Err("Invalid backup nonce".to_string());
+    }
+    Aes256Gcm::new_from_slice(key)
+        .map_err(|_| "Invalid wrapping key".to_string())?
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: &[version] })
+        .map_err(|_| "Backup authentication failed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oprf::OprfServerKey;
+
+    const MASTER_KEY: [u8; 32] = [7u8; 32];
+    const KDF_OUTPUT: [u8; 32] = [9u8; 32];
+    const PHRASE_SEED: [u8; 64] = [3u8; 64];
+
+    fn evaluated(kdf_output: &[u8], server: &OprfServerKey) -> (OprfBlind, Vec<u8>) {
+        let blind = HardenedBackup::blind_kdf_output(kdf_output).unwrap();
+        let element = server.evaluate_element(&blind.blinded_element()).unwrap().to_vec();
+        (blind, element)
+    }
+
+    fn seal(server: &OprfServerKey) -> HardenedBackup {
+        let (blind, element) = evaluated(&KDF_OUTPUT, server);
+        HardenedBackup::seal_with(&MASTER_KEY, &blind, &element, &PHRASE_SEED).unwrap()
+    }
+
+    #[test]
+    fn test_password_path_requires_server_evaluation() {
+        let server = OprfServerKey::generate();
+        let backup = seal(&server);
+
+        // Every open takes a fresh blind and a fresh server evaluation
+        let (blind, element) = evaluated(&KDF_OUTPUT, &server);
+        assert_eq!(backup.open_password_path(&blind, &element).unwrap(), MASTER_KEY.to_vec());
+
+        let (wrong_password, element) = evaluated(&[8u8; 32], &server);
+        assert!(backup.open_password_path(&wrong_password, &element).unwrap_err().contains("authentication failed"));
+
+        let (blind, element) = evaluated(&KDF_OUTPUT, &OprfServerKey::generate());
+        assert!(backup.open_password_path(&blind, &element).unwrap_err().contains("authentication failed"));
+
+        // The stored blob carries no server material
+        assert!(backup.share_commitment.is_empty());
+        assert!(backup.open_legacy_share_path(&KDF_OUTPUT, &[0u8; 32]).is_err());
+        assert!(HardenedBackup::blind_kdf_output(&[1u8; 16]).is_err());
+    }
+
+    #[test]
+    fn test_legacy_share_backups_still_open() {
+        let share = [5u8; SERVER_SHARE_LENGTH];
+        let ikm = [KDF_OUTPUT.as_slice(), share.as_slice()].concat();
+        let (password_nonce, password_ciphertext) =
+            wrap(&password_path_key(&ikm).unwrap(), &MASTER_KEY, LEGACY_SHARE_VERSION).unwrap();
+        let (phrase_nonce, phrase_ciphertext) =
+            wrap(&phrase_path_key(&PHRASE_SEED).unwrap(), &MASTER_KEY, LEGACY_SHARE_VERSION).unwrap();
+        let legacy = HardenedBackup {
+            version: LEGACY_SHARE_VERSION,
+            share_commitment: share_commitment(&share),
+            password_nonce,
+            password_ciphertext,
+            phrase_nonce,
+            phrase_ciphertext,
+            key_commitment_salt: Vec::new(),
+            key_commitment: Vec::new(),
+        };
+
+        assert_eq!(legacy.open_legacy_share_path(&KDF_OUTPUT, &share).unwrap(), MASTER_KEY.to_vec());
+        assert!(legacy.open_legacy_share_path(&KDF_OUTPUT, &[0u8; 32]).unwrap_err().contains("Server share"));
+        assert_eq!(legacy.open_phrase_path(&PHRASE_SEED).unwrap(), MASTER_KEY.to_vec());
+
+        let (blind, element) = evaluated(&KDF_OUTPUT, &OprfServerKey::generate());
+        assert!(legacy.open_password_path(&blind, &element).unwrap_err().contains("server share"));
+    }
+
+    #[test]
+    fn test_phrase_path_works_without_server() {
+        let backup = seal(&OprfServerKey::generate());
+        let restored: HardenedBackup = serde_json::from_str(&serde_json::to_string(&backup).unwrap()).unwrap();
+
+        assert_eq!(restored.open_phrase_path(&PHRASE_SEED).unwrap(), MASTER_KEY.to_vec());
+        assert!(restored.open_phrase_path(&[4u8; 64]).is_err());
+    }
+
+    #[test]
+    fn test_key_commitment_detects_stale_backup() {
+        let server = OprfServerKey::generate();
+        let backup = seal(&server);
+        assert_eq!(backup.matches_master_key(&MASTER_KEY), Ok(true));
+        assert_eq!(backup.matches_master_key(&[8u8; 32]), Ok(false));
+
+        // Same key, fresh salt: commitments differ but both verify
+        let resealed = seal(&server);
+        assert_ne!(resealed.key_commitment, backup.key_commitment);
+        assert_eq!(resealed.matches_master_key(&MASTER_KEY), Ok(true));
+
+        let mut legacy = serde_json::to_value(&backup).unwrap();
+        legacy.as_object_mut().unwrap().retain(|field, _| !field.starts_with("key_commitment"));
+        let legacy: HardenedBackup = serde_json::from_value(legacy).unwrap();
+        assert!(legacy.matches_master_key(&MASTER_KEY).is_err());
+        assert_eq!(legacy.open_phrase_path(&PHRASE_SEED).unwrap(), MASTER_KEY.to_vec());
+    }
+}
@@ -2,6 +2,10 @@ use wasm_bindgen::prelude::*;
 // use serde::{Serialize, Deserialize}; // Reserved for future use
 use js_sys::{Promise, Object};
 use wasm_bindgen_futures::future_to_promise;
+use crate::profile::{configure_security_profile, SecurityProfile, SecurityProfileSettings};
+
+/// In-place WASM memory entry points for React Native TurboModules / JSI
+pub mod rn;
 
 // Import console.log for debugging
 #[wasm_bindgen]
@@ -211,6 +215,16 @@ pub fn init_crypto_core_with_verification() -> Result<ModuleIntegrity, JsValue>
     Ok(integrity)
 }
 
+/// WASM initialization with integrity check and a security profile for all modules
+#[wasm_bindgen]
+pub fn init_crypto_core_with_profile(profile: SecurityProfile) -> Result<SecurityProfileSettings, JsValue> {
+    init_crypto_core_with_verification()?;
+
+    let settings = configure_security_profile(profile);
+    console_log!("Crypto core security profile: {:?}", profile);
+    Ok(settings)
+}
+
 /// Health check interface for validation
 #[wasm_bindgen]
 #[derive(Clone)]
@@ -404,13 +418,16 @@ export interface CryptoEnvelopeData {
   key_id: string;
   encrypted_data: Uint8Array;
   tag: Uint8Array;
+  schema_version?: number;
+  user_scope_tag?: string;
 }
 
 export interface KDFParams {
-  algorithm: 'argon2id';
+  algorithm: 'argon2id' | 'scrypt';
   memory_cost: number;
-  time_cost: number;
+  time_cost: number; // scrypt: log2(N)
   parallelism: number;
+  block_size?: number; // scrypt only (r)
 }
 
 export interface CryptoOperationResult<T> {
@@ -0,0 +1,185 @@
+use wasm_bindgen::prelude::*;
+use js_sys::{ArrayBuffer, Uint8Array};
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce, Tag};
+use zeroize::Zeroizing;
+use crate::memory::track_allocation;
+use crate::platform;
+
+// React Native (TurboModule / JSI) entry points
+// Payloads never cross the bridge as base64 and are never copied between JS and WASM
+// memory: JS allocates an `RnBuffer`, writes the payload straight into WASM memory
+// through a `Uint8Array` view, and the buffer is sealed or opened in place. The result
+// is read back through a view as well. Calls run synchronously on the calling thread;
+// hosts that must keep the JS thread free run this module on a background runtime.
+//
+// Sealed layout: nonce (12 bytes) || ciphertext || tag (16 bytes)
+
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const KEY_LENGTH: usize = 32;
+const RN_MAX_RANDOM_BYTES: usize = 4096;
+
+/// Payload buffer in WASM memory, sealed and opened in place
+#[wasm_bindgen]
+pub struct RnBuffer {
+    bytes: Zeroizing<Vec<u8>>,
+    sealed: bool,
+}
+
+#[wasm_bindgen]
+impl RnBuffer {
+    /// Buffer for a plaintext of `length` bytes; write it through `payload_view`
+    #[wasm_bindgen(js_name = forPlaintext)]
+    pub fn for_plaintext(length: usize) -> RnBuffer {
+        track_allocation(NONCE_LENGTH + length + TAG_LENGTH);
+        RnBuffer { bytes: Zeroizing::new(vec![0u8; NONCE_LENGTH + length + TAG_LENGTH]), sealed: false }
+    }
+
+    /// Buffer for a sealed payload of `length` bytes; write it through `view`
+    #[wasm_bindgen(js_name = forSealed)]
+    pub fn for_sealed(length: usize) -> Result<RnBuffer, JsValue> {
+        if length < NONCE_LENGTH + TAG_LENGTH {
+            return Err(JsValue::from_str("Sealed payload too short"));
+        }
+        track_allocation(length);
+        Ok(RnBuffer { bytes: Zeroizing::new(vec![0u8; length]), sealed: true })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sealed(&self) -> bool {
+        self.sealed
+    }
+
+    /// View of the whole buffer (the sealed payload once sealed). Valid until the
+    /// next call into this module, which may grow WASM memory
+    #[wasm_bindgen]
+    pub fn view(&mut self) -> Uint8Array {
+        // SAFETY: the view aliases `bytes`, which outlives it as long as JS drops the
+        // view before calling back into WASM, as documented above
+        unsafe { Uint8Array::view_mut_raw(self.bytes.as_mut_ptr(), self.bytes.len()) }
+    }
+
+    /// View of the plaintext region, under the same validity rule as `view`
+    #[wasm_bindgen]
+    pub fn payload_view(&mut self) -> Uint8Array {
+        let length = self.payload_length();
+        // SAFETY: as in `view`; the region lies within `bytes`
+        unsafe { Uint8Array::view_mut_raw(self.bytes.as_mut_ptr().add(NONCE_LENGTH), length) }
+    }
+
+    /// Encrypt the plaintext in place (AES-256-GCM)
+    #[wasm_bindgen]
+    pub fn seal(&mut self, key: &[u8], aad: &[u8]) -> Result<(), JsValue> {
+        self.seal_in_place(key, aad).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Decrypt the sealed payload in place; the plaintext is then in `payload_view`
+    #[wasm_bindgen]
+    pub fn open(&mut self, key: &[u8], aad: &[u8]) -> Result<(), JsValue> {
+        self.open_in_place(key, aad).map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl RnBuffer {
+    fn payload_length(&self) -> usize {
+        self.bytes.len() - NONCE_LENGTH - TAG_LENGTH
+    }
+
+    pub(crate) fn seal_in_place(&mut self, key: &[u8], aad: &[u8]) -> Result<(), String> {
+        if self.sealed {
+            return Err("Buffer is already sealed".to_string());
+        }
+        let cipher = cipher(key)?;
+        let payload_end = NONCE_LENGTH + self.payload_length();
+        let (nonce, rest) = self.bytes.split_at_mut(NONCE_LENGTH);
+        platform::fill_random(nonce);
+        let (payload, tag) = rest.split_at_mut(payload_end - NONCE_LENGTH);
+
+        let computed = cipher
+            .encrypt_in_place_detached(Nonce::from_slice(nonce), aad, payload)
+            .map_err(|_| "Encryption failed".to_string())?;
+        tag.copy_from_slice(&computed);
+        self.sealed = true;
+        Ok(())
+    }
+
+    pub(crate) fn open_in_place(&mut self, key: &[u8], aad: &[u8]) -> Result<(), String> {
+        if !self.sealed {
+            return Err("Buffer is not sealed".to_string());
+        }
+        let cipher = cipher(key)?;
+        let payload_end = NONCE_LENGTH + self.payload_length();
+        let (nonce, rest) = self.bytes.split_at_mut(NONCE_LENGTH);
+        let (payload, tag) = rest.split_at_mut(payload_end - NONCE_LENGTH);
+
+        cipher
+            .decrypt_in_place_detached(Nonce::from_slice(nonce), aad, payload, Tag::from_slice(tag))
+            .map_err(|_| "Decryption failed".to_string())?;
+        self.sealed = false;
+        Ok(())
+    }
+}
+
+fn cipher(key: &[u8]) -> Result<Aes256Gcm, String> {
+    if key.len() != KEY_LENGTH {
+        return Err("Key must be 32 bytes".to_string());
+    }
+    Aes256Gcm::new_from_slice(key).map_err(|_| "Invalid key".to_string())
+}
+
+/// Random bytes as an ArrayBuffer (at most 4096)
+#[wasm_bindgen]
+pub fn rn_random_bytes(length: usize) -> Result<ArrayBuffer, JsValue> {
+    if length == 0 || length > RN_MAX_RANDOM_BYTES {
+        return Err(JsValue::from_str("Invalid size: must be between 1 and 4096 bytes"));
+    }
+    let mut bytes = Zeroizing::new(vec![0u8; length]);
+    platform::fill_random(&mut bytes);
+    Ok(Uint8Array::from(bytes.as_slice()).buffer())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [3u8; 32];
+
+    fn sealed(plaintext: &[u8], aad: &[u8]) -> Vec<u8> {
+        let mut buffer = RnBuffer::for_plaintext(plaintext.len());
+        buffer.bytes[NONCE_LENGTH..NONCE_LENGTH + plaintext.len()].copy_from_slice(plaintext);
+        buffer.seal_in_place(&KEY, aad).unwrap();
+        buffer.bytes.to_vec()
+    }
+
+    fn opened(sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+        let mut buffer = RnBuffer::for_sealed(sealed.len()).map_err(|_| "too short".to_string())?;
+        buffer.bytes.copy_from_slice(sealed);
+        buffer.open_in_place(&KEY, aad)?;
+        Ok(buffer.bytes[NONCE_LENGTH..NONCE_LENGTH + buffer.payload_length()].to_vec())
+    }
+
+    #[test]
+    fn test_seal_open_in_place_roundtrip_with_aad() {
+        let sealed = sealed(b"cycle day 14", b"record-7");
+        assert_eq!(sealed.len(), NONCE_LENGTH + 12 + TAG_LENGTH);
+        assert_eq!(opened(&sealed, b"record-7").unwrap(), b"cycle day 14");
+        assert!(opened(&sealed, b"record-8").is_err());
+
+        let mut tampered = sealed.clone();
+        tampered[NONCE_LENGTH] ^= 1;
+        assert!(opened(&tampered, b"record-7").is_err());
+    }
+
+    #[test]
+    fn test_buffer_state_and_key_checks() {
+        let mut buffer = RnBuffer::for_plaintext(4);
+        assert!(buffer.open_in_place(&KEY, b"").is_err());
+        assert!(buffer.seal_in_place(&[1u8; 16], b"").is_err());
+        buffer.seal_in_place(&KEY, b"").unwrap();
+        assert!(buffer.seal_in_place(&KEY, b"").is_err());
+
+        buffer.open_in_place(&KEY, b"").unwrap();
+        assert_eq!(buffer.bytes[NONCE_LENGTH..NONCE_LENGTH + 4], [0u8; 4]);
+    }
+}
@@ -0,0 +1,362 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use sha2::{Digest, Sha256};
+use crate::inbox::device_fingerprint;
+use crate::key_rotation::{KeyHierarchyGraph, KeyHierarchyNodeKind};
+use crate::envelope::{decode_hex, to_hex};
+use crate::platform;
+use crate::signing::{self, SigningKeyPair};
+
+// Key ceremony evidence
+// After onboarding or a rotation, deployments running under institutional review
+// (clinical studies, security audits) need to file what was set up: which public keys
+// exist, their fingerprints, which algorithms are in use and when each step happened.
+// The bundle is assembled from public keys and key metadata only; private keys and
+// symmetric key material never enter it. Attestations are Ed25519 signatures over the
+// bundle digest (see `signing`): reviewers check them with the attester's published
+// public key, which lets them verify but not forge.
+
+const EVIDENCE_FORMAT_VERSION: u8 = 1;
+pub(crate) const ATTESTATION_CONTEXT: &[u8] = b"key-ceremony-attestation-v1";
+
+/// Event the evidence bundle documents
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CeremonyKind {
+    Onboarding,
+    Rotation,
+}
+
+/// Public key recorded in the bundle
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CeremonyPublicKey {
+    label: String,
+    algorithm: String,
+    public_key: String, // hex
+    fingerprint: String,
+    recorded_at: u64,
+}
+
+#[wasm_bindgen]
+impl CeremonyPublicKey {
+    #[wasm_bindgen(getter)]
+    pub fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn algorithm(&self) -> String {
+        self.algorithm.clone()
+    }
+
+    /// Same fingerprint format as `device_fingerprint`
+    #[wasm_bindgen(getter)]
+    pub fn fingerprint(&self) -> String {
+        self.fingerprint.clone()
+    }
+}
+
+/// Key version metadata taken from the key hierarchy snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CeremonyKeyVersion {
+    pub purpose: String,
+    pub version: String,
+    pub status: String,
+    pub created_at: Option<f64>,
+}
+
+/// Ed25519-signed attestation over the bundle digest
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CeremonyAttestation {
+    attester: String,
+    issued_at: u64,
+    bundle_digest: String,
+    signer_key_id: String,
+    signature: String, // hex
+}
+
+#[wasm_bindgen]
+impl CeremonyAttestation {
+    #[wasm_bindgen(getter)]
+    pub fn attester(&self) -> String {
+        self.attester.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn issued_at(&self) -> u64 {
+        self.issued_at
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn bundle_digest(&self) -> String {
+        self.bundle_digest.clone()
+    }
+
+    /// Fingerprint of the attester's public key
+    #[wasm_bindgen(getter)]
+    pub fn signer_key_id(&self) -> String {
+        self.signer_key_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn signature(&self) -> String {
+        self.signature.clone()
+    }
+}
+
+impl CeremonyAttestation {
+    fn signed_message(&self) -> Vec<u8> {
+        let mut message = ATTESTATION_CONTEXT.to_vec();
+        message.extend_from_slice(self.attester.as_bytes());
+        message.push(0);
+        message.extend_from_slice(&self.issued_at.to_le_bytes());
+        message.extend_from_slice(self.bundle_digest.as_bytes());
+        message
+    }
+}
+
+/// Contents covered by the digest; attestations are kept outside it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct EvidenceContents {
+    format_version: u8,
+    ceremony_id: String,
+    kind: CeremonyKind,
+    deployment_id: String,
+    created_at: u64,
+    algorithms: BTreeSet<String>,
+    public_keys: Vec<CeremonyPublicKey>,
+    key_versions: Vec<CeremonyKeyVersion>,
+}
+
+/// Non-secret evidence bundle for one onboarding or rotation ceremony
+#[wasm_bindgen]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyCeremonyEvidence {
+    contents: EvidenceContents,
+    attestations: Vec<CeremonyAttestation>,
+}
+
+#[wasm_bindgen]
+impl KeyCeremonyEvidence {
+    #[wasm_bindgen(constructor)]
+    pub fn new(kind: CeremonyKind, deployment_id: String) -> KeyCeremonyEvidence {
+        Self::new_at(kind, deployment_id, platform::now_ms())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ceremony_id(&self) -> String {
+        self.contents.ceremony_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> CeremonyKind {
+        self.contents.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn created_at(&self) -> u64 {
+        self.contents.created_at
+    }
+
+    /// Record a public key; `algorithm` is an identifier such as "x25519"
+    #[wasm_bindgen]
+    pub fn add_public_key(&mut self, label: String, algorithm: String, public_key: &[u8]) -> Result<(), JsValue> {
+        self.record_public_key(label, algorithm, public_key, platform::now_ms())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Record an algorithm in use that has no public key of its own (AEAD, KDF)
+    #[wasm_bindgen]
+    pub fn add_algorithm(&mut self, algorithm: String) {
+        self.contents.algorithms.insert(algorithm);
+    }
+
+    /// Record every key version in a hierarchy snapshot
+    #[wasm_bindgen]
+    pub fn add_key_versions(&mut self, graph: &KeyHierarchyGraph) {
+        let versions = graph
+            .nodes()
+            .iter()
+            .filter(|node| node.kind == KeyHierarchyNodeKind::Version)
+            .map(|node| CeremonyKeyVersion {
+                purpose: node.purpose.clone().unwrap_or_default(),
+                version: node.version.clone().unwrap_or_default(),
+                status: node.status.clone().unwrap_or_default(),
+                created_at: node.created_at,
+            });
+        self.contents.key_versions.extend(versions);
+    }
+
+    #[wasm_bindgen]
+    pub fn public_keys(&self) -> Vec<CeremonyPublicKey> {
+        self.contents.public_keys.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn attestations(&self) -> Vec<CeremonyAttestation> {
+        self.attestations.clone()
+    }
+
+    /// Hex SHA-256 over the bundle contents, excluding attestations
+    #[wasm_bindgen]
+    pub fn digest(&self) -> Result<String, JsValue> {
+        self.compute_digest().map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Attest to the current contents with the attester's signing key; adding data
+    /// afterwards invalidates the attestation
+    #[wasm_bindgen]
+    pub fn attest(&mut self, attester: String, signer: &SigningKeyPair) -> Result<CeremonyAttestation, JsValue> {
+        self.attest_at(attester, signer, platform::now_ms())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Whether `attestation` covers the current contents and is signed by the holder
+    /// of `attester_public_key`, as published by the attester
+    #[wasm_bindgen]
+    pub fn verify_attestation(&self, attestation: &CeremonyAttestation, attester_public_key: &[u8]) -> bool {
+        let Ok(digest) = self.compute_digest() else {
+            return false;
+        };
+        let Ok(signature) = decode_hex(&attestation.signature) else {
+            return false;
+        };
+        digest == attestation.bundle_digest
+            && attestation.signer_key_id == device_fingerprint(attester_public_key)
+            && signing::verify(attester_public_key, &attestation.signed_message(), &signature)
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize ceremony evidence: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<KeyCeremonyEvidence, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid ceremony evidence: {}", e)))
+    }
+}
+
+impl KeyCeremonyEvidence {
+    pub(crate) fn new_at(kind: CeremonyKind, deployment_id: String, now: u64) -> KeyCeremonyEvidence {
+        KeyCeremonyEvidence {
+            contents: EvidenceContents {
+                format_version: EVIDENCE_FORMAT_VERSION,
+                ceremony_id: platform::new_uuid(),
+                kind,
+                deployment_id,
+                created_at: now,
+                algorithms: BTreeSet::new(),
+                public_keys: Vec::new(),
+                key_versions: Vec::new(),
+            },
+            attestations: Vec::new(),
+        }
+    }
+
+    pub(crate) fn record_public_key(
+        &mut self,
+        label: String,
+        algorithm: String,
+        public_key: &[u8],
+        now: u64,
+    ) -> Result<(), String> {
+        if public_key.is_empty() {
+            return Err("Public key must not be empty".to_string());
+        }
+        let fingerprint = device_fingerprint(public_key);
+        if self.contents.public_keys.iter().any(|key| key.fingerprint == fingerprint) {
+            return Err(format!("Public key {} is already recorded", fingerprint));
+        }
+
+        self.contents.algorithms.insert(algorithm.clone());
+        self.contents.public_keys.push(CeremonyPublicKey {
+            label,
+            algorithm,
+            public_key: to_hex(public_key),
+            fingerprint,
+            recorded_at: now,
+        });
+        Ok(())
+    }
+
+    fn compute_digest(&self) -> Result<String, String> {
+        // Struct fields serialize in declaration order and algorithms are a BTreeSet,
+        // so the encoding is stable across devices
+        let encoded = serde_json::to_vec(&self.contents)
+            .map_err(|e| format!("Failed to encode ceremony evidence: {}", e))?;
+        Ok(to_hex(&Sha256::digest(&encoded)))
+    }
+
+    pub(crate) fn attest_at(
+        &mut self,
+        attester: String,
+        signer: &SigningKeyPair,
+        now: u64,
+    ) -> Result<CeremonyAttestation, String> {
+        if attester.is_empty() {
+            return Err("Attester must not be empty".to_string());
+        }
+        let bundle_digest = self.compute_digest()?;
+        let mut attestation = CeremonyAttestation {
+            attester,
+            issued_at: now,
+            bundle_digest,
+            signer_key_id: signer.key_id(),
+            signature: String::new(),
+        };
+        attestation.signature = to_hex(&signer.sign(&attestation.signed_message()));
+        self.attestations.push(attestation.clone());
+        Ok(attestation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evidence() -> KeyCeremonyEvidence {
+        let mut evidence = KeyCeremonyEvidence::new_at(CeremonyKind::Onboarding, "study-042".to_string(), 1_000);
+        evidence.record_public_key("phone".to_string(), "x25519".to_string(), &[1u8; 32], 1_100).unwrap();
+        evidence.record_public_key("laptop".to_string(), "x25519".to_string(), &[2u8; 32], 1_200).unwrap();
+        evidence.add_algorithm("aes-256-gcm".to_string());
+        evidence
+    }
+
+    #[test]
+    fn test_bundle_records_fingerprints_and_rejects_duplicates() {
+        let mut evidence = evidence();
+        assert_eq!(evidence.public_keys()[0].fingerprint(), device_fingerprint(&[1u8; 32]));
+        assert!(evidence.record_public_key("dup".to_string(), "x25519".to_string(), &[2u8; 32], 1_300).is_err());
+        assert!(evidence.record_public_key("empty".to_string(), "x25519".to_string(), &[], 1_300).is_err());
+
+        let json = serde_json::to_string(&evidence).unwrap();
+        assert!(json.contains("\"x25519\"") && json.contains("\"aes-256-gcm\""));
+    }
+
+    #[test]
+    fn test_attestation_covers_contents() {
+        let mut evidence = evidence();
+        let officer = SigningKeyPair::generate();
+        let attestation = evidence.attest_at("site-security-officer".to_string(), &officer, 2_000).unwrap();
+        assert!(evidence.verify_attestation(&attestation, &officer.public_key()));
+        assert!(!evidence.verify_attestation(&attestation, &SigningKeyPair::generate().public_key()));
+        assert!(evidence.attest_at(String::new(), &officer, 2_000).is_err());
+
+        // A reviewer holding only the public key cannot rewrite who attested or when
+        let mut relabeled = attestation.clone();
+        relabeled.attester = "irb-reviewer".to_string();
+        assert!(!evidence.verify_attestation(&relabeled, &officer.public_key()));
+        let mut backdated = attestation.clone();
+        backdated.issued_at = 1_500;
+        assert!(!evidence.verify_attestation(&backdated, &officer.public_key()));
+
+        evidence.add_algorithm("argon2id".to_string());
+        assert!(!evidence.verify_attestation(&attestation, &officer.public_key()));
+    }
+}
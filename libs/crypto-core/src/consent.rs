@@ -0,0 +1,284 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+use crate::crash_capture::{discard_crash_reports, pending_crash_report_count};
+use crate::envelope::to_hex;
+use crate::platform;
+use crate::security::constant_time_compare;
+
+// Feature-usage consent registry
+// Analytics cohorts, crash capture, escrow release and research sharing only run after
+// the user consented to them. Every grant or withdrawal is kept as a timestamped record,
+// chained to the previous one and signed with HMAC-SHA256, so the history can be
+// shown in the privacy report and tampering with it is detected. The host persists
+// the registry as one AES-256-GCM blob; both keys are derived from the registry key
+// the host supplies when opening it. Without an open registry, a record, or a valid
+// chain, every feature counts as not consented.
+
+type HmacSha256 = Hmac<Sha256>;
+
+const MIN_REGISTRY_KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+pub(crate) const SIGNING_KEY_INFO: &[u8] = b"aura-consent-signing-v1";
+pub(crate) const SEALING_KEY_INFO: &[u8] = b"aura-consent-sealing-v1";
+pub(crate) const REGISTRY_AAD: &[u8] = b"aura-consent-registry-v1";
+
+static CONSENT_REGISTRY: Mutex<Option<ConsentRegistry>> = Mutex::new(None);
+
+/// Privacy feature that needs recorded consent before it operates
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentFeature {
+    AnalyticsCohorts = 0,
+    CrashCapture = 1,
+    Escrow = 2,
+    ResearchSharing = 3,
+}
+
+impl ConsentFeature {
+    pub(crate) const ALL: [ConsentFeature; 4] = [
+        ConsentFeature::AnalyticsCohorts,
+        ConsentFeature::CrashCapture,
+        ConsentFeature::Escrow,
+        ConsentFeature::ResearchSharing,
+    ];
+}
+
+/// One signed grant or withdrawal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ConsentRecord {
+    seq: u64,
+    feature: ConsentFeature,
+    granted: bool,
+    policy_version: u32,
+    recorded_at: u64,
+    prev: String,
+    sig: String,
+}
+
+pub(crate) struct ConsentRegistry {
+    signing_key: Zeroizing<[u8; 32]>,
+    sealing_key: Zeroizing<[u8; 32]>,
+    records: Vec<ConsentRecord>,
+}
+
+impl ConsentRegistry {
+    pub(crate) fn with_key(registry_key: &[u8]) -> Result<Self, String> {
+        if registry_key.len() < MIN_REGISTRY_KEY_LENGTH {
+            return Err(format!("Consent registry key must be at least {} bytes", MIN_REGISTRY_KEY_LENGTH));
+        }
+        let hkdf = Hkdf::<Sha256>::new(None, registry_key);
+        let mut signing_key = Zeroizing::new([0u8; 32]);
+        let mut sealing_key = Zeroizing::new([0u8; 32]);
+        hkdf.expand(SIGNING_KEY_INFO, signing_key.as_mut())
+            .and_then(|_| hkdf.expand(SEALING_KEY_INFO, sealing_key.as_mut()))
+            .map_err(|_| "Consent key derivation failed".to_string())?;
+        Ok(ConsentRegistry { signing_key, sealing_key, records: Vec::new() })
+    }
+
+    /// Decrypt a persisted registry and verify its record chain
+    pub(crate) fn open(registry_key: &[u8], sealed: &[u8]) -> Result<Self, String> {
+        let mut registry = Self::with_key(registry_key)?;
+        if sealed.len() <= NONCE_LENGTH {
+            return Err("Sealed consent registry is truncated".to_string());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+        let plaintext = Zeroizing::new(
+            Aes256Gcm::new_from_slice(registry.sealing_key.as_ref())
+                .map_err(|_| "Invalid consent sealing key".to_string())?
+                .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: REGISTRY_AAD })
+                .map_err(|_| "Consent registry could not be decrypted".to_string())?,
+        );
+        registry.records = serde_json::from_slice(&plaintext)
+            .map_err(|e| format!("Invalid consent registry: {}", e))?;
+        if !registry.chain_valid() {
+            return Err("Consent registry signature chain is broken".to_string());
+        }
+        Ok(registry)
+    }
+
+    pub(crate) fn seal(&self) -> Result<Vec<u8>, String> {
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(&self.records).map_err(|e| format!("Failed to serialize consent registry: {}", e))?,
+        );
+        let mut nonce = [0u8; NONCE_LENGTH];
+        platform::fill_random(&mut nonce);
+        let ciphertext = Aes256Gcm::new_from_slice(self.sealing_key.as_ref())
+            .map_err(|_| "Invalid consent sealing key".to_string())?
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: REGISTRY_AAD })
+            .map_err(|_| "Consent registry encryption failed".to_string())?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    pub(crate) fn record_at(&mut self, feature: ConsentFeature, granted: bool, policy_version: u32, now: u64) {
+        let (seq, prev) = self.records.last().map_or((0, String::new()), |last| (last.seq + 1, last.sig.clone()));
+        // Keep the history ordered even if the clock steps back
+        let recorded_at = self.records.last().map_or(now, |last| now.max(last.recorded_at));
+        let mut record = ConsentRecord { seq, feature, granted, policy_version, recorded_at, prev, sig: String::new() };
+        record.sig = self.sign(&record);
+        self.records.push(record);
+    }
+
+    /// Latest decision for `feature`, if the whole chain still verifies
+    pub(crate) fn current(&self, feature: ConsentFeature) -> Option<&ConsentRecord> {
+        if !self.chain_valid() {
+            return None;
+        }
+        self.records.iter().rev().find(|record| record.feature == feature)
+    }
+
+    pub(crate) fn is_granted(&self, feature: ConsentFeature) -> bool {
+        self.current(feature).is_some_and(|record| record.granted)
+    }
+
+    fn chain_valid(&self) -> bool {
+        let mut prev = "";
+        for (seq, record) in self.records.iter().enumerate() {
+            if record.seq != seq as u64
+                || record.prev != prev
+                || !constant_time_compare(self.sign(record).as_bytes(), record.sig.as_bytes())
+            {
+                return false;
+            }
+            prev = &record.sig;
+        }
+        true
+    }
+
+    fn sign(&self, record: &ConsentRecord) -> String {
+        let unsigned = ConsentRecord { sig: String::new(), ..record.clone() };
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(self.signing_key.as_ref()).expect("HMAC accepts any key length");
+        mac.update(&serde_json::to_vec(&unsigned).unwrap_or_default());
+        to_hex(&mac.finalize().into_bytes())
+    }
+
+    fn report_value(&self) -> serde_json::Value {
+        let chain_valid = self.chain_valid();
+        let features: Vec<serde_json::Value> = ConsentFeature::ALL
+            .iter()
+            .map(|&feature| {
+                let current = self.current(feature);
+                serde_json::json!({
+                    "feature": feature,
+                    "granted": current.is_some_and(|record| record.granted),
+                    "policy_version": current.map(|record| record.policy_version),
+                    "recorded_at": current.map(|record| record.recorded_at),
+                })
+            })
+            .collect();
+        serde_json::json!({ "chain_valid": chain_valid, "features": features, "history": self.records })
+    }
+}
+
+/// Open the process-wide registry with the host's registry key (at least 32 bytes),
+/// loading `sealed` when the host has a persisted copy
+#[wasm_bindgen]
+pub fn open_consent_registry(registry_key: &[u8], sealed: Option<Vec<u8>>) -> Result<(), JsValue> {
+    let registry = match sealed {
+        Some(sealed) => ConsentRegistry::open(registry_key, &sealed),
+        None => ConsentRegistry::with_key(registry_key),
+    }
+    .map_err(|e| JsValue::from_str(&e))?;
+    let mut state = CONSENT_REGISTRY.lock().map_err(|_| JsValue::from_str("Consent registry unavailable"))?;
+    *state = Some(registry);
+    Ok(())
+}
+
+/// Record a grant or withdrawal and return the sealed registry for the host to persist.
+/// Withdrawing crash capture also discards queued crash reports.
+#[wasm_bindgen]
+pub fn record_consent(feature: ConsentFeature, granted: bool, policy_version: u32) -> Result<Vec<u8>, JsValue> {
+    record_consent_at(feature, granted, policy_version, platform::now_ms()).map_err(|e| JsValue::from_str(&e))
+}
+
+pub(crate) fn record_consent_at(
+    feature: ConsentFeature,
+    granted: bool,
+    policy_version: u32,
+    now: u64,
+) -> Result<Vec<u8>, String> {
+    let sealed = {
+        let mut state = CONSENT_REGISTRY.lock().map_err(|_| "Consent registry unavailable".to_string())?;
+        let registry = state.as_mut().ok_or_else(|| "Consent registry is not open".to_string())?;
+        registry.record_at(feature, granted, policy_version, now);
+        registry.seal()?
+    };
+    // Outside the registry lock: crash capture checks consent while holding its own
+    if feature == ConsentFeature::CrashCapture && !granted {
+        discard_crash_reports();
+    }
+    Ok(sealed)
+}
+
+/// Drop the open registry and its keys; true if one was open
+pub(crate) fn close_consent_registry() -> bool {
+    CONSENT_REGISTRY.lock().map(|mut state| state.take().is_some()).unwrap_or(false)
+}
+
+#[wasm_bindgen]
+pub fn has_consent(feature: ConsentFeature) -> bool {
+    consent_granted(feature)
+}
+
+pub(crate) fn consent_granted(feature: ConsentFeature) -> bool {
+    CONSENT_REGISTRY
+        .lock()
+        .map(|state| state.as_ref().is_some_and(|registry| registry.is_granted(feature)))
+        .unwrap_or(false)
+}
+
+/// GDPR privacy report as JSON: consent decisions and their signed history, plus
+/// the data currently held for consent-gated features
+#[wasm_bindgen]
+pub fn privacy_report() -> String {
+    let consent = CONSENT_REGISTRY
+        .lock()
+        .ok()
+        .and_then(|state| state.as_ref().map(ConsentRegistry::report_value))
+        .unwrap_or(serde_json::Value::Null);
+    serde_json::json!({
+        "generated_at": platform::now_ms(),
+        "crate_version": env!("CARGO_PKG_VERSION"),
+        "consent": consent,
+        "pending_crash_reports": pending_crash_report_count(),
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consent_records_are_signed_chained_and_sealed() {
+        let key = [7u8; 32];
+        let mut registry = ConsentRegistry::with_key(&key).unwrap();
+        assert!(!registry.is_granted(ConsentFeature::CrashCapture));
+
+        registry.record_at(ConsentFeature::CrashCapture, true, 2, 1_000);
+        registry.record_at(ConsentFeature::Escrow, true, 2, 2_000);
+        registry.record_at(ConsentFeature::CrashCapture, false, 3, 3_000);
+        assert!(!registry.is_granted(ConsentFeature::CrashCapture));
+        assert!(registry.is_granted(ConsentFeature::Escrow));
+        assert_eq!(registry.current(ConsentFeature::CrashCapture).unwrap().policy_version, 3);
+
+        let sealed = registry.seal().unwrap();
+        let reopened = ConsentRegistry::open(&key, &sealed).unwrap();
+        assert_eq!(reopened.records, registry.records);
+        assert!(ConsentRegistry::open(&[8u8; 32], &sealed).is_err());
+
+        // A rewritten decision breaks the chain, which withdraws every feature
+        registry.records[1].granted = false;
+        registry.records[2].granted = true;
+        assert!(!registry.is_granted(ConsentFeature::Escrow));
+        assert!(!registry.is_granted(ConsentFeature::CrashCapture));
+        assert_eq!(registry.report_value()["chain_valid"], false);
+    }
+}
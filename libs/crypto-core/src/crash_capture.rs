@@ -0,0 +1,206 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::panic::{catch_unwind, UnwindSafe};
+use std::sync::Mutex;
+use crate::consent::{consent_granted, ConsentFeature};
+use crate::memory::get_memory_stats;
+use crate::multi_recipient::MultiRecipientEnvelope;
+use crate::platform;
+use crate::scope::ScopeContext;
+
+// Crash-state capture
+// When a crypto operation panics or fails internally, a snapshot of the surrounding
+// state is sealed to the developer public key and queued until the user consents to
+// upload it. Snapshots carry operation and module names, key descriptors (ids and
+// purposes), the operation scope, memory counters and the error kind — never key
+// material or plaintext.
+// Nothing is captured until a developer key is installed and crash-capture consent is
+// recorded in the consent registry; the upload consent below is a separate, later
+// decision about sending what was captured. Panics are only caught
+// where the target unwinds; wasm builds with panic=abort lose the snapshot.
+
+const MAX_QUEUED_REPORTS: usize = 20;
+const MAX_REASON_LENGTH: usize = 200;
+pub(crate) const CRASH_REPORT_AAD: &[u8] = b"aura-crash-report-v1";
+
+static CRASH_CAPTURE: Mutex<CrashCaptureState> = Mutex::new(CrashCaptureState {
+    developer_key: None,
+    upload_consent: false,
+    queue: VecDeque::new(),
+});
+
+struct CrashCaptureState {
+    developer_key: Option<[u8; 32]>,
+    upload_consent: bool,
+    queue: VecDeque<String>,
+}
+
+/// Sanitized state at the time of a failure
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct CrashSnapshot {
+    timestamp: u64,
+    crate_version: &'static str,
+    operation: String,
+    module: String,
+    kind: &'static str, // "panic" or "internal_error"
+    reason: String,
+    key_descriptors: Vec<String>,
+    scope: ScopeContext,
+    secrets_allocated: usize,
+    secrets_zeroized: usize,
+    total_allocated: usize,
+    operations_count: usize,
+}
+
+impl CrashSnapshot {
+    fn new(operation: &str, module: &str, kind: &'static str, reason: &str, key_descriptors: &[String]) -> Self {
+        let stats = get_memory_stats();
+        CrashSnapshot {
+            timestamp: platform::now_ms(),
+            crate_version: env!("CARGO_PKG_VERSION"),
+            operation: operation.to_string(),
+            module: module.to_string(),
+            kind,
+            reason: reason.chars().take(MAX_REASON_LENGTH).collect(),
+            key_descriptors: key_descriptors.to_vec(),
+            scope: ScopeContext::current(),
+            secrets_allocated: stats.secrets_allocated,
+            secrets_zeroized: stats.secrets_zeroized,
+            total_allocated: stats.total_allocated,
+            operations_count: stats.operations_count,
+        }
+    }
+
+    fn seal(&self, developer_key: &[u8; 32]) -> Result<String, String> {
+        let payload = serde_json::to_vec(self).map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+        let sealed = MultiRecipientEnvelope::seal_to(&payload, CRASH_REPORT_AAD, &[*developer_key])?;
+        serde_json::to_string(&sealed).map_err(|e| format!("Failed to serialize sealed snapshot: {}", e))
+    }
+}
+
+/// Install the 32-byte X25519 developer key snapshots are sealed to
+#[wasm_bindgen]
+pub fn set_crash_report_key(developer_public_key: &[u8]) -> Result<(), JsValue> {
+    install_crash_report_key(developer_public_key).map_err(|e| JsValue::from_str(&e))
+}
+
+pub(crate) fn install_crash_report_key(developer_public_key: &[u8]) -> Result<(), String> {
+    let key: [u8; 32] = developer_public_key
+        .try_into()
+        .map_err(|_| "Developer public key must be 32 bytes".to_string())?;
+    let mut state = CRASH_CAPTURE.lock().map_err(|_| "Crash capture state unavailable".to_string())?;
+    state.developer_key = Some(key);
+    Ok(())
+}
+
+/// Record the user's upload consent; withdrawing it discards queued reports
+#[wasm_bindgen]
+pub fn set_crash_upload_consent(granted: bool) {
+    if let Ok(mut state) = CRASH_CAPTURE.lock() {
+        state.upload_consent = granted;
+        if !granted {
+            state.queue.clear();
+        }
+    }
+}
+
+/// Drop every queued report, e.g. when crash-capture consent is withdrawn
+pub(crate) fn discard_crash_reports() {
+    if let Ok(mut state) = CRASH_CAPTURE.lock() {
+        state.queue.clear();
+    }
+}
+
+#[wasm_bindgen]
+pub fn pending_crash_report_count() -> usize {
+    CRASH_CAPTURE.lock().map(|state| state.queue.len()).unwrap_or(0)
+}
+
+/// Drain sealed reports for upload as a JSON array; empty without both consents
+#[wasm_bindgen]
+pub fn take_crash_reports() -> String {
+    if !consent_granted(ConsentFeature::CrashCapture) {
+        return "[]".to_string();
+    }
+    let reports: Vec<String> = CRASH_CAPTURE
+        .lock()
+        .map(|mut state| {
+            if state.upload_consent { state.queue.drain(..).collect() } else { Vec::new() }
+        })
+        .unwrap_or_default();
+    format!("[{}]", reports.join(","))
+}
+
+/// Seal and queue a snapshot for an internal error; returns whether one was queued
+pub(crate) fn capture_internal_error(operation: &str, module: &str, reason: &str, key_descriptors: &[String]) -> bool {
+    capture(CrashSnapshot::new(operation, module, "internal_error", reason, key_descriptors))
+}
+
+/// Run `operation`, turning a panic into an error and capturing a snapshot for it
+pub(crate) fn guard<T, F>(operation: &str, module: &str, key_descriptors: &[String], f: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + UnwindSafe,
+{
+    catch_unwind(f).unwrap_or_else(|panic| {
+        let reason = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        capture(CrashSnapshot::new(operation, module, "panic", &reason, key_descriptors));
+        Err(format!("Internal error in {}", operation))
+    })
+}
+
+fn capture(snapshot: CrashSnapshot) -> bool {
+    if !consent_granted(ConsentFeature::CrashCapture) {
+        return false;
+    }
+    let Ok(mut state) = CRASH_CAPTURE.lock() else {
+        return false;
+    };
+    let Some(developer_key) = state.developer_key else {
+        return false;
+    };
+    let Ok(sealed) = snapshot.seal(&developer_key) else {
+        return false;
+    };
+    state.queue.push_back(sealed);
+    if state.queue.len() > MAX_QUEUED_REPORTS {
+        state.queue.pop_front();
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_recipient::RecipientKeyPair;
+
+    #[test]
+    fn test_snapshot_is_sealed_to_developer_key() {
+        let developer = RecipientKeyPair::generate();
+        let descriptors = vec!["cycle_data".to_string(), "cycle_data-1.2.0".to_string()];
+        let snapshot = CrashSnapshot::new("decrypt", "lib", "panic", &"x".repeat(500), &descriptors);
+        assert_eq!(snapshot.reason.len(), MAX_REASON_LENGTH);
+
+        let key: [u8; 32] = developer.public_key().try_into().unwrap();
+        let sealed: MultiRecipientEnvelope = serde_json::from_str(&snapshot.seal(&key).unwrap()).unwrap();
+        let opened = sealed.open_with(&developer.secret_key(), CRASH_REPORT_AAD).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&opened).unwrap();
+
+        assert_eq!(json["operation"], "decrypt");
+        assert_eq!(json["key_descriptors"][1], "cycle_data-1.2.0");
+        assert!(sealed.open_with(&RecipientKeyPair::generate().secret_key(), CRASH_REPORT_AAD).is_err());
+    }
+
+    #[test]
+    fn test_guard_converts_panics_into_errors() {
+        let ok: Result<u32, String> = guard("derive", "derivation", &[], || Ok(7));
+        assert_eq!(ok, Ok(7));
+
+        let err: Result<u32, String> = guard("derive", "derivation", &[], || panic!("boom"));
+        assert_eq!(err.unwrap_err(), "Internal error in derive");
+    }
+}
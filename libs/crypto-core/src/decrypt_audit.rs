@@ -0,0 +1,224 @@
+use wasm_bindgen::prelude::*;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use crate::derivation::DataCategory;
+use crate::platform;
+use crate::scope::ScopeContext;
+
+// Decrypt audit sampling
+// High-volume categories (daily cycle entries) would flood the audit log if every
+// decrypt were recorded, while shared healthcare data should be recorded in full.
+// Each category gets a sample rate and an outcome filter; the decrypt path calls
+// `record_decrypt` and the configured rule decides whether the event is kept.
+// Records hold the category, outcome and time only.
+
+const MAX_AUDIT_RECORDS: usize = 1000;
+
+static DECRYPT_AUDIT: Mutex<DecryptAuditState> = Mutex::new(DecryptAuditState {
+    config: DecryptAuditConfig {
+        default_rule: AuditSamplingRule::ALL,
+        rules: BTreeMap::new(),
+    },
+    records: VecDeque::new(),
+});
+
+struct DecryptAuditState {
+    config: DecryptAuditConfig,
+    records: VecDeque<String>,
+}
+
+/// Outcome of a decrypt attempt as seen by the audit hook
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecryptOutcome {
+    Success = 0,
+    Denied = 1, // Rejected by a policy check (scope, freshness) before decryption
+    Failed = 2,
+}
+
+impl DecryptOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DecryptOutcome::Success => "success",
+            DecryptOutcome::Denied => "denied",
+            DecryptOutcome::Failed => "failed",
+        }
+    }
+}
+
+/// Sample rate and outcome filter for one category
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AuditSamplingRule {
+    sample_rate: f64,
+    record_successes: bool,
+    record_denials: bool,
+    record_failures: bool,
+}
+
+#[wasm_bindgen]
+impl AuditSamplingRule {
+    /// `sample_rate` in [0, 1]; each outcome type can be filtered out entirely
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        sample_rate: f64,
+        record_successes: bool,
+        record_denials: bool,
+        record_failures: bool,
+    ) -> Result<AuditSamplingRule, JsValue> {
+        if !(0.0..=1.0).contains(&sample_rate) {
+            return Err(JsValue::from_str("Sample rate must be between 0 and 1"));
+        }
+        Ok(AuditSamplingRule { sample_rate, record_successes, record_denials, record_failures })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+}
+
+impl AuditSamplingRule {
+    /// Record every outcome
+    pub const ALL: AuditSamplingRule = AuditSamplingRule {
+        sample_rate: 1.0,
+        record_successes: true,
+        record_denials: true,
+        record_failures: true,
+    };
+
+    /// Sample successes at `sample_rate`; always record denials and failures
+    pub(crate) fn sampled(sample_rate: f64) -> AuditSamplingRule {
+        AuditSamplingRule { sample_rate: sample_rate.clamp(0.0, 1.0), ..Self::ALL }
+    }
+
+    fn allows(&self, outcome: DecryptOutcome) -> bool {
+        match outcome {
+            DecryptOutcome::Success => self.record_successes,
+            DecryptOutcome::Denied => self.record_denials,
+            DecryptOutcome::Failed => self.record_failures,
+        }
+    }
+
+    /// Denials and failures are security signals and are never sampled away
+    fn should_record(&self, outcome: DecryptOutcome, draw: u32) -> bool {
+        if !self.allows(outcome) {
+            return false;
+        }
+        outcome != DecryptOutcome::Success
+            || self.sample_rate >= 1.0
+            || (draw as f64) < self.sample_rate * (u32::MAX as f64 + 1.0)
+    }
+}
+
+/// Per-category sampling rules with a default for unlisted categories
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecryptAuditConfig {
+    default_rule: AuditSamplingRule,
+    rules: BTreeMap<String, AuditSamplingRule>,
+}
+
+#[wasm_bindgen]
+impl DecryptAuditConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new(default_rule: AuditSamplingRule) -> DecryptAuditConfig {
+        DecryptAuditConfig { default_rule, rules: BTreeMap::new() }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_rule(&mut self, category: DataCategory, rule: AuditSamplingRule) {
+        self.rules.insert(category.to_string(), rule);
+    }
+
+    #[wasm_bindgen]
+    pub fn rule_for(&self, category: DataCategory) -> AuditSamplingRule {
+        self.rule(&category)
+    }
+}
+
+impl DecryptAuditConfig {
+    fn rule(&self, category: &DataCategory) -> AuditSamplingRule {
+        self.rules.get(&category.to_string()).copied().unwrap_or(self.default_rule)
+    }
+}
+
+/// Install the process-wide sampling configuration
+#[wasm_bindgen]
+pub fn configure_decrypt_audit(config: &DecryptAuditConfig) {
+    if let Ok(mut state) = DECRYPT_AUDIT.lock() {
+        state.config = config.clone();
+    }
+}
+
+/// Drain recorded decrypt audit events as a JSON array
+#[wasm_bindgen]
+pub fn take_decrypt_audit_records() -> String {
+    format!("[{}]", drain_decrypt_audit_records().join(","))
+}
+
+pub(crate) fn drain_decrypt_audit_records() -> Vec<String> {
+    DECRYPT_AUDIT
+        .lock()
+        .map(|mut state| state.records.drain(..).collect())
+        .unwrap_or_default()
+}
+
+/// Put drained records back ahead of newer ones, e.g. after a failed flush
+pub(crate) fn requeue_decrypt_audit_records(records: Vec<String>) {
+    if let Ok(mut state) = DECRYPT_AUDIT.lock() {
+        for record in records.into_iter().rev() {
+            if state.records.len() == MAX_AUDIT_RECORDS {
+                break;
+            }
+            state.records.push_front(record);
+        }
+    }
+}
+
+/// Decrypt-time audit hook; returns whether the event was recorded
+pub(crate) fn record_decrypt(category: &DataCategory, outcome: DecryptOutcome) -> bool {
+    let Ok(mut state) = DECRYPT_AUDIT.lock() else {
+        return false;
+    };
+    if !state.config.rule(category).should_record(outcome, platform::random_u32()) {
+        return false;
+    }
+
+    let record = serde_json::json!({
+        "timestamp": platform::now_ms(),
+        "category": category.to_string(),
+        "outcome": outcome.as_str(),
+        "scope": ScopeContext::current().to_json_value(),
+    })
+    .to_string();
+    state.records.push_back(record);
+    if state.records.len() > MAX_AUDIT_RECORDS {
+        state.records.pop_front();
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling_rule_filters_and_samples_successes_only() {
+        let sampled = AuditSamplingRule::sampled(0.25);
+        assert!(sampled.should_record(DecryptOutcome::Success, 0));
+        assert!(!sampled.should_record(DecryptOutcome::Success, u32::MAX / 2));
+        assert!(sampled.should_record(DecryptOutcome::Denied, u32::MAX));
+        assert!(sampled.should_record(DecryptOutcome::Failed, u32::MAX));
+
+        let failures_only = AuditSamplingRule { record_successes: false, record_denials: false, ..AuditSamplingRule::ALL };
+        assert!(!failures_only.should_record(DecryptOutcome::Success, 0));
+        assert!(!failures_only.should_record(DecryptOutcome::Denied, 0));
+        assert!(AuditSamplingRule::ALL.should_record(DecryptOutcome::Success, u32::MAX));
+
+        let mut config = DecryptAuditConfig::new(AuditSamplingRule::ALL);
+        config.set_rule(DataCategory::CycleData, AuditSamplingRule::sampled(0.0));
+        assert!(!config.rule(&DataCategory::CycleData).should_record(DecryptOutcome::Success, 0));
+        assert_eq!(config.rule(&DataCategory::HealthcareSharing), AuditSamplingRule::ALL);
+    }
+}
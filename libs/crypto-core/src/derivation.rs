@@ -3,9 +3,13 @@ use crate::memory::SecureBuffer;
 use sha2::{Sha256, Digest};
 use hmac::{Hmac, Mac};
 use std::collections::HashMap;
+use zeroize::Zeroizing;
 
 type HmacSha256 = Hmac<Sha256>;
 
+// HMAC key for master key derivation from the seed
+pub(crate) const MASTER_KEY_DOMAIN: &[u8] = b"ed25519 seed";
+
 // Data categories for key isolation
 #[wasm_bindgen]
 #[derive(Clone, Debug, PartialEq)]
@@ -35,6 +39,21 @@ impl DataCategory {
             DataCategory::DeviceSync => "device_sync".to_string(),
         }
     }
+
+    /// BIP43-style purpose index used as the first hardened derivation level
+    pub fn purpose_index(&self) -> u32 {
+        match self {
+            DataCategory::CycleData => 44,           // Health data
+            DataCategory::Preferences => 45,         // Preferences
+            DataCategory::HealthcareSharing => 46,   // Sharing
+            DataCategory::DeviceSync => 47,          // Device sync
+        }
+    }
+
+    /// Hardened derivation path shared by all keys of this category (device level excluded)
+    pub fn derivation_path(&self) -> String {
+        format!("m/{}'/0'/0'", self.purpose_index())
+    }
 }
 
 // BIP32-style derivation path structure
@@ -130,7 +149,7 @@ impl ExtendedKey {
             return Err(JsValue::from_str("Seed length must be between 16 and 64 bytes"));
         }
 
-        let mut mac = HmacSha256::new_from_slice(b"ed25519 seed")
+        let mut mac = HmacSha256::new_from_slice(MASTER_KEY_DOMAIN)
             .map_err(|e| JsValue::from_str(&format!("HMAC creation failed: {}", e)))?;
         mac.update(seed);
         let result = mac.finalize().into_bytes();
@@ -243,12 +262,7 @@ impl HierarchicalKeyDerivation {
 
         // Purpose-specific derivation paths following BIP43/BIP44 pattern
         // m / purpose' / coin_type' / account' / change / address_index
-        let purpose = match category {
-            DataCategory::CycleData => 44u32,           // Health data
-            DataCategory::Preferences => 45u32,         // Preferences
-            DataCategory::HealthcareSharing => 46u32,   // Sharing
-            DataCategory::DeviceSync => 47u32,          // Device sync
-        };
+        let purpose = category.purpose_index();
 
         // Create derivation path: m / purpose' / 0' / 0' / device_hash
         let device_hash = {
@@ -351,6 +365,14 @@ impl HierarchicalKeyDerivation {
     }
 }
 
+impl HierarchicalKeyDerivation {
+    // Root key bytes of the live hierarchy; None until a seed is loaded
+    pub(crate) fn master_key_bytes(&self) -> Option<Zeroizing<Vec<u8>>> {
+        let master_key = self.master_key.as_ref()?;
+        master_key.key.as_slice().ok().map(|key| Zeroizing::new(key.to_vec()))
+    }
+}
+
 impl Clone for ExtendedKey {
     fn clone(&self) -> Self {
         // Get key bytes and recreate SecureBuffer
@@ -0,0 +1,373 @@
+use wasm_bindgen::prelude::*;
+use serde_json::{json, Value};
+use crate::aad::{USER_SCOPE_DOMAIN, USER_SCOPE_TAG_LENGTH};
+use crate::backup_hardening::{
+    KEY_COMMITMENT_DOMAIN, PASSWORD_PATH_INFO, PASSWORD_PATH_SALT, PHRASE_PATH_INFO, SHARE_COMMITMENT_DOMAIN,
+};
+use crate::ceremony::ATTESTATION_CONTEXT as CEREMONY_ATTESTATION_CONTEXT;
+use crate::consent::{REGISTRY_AAD, SEALING_KEY_INFO, SIGNING_KEY_INFO};
+use crate::crash_capture::CRASH_REPORT_AAD;
+use crate::derivation::{DataCategory, MASTER_KEY_DOMAIN};
+use crate::envelope::to_hex;
+use crate::export::CHUNK_AAD_DOMAIN;
+use crate::hybrid_wrap::HYBRID_WRAP_INFO;
+use crate::inbox::MESSAGE_AAD_DOMAIN;
+use crate::kdf_rehash::WRAP_AAD_DOMAIN;
+use crate::key_rotation::audit::{BACKEND_EVENT_CONTEXT, TRAIL_HEAD_CONTEXT};
+use crate::key_rotation::audit_epochs::{COMMITMENT_DOMAIN, EPOCH_KEY_INFO, KEYRING_AAD_DOMAIN, SEGMENT_AAD_DOMAIN};
+use crate::key_rotation::suite_migration::ATTESTATION_CONTEXT as CAMPAIGN_ATTESTATION_CONTEXT;
+use crate::keys::KEY_FINGERPRINT_DOMAIN;
+use crate::multi_device::PAIRING_POW_DOMAIN;
+use crate::multi_recipient::WRAP_INFO;
+use crate::oprf::{FINALIZE_DOMAIN as OPRF_FINALIZE_DOMAIN, HASH_TO_GROUP_DOMAIN as OPRF_HASH_TO_GROUP_DOMAIN};
+use crate::pagination::{OFFSET_DOMAIN, TAG_DOMAIN};
+use crate::pseudonymization::{DATE_SHIFT_KEY_INFO, PSEUDONYM_KEY_INFO};
+use crate::recovery_rehearsal::REHEARSAL_KEY_INFO;
+use crate::verifier::{VERIFIER_BRANCH, VERIFIER_HKDF_SALT};
+
+// Derivation transcript
+// Machine-readable description of every key derivation in this crate, for external
+// protocol review without source access. It is assembled from the same constants the
+// derivation code uses, so it cannot drift from the implementation. It describes
+// functions, context strings and paths only; no key, seed or identifier appears in it.
+// `domains` lists the separators of hashes, MACs, AEAD associated data and signatures
+// that do not derive a key, so every context string in the crate is accounted for.
+
+const TRANSCRIPT_VERSION: u32 = 1;
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+const ALL_CATEGORIES: [DataCategory; 4] = [
+    DataCategory::CycleData,
+    DataCategory::Preferences,
+    DataCategory::HealthcareSharing,
+    DataCategory::DeviceSync,
+];
+
+/// JSON transcript of derivation functions, context strings, paths and parameters
+#[wasm_bindgen]
+pub fn export_derivation_transcript() -> String {
+    serde_json::to_string_pretty(&derivation_transcript()).unwrap_or_default()
+}
+
+pub(crate) fn derivation_transcript() -> Value {
+    json!({
+        "transcript_version": TRANSCRIPT_VERSION,
+        "crate_version": env!("CARGO_PKG_VERSION"),
+        "conventions": {
+            "integers": "big-endian",
+            "strings": "utf-8",
+            "concatenation": "||",
+            "hardened_offset": HARDENED_OFFSET,
+        },
+        "functions": [
+            {
+                "name": "master_key",
+                "primitive": "HMAC-SHA256",
+                "key": context(MASTER_KEY_DOMAIN),
+                "message": ["seed"],
+                "parameters": { "seed_bytes": { "min": 16, "max": 64 }, "prf_output_bytes": 32 },
+                "output": { "key": [0, 32], "chain_code": [32, 64] },
+            },
+            {
+                "name": "child_key",
+                "primitive": "HMAC-SHA256",
+                "key": "parent.chain_code",
+                "message": ["0x00", "parent.key", "u32(index)"],
+                "parameters": { "prf_output_bytes": 32 },
+                "output": { "key": [0, 32], "chain_code": [32, 64] },
+                "notes": "Indices below the hardened offset use the same private-key construction",
+            },
+            {
+                "name": "device_index",
+                "primitive": "SHA-256",
+                "message": ["device_id"],
+                "output": "u32(digest[0..4]) & 0x7fffffff",
+            },
+            {
+                "name": "verifier_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "key at verifier_branch_path",
+                "salt": context(VERIFIER_HKDF_SALT),
+                "info": "category || '|' || purpose || '|' || key_version",
+                "parameters": { "output_bytes": 32 },
+            },
+            {
+                "name": "recipient_wrapping_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "X25519(ephemeral_secret, recipient_public)",
+                "salt": "ephemeral_public || recipient_public",
+                "info": context(WRAP_INFO),
+                "parameters": { "output_bytes": 32 },
+            },
+            {
+                "name": "audit_epoch_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "previous epoch key",
+                "salt": null,
+                "info": format!("{} || '|' || decimal(epoch)", EPOCH_KEY_INFO),
+                "parameters": { "output_bytes": 32 },
+                "notes": "Previous epoch key is zeroized after derivation (forward security)",
+            },
+            {
+                "name": "user_scope_tag",
+                "primitive": "HMAC-SHA256",
+                "key": "user scope key (at least 32 bytes)",
+                "message": [context(USER_SCOPE_DOMAIN), "user_id"],
+                "parameters": { "output_bytes": USER_SCOPE_TAG_LENGTH },
+                "output": "truncated digest",
+            },
+            {
+                "name": "key_fingerprint",
+                "primitive": "SHA-256",
+                "message": [context(KEY_FINGERPRINT_DOMAIN), "key"],
+                "output": "hex(digest)",
+            },
+            {
+                "name": "hybrid_wrapping_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "X25519(ephemeral_secret, recipient_public) || kem_shared_secret",
+                "salt": "recipient_public",
+                "info": [context(HYBRID_WRAP_INFO), "wrap header"],
+                "parameters": { "output_bytes": 32 },
+                "notes": "kem_shared_secret is empty when no KEM is configured",
+            },
+            {
+                "name": "backup_password_oprf_output",
+                "primitive": "2HashDH OPRF over ristretto255",
+                "hash_to_group": ["SHA-512", context(OPRF_HASH_TO_GROUP_DOMAIN), "password_kdf_output"],
+                "finalize": ["SHA-256", context(OPRF_FINALIZE_DOMAIN), "len(password_kdf_output)", "password_kdf_output", "unblinded element"],
+                "parameters": { "output_bytes": 32 },
+                "notes": "evaluated by the server's OPRF key on a blinded element",
+            },
+            {
+                "name": "backup_password_path_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "backup_password_oprf_output",
+                "salt": context(PASSWORD_PATH_SALT),
+                "info": context(PASSWORD_PATH_INFO),
+                "parameters": { "output_bytes": 32 },
+                "notes": "version 1 backups use password_kdf_output || server_share as ikm",
+            },
+            {
+                "name": "backup_phrase_path_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "recovery phrase seed",
+                "salt": null,
+                "info": context(PHRASE_PATH_INFO),
+                "parameters": { "output_bytes": 32 },
+            },
+            {
+                "name": "recovery_rehearsal_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "recovery phrase seed",
+                "salt": null,
+                "info": context(REHEARSAL_KEY_INFO),
+                "parameters": { "output_bytes": 32 },
+            },
+            {
+                "name": "consent_signing_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "consent registry key (at least 32 bytes)",
+                "salt": null,
+                "info": context(SIGNING_KEY_INFO),
+                "parameters": { "output_bytes": 32 },
+            },
+            {
+                "name": "consent_sealing_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "consent registry key (at least 32 bytes)",
+                "salt": null,
+                "info": context(SEALING_KEY_INFO),
+                "parameters": { "output_bytes": 32 },
+            },
+            {
+                "name": "research_pseudonym_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "research key (at least 32 bytes)",
+                "salt": "study_id",
+                "info": context(PSEUDONYM_KEY_INFO),
+                "parameters": { "output_bytes": 32 },
+            },
+            {
+                "name": "research_date_shift_key",
+                "primitive": "HKDF-SHA256",
+                "ikm": "research key (at least 32 bytes)",
+                "salt": "study_id",
+                "info": context(DATE_SHIFT_KEY_INFO),
+                "parameters": { "output_bytes": 32 },
+            },
+            {
+                "name": "order_token_offset",
+                "primitive": "HMAC-SHA256",
+                "key": "order token key (at least 32 bytes)",
+                "message": [context(OFFSET_DOMAIN), "u32(epoch)"],
+                "output": "u32(digest[0..4])",
+            },
+            {
+                "name": "order_token_tag",
+                "primitive": "HMAC-SHA256",
+                "key": "order token key (at least 32 bytes)",
+                "message": [context(TAG_DOMAIN), "previous_tag", "u32(epoch)", "u64(position)"],
+                "output": "hex(digest[0..8])",
+            },
+            {
+                "name": "export_chunk_key",
+                "primitive": "none",
+                "key": "export key (32 bytes, supplied by the host)",
+                "notes": "Every chunk and the manifest are sealed under the export key itself; chunks are told apart by their associated data (see domains)",
+            },
+        ],
+        "domains": [
+            {
+                "name": "export_chunk_aad",
+                "primitive": "AES-256-GCM",
+                "aad": format!("{}-v || decimal(version) || '|' || export_id || '|' || decimal(sequence) || '|' || kind || '|' || decimal(record_start) || '|' || decimal(record_count)", CHUNK_AAD_DOMAIN),
+                "context": context(CHUNK_AAD_DOMAIN.as_bytes()),
+            },
+            {
+                "name": "inbox_message_aad",
+                "primitive": "AES-256-GCM",
+                "aad": format!("{}-v || decimal(version) || '|' || message_id || '|' || sender_fingerprint || '|' || recipient_fingerprint || '|' || decimal(sequence) || '|' || decimal(created_at) || '|' || decimal(expires_at)", MESSAGE_AAD_DOMAIN),
+                "context": context(MESSAGE_AAD_DOMAIN.as_bytes()),
+            },
+            {
+                "name": "audit_segment_aad",
+                "primitive": "AES-256-GCM",
+                "aad": format!("{} || '|' || decimal(epoch_id) || '|' || decimal(segment_index)", SEGMENT_AAD_DOMAIN),
+                "context": context(SEGMENT_AAD_DOMAIN.as_bytes()),
+            },
+            {
+                "name": "audit_keyring_aad",
+                "primitive": "AES-256-GCM",
+                "key": "audit keyring wrapping key",
+                "context": context(KEYRING_AAD_DOMAIN),
+            },
+            {
+                "name": "password_wrap_aad",
+                "primitive": "AES-256-GCM",
+                "aad": format!("{} || '|' || kind || '|' || artifact_id", WRAP_AAD_DOMAIN),
+                "context": context(WRAP_AAD_DOMAIN.as_bytes()),
+            },
+            {
+                "name": "consent_registry_aad",
+                "primitive": "AES-256-GCM",
+                "key": "consent_sealing_key",
+                "context": context(REGISTRY_AAD),
+            },
+            {
+                "name": "crash_report_aad",
+                "primitive": "multi-recipient envelope",
+                "context": context(CRASH_REPORT_AAD),
+            },
+            {
+                "name": "audit_epoch_commitment",
+                "primitive": "SHA-256",
+                "message": [context(COMMITMENT_DOMAIN), "epoch key"],
+            },
+            {
+                "name": "server_share_commitment",
+                "primitive": "SHA-256",
+                "message": [context(SHARE_COMMITMENT_DOMAIN), "server_share"],
+            },
+            {
+                "name": "backup_key_commitment",
+                "primitive": "HMAC-SHA256",
+                "key": "backup master key",
+                "message": [context(KEY_COMMITMENT_DOMAIN), "salt"],
+            },
+            {
+                "name": "pairing_proof_of_work",
+                "primitive": "SHA-256",
+                "message": [context(PAIRING_POW_DOMAIN), "u32(len(device_id))", "device_id", "public_key", "challenge_nonce", "u64(timestamp)", "u64(nonce)"],
+            },
+            {
+                "name": "campaign_attestation_signature",
+                "primitive": "Ed25519",
+                "context": context(CAMPAIGN_ATTESTATION_CONTEXT),
+            },
+            {
+                "name": "ceremony_attestation_signature",
+                "primitive": "Ed25519",
+                "context": context(CEREMONY_ATTESTATION_CONTEXT),
+            },
+            {
+                "name": "audit_trail_head_signature",
+                "primitive": "Ed25519",
+                "context": context(TRAIL_HEAD_CONTEXT),
+            },
+            {
+                "name": "backend_audit_event_signature",
+                "primitive": "Ed25519",
+                "message": [context(BACKEND_EVENT_CONTEXT), "event JSON"],
+            },
+        ],
+        "paths": ALL_CATEGORIES.iter().map(|category| json!({
+            "category": category.to_string(),
+            "purpose_index": category.purpose_index(),
+            "data_key_path": format!("{}/device_index", category.derivation_path()),
+            "verifier_branch_path": format!("m/{}'/{}'/0'", category.purpose_index(), VERIFIER_BRANCH),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn context(bytes: &[u8]) -> Value {
+    json!({
+        "ascii": String::from_utf8_lossy(bytes),
+        "hex": to_hex(bytes),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_lists_paths_and_context_strings() {
+        let transcript: Value = serde_json::from_str(&export_derivation_transcript()).unwrap();
+
+        let paths = transcript["paths"].as_array().unwrap();
+        assert_eq!(paths.len(), ALL_CATEGORIES.len());
+        assert_eq!(paths[0]["data_key_path"], "m/44'/0'/0'/device_index");
+        assert_eq!(paths[0]["verifier_branch_path"], "m/44'/1'/0'");
+
+        let functions = transcript["functions"].as_array().unwrap();
+        let wrap = functions.iter().find(|f| f["name"] == "recipient_wrapping_key").unwrap();
+        assert_eq!(wrap["info"]["ascii"], "aura-multi-recipient-wrap-v1");
+        assert!(functions.iter().all(|f| f["primitive"].is_string()));
+    }
+
+    // Byte-string constants and `aura-` context strings anywhere in the crate
+    fn source_context_strings(dir: &std::path::Path, found: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                source_context_strings(&path, found);
+                continue;
+            }
+            if path.extension().is_none_or(|ext| ext != "rs") || path.ends_with("derivation_transcript.rs") {
+                continue;
+            }
+            for line in std::fs::read_to_string(&path).unwrap().lines() {
+                let start = if let Some(at) = line.find("b\"aura-") {
+                    at + 2
+                } else if line.contains("const ") && (line.contains(": &[u8] = b\"") || line.contains(": &str = \"aura-")) {
+                    line.find('"').unwrap() + 1
+                } else {
+                    continue;
+                };
+                let literal = &line[start..];
+                found.push(literal[..literal.find('"').unwrap()].to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_context_string_in_the_crate_is_in_the_transcript() {
+        let transcript = export_derivation_transcript();
+        let mut contexts = Vec::new();
+        source_context_strings(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut contexts);
+
+        assert!(contexts.len() >= 25);
+        let missing: Vec<&String> = contexts.iter().filter(|context| !transcript.contains(context.as_str())).collect();
+        assert!(missing.is_empty(), "Missing from the derivation transcript: {:?}", missing);
+    }
+}
@@ -1,5 +1,8 @@
 use wasm_bindgen::prelude::*;
 use std::collections::HashMap;
+use crate::envelope::KDFParams;
+use crate::security::{scrypt_memory_bytes, KDF_MAX_MEMORY_BYTES, SCRYPT_DEFAULT_BLOCK_SIZE, SCRYPT_MAX_LOG_N, SCRYPT_MIN_LOG_N};
+use crate::platform;
 
 // Device classification based on hardware capabilities
 #[wasm_bindgen]
@@ -329,7 +332,7 @@ impl DeviceCapabilityDetector {
         }
 
         // Perform benchmark (simplified mock implementation)
-        let _start_time = js_sys::Date::now();
+        let _start_time = platform::now_ms() as f64;
         
         // Mock Argon2 operation (in real implementation, this would be actual Argon2)
         let mock_operation_time = (test_params.memory_kb() as f64 * test_params.iterations() as f64) / 1000.0;
@@ -398,7 +401,46 @@ impl DeviceCapabilityDetector {
         Ok(best_params)
     }
 
+    // Negotiate the KDF for new backups/envelopes: Argon2id when its memory cost
+    // fits the budget and calibration succeeded, otherwise scrypt sized to the budget
+    #[wasm_bindgen]
+    pub fn select_kdf_params(
+        &self,
+        capabilities: &DeviceCapabilities,
+        memory_budget_mb: u32,
+        calibration: Option<BenchmarkResult>,
+    ) -> KDFParams {
+        let argon2 = self.get_optimal_argon2_params(capabilities);
+        // SecureKDF refuses Argon2 memory costs above 64 MB
+        let argon2_memory_kb = argon2.memory_kb().min(64 * 1024);
+        let calibration_ok = calibration.is_none_or(|benchmark| benchmark.success());
+
+        if calibration_ok && argon2_memory_kb <= memory_budget_mb.saturating_mul(1024) {
+            return KDFParams::argon2id(
+                argon2.iterations().clamp(1, 10),
+                argon2_memory_kb,
+                argon2.parallelism().clamp(1, 4),
+            );
+        }
+
+        KDFParams::scrypt(
+            Self::scrypt_log_n_for_budget(memory_budget_mb),
+            SCRYPT_DEFAULT_BLOCK_SIZE,
+            1,
+        )
+    }
+
     // Private helper methods
+    fn scrypt_log_n_for_budget(memory_budget_mb: u32) -> u8 {
+        // Never pick a cost the KDF itself would refuse
+        let budget_bytes = (memory_budget_mb as u64 * 1024 * 1024).min(KDF_MAX_MEMORY_BYTES);
+
+        (SCRYPT_MIN_LOG_N..=SCRYPT_MAX_LOG_N)
+            .rev()
+            .find(|&log_n| scrypt_memory_bytes(log_n, SCRYPT_DEFAULT_BLOCK_SIZE, 1) <= budget_bytes)
+            .unwrap_or(SCRYPT_MIN_LOG_N)
+    }
+
     fn classify_device(
         &self,
         available_memory_mb: u64,
@@ -509,6 +551,28 @@ mod tests {
         assert_eq!(web_limited.argon2_parallelism(), 1);
     }
 
+    #[test]
+    fn test_kdf_negotiation_falls_back_to_scrypt() {
+        let detector = DeviceCapabilityDetector::new();
+        let capabilities = detector.detect_capabilities(2000, 2, "web".to_string(), false);
+
+        let roomy = detector.select_kdf_params(&capabilities, 128, None);
+        assert_eq!(roomy.algorithm(), "argon2id");
+        assert_eq!(roomy.memory_cost(), Some(64 * 1024));
+
+        // 32 MB budget cannot fit 64 MB Argon2; largest scrypt N that fits is 2^15
+        let constrained = detector.select_kdf_params(&capabilities, 32, None);
+        assert_eq!(constrained.algorithm(), "scrypt");
+        assert_eq!(constrained.iterations(), 15);
+        assert_eq!(constrained.block_size(), Some(8));
+
+        let failed_calibration = BenchmarkResult::new(900.0, 64.0, 2, false, None);
+        let fallback = detector.select_kdf_params(&capabilities, 128, Some(failed_calibration));
+        // A 128 MB budget is still held to the 64 MiB KDF cap
+        assert_eq!(fallback.algorithm(), "scrypt");
+        assert_eq!(fallback.iterations(), 16);
+    }
+
     #[test]
     fn test_performance_score_calculation() {
         let detector = DeviceCapabilityDetector::new();
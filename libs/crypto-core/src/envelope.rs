@@ -1,5 +1,8 @@
 use wasm_bindgen::prelude::*;
 use zeroize::Zeroize;
+use crate::security::{KdfAlgorithm, SCRYPT_DEFAULT_BLOCK_SIZE};
+use crate::aad::{SchemaMismatch, ScopeViolation};
+use crate::security::constant_time_compare;
 
 // Crypto envelope version for compatibility
 #[wasm_bindgen]
@@ -18,6 +21,7 @@ pub enum CryptoAlgorithm {
 }
 
 // KDF parameters for key derivation
+// For scrypt, `iterations` holds the cost exponent (log2 N) and `block_size` holds r
 #[wasm_bindgen]
 #[derive(Debug, Clone)]
 pub struct KDFParams {
@@ -25,6 +29,7 @@ pub struct KDFParams {
     iterations: u32,
     memory_cost: Option<u32>,
     parallelism: Option<u32>,
+    block_size: Option<u32>,
 }
 
 // Crypto envelope for secure data handling with complete metadata
@@ -40,6 +45,9 @@ pub struct CryptoEnvelope {
     encrypted_data: Vec<u8>,
     tag: Vec<u8>,
     aad_hash: Vec<u8>,
+    schema_version: Option<u32>,
+    user_scope_tag: Option<Vec<u8>>,
+    order_token: Option<String>,
 }
 
 impl Default for CryptoEnvelope {
@@ -58,9 +66,30 @@ impl KDFParams {
             iterations,
             memory_cost: None,
             parallelism: None,
+            block_size: None,
         }
     }
 
+    // Argon2id parameters (memory cost in KB)
+    #[wasm_bindgen]
+    #[must_use]
+    pub fn argon2id(iterations: u32, memory_cost_kb: u32, parallelism: u32) -> KDFParams {
+        let mut params = KDFParams::new(KdfAlgorithm::Argon2id.as_str().to_string(), iterations);
+        params.memory_cost = Some(memory_cost_kb);
+        params.parallelism = Some(parallelism);
+        params
+    }
+
+    // scrypt parameters for platforms that cannot afford Argon2 memory
+    #[wasm_bindgen]
+    #[must_use]
+    pub fn scrypt(log_n: u8, block_size: u32, parallelism: u32) -> KDFParams {
+        let mut params = KDFParams::new(KdfAlgorithm::Scrypt.as_str().to_string(), log_n as u32);
+        params.block_size = Some(block_size);
+        params.parallelism = Some(parallelism);
+        params
+    }
+
     #[wasm_bindgen(getter)]
     #[must_use]
     pub fn algorithm(&self) -> String {
@@ -73,6 +102,24 @@ impl KDFParams {
         self.iterations
     }
 
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn memory_cost(&self) -> Option<u32> {
+        self.memory_cost
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn parallelism(&self) -> Option<u32> {
+        self.parallelism
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn block_size(&self) -> Option<u32> {
+        self.block_size
+    }
+
     #[wasm_bindgen]
     pub fn set_memory_cost(&mut self, memory_cost: u32) {
         self.memory_cost = Some(memory_cost);
@@ -82,6 +129,66 @@ impl KDFParams {
     pub fn set_parallelism(&mut self, parallelism: u32) {
         self.parallelism = Some(parallelism);
     }
+
+    #[wasm_bindgen]
+    pub fn set_block_size(&mut self, block_size: u32) {
+        self.block_size = Some(block_size);
+    }
+}
+
+impl KDFParams {
+    /// Parsed KDF identifier, `None` for algorithms this build cannot derive with
+    pub fn kdf_algorithm(&self) -> Option<KdfAlgorithm> {
+        KdfAlgorithm::from_name(&self.algorithm)
+    }
+
+    /// True if deriving under these parameters costs at least the memory and work of `other`;
+    /// Argon2id is never considered replaceable by the scrypt fallback
+    pub(crate) fn at_least_as_strong_as(&self, other: &KDFParams) -> bool {
+        if self.kdf_algorithm() == Some(KdfAlgorithm::Scrypt) && other.kdf_algorithm() == Some(KdfAlgorithm::Argon2id) {
+            return false;
+        }
+        match (self.cost(), other.cost()) {
+            (Some((memory, work)), Some((other_memory, other_work))) => memory >= other_memory && work >= other_work,
+            _ => false,
+        }
+    }
+
+    // Memory in bytes and memory times passes; None when the parameters cannot derive
+    fn cost(&self) -> Option<(u64, u64)> {
+        match self.kdf_algorithm()? {
+            KdfAlgorithm::Argon2id => {
+                let memory = u64::from(self.memory_cost?) * 1024;
+                Some((memory, memory.saturating_mul(u64::from(self.iterations))))
+            }
+            KdfAlgorithm::Scrypt => {
+                let block_size = u64::from(self.block_size.unwrap_or(SCRYPT_DEFAULT_BLOCK_SIZE));
+                let memory = 128u64.saturating_mul(block_size).saturating_mul(1u64.checked_shl(self.iterations)?);
+                Some((memory, memory.saturating_mul(u64::from(self.parallelism.unwrap_or(1)))))
+            }
+        }
+    }
+
+    pub(crate) fn to_json_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "algorithm": self.algorithm,
+            "iterations": self.iterations,
+            "memory_cost": self.memory_cost,
+            "parallelism": self.parallelism,
+            "block_size": self.block_size,
+        })
+    }
+
+    pub(crate) fn from_json_value(value: &serde_json::Value) -> Option<KDFParams> {
+        let algorithm = value["algorithm"].as_str()?;
+        let iterations = value["iterations"].as_u64()? as u32;
+
+        let mut params = KDFParams::new(algorithm.to_string(), iterations);
+        params.memory_cost = value["memory_cost"].as_u64().map(|v| v as u32);
+        params.parallelism = value["parallelism"].as_u64().map(|v| v as u32);
+        params.block_size = value["block_size"].as_u64().map(|v| v as u32);
+        Some(params)
+    }
 }
 
 #[wasm_bindgen]
@@ -99,6 +206,9 @@ impl CryptoEnvelope {
             encrypted_data: Vec::new(),
             tag: Vec::new(),
             aad_hash: Vec::new(),
+            schema_version: None,
+            user_scope_tag: None,
+            order_token: None,
         }
     }
 
@@ -115,6 +225,12 @@ impl CryptoEnvelope {
         self.algorithm as u8
     }
 
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn kdf_params(&self) -> Option<KDFParams> {
+        self.kdf_params.clone()
+    }
+
     #[wasm_bindgen(getter)]
     #[must_use]
     pub fn salt(&self) -> Vec<u8> {
@@ -207,6 +323,60 @@ impl CryptoEnvelope {
         self.aad_hash = aad_hash;
     }
 
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn schema_version(&self) -> Option<u32> {
+        self.schema_version
+    }
+
+    // Record the schema version that was bound into the AAD at encryption time
+    #[wasm_bindgen]
+    pub fn set_schema_version(&mut self, schema_version: u32) {
+        self.schema_version = Some(schema_version);
+    }
+
+    // Refuse to decrypt records written under a different data-layer schema
+    #[wasm_bindgen]
+    pub fn check_schema_compat(&self, expected: u32) -> Result<(), SchemaMismatch> {
+        match self.schema_version {
+            Some(found) if found == expected => Ok(()),
+            found => Err(SchemaMismatch::new(expected, found)),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn user_scope_tag(&self) -> Option<Vec<u8>> {
+        self.user_scope_tag.clone()
+    }
+
+    // Record the user scope tag that was bound into the AAD at encryption time
+    #[wasm_bindgen]
+    pub fn set_user_scope_tag(&mut self, user_scope_tag: Vec<u8>) {
+        self.user_scope_tag = Some(user_scope_tag);
+    }
+
+    // Refuse envelopes that do not carry the expected user's scope tag
+    #[wasm_bindgen]
+    pub fn check_user_scope(&self, expected_tag: &[u8]) -> Result<(), ScopeViolation> {
+        match self.user_scope_tag {
+            Some(ref found) if constant_time_compare(found, expected_tag) => Ok(()),
+            ref found => Err(ScopeViolation::new(found.is_some(), true)),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn order_token(&self) -> Option<String> {
+        self.order_token.clone()
+    }
+
+    // Opaque pagination key from `OrderTokenGenerator`; not part of the AAD
+    #[wasm_bindgen]
+    pub fn set_order_token(&mut self, order_token: String) {
+        self.order_token = Some(order_token);
+    }
+
     // Validation methods
     #[wasm_bindgen]
     #[must_use]
@@ -306,12 +476,16 @@ pub fn serialize_envelope(envelope: &CryptoEnvelope) -> Result<String, JsValue>
     let json_obj = json!({
         "version": envelope.version() as u8,
         "algorithm": envelope.algorithm() as u8,
+        "kdf_params": envelope.kdf_params().map(|params| params.to_json_value()),
         "salt": base64_encode(&envelope.salt()),
         "nonce": base64_encode(&envelope.nonce()),
         "key_id": envelope.key_id(),
         "encrypted_data": base64_encode(&envelope.encrypted_data()),
         "tag": base64_encode(&envelope.tag()),
-        "aad_hash": base64_encode(&envelope.aad_hash())
+        "aad_hash": base64_encode(&envelope.aad_hash()),
+        "schema_version": envelope.schema_version(),
+        "user_scope_tag": envelope.user_scope_tag().map(|tag| base64_encode(&tag)),
+        "order_token": envelope.order_token()
     });
     
     serde_json::to_string(&json_obj)
@@ -335,6 +509,22 @@ pub fn deserialize_envelope(json_str: &str) -> Result<CryptoEnvelope, JsValue> {
         envelope.set_algorithm(algorithm as u8)?;
     }
     
+    if let Some(params) = KDFParams::from_json_value(&json_val["kdf_params"]) {
+        envelope.set_kdf_params(params);
+    }
+    
+    if let Some(schema_version) = json_val["schema_version"].as_u64() {
+        envelope.set_schema_version(schema_version as u32);
+    }
+    
+    if let Some(scope_b64) = json_val["user_scope_tag"].as_str() {
+        envelope.set_user_scope_tag(base64_decode(scope_b64)?);
+    }
+    
+    if let Some(order_token) = json_val["order_token"].as_str() {
+        envelope.set_order_token(order_token.to_string());
+    }
+    
     if let Some(salt_b64) = json_val["salt"].as_str() {
         envelope.set_salt(base64_decode(salt_b64)?);
     }
@@ -363,8 +553,23 @@ pub fn deserialize_envelope(json_str: &str) -> Result<CryptoEnvelope, JsValue> {
     Ok(envelope)
 }
 
+// Lowercase hex, as used for digests and fingerprints in serialized artifacts
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn decode_hex(encoded: &str) -> Result<Vec<u8>, String> {
+    if !encoded.len().is_multiple_of(2) || !encoded.is_ascii() {
+        return Err("Invalid hex string".to_string());
+    }
+    (0..encoded.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&encoded[i..i + 2], 16).map_err(|_| "Invalid hex string".to_string()))
+        .collect()
+}
+
 // Base64 encoding helper
-fn base64_encode(data: &[u8]) -> String {
+pub(crate) fn base64_encode(data: &[u8]) -> String {
     // Simple base64 implementation for WASM
 
     
@@ -394,6 +599,11 @@ fn base64_encode(data: &[u8]) -> String {
 
 // Base64 decoding helper
 fn base64_decode(encoded: &str) -> Result<Vec<u8>, JsValue> {
+    decode_base64(encoded).map_err(|e| JsValue::from_str(&e))
+}
+
+// Base64 decoding without JsValue, for callers that also run natively
+pub(crate) fn decode_base64(encoded: &str) -> Result<Vec<u8>, String> {
     // Simple base64 decoding for WASM
     use std::collections::HashMap;
     
@@ -410,10 +620,10 @@ fn base64_decode(encoded: &str) -> Result<Vec<u8>, JsValue> {
     while i + 3 < cleaned.len() {
         let chars: Vec<char> = cleaned.chars().skip(i).take(4).collect();
         let values: Result<Vec<usize>, _> = chars.iter()
-            .map(|c| char_map.get(c).copied().ok_or("Invalid base64 character"))
+            .map(|c| char_map.get(c).copied().ok_or_else(|| "Invalid base64 character".to_string()))
             .collect();
         
-        let values = values.map_err(|e| JsValue::from_str(e))?;
+        let values = values?;
         let bitmap = (values[0] << 18) | (values[1] << 12) | (values[2] << 6) | values[3];
         
         result.push((bitmap >> 16) as u8);
@@ -428,10 +638,10 @@ fn base64_decode(encoded: &str) -> Result<Vec<u8>, JsValue> {
         let remaining: Vec<char> = cleaned.chars().skip(i).collect();
         if remaining.len() >= 2 {
             let values: Result<Vec<usize>, _> = remaining.iter()
-                .map(|c| char_map.get(c).copied().ok_or("Invalid base64 character"))
+                .map(|c| char_map.get(c).copied().ok_or_else(|| "Invalid base64 character".to_string()))
                 .collect();
             
-            let values = values.map_err(|e| JsValue::from_str(e))?;
+            let values = values?;
             let bitmap = (values[0] << 18) | (values[1] << 12) |
                          (if values.len() > 2 { values[2] << 6 } else { 0 }) |
                          (if values.len() > 3 { values[3] } else { 0 });
@@ -0,0 +1,711 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+use crate::memory::{track_secret_allocation, track_secret_zeroization};
+use crate::host_calls::{call_host, HostCallbackKind};
+use crate::envelope::to_hex;
+use crate::platform;
+
+// Pull-based bulk export
+// Multi-year exports never sit in memory at once: records are read from the host one at a
+// time, packed into sealed chunks of roughly `chunk_size` bytes and handed back per call.
+// Each chunk is chained into a running hash, and a sealed manifest closes the stream so a
+// reader can detect dropped, reordered or truncated chunks. The cursor is small and
+// serializable, so an interrupted export resumes from the last chunk the host persisted.
+
+const EXPORT_FORMAT_VERSION: u8 = 1;
+const NONCE_LENGTH: usize = 12;
+const RECORD_LENGTH_PREFIX: usize = 4;
+const MIN_CHUNK_SIZE: usize = 1024;
+pub(crate) const CHUNK_AAD_DOMAIN: &str = "aura-export";
+
+fn chain(previous: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(previous);
+    hasher.update(ciphertext);
+    hasher.finalize().to_vec()
+}
+
+/// Record provider for an export stream; records are read lazily by index
+pub trait ExportSource {
+    fn record_count(&self) -> u64;
+    fn read_record(&mut self, index: u64) -> Result<Vec<u8>, String>;
+}
+
+impl ExportSource for Vec<Vec<u8>> {
+    fn record_count(&self) -> u64 {
+        self.len() as u64
+    }
+
+    fn read_record(&mut self, index: u64) -> Result<Vec<u8>, String> {
+        self.get(index as usize)
+            .cloned()
+            .ok_or_else(|| format!("Export record {} out of range", index))
+    }
+}
+
+// Host callback `(index: number) => Uint8Array`
+struct JsExportSource {
+    record_count: u64,
+    read_record: js_sys::Function,
+}
+
+impl ExportSource for JsExportSource {
+    fn record_count(&self) -> u64 {
+        self.record_count
+    }
+
+    fn read_record(&mut self, index: u64) -> Result<Vec<u8>, String> {
+        let value = call_host(HostCallbackKind::Storage, || {
+            self.read_record
+                .call1(&JsValue::NULL, &JsValue::from_f64(index as f64))
+                .map_err(|_| format!("Export source failed reading record {}", index))
+        })?;
+        if !value.is_instance_of::<js_sys::Uint8Array>() {
+            return Err(format!("Export source returned a non-Uint8Array for record {}", index));
+        }
+        Ok(js_sys::Uint8Array::from(value).to_vec())
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportChunkKind {
+    Data = 0,
+    Manifest = 1,
+}
+
+/// One sealed unit of export output, written by the host as-is
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportChunk {
+    version: u8,
+    export_id: String,
+    sequence: u64,
+    kind: ExportChunkKind,
+    record_start: u64,
+    record_count: u64,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl ExportChunk {
+    #[wasm_bindgen(getter)]
+    pub fn export_id(&self) -> String {
+        self.export_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> ExportChunkKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn record_start(&self) -> u64 {
+        self.record_start
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn record_count(&self) -> u64 {
+        self.record_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ciphertext_length(&self) -> usize {
+        self.ciphertext.len()
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize export chunk: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<ExportChunk, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid export chunk: {}", e)))
+    }
+}
+
+impl ExportChunk {
+    // Chunk position and kind are authenticated so chunks cannot be swapped between
+    // exports, reordered, or passed off as the manifest
+    fn header_aad(&self) -> Vec<u8> {
+        format!(
+            "{}-v{}|{}|{}|{:?}|{}|{}",
+            CHUNK_AAD_DOMAIN,
+            self.version,
+            self.export_id,
+            self.sequence,
+            self.kind,
+            self.record_start,
+            self.record_count,
+        )
+        .into_bytes()
+    }
+}
+
+/// Final summary sealed as the last chunk of an export
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    export_id: String,
+    chunk_count: u64,
+    record_count: u64,
+    plaintext_bytes: u64,
+    chain_hash: String,
+    completed_at: u64,
+}
+
+#[wasm_bindgen]
+impl ExportManifest {
+    #[wasm_bindgen(getter)]
+    pub fn export_id(&self) -> String {
+        self.export_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn chunk_count(&self) -> u64 {
+        self.chunk_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn record_count(&self) -> u64 {
+        self.record_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn plaintext_bytes(&self) -> u64 {
+        self.plaintext_bytes
+    }
+
+    /// Hex SHA-256 chain over every data chunk ciphertext in sequence order
+    #[wasm_bindgen(getter)]
+    pub fn chain_hash(&self) -> String {
+        self.chain_hash.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn completed_at(&self) -> u64 {
+        self.completed_at
+    }
+}
+
+/// Resumable position in an export; persist after each chunk is durably written
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportCursor {
+    export_id: String,
+    total_records: u64,
+    next_record: u64,
+    next_sequence: u64,
+    plaintext_bytes: u64,
+    chain_hash: Vec<u8>,
+    finished: bool,
+}
+
+#[wasm_bindgen]
+impl ExportCursor {
+    #[wasm_bindgen(getter)]
+    pub fn export_id(&self) -> String {
+        self.export_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn next_record(&self) -> u64 {
+        self.next_record
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize export cursor: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<ExportCursor, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid export cursor: {}", e)))
+    }
+}
+
+/// Pull-based export producing sealed chunks followed by a manifest
+#[wasm_bindgen]
+pub struct ExportStream {
+    key: Vec<u8>,
+    source: Box<dyn ExportSource>,
+    cursor: ExportCursor,
+    chunk_size: usize,
+    max_bytes_per_second: u64, // 0 disables throttling
+    next_allowed_at: u64,
+}
+
+#[wasm_bindgen]
+impl ExportStream {
+    /// Start a new export; `read_record(index)` must return the record as a Uint8Array
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        export_key: &[u8],
+        record_count: u64,
+        read_record: js_sys::Function,
+        chunk_size: usize,
+        max_bytes_per_second: u64,
+    ) -> Result<ExportStream, JsValue> {
+        let source = JsExportSource { record_count, read_record };
+        Self::with_source(export_key, Box::new(source), chunk_size, max_bytes_per_second)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Continue an interrupted export from a persisted cursor
+    #[wasm_bindgen]
+    pub fn resume(
+        export_key: &[u8],
+        cursor: ExportCursor,
+        read_record: js_sys::Function,
+        chunk_size: usize,
+        max_bytes_per_second: u64,
+    ) -> Result<ExportStream, JsValue> {
+        let source = JsExportSource { record_count: cursor.total_records, read_record };
+        Self::resume_with_source(export_key, cursor, Box::new(source), chunk_size, max_bytes_per_second)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn export_id(&self) -> String {
+        self.cursor.export_id.clone()
+    }
+
+    /// Current position to persist alongside the last written chunk
+    #[wasm_bindgen]
+    pub fn cursor(&self) -> ExportCursor {
+        self.cursor.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_finished(&self) -> bool {
+        self.cursor.finished
+    }
+
+    /// Milliseconds the host should wait before pulling the next chunk
+    #[wasm_bindgen]
+    pub fn ready_in_ms(&self) -> u64 {
+        self.next_allowed_at.saturating_sub(platform::now_ms())
+    }
+
+    /// Next sealed chunk, the manifest after the last data chunk, then `undefined`
+    #[wasm_bindgen]
+    pub fn next_chunk(&mut self) -> Result<Option<ExportChunk>, JsValue> {
+        self.next_chunk_at(platform::now_ms()).map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl ExportStream {
+    pub fn with_source(
+        export_key: &[u8],
+        source: Box<dyn ExportSource>,
+        chunk_size: usize,
+        max_bytes_per_second: u64,
+    ) -> Result<ExportStream, String> {
+        let cursor = ExportCursor {
+            export_id: platform::new_uuid(),
+            total_records: source.record_count(),
+            next_record: 0,
+            next_sequence: 0,
+            plaintext_bytes: 0,
+            chain_hash: vec![0u8; 32],
+            finished: false,
+        };
+        Self::resume_with_source(export_key, cursor, source, chunk_size, max_bytes_per_second)
+    }
+
+    pub fn resume_with_source(
+        export_key: &[u8],
+        cursor: ExportCursor,
+        source: Box<dyn ExportSource>,
+        chunk_size: usize,
+        max_bytes_per_second: u64,
+    ) -> Result<ExportStream, String> {
+        if export_key.len() != 32 {
+            return Err("Export key must be 32 bytes".to_string());
+        }
+        if chunk_size < MIN_CHUNK_SIZE {
+            return Err(format!("Export chunk size must be at least {} bytes", MIN_CHUNK_SIZE));
+        }
+        if source.record_count() != cursor.total_records || cursor.next_record > cursor.total_records {
+            return Err("Export cursor does not match the record source".to_string());
+        }
+
+        track_secret_allocation();
+        Ok(ExportStream {
+            key: export_key.to_vec(),
+            source,
+            cursor,
+            chunk_size,
+            max_bytes_per_second,
+            next_allowed_at: 0,
+        })
+    }
+
+    pub(crate) fn next_chunk_at(&mut self, now: u64) -> Result<Option<ExportChunk>, String> {
+        if self.cursor.finished {
+            return Ok(None);
+        }
+        if now < self.next_allowed_at {
+            return Err(format!("Export throttled, retry in {} ms", self.next_allowed_at - now));
+        }
+
+        let chunk = if self.cursor.next_record < self.cursor.total_records {
+            self.seal_data_chunk()?
+        } else {
+            self.seal_manifest(now)?
+        };
+
+        let budget = (chunk.ciphertext.len() as u64).saturating_mul(1000);
+        if let Some(delay) = budget.checked_div(self.max_bytes_per_second) {
+            self.next_allowed_at = now.saturating_add(delay);
+        }
+
+        Ok(Some(chunk))
+    }
+
+    fn seal_data_chunk(&mut self) -> Result<ExportChunk, String> {
+        let record_start = self.cursor.next_record;
+        let mut plaintext = Vec::with_capacity(self.chunk_size);
+        let mut record_index = record_start;
+
+        // Always take at least one record so an oversized record still makes progress
+        while record_index < self.cursor.total_records {
+            let mut record = self.source.read_record(record_index)?;
+            let framed_length = RECORD_LENGTH_PREFIX + record.len();
+            if record_index > record_start && plaintext.len() + framed_length > self.chunk_size {
+                record.zeroize();
+                break;
+            }
+            let length = u32::try_from(record.len())
+                .map_err(|_| format!("Export record {} exceeds 4 GiB", record_index))?;
+            plaintext.extend_from_slice(&length.to_le_bytes());
+            plaintext.extend_from_slice(&record);
+            record.zeroize();
+            record_index += 1;
+        }
+
+        let mut chunk = self.empty_chunk(ExportChunkKind::Data, record_start, record_index - record_start);
+        let sealed = self.seal(&mut chunk, &plaintext);
+        let plaintext_len = plaintext.len() as u64;
+        plaintext.zeroize();
+        sealed?;
+
+        self.cursor.chain_hash = chain(&self.cursor.chain_hash, &chunk.ciphertext);
+        self.cursor.plaintext_bytes += plaintext_len;
+        self.cursor.next_record = record_index;
+        self.cursor.next_sequence += 1;
+        Ok(chunk)
+    }
+
+    fn seal_manifest(&mut self, now: u64) -> Result<ExportChunk, String> {
+        let manifest = ExportManifest {
+            export_id: self.cursor.export_id.clone(),
+            chunk_count: self.cursor.next_sequence,
+            record_count: self.cursor.total_records,
+            plaintext_bytes: self.cursor.plaintext_bytes,
+            chain_hash: to_hex(&self.cursor.chain_hash),
+            completed_at: now,
+        };
+        let payload = serde_json::to_vec(&manifest)
+            .map_err(|e| format!("Failed to serialize export manifest: {}", e))?;
+
+        let mut chunk = self.empty_chunk(ExportChunkKind::Manifest, self.cursor.total_records, 0);
+        self.seal(&mut chunk, &payload)?;
+
+        self.cursor.next_sequence += 1;
+        self.cursor.finished = true;
+        Ok(chunk)
+    }
+
+    fn empty_chunk(&self, kind: ExportChunkKind, record_start: u64, record_count: u64) -> ExportChunk {
+        ExportChunk {
+            version: EXPORT_FORMAT_VERSION,
+            export_id: self.cursor.export_id.clone(),
+            sequence: self.cursor.next_sequence,
+            kind,
+            record_start,
+            record_count,
+            nonce: Vec::new(),
+            ciphertext: Vec::new(),
+        }
+    }
+
+    fn seal(&self, chunk: &mut ExportChunk, plaintext: &[u8]) -> Result<(), String> {
+        let cipher = Aes256Gcm::new_from_slice(&self.key)
+            .map_err(|_| "Export key must be 32 bytes".to_string())?;
+        let mut nonce = vec![0u8; NONCE_LENGTH];
+        platform::fill_random(&mut nonce);
+
+        let aad = chunk.header_aad();
+        chunk.ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad: &aad })
+            .map_err(|_| "Export encryption failed".to_string())?;
+        chunk.nonce = nonce;
+        Ok(())
+    }
+}
+
+impl Drop for ExportStream {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        track_secret_zeroization();
+    }
+}
+
+/// Opens export chunks in order and verifies the closing manifest
+#[wasm_bindgen]
+pub struct ExportReader {
+    key: Vec<u8>,
+    export_id: Option<String>,
+    next_sequence: u64,
+    next_record: u64,
+    plaintext_bytes: u64,
+    chain_hash: Vec<u8>,
+    manifest: Option<ExportManifest>,
+}
+
+#[wasm_bindgen]
+impl ExportReader {
+    #[wasm_bindgen(constructor)]
+    pub fn new(export_key: &[u8]) -> Result<ExportReader, JsValue> {
+        if export_key.len() != 32 {
+            return Err(JsValue::from_str("Export key must be 32 bytes"));
+        }
+        track_secret_allocation();
+        Ok(ExportReader {
+            key: export_key.to_vec(),
+            export_id: None,
+            next_sequence: 0,
+            next_record: 0,
+            plaintext_bytes: 0,
+            chain_hash: vec![0u8; 32],
+            manifest: None,
+        })
+    }
+
+    /// Decrypt the next chunk; data chunks yield their records, the manifest yields none
+    #[wasm_bindgen]
+    pub fn open_chunk(&mut self, chunk: &ExportChunk) -> Result<Vec<js_sys::Uint8Array>, JsValue> {
+        let records = self.open(chunk).map_err(|e| JsValue::from_str(&e))?;
+        Ok(records.iter().map(|record| js_sys::Uint8Array::from(&record[..])).collect())
+    }
+
+    /// Manifest once the final chunk has been opened and verified
+    #[wasm_bindgen]
+    pub fn manifest(&self) -> Option<ExportManifest> {
+        self.manifest.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_complete(&self) -> bool {
+        self.manifest.is_some()
+    }
+}
+
+impl ExportReader {
+    pub(crate) fn open(&mut self, chunk: &ExportChunk) -> Result<Vec<Vec<u8>>, String> {
+        if self.manifest.is_some() {
+            return Err("Export already complete".to_string());
+        }
+        if chunk.version != EXPORT_FORMAT_VERSION {
+            return Err(format!("Unsupported export chunk version: {}", chunk.version));
+        }
+        if self.export_id.as_ref().is_some_and(|id| *id != chunk.export_id) {
+            return Err("Export chunk belongs to a different export".to_string());
+        }
+        if chunk.sequence != self.next_sequence {
+            return Err(format!("Expected export chunk {}, got {}", self.next_sequence, chunk.sequence));
+        }
+        if chunk.nonce.len() != NONCE_LENGTH {
+            return Err("Export chunk nonce must be 12 bytes".to_string());
+        }
+
+        let cipher = Aes256Gcm::new_from_slice(&self.key)
+            .map_err(|_| "Export key must be 32 bytes".to_string())?;
+        let aad = chunk.header_aad();
+        let mut plaintext = cipher
+            .decrypt(Nonce::from_slice(&chunk.nonce), Payload { msg: &chunk.ciphertext, aad: &aad })
+            .map_err(|_| "Export chunk failed authentication".to_string())?;
+
+        self.export_id.get_or_insert_with(|| chunk.export_id.clone());
+        self.next_sequence += 1;
+
+        let records = match chunk.kind {
+            ExportChunkKind::Data => {
+                let records = split_records(&plaintext, chunk.record_count);
+                if records.is_ok() {
+                    self.chain_hash = chain(&self.chain_hash, &chunk.ciphertext);
+                    self.next_record += chunk.record_count;
+                    self.plaintext_bytes += plaintext.len() as u64;
+                }
+                records
+            }
+            ExportChunkKind::Manifest => self.verify_manifest(&plaintext).map(|_| Vec::new()),
+        };
+        plaintext.zeroize();
+        records
+    }
+
+    fn verify_manifest(&mut self, payload: &[u8]) -> Result<(), String> {
+        let manifest: ExportManifest = serde_json::from_slice(payload)
+            .map_err(|e| format!("Invalid export manifest: {}", e))?;
+
+        let complete = manifest.chunk_count + 1 == self.next_sequence
+            && manifest.record_count == self.next_record
+            && manifest.plaintext_bytes == self.plaintext_bytes
+            && manifest.chain_hash == to_hex(&self.chain_hash);
+        if !complete {
+            return Err("Export manifest does not match the chunks received".to_string());
+        }
+
+        self.manifest = Some(manifest);
+        Ok(())
+    }
+}
+
+fn split_records(plaintext: &[u8], expected: u64) -> Result<Vec<Vec<u8>>, String> {
+    let mut records = Vec::new();
+    let mut offset = 0;
+    while offset < plaintext.len() {
+        let prefix = plaintext
+            .get(offset..offset + RECORD_LENGTH_PREFIX)
+            .ok_or("Truncated export record header")?;
+        let length = u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+        offset += RECORD_LENGTH_PREFIX;
+        let record = plaintext
+            .get(offset..offset + length)
+            .ok_or("Truncated export record")?;
+        records.push(record.to_vec());
+        offset += length;
+    }
+
+    if records.len() as u64 != expected {
+        return Err("Export chunk record count mismatch".to_string());
+    }
+    Ok(records)
+}
+
+impl Drop for ExportReader {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        track_secret_zeroization();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7u8; 32];
+
+    fn records(count: usize, size: usize) -> Vec<Vec<u8>> {
+        (0..count).map(|i| vec![i as u8; size]).collect()
+    }
+
+    fn new_reader() -> ExportReader {
+        ExportReader {
+            key: KEY.to_vec(),
+            export_id: None,
+            next_sequence: 0,
+            next_record: 0,
+            plaintext_bytes: 0,
+            chain_hash: vec![0u8; 32],
+            manifest: None,
+        }
+    }
+
+    #[test]
+    fn test_export_streams_chunks_and_manifest() {
+        let source = records(50, 300);
+        let mut stream = ExportStream::with_source(&KEY, Box::new(source.clone()), 2048, 0).unwrap();
+        let mut reader = new_reader();
+        let mut exported = Vec::new();
+        let mut data_chunks = 0;
+
+        while let Some(chunk) = stream.next_chunk_at(1_000).unwrap() {
+            if chunk.kind() == ExportChunkKind::Data {
+                data_chunks += 1;
+            }
+            exported.extend(reader.open(&chunk).unwrap());
+        }
+
+        assert_eq!(exported, source);
+        assert!(data_chunks > 1);
+        let manifest = reader.manifest.as_ref().unwrap();
+        assert_eq!(manifest.chunk_count(), data_chunks);
+        assert_eq!(manifest.record_count(), 50);
+    }
+
+    #[test]
+    fn test_export_resumes_from_cursor() {
+        let source = records(20, 500);
+        let mut stream = ExportStream::with_source(&KEY, Box::new(source.clone()), 1024, 0).unwrap();
+        let mut reader = new_reader();
+        let mut exported = Vec::new();
+
+        for _ in 0..3 {
+            let chunk = stream.next_chunk_at(0).unwrap().unwrap();
+            exported.extend(reader.open(&chunk).unwrap());
+        }
+        let cursor = stream.cursor();
+        drop(stream);
+
+        let mut resumed = ExportStream::resume_with_source(&KEY, cursor, Box::new(source.clone()), 1024, 0).unwrap();
+        while let Some(chunk) = resumed.next_chunk_at(0).unwrap() {
+            exported.extend(reader.open(&chunk).unwrap());
+        }
+
+        assert_eq!(exported, source);
+        assert!(reader.manifest.is_some());
+    }
+
+    #[test]
+    fn test_export_throttles_and_detects_dropped_chunk() {
+        let mut stream = ExportStream::with_source(&KEY, Box::new(records(10, 800)), 1024, 1024).unwrap();
+        let first = stream.next_chunk_at(0).unwrap().unwrap();
+        assert!(stream.next_chunk_at(10).is_err());
+
+        let _dropped = stream.next_chunk_at(5_000).unwrap().unwrap();
+        let third = stream.next_chunk_at(10_000).unwrap().unwrap();
+
+        let mut reader = new_reader();
+        reader.open(&first).unwrap();
+        assert!(reader.open(&third).is_err());
+    }
+
+    #[test]
+    fn test_export_rejects_malformed_nonce() {
+        let mut stream = ExportStream::with_source(&KEY, Box::new(records(2, 100)), 1024, 0).unwrap();
+        let mut chunk = stream.next_chunk_at(0).unwrap().unwrap();
+        chunk.nonce.truncate(8);
+
+        let mut reader = new_reader();
+        assert!(reader.open(&chunk).unwrap_err().contains("nonce"));
+        assert_eq!(reader.next_sequence, 0);
+    }
+}
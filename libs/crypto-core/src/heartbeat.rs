@@ -0,0 +1,224 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+use crate::envelope::to_hex;
+use crate::key_rotation::{AuditTrailManager, KeyRotationManager};
+use crate::memory::sample_secret_canaries;
+use crate::platform;
+use crate::security::constant_time_compare;
+use crate::storage_migration::{HostStorageBackend, StorageBackend};
+
+// Integrity heartbeat
+// Cheap self-check meant for background tasks. Each run checks the guard bytes of a
+// random sample of live secret buffers (key material included), re-verifies the tail
+// of every key rotation audit trail against its hash chain, round-trips a random probe
+// value through the storage backend, and compares the active keys' material
+// fingerprints with the ones pinned on first sight. The result is a compact record
+// chained to the previous one and authenticated with HMAC-SHA256 under the heartbeat key.
+
+type HmacSha256 = Hmac<Sha256>;
+
+const CANARY_SAMPLE_SIZE: usize = 4;
+const AUDIT_TAIL_LENGTH: usize = 8;
+const STORAGE_PROBE_KEY: &str = "integrity-heartbeat-probe";
+const STORAGE_PROBE_LENGTH: usize = 16;
+const MIN_HEARTBEAT_KEY_LENGTH: usize = 32;
+
+/// One signed heartbeat result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct HeartbeatRecord {
+    seq: u64,
+    ts: u64,
+    canaries: [usize; 2], // [passed, sampled]
+    audit_ok: bool,
+    storage_ok: bool,
+    keys: [usize; 2], // [matching, checked]
+    healthy: bool,
+    prev: String,
+    mac: String,
+}
+
+/// Background integrity checker emitting chained, signed health records
+#[wasm_bindgen]
+pub struct IntegrityHeartbeat {
+    signing_key: Zeroizing<Vec<u8>>,
+    pinned_fingerprints: HashMap<String, String>,
+    previous_mac: String,
+    sequence: u64,
+}
+
+#[wasm_bindgen]
+impl IntegrityHeartbeat {
+    /// `signing_key` (at least 32 bytes) authenticates the emitted records
+    #[wasm_bindgen(constructor)]
+    pub fn new(signing_key: &[u8]) -> Result<IntegrityHeartbeat, JsValue> {
+        Self::with_key(signing_key).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Run every check once and return the signed record as compact JSON
+    #[wasm_bindgen]
+    pub fn run_integrity_heartbeat(
+        &mut self,
+        manager: &KeyRotationManager,
+        audit: &AuditTrailManager,
+        storage: &mut HostStorageBackend,
+    ) -> String {
+        let record = self.run_at(&manager.active_key_fingerprints(), audit, storage, platform::now_ms());
+        serde_json::to_string(&record).unwrap_or_default()
+    }
+
+    /// Check a record's MAC under this heartbeat's key
+    #[wasm_bindgen]
+    pub fn verify_record(&self, record_json: &str) -> bool {
+        serde_json::from_str::<HeartbeatRecord>(record_json)
+            .map(|record| self.mac_valid(&record))
+            .unwrap_or(false)
+    }
+}
+
+impl IntegrityHeartbeat {
+    pub(crate) fn with_key(signing_key: &[u8]) -> Result<IntegrityHeartbeat, String> {
+        if signing_key.len() < MIN_HEARTBEAT_KEY_LENGTH {
+            return Err("Heartbeat key must be at least 32 bytes".to_string());
+        }
+        Ok(IntegrityHeartbeat {
+            signing_key: Zeroizing::new(signing_key.to_vec()),
+            pinned_fingerprints: HashMap::new(),
+            previous_mac: String::new(),
+            sequence: 0,
+        })
+    }
+
+    pub(crate) fn run_at(
+        &mut self,
+        key_fingerprints: &[(String, Option<String>)],
+        audit: &AuditTrailManager,
+        storage: &mut dyn StorageBackend,
+        now: u64,
+    ) -> HeartbeatRecord {
+        let canaries = sample_secret_canaries(CANARY_SAMPLE_SIZE);
+        let audit_ok = audit.chain_tails_valid(AUDIT_TAIL_LENGTH);
+        let storage_ok = probe_storage(storage);
+        let keys = self.check_key_fingerprints(key_fingerprints);
+        let healthy = canaries[0] == canaries[1] && audit_ok && storage_ok && keys[0] == keys[1];
+
+        let mut record = HeartbeatRecord {
+            seq: self.sequence,
+            ts: now,
+            canaries,
+            audit_ok,
+            storage_ok,
+            keys,
+            healthy,
+            prev: self.previous_mac.clone(),
+            mac: String::new(),
+        };
+        record.mac = self.compute_mac(&record);
+
+        self.sequence += 1;
+        self.previous_mac = record.mac.clone();
+        record
+    }
+
+    // Fingerprints are pinned the first time a key id is seen; pins for retired ids are dropped
+    fn check_key_fingerprints(&mut self, key_fingerprints: &[(String, Option<String>)]) -> [usize; 2] {
+        self.pinned_fingerprints.retain(|key_id, _| key_fingerprints.iter().any(|(id, _)| id == key_id));
+        let matching = key_fingerprints
+            .iter()
+            .filter(|(key_id, fingerprint)| match fingerprint {
+                Some(fingerprint) => {
+                    let pinned = self.pinned_fingerprints.entry(key_id.clone()).or_insert_with(|| fingerprint.clone());
+                    constant_time_compare(pinned.as_bytes(), fingerprint.as_bytes())
+                }
+                None => false,
+            })
+            .count();
+        [matching, key_fingerprints.len()]
+    }
+
+    fn compute_mac(&self, record: &HeartbeatRecord) -> String {
+        let unsigned = HeartbeatRecord { mac: String::new(), ..record.clone() };
+        let mut mac = HmacSha256::new_from_slice(&self.signing_key).expect("HMAC accepts any key length");
+        mac.update(&serde_json::to_vec(&unsigned).unwrap_or_default());
+        to_hex(&mac.finalize().into_bytes())
+    }
+
+    fn mac_valid(&self, record: &HeartbeatRecord) -> bool {
+        constant_time_compare(self.compute_mac(record).as_bytes(), record.mac.as_bytes())
+    }
+}
+
+// Write, read back and delete a fresh random value; the probe is removed even when
+// the read fails
+fn probe_storage(storage: &mut dyn StorageBackend) -> bool {
+    let mut probe = [0u8; STORAGE_PROBE_LENGTH];
+    platform::fill_random(&mut probe);
+    let read_back = storage
+        .write(STORAGE_PROBE_KEY, &probe)
+        .and_then(|_| storage.read(STORAGE_PROBE_KEY));
+    let removed = storage.delete(STORAGE_PROBE_KEY).is_ok();
+    matches!(read_back, Ok(Some(value)) if value == probe) && removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::CryptoKey;
+    use crate::test_support::InMemoryStorageBackend;
+
+    fn keys(fingerprint: &str) -> Vec<(String, Option<String>)> {
+        vec![
+            ("cycle_data-1.0.0".to_string(), Some(fingerprint.to_string())),
+            ("preferences-1.0.0".to_string(), Some("bb".to_string())),
+        ]
+    }
+
+    fn audit_trail() -> AuditTrailManager {
+        let mut audit = AuditTrailManager::new();
+        let event = r#"{"source":"app","event_type":"export.requested","occurred_at":1700000000000,"user_id":"user-1","attributes":{}}"#;
+        audit.ingest_external_event_at(event, u64::MAX / 2).unwrap();
+        audit
+    }
+
+    #[test]
+    fn test_heartbeat_detects_fingerprint_change_and_storage_failure() {
+        let mut key = CryptoKey::new("encryption".to_string());
+        key.generate().unwrap();
+        let audit = audit_trail();
+        let mut storage = InMemoryStorageBackend::new();
+        let mut heartbeat = IntegrityHeartbeat::with_key(&[1u8; 32]).unwrap();
+
+        let first = heartbeat.run_at(&keys("aa"), &audit, &mut storage, 1_000);
+        assert!(first.healthy && first.audit_ok && first.storage_ok);
+        assert_eq!((first.canaries, first.keys), ([CANARY_SAMPLE_SIZE, CANARY_SAMPLE_SIZE], [2, 2]));
+        assert!(storage.is_empty());
+
+        let changed = heartbeat.run_at(&keys("cc"), &audit, &mut storage, 2_000);
+        assert_eq!(changed.keys, [1, 2]);
+        assert!(!changed.healthy);
+        assert_eq!(changed.prev, first.mac);
+
+        storage.set_failing(true);
+        let storage_down = heartbeat.run_at(&keys("aa"), &audit, &mut storage, 3_000);
+        assert!(storage_down.audit_ok && !storage_down.storage_ok && !storage_down.healthy);
+        storage.set_failing(false);
+        let unfingerprinted = [("device_sync-1.0.0".to_string(), None)];
+        assert!(!heartbeat.run_at(&unfingerprinted, &audit, &mut storage, 4_000).healthy);
+        assert!(key.validate_memory_protection());
+    }
+
+    #[test]
+    fn test_heartbeat_records_are_signed_and_chained() {
+        let mut heartbeat = IntegrityHeartbeat::with_key(&[1u8; 32]).unwrap();
+        let mut storage = InMemoryStorageBackend::new();
+        let record = heartbeat.run_at(&keys("aa"), &audit_trail(), &mut storage, 1_000);
+        let record = serde_json::to_string(&record).unwrap();
+        assert!(heartbeat.verify_record(&record));
+        assert!(!heartbeat.verify_record(&record.replace("\"storage_ok\":true", "\"storage_ok\":false")));
+        assert!(!IntegrityHeartbeat::with_key(&[2u8; 32]).unwrap().verify_record(&record));
+        assert!(IntegrityHeartbeat::with_key(&[1u8; 16]).is_err());
+    }
+}
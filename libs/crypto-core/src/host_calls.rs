@@ -0,0 +1,318 @@
+use wasm_bindgen::prelude::*;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use crate::platform;
+
+// Circuit breakers around host callbacks
+// Host callbacks (export record reads, event listeners) run synchronously and cannot
+// be preempted, so a call that returns after `call_timeout_ms` counts as a failure
+// even though its result is still used. After `failure_threshold` consecutive
+// failures the breaker for that callback kind opens and calls fail fast instead of
+// piling onto a wedged host. Once `open_duration_ms` has passed one probe call is let
+// through (half-open): success closes the breaker, failure opens it again. Work that
+// cannot be refused outright, such as event delivery, waits in a bounded `RetryQueue`
+// and is replayed in order once the breaker admits calls. No lock is held while the
+// host runs, so callbacks may call back into the crate.
+
+const MAX_RETRY_QUEUE_LENGTH: usize = 256;
+
+/// Kind of host callback, each with its own breaker
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostCallbackKind {
+    Storage = 0,   // Record sources read during export
+    Transport = 1, // Event listeners notified of SLA and watchdog events
+    Crypto = 2,    // Host-provided primitives such as the post-quantum KEM
+}
+
+impl HostCallbackKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HostCallbackKind::Storage => "storage",
+            HostCallbackKind::Transport => "transport",
+            HostCallbackKind::Crypto => "crypto",
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    Closed = 0,
+    Open = 1,
+    HalfOpen = 2,
+}
+
+/// Thresholds shared by every host callback breaker
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerPolicy {
+    failure_threshold: u32,
+    call_timeout_ms: u64,
+    open_duration_ms: u64,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self { failure_threshold: 3, call_timeout_ms: 2_000, open_duration_ms: 30_000 }
+    }
+}
+
+#[wasm_bindgen]
+impl CircuitBreakerPolicy {
+    #[wasm_bindgen(constructor)]
+    pub fn new(failure_threshold: u32, call_timeout_ms: u64, open_duration_ms: u64) -> Result<CircuitBreakerPolicy, JsValue> {
+        if failure_threshold == 0 {
+            return Err(JsValue::from_str("Failure threshold must be greater than 0"));
+        }
+        Ok(CircuitBreakerPolicy { failure_threshold, call_timeout_ms, open_duration_ms })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn failure_threshold(&self) -> u32 {
+        self.failure_threshold
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn call_timeout_ms(&self) -> u64 {
+        self.call_timeout_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn open_duration_ms(&self) -> u64 {
+        self.open_duration_ms
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CircuitBreaker {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: u64,
+    probe_in_flight: bool,
+}
+
+impl CircuitBreaker {
+    const fn new() -> Self {
+        CircuitBreaker { state: BreakerState::Closed, consecutive_failures: 0, opened_at: 0, probe_in_flight: false }
+    }
+
+    // Admit a call, moving an expired open breaker to half-open; only one probe at a time
+    pub(crate) fn admit(&mut self, policy: &CircuitBreakerPolicy, now: u64) -> Result<(), u64> {
+        if self.state == BreakerState::Open {
+            let reopen_at = self.opened_at.saturating_add(policy.open_duration_ms);
+            if now < reopen_at {
+                return Err(reopen_at - now);
+            }
+            self.state = BreakerState::HalfOpen;
+        }
+        if self.state == BreakerState::HalfOpen {
+            if self.probe_in_flight {
+                return Err(0);
+            }
+            self.probe_in_flight = true;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn record(&mut self, policy: &CircuitBreakerPolicy, succeeded: bool, elapsed_ms: u64, now: u64) {
+        self.probe_in_flight = false;
+        if succeeded && elapsed_ms <= policy.call_timeout_ms {
+            *self = CircuitBreaker::new();
+            return;
+        }
+        self.consecutive_failures += 1;
+        if self.state == BreakerState::HalfOpen || self.consecutive_failures >= policy.failure_threshold {
+            self.state = BreakerState::Open;
+            self.opened_at = now;
+        }
+    }
+}
+
+struct HostBreakers {
+    policy: CircuitBreakerPolicy,
+    breakers: [CircuitBreaker; 3],
+}
+
+static HOST_BREAKERS: Mutex<HostBreakers> = Mutex::new(HostBreakers {
+    policy: CircuitBreakerPolicy { failure_threshold: 3, call_timeout_ms: 2_000, open_duration_ms: 30_000 },
+    breakers: [CircuitBreaker::new(); 3],
+});
+
+/// Install the process-wide breaker policy; breaker states are kept
+#[wasm_bindgen]
+pub fn set_circuit_breaker_policy(policy: &CircuitBreakerPolicy) {
+    if let Ok(mut host) = HOST_BREAKERS.lock() {
+        host.policy = *policy;
+    }
+}
+
+#[wasm_bindgen]
+pub fn host_breaker_state(kind: HostCallbackKind) -> BreakerState {
+    HOST_BREAKERS
+        .lock()
+        .map(|host| host.breakers[kind as usize].state)
+        .unwrap_or(BreakerState::Open)
+}
+
+/// Close the breaker by hand, e.g. after the host reports it recovered
+#[wasm_bindgen]
+pub fn reset_host_breaker(kind: HostCallbackKind) {
+    if let Ok(mut host) = HOST_BREAKERS.lock() {
+        host.breakers[kind as usize] = CircuitBreaker::new();
+    }
+}
+
+/// `(kind, state)` for every breaker, for the health check
+pub(crate) fn host_breaker_states() -> Vec<(&'static str, BreakerState)> {
+    [HostCallbackKind::Storage, HostCallbackKind::Transport, HostCallbackKind::Crypto]
+        .iter()
+        .map(|kind| (kind.as_str(), host_breaker_state(*kind)))
+        .collect()
+}
+
+/// Whether a call of `kind` would currently be let through
+pub(crate) fn host_call_admitted(kind: HostCallbackKind) -> bool {
+    match HOST_BREAKERS.lock() {
+        Ok(host) => {
+            let breaker = &host.breakers[kind as usize];
+            match breaker.state {
+                BreakerState::Closed => true,
+                BreakerState::HalfOpen => !breaker.probe_in_flight,
+                BreakerState::Open => platform::now_ms() >= breaker.opened_at.saturating_add(host.policy.open_duration_ms),
+            }
+        }
+        Err(_) => false,
+    }
+}
+
+/// Run one host callback under the breaker for `kind`
+pub(crate) fn call_host<T>(kind: HostCallbackKind, call: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    let started_at = platform::now_ms();
+    {
+        let mut host = HOST_BREAKERS.lock().map_err(|_| "Host callback breakers unavailable".to_string())?;
+        let policy = host.policy;
+        host.breakers[kind as usize].admit(&policy, started_at).map_err(|retry_in_ms| {
+            format!("Host {} callback circuit is open; retry in {} ms", kind.as_str(), retry_in_ms)
+        })?;
+    }
+
+    let result = call();
+
+    let finished_at = platform::now_ms();
+    if let Ok(mut host) = HOST_BREAKERS.lock() {
+        let policy = host.policy;
+        host.breakers[kind as usize].record(&policy, result.is_ok(), finished_at.saturating_sub(started_at), finished_at);
+    }
+    result
+}
+
+/// Bounded FIFO of operations deferred while a breaker is open; the oldest are dropped first
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RetryQueue<T> {
+    pending: VecDeque<T>,
+    dropped: u64,
+}
+
+impl<T> Default for RetryQueue<T> {
+    fn default() -> Self {
+        RetryQueue { pending: VecDeque::new(), dropped: 0 }
+    }
+}
+
+impl<T> RetryQueue<T> {
+    pub(crate) fn push(&mut self, item: T) {
+        if self.pending.len() == MAX_RETRY_QUEUE_LENGTH {
+            self.pending.pop_front();
+            self.dropped += 1;
+        }
+        self.pending.push_back(item);
+    }
+
+    /// Replay queued items in order while `admitted` allows; each item is handed out once
+    pub(crate) fn replay(&mut self, mut admitted: impl FnMut() -> bool, mut run: impl FnMut(T)) {
+        while !self.pending.is_empty() && admitted() {
+            if let Some(item) = self.pending.pop_front() {
+                run(item);
+            }
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// Queue `events` behind any earlier ones, then deliver as many as the transport breaker
+/// admits. A failing listener does not stop delivery to the others, and no event is
+/// delivered twice to the same listener.
+pub(crate) fn deliver_events<E: Clone + Into<JsValue>>(
+    listeners: &[js_sys::Function],
+    pending: &mut RetryQueue<E>,
+    events: &[E],
+) {
+    if listeners.is_empty() {
+        return;
+    }
+    for event in events {
+        pending.push(event.clone());
+    }
+    pending.replay(
+        || host_call_admitted(HostCallbackKind::Transport),
+        |event| {
+            let value: JsValue = event.into();
+            for listener in listeners {
+                let _ = call_host(HostCallbackKind::Transport, || {
+                    listener.call1(&JsValue::NULL, &value).map(|_| ()).map_err(|_| "Listener threw".to_string())
+                });
+            }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_after_failures_and_probes_after_cooldown() {
+        let policy = CircuitBreakerPolicy::default();
+        let mut breaker = CircuitBreaker::new();
+
+        // Slow successes count as failures
+        for now in [0, 10] {
+            assert!(breaker.admit(&policy, now).is_ok());
+            breaker.record(&policy, true, 2_500, now);
+        }
+        assert_eq!(breaker.state, BreakerState::Closed);
+        breaker.record(&policy, false, 1, 20);
+        assert_eq!(breaker.state, BreakerState::Open);
+        assert_eq!(breaker.admit(&policy, 1_020), Err(29_000));
+
+        // One probe at a time once the cooldown passed; a failed probe reopens
+        assert!(breaker.admit(&policy, 30_020).is_ok());
+        assert_eq!(breaker.state, BreakerState::HalfOpen);
+        assert!(breaker.admit(&policy, 30_021).is_err());
+        breaker.record(&policy, false, 1, 30_030);
+        assert_eq!(breaker.state, BreakerState::Open);
+
+        assert!(breaker.admit(&policy, 60_030).is_ok());
+        breaker.record(&policy, true, 5, 60_035);
+        assert_eq!(breaker, CircuitBreaker::new());
+    }
+
+    #[test]
+    fn test_retry_queue_replays_in_order_and_bounds_backlog() {
+        let mut queue = RetryQueue::default();
+        for item in 0..MAX_RETRY_QUEUE_LENGTH + 2 {
+            queue.push(item);
+        }
+        assert_eq!((queue.len(), queue.dropped), (MAX_RETRY_QUEUE_LENGTH, 2));
+
+        let mut budget = 3;
+        let mut replayed = Vec::new();
+        queue.replay(|| { budget -= 1; budget >= 0 }, |item| replayed.push(item));
+        assert_eq!(replayed, [2, 3, 4]);
+        assert_eq!(queue.len(), MAX_RETRY_QUEUE_LENGTH - 3);
+    }
+}
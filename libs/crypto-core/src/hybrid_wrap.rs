@@ -0,0 +1,588 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::{Zeroize, Zeroizing};
+use crate::host_calls::{call_host, HostCallbackKind};
+use crate::integration::{require_owner, RestrictedOperation};
+use crate::memory::track_secret_allocation;
+use crate::platform;
+use crate::recovery::{KeyBackup, RecoverySystem};
+
+// Hybrid post-quantum key wrapping
+// Master-seed backups and escrow shares must stay confidential for decades, so a wrap
+// recorded today has to survive a future quantum adversary. In hybrid mode the wrapping
+// key is derived from an X25519 agreement and a post-quantum KEM (ML-KEM-768) together
+// and holds as long as either one does. The crate has no ML-KEM implementation of its
+// own: the KEM comes in through `PostQuantumKem`, which the host backs with a vetted
+// library. The mode, the KEM, the ephemeral X25519 key and the KEM ciphertext sit in the
+// wrap header, which is bound into the key derivation and authenticated as AAD, so a
+// hybrid wrap cannot be downgraded to classical. Wrapping falls back to X25519 alone
+// when no KEM is configured or the recipient has no post-quantum key; unwrapping a
+// hybrid artifact without the matching KEM fails instead. `RecoverySystem` hands
+// master-seed backups to an escrow holder only through this wrap (`escrow_backup`);
+// escrow shares are wrapped by the host with `HybridKeyWrapper::wrap` directly.
+
+const HYBRID_WRAP_VERSION: u8 = 1;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+pub(crate) const HYBRID_WRAP_INFO: &[u8] = b"aura-hybrid-wrap-v1";
+
+pub const ML_KEM_768: &str = "ML-KEM-768";
+
+/// Key-establishment mode recorded in a wrap header
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WrapMode {
+    Classical = 0, // X25519 only
+    Hybrid = 1,    // X25519 combined with a post-quantum KEM
+}
+
+/// Long-lived artifact a wrap protects; a wrap only opens as the kind it was made for
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WrappedArtifact {
+    MasterSeedBackup = 0,
+    EscrowShare = 1,
+}
+
+/// A key-encapsulation mechanism supplied from outside the crate
+pub trait PostQuantumKem {
+    /// Algorithm name recorded in wrap headers, e.g. `ML-KEM-768`
+    fn algorithm(&self) -> String;
+
+    /// Returns the KEM ciphertext and the 32-byte shared secret
+    fn encapsulate(&self, public_key: &[u8]) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), String>;
+
+    fn decapsulate(&self, secret_key: &[u8], ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, String>;
+}
+
+// Host callbacks `(publicKey) => Uint8Array` returning the ciphertext followed by the
+// 32-byte shared secret, and `(secretKey, ciphertext) => Uint8Array` returning the secret
+struct JsPostQuantumKem {
+    algorithm: String,
+    encapsulate: js_sys::Function,
+    decapsulate: js_sys::Function,
+}
+
+impl JsPostQuantumKem {
+    fn bytes_from_host(value: JsValue, operation: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+        if !value.is_instance_of::<js_sys::Uint8Array>() {
+            return Err(format!("Host KEM {} returned a non-Uint8Array", operation));
+        }
+        Ok(Zeroizing::new(js_sys::Uint8Array::from(value).to_vec()))
+    }
+}
+
+impl PostQuantumKem for JsPostQuantumKem {
+    fn algorithm(&self) -> String {
+        self.algorithm.clone()
+    }
+
+    fn encapsulate(&self, public_key: &[u8]) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), String> {
+        let value = call_host(HostCallbackKind::Crypto, || {
+            self.encapsulate
+                .call1(&JsValue::NULL, &js_sys::Uint8Array::from(public_key))
+                .map_err(|_| "Host KEM encapsulation failed".to_string())
+        })?;
+        let output = Self::bytes_from_host(value, "encapsulation")?;
+        if output.len() <= KEY_LENGTH {
+            return Err("Host KEM encapsulation output is too short".to_string());
+        }
+        let (ciphertext, shared) = output.split_at(output.len() - KEY_LENGTH);
+        Ok((ciphertext.to_vec(), Zeroizing::new(shared.to_vec())))
+    }
+
+    fn decapsulate(&self, secret_key: &[u8], ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+        let value = call_host(HostCallbackKind::Crypto, || {
+            self.decapsulate
+                .call2(
+                    &JsValue::NULL,
+                    &js_sys::Uint8Array::from(secret_key),
+                    &js_sys::Uint8Array::from(ciphertext),
+                )
+                .map_err(|_| "Host KEM decapsulation failed".to_string())
+        })?;
+        Self::bytes_from_host(value, "decapsulation")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct HybridWrapHeader {
+    version: u8,
+    mode: WrapMode,
+    artifact: WrappedArtifact,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kem_algorithm: Option<String>,
+    ephemeral_public_key: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    kem_ciphertext: Vec<u8>,
+}
+
+impl HybridWrapHeader {
+    fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(self).map_err(|e| format!("Failed to serialize wrap header: {}", e))
+    }
+}
+
+/// A secret wrapped to one recipient, classically or in hybrid mode
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HybridWrappedKey {
+    header: HybridWrapHeader,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl HybridWrappedKey {
+    #[wasm_bindgen(getter)]
+    pub fn mode(&self) -> WrapMode {
+        self.header.mode
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn artifact(&self) -> WrappedArtifact {
+        self.header.artifact
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kem_algorithm(&self) -> Option<String> {
+        self.header.kem_algorithm.clone()
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize wrapped key: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<HybridWrappedKey, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid wrapped key: {}", e)))
+    }
+}
+
+/// Wraps long-lived secrets, in hybrid mode whenever a post-quantum KEM is configured
+#[wasm_bindgen]
+pub struct HybridKeyWrapper {
+    kem: Option<Box<dyn PostQuantumKem>>,
+}
+
+impl Default for HybridKeyWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl HybridKeyWrapper {
+    /// Classical-only wrapper; it cannot open hybrid artifacts
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> HybridKeyWrapper {
+        HybridKeyWrapper { kem: None }
+    }
+
+    /// Wrapper backed by a host KEM: `encapsulate(publicKey)` returns the ciphertext
+    /// followed by the 32-byte shared secret, `decapsulate(secretKey, ciphertext)` the secret
+    #[wasm_bindgen]
+    pub fn with_host_kem(
+        algorithm: String,
+        encapsulate: js_sys::Function,
+        decapsulate: js_sys::Function,
+    ) -> HybridKeyWrapper {
+        Self::with_kem(Box::new(JsPostQuantumKem { algorithm, encapsulate, decapsulate }))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn hybrid_available(&self) -> bool {
+        self.kem.is_some()
+    }
+
+    /// Wrap `secret` to the recipient's X25519 key, adding the post-quantum layer
+    /// when both a KEM and `recipient_pq_public_key` are present
+    #[wasm_bindgen]
+    pub fn wrap(
+        &self,
+        artifact: WrappedArtifact,
+        secret: &[u8],
+        recipient_public_key: &[u8],
+        recipient_pq_public_key: Option<Vec<u8>>,
+    ) -> Result<HybridWrappedKey, JsValue> {
+        self.wrap_secret(artifact, secret, recipient_public_key, recipient_pq_public_key.as_deref())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn unwrap(
+        &self,
+        artifact: WrappedArtifact,
+        wrapped: &HybridWrappedKey,
+        recipient_secret_key: &[u8],
+        recipient_pq_secret_key: Option<Vec<u8>>,
+    ) -> Result<Vec<u8>, JsValue> {
+        let secret_key = recipient_pq_secret_key.map(Zeroizing::new);
+        self.unwrap_secret(artifact, wrapped, recipient_secret_key, secret_key.as_ref().map(|key| key.as_slice()))
+            .map(|secret| secret.to_vec())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl HybridKeyWrapper {
+    pub fn with_kem(kem: Box<dyn PostQuantumKem>) -> HybridKeyWrapper {
+        HybridKeyWrapper { kem: Some(kem) }
+    }
+
+    pub(crate) fn wrap_secret(
+        &self,
+        artifact: WrappedArtifact,
+        secret: &[u8],
+        recipient_public_key: &[u8],
+        recipient_pq_public_key: Option<&[u8]>,
+    ) -> Result<HybridWrappedKey, String> {
+        let recipient_public: [u8; KEY_LENGTH] = recipient_public_key
+            .try_into()
+            .map_err(|_| "Recipient public key must be 32 bytes".to_string())?;
+
+        let mut ephemeral_bytes = Zeroizing::new([0u8; KEY_LENGTH]);
+        platform::fill_random(ephemeral_bytes.as_mut());
+        let ephemeral_secret = StaticSecret::from(*ephemeral_bytes);
+        let ephemeral_public = PublicKey::from(&ephemeral_secret);
+        let shared = ephemeral_secret.diffie_hellman(&PublicKey::from(recipient_public));
+
+        let mut header = HybridWrapHeader {
+            version: HYBRID_WRAP_VERSION,
+            mode: WrapMode::Classical,
+            artifact,
+            kem_algorithm: None,
+            ephemeral_public_key: ephemeral_public.as_bytes().to_vec(),
+            kem_ciphertext: Vec::new(),
+        };
+        let mut pq_shared = Zeroizing::new(Vec::new());
+        if let (Some(kem), Some(pq_public_key)) = (&self.kem, recipient_pq_public_key) {
+            let (kem_ciphertext, kem_shared) = kem.encapsulate(pq_public_key)?;
+            check_kem_secret(&kem_shared)?;
+            header.mode = WrapMode::Hybrid;
+            header.kem_algorithm = Some(kem.algorithm());
+            header.kem_ciphertext = kem_ciphertext;
+            pq_shared = kem_shared;
+        }
+
+        let header_bytes = header.to_bytes()?;
+        let kek = derive_hybrid_key(shared.as_bytes(), &pq_shared, &header_bytes, &recipient_public)?;
+
+        let mut nonce = vec![0u8; NONCE_LENGTH];
+        platform::fill_random(&mut nonce);
+        let ciphertext = Aes256Gcm::new_from_slice(kek.as_ref())
+            .map_err(|_| "Invalid wrapping key".to_string())?
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: secret, aad: &header_bytes })
+            .map_err(|_| "Key wrapping failed".to_string())?;
+
+        track_secret_allocation();
+        Ok(HybridWrappedKey { header, nonce, ciphertext })
+    }
+
+    pub(crate) fn unwrap_secret(
+        &self,
+        artifact: WrappedArtifact,
+        wrapped: &HybridWrappedKey,
+        recipient_secret_key: &[u8],
+        recipient_pq_secret_key: Option<&[u8]>,
+    ) -> Result<Zeroizing<Vec<u8>>, String> {
+        let header = &wrapped.header;
+        if header.version != HYBRID_WRAP_VERSION {
+            return Err(format!("Unsupported hybrid wrap version: {}", header.version));
+        }
+        if header.artifact != artifact {
+            return Err(format!("Wrapped key protects a {:?}, not a {:?}", header.artifact, artifact));
+        }
+        if wrapped.nonce.len() != NONCE_LENGTH {
+            return Err("Malformed wrapped key nonce".to_string());
+        }
+
+        let pq_shared = match header.mode {
+            WrapMode::Classical => Zeroizing::new(Vec::new()),
+            WrapMode::Hybrid => {
+                let algorithm = header.kem_algorithm.as_deref().unwrap_or("unknown");
+                let kem = self.kem.as_ref().filter(|kem| kem.algorithm() == algorithm).ok_or_else(|| {
+                    format!("Artifact is wrapped in hybrid mode; a {} KEM is required to unwrap it", algorithm)
+                })?;
+                let pq_secret_key = recipient_pq_secret_key
+                    .ok_or_else(|| "Hybrid artifact needs the recipient's post-quantum secret key".to_string())?;
+                let kem_shared = kem.decapsulate(pq_secret_key, &header.kem_ciphertext)?;
+                check_kem_secret(&kem_shared)?;
+                kem_shared
+            }
+        };
+
+        let mut secret_bytes: [u8; KEY_LENGTH] = recipient_secret_key
+            .try_into()
+            .map_err(|_| "Recipient secret key must be 32 bytes".to_string())?;
+        let secret = StaticSecret::from(secret_bytes);
+        secret_bytes.zeroize();
+        let recipient_public = PublicKey::from(&secret);
+        let ephemeral_public: [u8; KEY_LENGTH] = header.ephemeral_public_key
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Malformed wrap header".to_string())?;
+        let shared = secret.diffie_hellman(&PublicKey::from(ephemeral_public));
+
+        let header_bytes = header.to_bytes()?;
+        let kek = derive_hybrid_key(shared.as_bytes(), &pq_shared, &header_bytes, recipient_public.as_bytes())?;
+
+        Aes256Gcm::new_from_slice(kek.as_ref())
+            .map_err(|_| "Invalid wrapping key".to_string())?
+            .decrypt(
+                Nonce::from_slice(&wrapped.nonce),
+                Payload { msg: &wrapped.ciphertext, aad: &header_bytes },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| "Failed to unwrap key".to_string())
+    }
+}
+
+#[wasm_bindgen]
+impl RecoverySystem {
+    /// Wrap a backup for an escrow holder, in hybrid mode when `wrapper` has a KEM
+    /// and the holder a post-quantum key
+    #[wasm_bindgen]
+    pub fn escrow_backup(
+        &self,
+        backup_id: String,
+        wrapper: &HybridKeyWrapper,
+        holder_public_key: &[u8],
+        holder_pq_public_key: Option<Vec<u8>>,
+    ) -> Result<HybridWrappedKey, JsValue> {
+        self.escrow_backup_with(&backup_id, wrapper, holder_public_key, holder_pq_public_key.as_deref())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Take back a backup released by the escrow holder; returns its backup id
+    #[wasm_bindgen]
+    pub fn import_escrowed_backup(
+        &mut self,
+        wrapped: &HybridWrappedKey,
+        wrapper: &HybridKeyWrapper,
+        holder_secret_key: &[u8],
+        holder_pq_secret_key: Option<Vec<u8>>,
+    ) -> Result<String, JsValue> {
+        let pq_secret_key = holder_pq_secret_key.map(Zeroizing::new);
+        self.import_escrowed_backup_with(wrapped, wrapper, holder_secret_key, pq_secret_key.as_ref().map(|key| key.as_slice()))
+            .map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl RecoverySystem {
+    pub(crate) fn escrow_backup_with(
+        &self,
+        backup_id: &str,
+        wrapper: &HybridKeyWrapper,
+        holder_public_key: &[u8],
+        holder_pq_public_key: Option<&[u8]>,
+    ) -> Result<HybridWrappedKey, String> {
+        require_owner(RestrictedOperation::Recovery).map_err(|denied| denied.to_string())?;
+        let backup = self.backup(backup_id).ok_or_else(|| "Backup not found".to_string())?;
+        let serialized = Zeroizing::new(
+            serde_json::to_vec(backup).map_err(|e| format!("Failed to serialize backup: {}", e))?,
+        );
+        wrapper.wrap_secret(WrappedArtifact::MasterSeedBackup, &serialized, holder_public_key, holder_pq_public_key)
+    }
+
+    pub(crate) fn import_escrowed_backup_with(
+        &mut self,
+        wrapped: &HybridWrappedKey,
+        wrapper: &HybridKeyWrapper,
+        holder_secret_key: &[u8],
+        holder_pq_secret_key: Option<&[u8]>,
+    ) -> Result<String, String> {
+        require_owner(RestrictedOperation::Recovery).map_err(|denied| denied.to_string())?;
+        let serialized = wrapper.unwrap_secret(WrappedArtifact::MasterSeedBackup, wrapped, holder_secret_key, holder_pq_secret_key)?;
+        let backup: KeyBackup = serde_json::from_slice(&serialized).map_err(|_| "Escrowed backup is malformed".to_string())?;
+        let backup_id = backup.backup_id();
+        self.insert_backup(backup);
+        Ok(backup_id)
+    }
+}
+
+// Both shared secrets feed one extraction, so the KEK stays secret while either holds;
+// the header goes into the info string to commit the key to mode and KEM ciphertext
+fn derive_hybrid_key(
+    classical_shared: &[u8],
+    pq_shared: &[u8],
+    header: &[u8],
+    recipient_public: &[u8],
+) -> Result<Zeroizing<[u8; KEY_LENGTH]>, String> {
+    let mut ikm = Zeroizing::new(Vec::with_capacity(classical_shared.len() + pq_shared.len()));
+    ikm.extend_from_slice(classical_shared);
+    ikm.extend_from_slice(pq_shared);
+
+    let mut info = Vec::with_capacity(HYBRID_WRAP_INFO.len() + header.len());
+    info.extend_from_slice(HYBRID_WRAP_INFO);
+    info.extend_from_slice(header);
+
+    let mut kek = Zeroizing::new([0u8; KEY_LENGTH]);
+    Hkdf::<Sha256>::new(Some(recipient_public), &ikm)
+        .expand(&info, kek.as_mut())
+        .map_err(|_| "Wrapping key derivation failed".to_string())?;
+    Ok(kek)
+}
+
+// A host KEM returning a short (or empty) secret would silently weaken the hybrid key
+fn check_kem_secret(shared: &[u8]) -> Result<(), String> {
+    if shared.len() != KEY_LENGTH {
+        return Err(format!("KEM shared secret must be {} bytes, got {}", KEY_LENGTH, shared.len()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Stand-in KEM for exercising the format only: the "ciphertext" is the shared
+    // secret masked with the key, and public and secret key are the same bytes
+    struct MaskKem;
+
+    impl PostQuantumKem for MaskKem {
+        fn algorithm(&self) -> String {
+            ML_KEM_768.to_string()
+        }
+
+        fn encapsulate(&self, public_key: &[u8]) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), String> {
+            let mut shared = Zeroizing::new(vec![0u8; KEY_LENGTH]);
+            platform::fill_random(&mut shared);
+            let ciphertext = shared.iter().zip(public_key).map(|(s, k)| s ^ k).collect();
+            Ok((ciphertext, shared))
+        }
+
+        fn decapsulate(&self, secret_key: &[u8], ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+            Ok(Zeroizing::new(ciphertext.iter().zip(secret_key).map(|(c, k)| c ^ k).collect()))
+        }
+    }
+
+    #[test]
+    fn test_hybrid_wrap_round_trips_and_falls_back_to_classical() {
+        let recipient_secret = [11u8; KEY_LENGTH];
+        let recipient_public = *PublicKey::from(&StaticSecret::from(recipient_secret)).as_bytes();
+        let pq_key = [29u8; KEY_LENGTH];
+        let seed = b"master seed bytes";
+
+        let hybrid = HybridKeyWrapper::with_kem(Box::new(MaskKem));
+        let wrapped = hybrid
+            .wrap_secret(WrappedArtifact::MasterSeedBackup, seed, &recipient_public, Some(&pq_key))
+            .unwrap();
+        assert_eq!(wrapped.mode(), WrapMode::Hybrid);
+        assert_eq!(wrapped.kem_algorithm().as_deref(), Some(ML_KEM_768));
+        let opened = hybrid
+            .unwrap_secret(WrappedArtifact::MasterSeedBackup, &wrapped, &recipient_secret, Some(&pq_key))
+            .unwrap();
+        assert_eq!(opened.as_slice(), seed);
+
+        // Hybrid artifacts need the KEM, the right artifact kind, and an intact header
+        let classical = HybridKeyWrapper::new();
+        assert!(classical
+            .unwrap_secret(WrappedArtifact::MasterSeedBackup, &wrapped, &recipient_secret, Some(&pq_key))
+            .unwrap_err()
+            .contains("hybrid mode"));
+        assert!(hybrid
+            .unwrap_secret(WrappedArtifact::EscrowShare, &wrapped, &recipient_secret, Some(&pq_key))
+            .is_err());
+        let mut downgraded = wrapped.clone();
+        downgraded.header.mode = WrapMode::Classical;
+        downgraded.header.kem_algorithm = None;
+        downgraded.header.kem_ciphertext.clear();
+        assert!(hybrid
+            .unwrap_secret(WrappedArtifact::MasterSeedBackup, &downgraded, &recipient_secret, None)
+            .is_err());
+
+        // Without a post-quantum recipient key the wrap is classical and opens anywhere
+        let fallback = hybrid.wrap_secret(WrappedArtifact::EscrowShare, seed, &recipient_public, None).unwrap();
+        assert_eq!(fallback.mode(), WrapMode::Classical);
+        let restored: HybridWrappedKey = serde_json::from_str(&serde_json::to_string(&fallback).unwrap()).unwrap();
+        let opened = classical
+            .unwrap_secret(WrappedArtifact::EscrowShare, &restored, &recipient_secret, None)
+            .unwrap();
+        assert_eq!(opened.as_slice(), seed);
+
+        // A tampered nonce length is refused rather than panicking
+        let mut truncated = restored.clone();
+        truncated.nonce.pop();
+        assert_eq!(
+            classical.unwrap_secret(WrappedArtifact::EscrowShare, &truncated, &recipient_secret, None).unwrap_err(),
+            "Malformed wrapped key nonce"
+        );
+    }
+
+    // `MaskKem` with its shared secrets truncated to 16 bytes
+    struct TruncatingKem;
+
+    impl PostQuantumKem for TruncatingKem {
+        fn algorithm(&self) -> String {
+            ML_KEM_768.to_string()
+        }
+
+        fn encapsulate(&self, public_key: &[u8]) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), String> {
+            let (ciphertext, shared) = MaskKem.encapsulate(public_key)?;
+            Ok((ciphertext, Zeroizing::new(shared[..16].to_vec())))
+        }
+
+        fn decapsulate(&self, secret_key: &[u8], ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+            Ok(Zeroizing::new(MaskKem.decapsulate(secret_key, ciphertext)?[..16].to_vec()))
+        }
+    }
+
+    #[test]
+    fn test_kem_secret_of_wrong_length_is_refused() {
+        let recipient_secret = [11u8; KEY_LENGTH];
+        let recipient_public = *PublicKey::from(&StaticSecret::from(recipient_secret)).as_bytes();
+        let pq_key = [29u8; KEY_LENGTH];
+
+        let wrapped = HybridKeyWrapper::with_kem(Box::new(MaskKem))
+            .wrap_secret(WrappedArtifact::EscrowShare, b"share", &recipient_public, Some(&pq_key))
+            .unwrap();
+        assert!(HybridKeyWrapper::with_kem(Box::new(TruncatingKem))
+            .unwrap_secret(WrappedArtifact::EscrowShare, &wrapped, &recipient_secret, Some(&pq_key))
+            .unwrap_err()
+            .contains("must be 32 bytes"));
+
+        assert!(HybridKeyWrapper::with_kem(Box::new(TruncatingKem))
+            .wrap_secret(WrappedArtifact::EscrowShare, b"share", &recipient_public, Some(&pq_key))
+            .is_err());
+    }
+
+    #[test]
+    fn test_backups_go_to_escrow_in_hybrid_mode() {
+        use crate::keys::CryptoKey;
+        use crate::recovery::{RecoveryPhrase, WordlistLanguage};
+
+        let holder_secret = [41u8; KEY_LENGTH];
+        let holder_public = *PublicKey::from(&StaticSecret::from(holder_secret)).as_bytes();
+        let holder_pq_key = [43u8; KEY_LENGTH];
+        let wrapper = HybridKeyWrapper::with_kem(Box::new(MaskKem));
+
+        let mut system = RecoverySystem::new("device-1".to_string(), 0, 3, 60_000);
+        let phrase = RecoveryPhrase::generate(128, WordlistLanguage::English as u8).unwrap();
+        let backup = system.create_backup(&CryptoKey::new("encryption".to_string()), &phrase, vec![1, 2, 3]).unwrap();
+
+        let escrowed = system.escrow_backup_with(&backup.backup_id(), &wrapper, &holder_public, Some(&holder_pq_key)).unwrap();
+        assert_eq!((escrowed.mode(), escrowed.artifact()), (WrapMode::Hybrid, WrappedArtifact::MasterSeedBackup));
+        assert!(system.escrow_backup_with("missing", &wrapper, &holder_public, None).is_err());
+
+        // A fresh device takes the backup back from the holder and can recover from it
+        let mut restored = RecoverySystem::new("device-2".to_string(), 0, 3, 60_000);
+        assert!(restored
+            .import_escrowed_backup_with(&escrowed, &HybridKeyWrapper::new(), &holder_secret, Some(&holder_pq_key))
+            .is_err());
+        let backup_id = restored
+            .import_escrowed_backup_with(&escrowed, &wrapper, &holder_secret, Some(&holder_pq_key))
+            .unwrap();
+        assert_eq!(backup_id, backup.backup_id());
+        assert_eq!(restored.backup(&backup_id).unwrap().encrypted_master_key(), backup.encrypted_master_key());
+    }
+}
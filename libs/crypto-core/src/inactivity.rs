@@ -0,0 +1,400 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::auth_freshness::last_reported_authentication;
+use crate::consent::{consent_granted, ConsentFeature};
+use crate::derivation::DataCategory;
+
+// Inactivity (dead-man) switch
+// Opt-in policy that acts when the user has not authenticated for a configured
+// number of days. Nothing happens in a single step: the switch first warns, then
+// issues a final notice, then releases one action per evaluation, and any
+// authentication before the last action is released cancels the run and returns
+// the switch to `Armed`. Time comes from the host as a trusted timestamp (server
+// verified, not the device clock) and is refused if it moves backwards, so setting
+// the device clock forward cannot trigger the switch early. Authentications are read
+// from `report_authentication`, the hook auth freshness already uses, and are clamped
+// to the last trusted time, so a forward-dated report cannot postpone the switch.
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+const HOUR_MS: u64 = 60 * 60 * 1000;
+
+/// Action released once the inactivity period and both notice periods have passed
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InactivityAction {
+    NotifyEmergencyContacts = 0,
+    ReleaseEscrowShare = 1,
+    CryptoShred = 2, // Destroy the keys of the policy's shred categories
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InactivityState {
+    Disabled = 0,
+    Armed = 1,
+    Warning = 2,      // Checkpoint 1: user warned, authentication cancels
+    FinalNotice = 3,  // Checkpoint 2: last notice before actions start
+    Executing = 4,    // Checkpoint 3: authentication still cancels remaining actions
+    Completed = 5,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InactivityEventKind {
+    WarningIssued = 0,
+    FinalNoticeIssued = 1,
+    ActionDue = 2,
+    Cancelled = 3,
+    Completed = 4,
+    ActionBlocked = 5, // Due, but the feature has no recorded consent; the host must not run it
+}
+
+/// Inactivity thresholds and the ordered actions to release
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InactivityPolicy {
+    inactivity_days: u32,
+    warning_period_hours: u32,
+    final_notice_hours: u32,
+    actions: Vec<InactivityAction>,
+    shred_categories: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl InactivityPolicy {
+    /// Policy with a 72 hour warning period, a 24 hour final notice and no actions
+    #[wasm_bindgen(constructor)]
+    pub fn new(inactivity_days: u32) -> InactivityPolicy {
+        InactivityPolicy {
+            inactivity_days,
+            warning_period_hours: 72,
+            final_notice_hours: 24,
+            actions: Vec::new(),
+            shred_categories: Vec::new(),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_notice_periods(&mut self, warning_period_hours: u32, final_notice_hours: u32) {
+        self.warning_period_hours = warning_period_hours;
+        self.final_notice_hours = final_notice_hours;
+    }
+
+    /// Append an action; actions are released in the order added
+    #[wasm_bindgen]
+    pub fn add_action(&mut self, action: InactivityAction) {
+        if !self.actions.contains(&action) {
+            self.actions.push(action);
+        }
+    }
+
+    /// Category whose keys `CryptoShred` destroys
+    #[wasm_bindgen]
+    pub fn add_shred_category(&mut self, category: DataCategory) {
+        let category = category.to_string();
+        if !self.shred_categories.contains(&category) {
+            self.shred_categories.push(category);
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn inactivity_days(&self) -> u32 {
+        self.inactivity_days
+    }
+
+    #[wasm_bindgen]
+    pub fn actions(&self) -> Vec<InactivityAction> {
+        self.actions.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn shred_categories(&self) -> Vec<String> {
+        self.shred_categories.clone()
+    }
+}
+
+impl InactivityPolicy {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !(7..=3650).contains(&self.inactivity_days) {
+            return Err("Inactivity period must be between 7 and 3650 days".to_string());
+        }
+        if self.warning_period_hours == 0 || self.final_notice_hours == 0 {
+            return Err("Notice periods must be greater than 0".to_string());
+        }
+        if self.actions.is_empty() {
+            return Err("Inactivity policy has no actions".to_string());
+        }
+        if self.actions.contains(&InactivityAction::CryptoShred) && self.shred_categories.is_empty() {
+            return Err("Crypto-shred requires at least one category".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Transition or action released by the switch
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InactivityEvent {
+    kind: InactivityEventKind,
+    action: Option<InactivityAction>,
+    categories: Vec<String>, // Set for CryptoShred
+    occurred_at: u64,
+}
+
+#[wasm_bindgen]
+impl InactivityEvent {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> InactivityEventKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn action(&self) -> Option<InactivityAction> {
+        self.action
+    }
+
+    #[wasm_bindgen]
+    pub fn categories(&self) -> Vec<String> {
+        self.categories.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn occurred_at(&self) -> u64 {
+        self.occurred_at
+    }
+}
+
+/// Persistent inactivity state machine
+#[wasm_bindgen]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InactivitySwitch {
+    policy: InactivityPolicy,
+    state: InactivityState,
+    last_authenticated_ms: u64,
+    #[serde(default)]
+    last_report_seen_ms: u64, // As reported, before clamping
+    last_trusted_time_ms: u64,
+    state_entered_ms: u64,
+    next_action: usize,
+}
+
+#[wasm_bindgen]
+impl InactivitySwitch {
+    /// Create a disabled switch; `enable` validates the policy and arms it
+    #[wasm_bindgen(constructor)]
+    pub fn new(policy: InactivityPolicy, trusted_now_ms: u64) -> InactivitySwitch {
+        InactivitySwitch {
+            policy,
+            state: InactivityState::Disabled,
+            last_authenticated_ms: trusted_now_ms,
+            last_report_seen_ms: trusted_now_ms,
+            last_trusted_time_ms: trusted_now_ms,
+            state_entered_ms: trusted_now_ms,
+            next_action: 0,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> InactivityState {
+        self.state
+    }
+
+    #[wasm_bindgen]
+    pub fn enable(&mut self) -> Result<(), JsValue> {
+        self.policy.validate().map_err(|e| JsValue::from_str(&e))?;
+        if self.state == InactivityState::Disabled {
+            self.enter(InactivityState::Armed, self.last_trusted_time_ms);
+        }
+        Ok(())
+    }
+
+    /// Opt out; only possible before the switch has completed
+    #[wasm_bindgen]
+    pub fn disable(&mut self) -> bool {
+        if self.state == InactivityState::Completed {
+            return false;
+        }
+        self.enter(InactivityState::Disabled, self.last_trusted_time_ms);
+        true
+    }
+
+    /// Advance the state machine using a server-verified timestamp. An authentication
+    /// reported since the last evaluation cancels a pending run first
+    #[wasm_bindgen]
+    pub fn evaluate(&mut self, trusted_now_ms: u64) -> Result<Vec<InactivityEvent>, JsValue> {
+        self.evaluate_at(trusted_now_ms, last_reported_authentication())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize inactivity switch: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<InactivitySwitch, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid inactivity switch: {}", e)))
+    }
+}
+
+impl InactivitySwitch {
+    fn enter(&mut self, state: InactivityState, now: u64) {
+        self.state = state;
+        self.state_entered_ms = now;
+        if state != InactivityState::Executing {
+            self.next_action = 0;
+        }
+    }
+
+    fn event(&self, kind: InactivityEventKind, action: Option<InactivityAction>, now: u64) -> InactivityEvent {
+        let categories = if action == Some(InactivityAction::CryptoShred) {
+            self.policy.shred_categories.clone()
+        } else {
+            Vec::new()
+        };
+        InactivityEvent { kind, action, categories, occurred_at: now }
+    }
+
+    // Only reports newer than the last one seen count; comparing the unclamped value
+    // keeps a single forward-dated report from renewing itself on every evaluation
+    fn authenticate_at(&mut self, authenticated_at_ms: u64) -> Option<InactivityEvent> {
+        if authenticated_at_ms <= self.last_report_seen_ms {
+            return None;
+        }
+        self.last_report_seen_ms = authenticated_at_ms;
+        let authenticated_at_ms = authenticated_at_ms.min(self.last_trusted_time_ms);
+        if authenticated_at_ms <= self.last_authenticated_ms {
+            return None;
+        }
+        self.last_authenticated_ms = authenticated_at_ms;
+        match self.state {
+            InactivityState::Warning | InactivityState::FinalNotice | InactivityState::Executing => {
+                let event = self.event(InactivityEventKind::Cancelled, None, authenticated_at_ms);
+                self.enter(InactivityState::Armed, authenticated_at_ms);
+                Some(event)
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn evaluate_at(
+        &mut self,
+        trusted_now_ms: u64,
+        last_authenticated_ms: Option<u64>,
+    ) -> Result<Vec<InactivityEvent>, String> {
+        if trusted_now_ms < self.last_trusted_time_ms {
+            return Err("Trusted time moved backwards".to_string());
+        }
+        self.last_trusted_time_ms = trusted_now_ms;
+        let now = trusted_now_ms;
+
+        let mut events: Vec<InactivityEvent> = last_authenticated_ms
+            .and_then(|authenticated_at_ms| self.authenticate_at(authenticated_at_ms))
+            .into_iter()
+            .collect();
+        let inactive_ms = now.saturating_sub(self.last_authenticated_ms);
+        let in_state_ms = now.saturating_sub(self.state_entered_ms);
+
+        match self.state {
+            InactivityState::Armed if inactive_ms >= self.policy.inactivity_days as u64 * DAY_MS => {
+                self.enter(InactivityState::Warning, now);
+                events.push(self.event(InactivityEventKind::WarningIssued, None, now));
+            }
+            InactivityState::Warning if in_state_ms >= self.policy.warning_period_hours as u64 * HOUR_MS => {
+                self.enter(InactivityState::FinalNotice, now);
+                events.push(self.event(InactivityEventKind::FinalNoticeIssued, None, now));
+            }
+            InactivityState::FinalNotice if in_state_ms >= self.policy.final_notice_hours as u64 * HOUR_MS => {
+                self.enter(InactivityState::Executing, now);
+            }
+            _ => {}
+        }
+
+        // One action per evaluation leaves a cancellation window between actions
+        if self.state == InactivityState::Executing {
+            if let Some(action) = self.policy.actions.get(self.next_action).copied() {
+                self.next_action += 1;
+                let kind = if action == InactivityAction::ReleaseEscrowShare && !consent_granted(ConsentFeature::Escrow) {
+                    InactivityEventKind::ActionBlocked
+                } else {
+                    InactivityEventKind::ActionDue
+                };
+                events.push(self.event(kind, Some(action), now));
+            }
+            if self.next_action >= self.policy.actions.len() {
+                self.enter(InactivityState::Completed, now);
+                events.push(self.event(InactivityEventKind::Completed, None, now));
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn switch() -> InactivitySwitch {
+        let mut policy = InactivityPolicy::new(30);
+        policy.add_action(InactivityAction::NotifyEmergencyContacts);
+        policy.add_action(InactivityAction::CryptoShred);
+        policy.add_shred_category(DataCategory::HealthcareSharing);
+        assert!(policy.validate().is_ok());
+
+        let mut switch = InactivitySwitch::new(policy, 0);
+        switch.enter(InactivityState::Armed, 0);
+        switch
+    }
+
+    fn kinds(events: &[InactivityEvent]) -> Vec<InactivityEventKind> {
+        events.iter().map(InactivityEvent::kind).collect()
+    }
+
+    #[test]
+    fn test_switch_walks_checkpoints_then_releases_actions_one_at_a_time() {
+        let mut switch = switch();
+        assert!(switch.evaluate_at(29 * DAY_MS, None).unwrap().is_empty());
+
+        let warned = 30 * DAY_MS;
+        assert_eq!(kinds(&switch.evaluate_at(warned, None).unwrap()), vec![InactivityEventKind::WarningIssued]);
+        let noticed = warned + 72 * HOUR_MS;
+        assert_eq!(kinds(&switch.evaluate_at(noticed, None).unwrap()), vec![InactivityEventKind::FinalNoticeIssued]);
+
+        let first = switch.evaluate_at(noticed + 24 * HOUR_MS, None).unwrap();
+        assert_eq!(first[0].action(), Some(InactivityAction::NotifyEmergencyContacts));
+        assert_eq!(switch.state(), InactivityState::Executing);
+
+        let second = switch.evaluate_at(noticed + 25 * HOUR_MS, None).unwrap();
+        assert_eq!(second[0].categories(), vec!["healthcare_sharing".to_string()]);
+        assert_eq!(second[1].kind(), InactivityEventKind::Completed);
+        assert!(switch.evaluate_at(noticed + 26 * HOUR_MS, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_authentication_cancels_and_time_cannot_rewind() {
+        let mut switch = switch();
+        switch.evaluate_at(30 * DAY_MS, None).unwrap();
+        assert_eq!(switch.state(), InactivityState::Warning);
+
+        let cancelled = switch.evaluate_at(30 * DAY_MS + 1, Some(30 * DAY_MS + 1)).unwrap();
+        assert_eq!(kinds(&cancelled), vec![InactivityEventKind::Cancelled]);
+        assert_eq!(switch.state(), InactivityState::Armed);
+        // The same report seen again is not a new authentication
+        assert!(switch.evaluate_at(31 * DAY_MS, Some(30 * DAY_MS + 1)).unwrap().is_empty());
+        assert!(switch.evaluate_at(DAY_MS, None).is_err());
+
+        // A forward-dated report counts as of the trusted time, not a year ahead
+        switch.evaluate_at(32 * DAY_MS, Some(400 * DAY_MS)).unwrap();
+        assert_eq!(switch.last_authenticated_ms, 32 * DAY_MS);
+        let warned = switch.evaluate_at(62 * DAY_MS, Some(400 * DAY_MS)).unwrap();
+        assert_eq!(kinds(&warned), vec![InactivityEventKind::WarningIssued]);
+
+        let mut empty = InactivityPolicy::new(30);
+        assert!(empty.validate().is_err());
+        empty.add_action(InactivityAction::CryptoShred);
+        assert!(empty.validate().is_err());
+    }
+}
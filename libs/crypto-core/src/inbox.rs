@@ -0,0 +1,671 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use sha2::{Digest, Sha256};
+use crate::envelope::to_hex;
+use crate::memory::{track_secret_allocation, track_secret_zeroization};
+use crate::platform;
+use crate::timeouts::{Deadline, OperationTimeouts, TimedOperation, Timeout};
+
+// Store-and-forward sync inbox
+// Messages are sealed per recipient device and addressed by fingerprint so an
+// untrusted relay can hold them until the recipient comes online. Ordering,
+// deduplication, expiry and acknowledgment handling all happen client-side. The relay
+// can inject messages, so nothing it sends is trusted before decryption: every
+// candidate for a sequence number is held, and a message id only counts as seen once
+// its message authenticated. Gaps are timed from local receipt, never from the
+// unauthenticated header, and only an authenticated delivery moves the expected
+// sequence forward. Held messages are capped in total and per sequence number.
+
+const INBOX_FORMAT_VERSION: u8 = 1;
+const NONCE_LENGTH: usize = 12;
+const MAX_HELD_MESSAGES: usize = 4096;
+const MAX_CANDIDATES_PER_SEQUENCE: usize = 4;
+pub(crate) const MESSAGE_AAD_DOMAIN: &str = "aura-inbox";
+
+/// Stable device address derived from its public key (hex of first 16 bytes of SHA-256)
+#[wasm_bindgen]
+pub fn device_fingerprint(public_key: &[u8]) -> String {
+    to_hex(&Sha256::digest(public_key)[..16])
+}
+
+/// Sealed message as stored by the relay; only the routing header is readable
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SealedInboxMessage {
+    version: u8,
+    message_id: String,
+    sender_fingerprint: String,
+    recipient_fingerprint: String,
+    sequence: u64,
+    created_at: u64,
+    expires_at: u64,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl SealedInboxMessage {
+    #[wasm_bindgen(getter)]
+    pub fn message_id(&self) -> String {
+        self.message_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sender_fingerprint(&self) -> String {
+        self.sender_fingerprint.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn recipient_fingerprint(&self) -> String {
+        self.recipient_fingerprint.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn expires_at(&self) -> u64 {
+        self.expires_at
+    }
+
+    #[wasm_bindgen]
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Serialize for hand-off to the relay
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize inbox message: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<SealedInboxMessage, JsValue> {
+        let message: SealedInboxMessage = serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid inbox message: {}", e)))?;
+        message.check_nonce().map_err(|e| JsValue::from_str(&e))?;
+        Ok(message)
+    }
+}
+
+impl SealedInboxMessage {
+    fn check_nonce(&self) -> Result<(), String> {
+        if self.nonce.len() != NONCE_LENGTH {
+            return Err("Malformed inbox message nonce".to_string());
+        }
+        Ok(())
+    }
+
+    // Every header field is authenticated so the relay cannot re-route or re-order messages
+    fn header_aad(&self) -> Vec<u8> {
+        format!(
+            "{}-v{}|{}|{}|{}|{}|{}|{}",
+            MESSAGE_AAD_DOMAIN,
+            self.version,
+            self.message_id,
+            self.sender_fingerprint,
+            self.recipient_fingerprint,
+            self.sequence,
+            self.created_at,
+            self.expires_at,
+        )
+        .into_bytes()
+    }
+}
+
+/// Decrypted message handed to the application in per-sender sequence order
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct InboxDelivery {
+    message_id: String,
+    sender_fingerprint: String,
+    sequence: u64,
+    payload: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl InboxDelivery {
+    #[wasm_bindgen(getter)]
+    pub fn message_id(&self) -> String {
+        self.message_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sender_fingerprint(&self) -> String {
+        self.sender_fingerprint.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn payload(&self) -> Vec<u8> {
+        self.payload.clone()
+    }
+}
+
+impl Drop for InboxDelivery {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.payload.zeroize();
+    }
+}
+
+// A held message with the local time it arrived
+#[derive(Debug, Clone)]
+struct HeldMessage {
+    message: SealedInboxMessage,
+    received_at: u64,
+}
+
+/// Per-device encrypted inbox with store-and-forward semantics
+#[wasm_bindgen]
+pub struct EncryptedInbox {
+    fingerprint: String,
+    next_outbound_sequence: HashMap<String, u64>, // recipient -> next sequence
+    outbox: HashMap<String, u64>,                 // sent message id -> expiry, until acknowledged
+    held: HashMap<String, BTreeMap<u64, Vec<HeldMessage>>>, // sender -> sequence -> candidates
+    next_expected_sequence: HashMap<String, u64>, // sender -> next sequence to deliver
+    seen_message_ids: HashMap<String, u64>,       // delivered message id -> expiry, for deduplication
+    pending_acks: Vec<String>,
+    gap_timeout_ms: u64,
+    rejected_count: u32,
+    timeouts: OperationTimeouts,
+}
+
+#[wasm_bindgen]
+impl EncryptedInbox {
+    /// Create inbox for the local device; held messages wait at most `gap_timeout_ms` for a missing predecessor
+    #[wasm_bindgen(constructor)]
+    pub fn new(device_public_key: &[u8], gap_timeout_ms: u64) -> Self {
+        Self {
+            fingerprint: device_fingerprint(device_public_key),
+            next_outbound_sequence: HashMap::new(),
+            outbox: HashMap::new(),
+            held: HashMap::new(),
+            next_expected_sequence: HashMap::new(),
+            seen_message_ids: HashMap::new(),
+            pending_acks: Vec::new(),
+            gap_timeout_ms,
+            rejected_count: 0,
+            timeouts: OperationTimeouts::default(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn fingerprint(&self) -> String {
+        self.fingerprint.clone()
+    }
+
+    /// Seal a payload for one recipient device under the pairwise shared key
+    #[wasm_bindgen]
+    pub fn seal(
+        &mut self,
+        recipient_fingerprint: String,
+        shared_key: &[u8],
+        payload: &[u8],
+        ttl_ms: u64,
+    ) -> Result<SealedInboxMessage, JsValue> {
+        let cipher = Aes256Gcm::new_from_slice(shared_key)
+            .map_err(|_| JsValue::from_str("Inbox key must be 32 bytes"))?;
+
+        let sequence = self.next_outbound_sequence
+            .entry(recipient_fingerprint.clone())
+            .or_insert(0);
+        let created_at = platform::now_ms();
+
+        let mut nonce = vec![0u8; NONCE_LENGTH];
+        platform::fill_random(&mut nonce);
+
+        let mut message = SealedInboxMessage {
+            version: INBOX_FORMAT_VERSION,
+            message_id: platform::new_uuid(),
+            sender_fingerprint: self.fingerprint.clone(),
+            recipient_fingerprint,
+            sequence: *sequence,
+            created_at,
+            expires_at: created_at.saturating_add(ttl_ms),
+            nonce,
+            ciphertext: Vec::new(),
+        };
+
+        let aad = message.header_aad();
+        message.ciphertext = cipher
+            .encrypt(Nonce::from_slice(&message.nonce), Payload { msg: payload, aad: &aad })
+            .map_err(|_| JsValue::from_str("Inbox encryption failed"))?;
+
+        *sequence += 1;
+        self.outbox.insert(message.message_id.clone(), message.expires_at);
+        track_secret_allocation();
+
+        Ok(message)
+    }
+
+    /// Accept a message fetched from the relay; returns false for duplicates and expired messages
+    #[wasm_bindgen]
+    pub fn receive(&mut self, message: SealedInboxMessage) -> Result<bool, JsValue> {
+        self.accept(message, platform::now_ms()).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Decrypt and return the next in-order messages from one sender. If the sync
+    /// application timeout passes, nothing is delivered and the error is a `Timeout`
+    #[wasm_bindgen]
+    pub fn deliver(
+        &mut self,
+        sender_fingerprint: String,
+        shared_key: &[u8],
+    ) -> Result<Vec<InboxDelivery>, JsValue> {
+        self.deliver_ready(&sender_fingerprint, shared_key, platform::now_ms())
+            .map_err(|e| match e.downcast::<Timeout>() {
+                Ok(timeout) => JsValue::from(*timeout),
+                Err(e) => JsValue::from_str(&e.to_string()),
+            })
+    }
+
+    #[wasm_bindgen]
+    pub fn set_operation_timeouts(&mut self, timeouts: &OperationTimeouts) {
+        self.timeouts = *timeouts;
+    }
+
+    /// Message ids the relay may delete, cleared once returned
+    #[wasm_bindgen]
+    pub fn take_acknowledgments(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_acks)
+    }
+
+    /// Stop tracking outbound messages the recipient has acknowledged
+    #[wasm_bindgen]
+    pub fn process_acknowledgments(&mut self, message_ids: Vec<String>) -> usize {
+        message_ids
+            .iter()
+            .filter(|id| self.outbox.remove(id.as_str()).is_some())
+            .count()
+    }
+
+    /// Outbound messages not yet acknowledged by their recipient
+    #[wasm_bindgen]
+    pub fn unacknowledged_count(&self) -> usize {
+        self.outbox.len()
+    }
+
+    /// Messages waiting on a missing predecessor
+    #[wasm_bindgen]
+    pub fn held_count(&self) -> usize {
+        self.held.values().flat_map(|messages| messages.values()).map(Vec::len).sum()
+    }
+
+    /// Messages discarded because they failed authentication
+    #[wasm_bindgen(getter)]
+    pub fn rejected_count(&self) -> u32 {
+        self.rejected_count
+    }
+
+    /// Drop expired held messages, outbox entries and deduplication records
+    #[wasm_bindgen]
+    pub fn sweep_expired(&mut self) -> usize {
+        self.sweep(platform::now_ms())
+    }
+}
+
+impl EncryptedInbox {
+    pub(crate) fn accept(&mut self, message: SealedInboxMessage, now: u64) -> Result<bool, String> {
+        if message.version != INBOX_FORMAT_VERSION {
+            return Err(format!("Unsupported inbox message version: {}", message.version));
+        }
+
+        if message.recipient_fingerprint != self.fingerprint {
+            return Err("Inbox message addressed to another device".to_string());
+        }
+        message.check_nonce()?;
+
+        // Expired or already-seen messages are still acknowledged so the relay drops them
+        if message.is_expired(now) || self.seen_message_ids.contains_key(&message.message_id) {
+            self.pending_acks.push(message.message_id);
+            return Ok(false);
+        }
+
+        let next_expected = self.next_expected_sequence
+            .get(&message.sender_fingerprint)
+            .copied()
+            .unwrap_or(0);
+        if message.sequence < next_expected {
+            self.pending_acks.push(message.message_id);
+            return Ok(false);
+        }
+
+        // The id is not recorded yet: an unauthenticated copy must not shadow the real message
+        let held_count = self.held_count();
+        let candidates = self.held
+            .entry(message.sender_fingerprint.clone())
+            .or_default()
+            .entry(message.sequence)
+            .or_default();
+        if candidates.iter().any(|held| held.message == message) {
+            self.pending_acks.push(message.message_id);
+            return Ok(false);
+        }
+        // Not acknowledged: the relay keeps the message and can offer it again later
+        if held_count >= MAX_HELD_MESSAGES || candidates.len() >= MAX_CANDIDATES_PER_SEQUENCE {
+            let sender = message.sender_fingerprint.clone();
+            self.drop_empty_slot(&sender, message.sequence);
+            return Err("Inbox is holding too many messages".to_string());
+        }
+        candidates.push(HeldMessage { message, received_at: now });
+
+        Ok(true)
+    }
+
+    pub(crate) fn deliver_ready(
+        &mut self,
+        sender_fingerprint: &str,
+        shared_key: &[u8],
+        now: u64,
+    ) -> Result<Vec<InboxDelivery>, Box<dyn std::error::Error>> {
+        let cipher = Aes256Gcm::new_from_slice(shared_key)
+            .map_err(|_| "Inbox key must be 32 bytes".to_string())?;
+        let deadline = Deadline::start(TimedOperation::SyncApplication, &self.timeouts);
+
+        let mut next_expected = self.next_expected_sequence
+            .get(sender_fingerprint)
+            .copied()
+            .unwrap_or(0);
+        let mut deliveries = Vec::new();
+
+        let Some(held) = self.held.get_mut(sender_fingerprint) else {
+            return Ok(deliveries);
+        };
+
+        // Last consistent point: messages taken off the held queue are put back on timeout
+        let acks_before = self.pending_acks.len();
+        let rejected_before = self.rejected_count;
+        let mut taken = Vec::new();
+        let mut newly_seen = Vec::new();
+
+        while let Some((&sequence, candidates)) = held.iter().next() {
+            // Skip a gap once the first held message has waited past the timeout since it arrived
+            let oldest = candidates.iter().map(|held| held.received_at).min().unwrap_or(now);
+            let gap_expired = now.saturating_sub(oldest) >= self.gap_timeout_ms;
+            if sequence != next_expected && !gap_expired {
+                break;
+            }
+
+            let candidates = held.remove(&sequence).expect("held message present");
+            let mut delivered = false;
+            for HeldMessage { message, .. } in &candidates {
+                let aad = message.header_aad();
+                let decrypted = cipher.decrypt(
+                    Nonce::from_slice(&message.nonce),
+                    Payload { msg: &message.ciphertext, aad: &aad },
+                );
+
+                // Forged or corrupted messages are discarded (and acknowledged so the relay drops them)
+                self.pending_acks.push(message.message_id.clone());
+                match decrypted {
+                    Ok(payload) if !delivered => {
+                        delivered = true;
+                        self.seen_message_ids.insert(message.message_id.clone(), message.expires_at);
+                        newly_seen.push(message.message_id.clone());
+                        deliveries.push(InboxDelivery {
+                            message_id: message.message_id.clone(),
+                            sender_fingerprint: message.sender_fingerprint.clone(),
+                            sequence,
+                            payload,
+                        });
+                    }
+                    Ok(_) => {}
+                    Err(_) => self.rejected_count += 1,
+                }
+            }
+            // Only an authenticated message skips a gap or consumes its slot; forgeries,
+            // whatever sequence they claim, leave the expected sequence where it was
+            if delivered {
+                next_expected = sequence + 1;
+            }
+            taken.push((sequence, candidates));
+
+            if let Err(timeout) = deadline.check(&format!("message {}", sequence)) {
+                for (sequence, candidates) in taken {
+                    held.insert(sequence, candidates);
+                }
+                for message_id in newly_seen {
+                    self.seen_message_ids.remove(&message_id);
+                }
+                self.pending_acks.truncate(acks_before);
+                self.rejected_count = rejected_before;
+                drop(deliveries); // InboxDelivery zeroizes its payload on drop
+                return Err(timeout.into());
+            }
+        }
+
+        self.next_expected_sequence.insert(sender_fingerprint.to_string(), next_expected);
+        Ok(deliveries)
+    }
+
+    pub(crate) fn sweep(&mut self, now: u64) -> usize {
+        let mut removed = 0;
+
+        for messages in self.held.values_mut() {
+            for candidates in messages.values_mut() {
+                let before = candidates.len();
+                candidates.retain(|held| !held.message.is_expired(now));
+                removed += before - candidates.len();
+            }
+            messages.retain(|_, candidates| !candidates.is_empty());
+        }
+        self.held.retain(|_, messages| !messages.is_empty());
+
+        let outbox_before = self.outbox.len();
+        self.outbox.retain(|_, expires_at| *expires_at > now);
+        removed += outbox_before - self.outbox.len();
+
+        self.seen_message_ids.retain(|_, expires_at| *expires_at > now);
+        removed
+    }
+}
+
+impl EncryptedInbox {
+    fn drop_empty_slot(&mut self, sender_fingerprint: &str, sequence: u64) {
+        if let Some(messages) = self.held.get_mut(sender_fingerprint) {
+            if messages.get(&sequence).is_some_and(Vec::is_empty) {
+                messages.remove(&sequence);
+            }
+            if messages.is_empty() {
+                self.held.remove(sender_fingerprint);
+            }
+        }
+    }
+}
+
+impl Drop for EncryptedInbox {
+    fn drop(&mut self) {
+        self.held.clear();
+        track_secret_zeroization();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [42u8; 32];
+
+    fn pair() -> (EncryptedInbox, EncryptedInbox) {
+        (EncryptedInbox::new(b"phone-public-key", 60_000), EncryptedInbox::new(b"laptop-public-key", 60_000))
+    }
+
+    #[test]
+    fn test_inbox_orders_and_deduplicates() {
+        let (mut phone, mut laptop) = pair();
+        let first = phone.seal(laptop.fingerprint(), &KEY, b"cycle entry 1", 60_000).unwrap();
+        let second = phone.seal(laptop.fingerprint(), &KEY, b"cycle entry 2", 60_000).unwrap();
+        let now = first.created_at();
+
+        // Relay hands messages over out of order, one of them twice
+        assert!(laptop.accept(second.clone(), now).unwrap());
+        assert!(laptop.deliver_ready(&phone.fingerprint(), &KEY, now).unwrap().is_empty());
+        assert!(laptop.accept(first, now).unwrap());
+        assert!(!laptop.accept(second, now).unwrap());
+
+        let delivered = laptop.deliver_ready(&phone.fingerprint(), &KEY, now).unwrap();
+        let payloads: Vec<Vec<u8>> = delivered.iter().map(|d| d.payload()).collect();
+        assert_eq!(payloads, vec![b"cycle entry 1".to_vec(), b"cycle entry 2".to_vec()]);
+
+        // Duplicate and both deliveries are acknowledged back to the sender
+        let acks = laptop.take_acknowledgments();
+        assert_eq!(acks.len(), 3);
+        assert_eq!(phone.process_acknowledgments(acks), 2);
+        assert_eq!(phone.unacknowledged_count(), 0);
+    }
+
+    #[test]
+    fn test_inbox_expiry_and_gap_timeout() {
+        let (mut phone, mut laptop) = pair();
+        let _lost = phone.seal(laptop.fingerprint(), &KEY, b"lost", 60_000).unwrap();
+        let later = phone.seal(laptop.fingerprint(), &KEY, b"later", 120_000).unwrap();
+        let now = later.created_at();
+
+        assert!(laptop.accept(later, now).unwrap());
+        assert_eq!(laptop.held_count(), 1);
+
+        // Held message is released once the gap timeout passes
+        let delivered = laptop.deliver_ready(&phone.fingerprint(), &KEY, now + 60_000).unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].sequence(), 1);
+
+        let expired = phone.seal(laptop.fingerprint(), &KEY, b"stale", 1_000).unwrap();
+        assert!(!laptop.accept(expired, now + 5_000).unwrap());
+        assert_eq!(phone.sweep(now + 500_000), 3);
+    }
+
+    #[test]
+    fn test_inbox_rejects_tampered_header() {
+        let (mut phone, mut laptop) = pair();
+        let mut message = phone.seal(laptop.fingerprint(), &KEY, b"secret", 60_000).unwrap();
+        let now = message.created_at();
+        message.expires_at += 1_000_000;
+
+        assert!(laptop.accept(message, now).unwrap());
+        assert!(laptop.deliver_ready(&phone.fingerprint(), &KEY, now).unwrap().is_empty());
+        assert_eq!(laptop.rejected_count(), 1);
+    }
+
+    #[test]
+    fn test_forged_copy_cannot_shadow_the_real_message() {
+        let (mut phone, mut laptop) = pair();
+        let real = phone.seal(laptop.fingerprint(), &KEY, b"cycle entry", 60_000).unwrap();
+        let now = real.created_at();
+
+        // The relay injects a forgery carrying the real id and sequence before the real message
+        let mut forged = real.clone();
+        forged.ciphertext[0] ^= 1;
+        assert!(laptop.accept(forged.clone(), now).unwrap());
+        assert!(laptop.accept(real.clone(), now).unwrap());
+        let delivered = laptop.deliver_ready(&phone.fingerprint(), &KEY, now).unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].payload(), b"cycle entry".to_vec());
+        assert_eq!(laptop.rejected_count(), 1);
+        assert!(!laptop.accept(real.clone(), now).unwrap());
+
+        // A forgery alone does not consume the sequence number either
+        let (mut phone, mut laptop) = pair();
+        let real = phone.seal(laptop.fingerprint(), &KEY, b"cycle entry", 60_000).unwrap();
+        let mut forged = real.clone();
+        forged.ciphertext[0] ^= 1;
+        assert!(laptop.accept(forged, now).unwrap());
+        assert!(laptop.deliver_ready(&phone.fingerprint(), &KEY, now).unwrap().is_empty());
+        assert!(laptop.accept(real, now).unwrap());
+        assert_eq!(laptop.deliver_ready(&phone.fingerprint(), &KEY, now).unwrap().len(), 1);
+
+        // Malformed nonces are refused on receipt instead of panicking at delivery
+        let mut short = phone.seal(laptop.fingerprint(), &KEY, b"x", 60_000).unwrap();
+        short.nonce.truncate(4);
+        assert_eq!(laptop.accept(short, now).unwrap_err(), "Malformed inbox message nonce");
+    }
+
+    #[test]
+    fn test_forged_far_sequence_cannot_skip_real_messages() {
+        let (mut phone, mut laptop) = pair();
+        let first = phone.seal(laptop.fingerprint(), &KEY, b"cycle entry 1", 120_000).unwrap();
+        let now = first.created_at();
+
+        // The relay injects a forgery far ahead, backdated so its header claims a long wait
+        let mut forged = first.clone();
+        forged.message_id = "forged".to_string();
+        forged.sequence = 1000;
+        forged.created_at = 0;
+        assert!(laptop.accept(forged, now).unwrap());
+        assert!(laptop.deliver_ready(&phone.fingerprint(), &KEY, now).unwrap().is_empty());
+        assert_eq!(laptop.rejected_count(), 0);
+
+        // Once it has waited out the gap locally it is tried, fails, and skips nothing
+        assert!(laptop.deliver_ready(&phone.fingerprint(), &KEY, now + 60_000).unwrap().is_empty());
+        assert_eq!(laptop.rejected_count(), 1);
+        assert!(laptop.accept(first, now + 60_000).unwrap());
+        let delivered = laptop.deliver_ready(&phone.fingerprint(), &KEY, now + 60_000).unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].payload(), b"cycle entry 1".to_vec());
+    }
+
+    #[test]
+    fn test_held_messages_are_capped() {
+        let (mut phone, mut laptop) = pair();
+        let real = phone.seal(laptop.fingerprint(), &KEY, b"cycle entry", 60_000).unwrap();
+        let now = real.created_at();
+
+        for copy in 0..MAX_CANDIDATES_PER_SEQUENCE {
+            let mut forged = real.clone();
+            forged.message_id = format!("forged-{}", copy);
+            assert!(laptop.accept(forged, now).unwrap());
+        }
+        assert!(laptop.accept(real.clone(), now).unwrap_err().contains("too many"));
+        assert_eq!(laptop.held_count(), MAX_CANDIDATES_PER_SEQUENCE);
+
+        let mut flooded = EncryptedInbox::new(b"laptop-public-key", 60_000);
+        for sequence in 0..MAX_HELD_MESSAGES as u64 {
+            let mut forged = real.clone();
+            forged.sender_fingerprint = format!("sender-{}", sequence % 7);
+            forged.sequence = sequence + 1;
+            assert!(flooded.accept(forged, now).unwrap());
+        }
+        assert!(flooded.accept(real, now).is_err());
+        assert_eq!(flooded.held_count(), MAX_HELD_MESSAGES);
+        assert!(!flooded.held.contains_key(&phone.fingerprint()));
+    }
+
+    #[test]
+    fn test_inbox_rolls_back_on_sync_timeout() {
+        let (mut phone, mut laptop) = pair();
+        let first = phone.seal(laptop.fingerprint(), &KEY, b"cycle entry 1", 60_000).unwrap();
+        let second = phone.seal(laptop.fingerprint(), &KEY, b"cycle entry 2", 60_000).unwrap();
+        let now = first.created_at();
+        assert!(laptop.accept(first, now).unwrap());
+        assert!(laptop.accept(second, now).unwrap());
+
+        let mut timeouts = OperationTimeouts::new();
+        timeouts.set_timeout(TimedOperation::SyncApplication, Some(0));
+        laptop.set_operation_timeouts(&timeouts);
+
+        let timeout = laptop.deliver_ready(&phone.fingerprint(), &KEY, now).unwrap_err().downcast::<Timeout>().unwrap();
+        assert_eq!(timeout.phase(), "message 0");
+        assert_eq!(laptop.held_count(), 2);
+        assert!(laptop.take_acknowledgments().is_empty());
+
+        // Retrying without the deadline applies everything from the restored point
+        timeouts.set_timeout(TimedOperation::SyncApplication, None);
+        laptop.set_operation_timeouts(&timeouts);
+        assert_eq!(laptop.deliver_ready(&phone.fingerprint(), &KEY, now).unwrap().len(), 2);
+    }
+}
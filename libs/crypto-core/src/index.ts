@@ -54,11 +54,9 @@ export class IntegrityVerificationError extends Error {
 let wasmInitialized = false;
 let initPromise: Promise<ModuleIntegrity> | null = null;
 
-export async function initializeCrypto(): Promise<ModuleIntegrity> {
-  if (wasmInitialized && initPromise) {
-    return initPromise;
-  }
-
+export async function initializeCrypto(
+  profile?: wasm.SecurityProfile
+): Promise<ModuleIntegrity> {
   if (!initPromise) {
     initPromise = (async () => {
       try {
@@ -93,7 +91,14 @@ export async function initializeCrypto(): Promise<ModuleIntegrity> {
     })();
   }
 
-  return initPromise;
+  const integrity = await initPromise;
+
+  // Apply the security profile once, before modules read their settings
+  if (profile !== undefined) {
+    wasm.configure_security_profile(profile);
+  }
+
+  return integrity;
 }
 
 // Promise-based crypto operations with proper error handling
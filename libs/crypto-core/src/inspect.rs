@@ -0,0 +1,308 @@
+use wasm_bindgen::prelude::*;
+use serde_json::{Map, Value};
+use crate::envelope::{decode_base64, to_hex, CryptoAlgorithm};
+use crate::profile::PaddingPolicy;
+
+// Envelope inspection
+// Parses the serialized (JSONB) envelope header for sync logic and support tooling.
+// Nothing here takes a key or touches plaintext: ciphertext, nonce and tag are only
+// decoded to check their lengths, and the summary carries lengths and digests only.
+
+const MAX_ENVELOPE_BYTES: usize = 32 * 1024 * 1024;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const AAD_DIGEST_LENGTH: usize = 32;
+const MIN_SIZE_CLASS: usize = 256;
+const FIXED_BLOCK_SIZE: usize = 4096;
+
+const KNOWN_FIELDS: [&str; 12] = [
+    "version",
+    "algorithm",
+    "kdf_params",
+    "salt",
+    "nonce",
+    "key_id",
+    "encrypted_data",
+    "tag",
+    "aad_hash",
+    "schema_version",
+    "user_scope_tag",
+    "order_token",
+];
+
+/// Key-free summary of an envelope header
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    version: u8,
+    algorithm: CryptoAlgorithm,
+    key_id: Option<String>,
+    kdf_algorithm: Option<String>,
+    schema_version: Option<u32>,
+    scope_tagged: bool,
+    order_token: Option<String>,
+    aad_digest: Option<String>,
+    ciphertext_length: usize,
+    size_class: usize,
+    padding_hint: PaddingPolicy,
+}
+
+#[wasm_bindgen]
+impl EnvelopeHeader {
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn algorithm(&self) -> CryptoAlgorithm {
+        self.algorithm
+    }
+
+    /// Key version identifier the envelope was sealed under
+    #[wasm_bindgen(getter)]
+    pub fn key_id(&self) -> Option<String> {
+        self.key_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kdf_algorithm(&self) -> Option<String> {
+        self.kdf_algorithm.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn schema_version(&self) -> Option<u32> {
+        self.schema_version
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn scope_tagged(&self) -> bool {
+        self.scope_tagged
+    }
+
+    /// Opaque pagination key, if the envelope carries one
+    #[wasm_bindgen(getter)]
+    pub fn order_token(&self) -> Option<String> {
+        self.order_token.clone()
+    }
+
+    /// Hex SHA-256 of the AAD recorded at encryption time
+    #[wasm_bindgen(getter)]
+    pub fn aad_digest(&self) -> Option<String> {
+        self.aad_digest.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ciphertext_length(&self) -> usize {
+        self.ciphertext_length
+    }
+
+    /// Power-of-two bucket (at least 256 bytes) the ciphertext falls into
+    #[wasm_bindgen(getter)]
+    pub fn size_class(&self) -> usize {
+        self.size_class
+    }
+
+    /// Most specific padding policy the ciphertext length is consistent with;
+    /// envelopes do not record padding, so this is inferred, not authoritative
+    #[wasm_bindgen(getter)]
+    pub fn padding_hint(&self) -> PaddingPolicy {
+        self.padding_hint
+    }
+}
+
+/// Parse and validate a serialized envelope header without any key
+#[wasm_bindgen]
+pub fn inspect_envelope(bytes: &[u8]) -> Result<EnvelopeHeader, JsValue> {
+    inspect_envelope_bytes(bytes).map_err(|e| JsValue::from_str(&e))
+}
+
+pub(crate) fn inspect_envelope_bytes(bytes: &[u8]) -> Result<EnvelopeHeader, String> {
+    if bytes.len() > MAX_ENVELOPE_BYTES {
+        return Err("Envelope exceeds maximum inspectable size".to_string());
+    }
+    let value: Value = serde_json::from_slice(bytes).map_err(|e| format!("Envelope is not valid JSON: {}", e))?;
+    let fields = value.as_object().ok_or("Envelope must be a JSON object")?;
+
+    if let Some(unknown) = fields.keys().find(|key| !KNOWN_FIELDS.contains(&key.as_str())) {
+        return Err(format!("Unknown envelope field: {}", unknown));
+    }
+
+    let version = match required_u64(fields, "version")? {
+        1 => 1,
+        2 => 2,
+        _ => return Err("Unsupported envelope version".to_string()),
+    };
+    let algorithm = match required_u64(fields, "algorithm")? {
+        1 => CryptoAlgorithm::AES256GCM,
+        2 => CryptoAlgorithm::ChaCha20Poly1305,
+        _ => return Err("Unsupported algorithm".to_string()),
+    };
+
+    let nonce_length = required_bytes(fields, "nonce")?.len();
+    if nonce_length != NONCE_LENGTH {
+        return Err(format!("Invalid nonce length: {}", nonce_length));
+    }
+    let tag_length = required_bytes(fields, "tag")?.len();
+    if tag_length != TAG_LENGTH {
+        return Err(format!("Invalid tag length: {}", tag_length));
+    }
+    let ciphertext_length = required_bytes(fields, "encrypted_data")?.len();
+    if ciphertext_length == 0 {
+        return Err("Envelope has no ciphertext".to_string());
+    }
+
+    let aad_digest = match optional_bytes(fields, "aad_hash")? {
+        Some(digest) if digest.is_empty() => None,
+        Some(digest) if digest.len() == AAD_DIGEST_LENGTH => {
+            Some(to_hex(&digest))
+        }
+        Some(digest) => return Err(format!("Invalid AAD digest length: {}", digest.len())),
+        None => None,
+    };
+
+    let key_id = optional_str(fields, "key_id")?.map(str::to_string);
+    let schema_version = match optional_u64(fields, "schema_version")? {
+        Some(version) => Some(u32::try_from(version).map_err(|_| "Schema version out of range".to_string())?),
+        None => None,
+    };
+    let kdf_algorithm = match fields.get("kdf_params") {
+        None | Some(Value::Null) => None,
+        Some(Value::Object(params)) => Some(optional_str(params, "algorithm")?
+            .ok_or("KDF parameters are missing an algorithm")?
+            .to_string()),
+        Some(_) => return Err("Field kdf_params must be an object".to_string()),
+    };
+    let scope_tagged = optional_bytes(fields, "user_scope_tag")?.is_some_and(|tag| !tag.is_empty());
+    let order_token = optional_str(fields, "order_token")?.map(str::to_string);
+    optional_bytes(fields, "salt")?;
+
+    Ok(EnvelopeHeader {
+        version,
+        algorithm,
+        key_id,
+        kdf_algorithm,
+        schema_version,
+        scope_tagged,
+        order_token,
+        aad_digest,
+        ciphertext_length,
+        size_class: ciphertext_length.max(MIN_SIZE_CLASS).next_power_of_two(),
+        padding_hint: padding_hint(ciphertext_length),
+    })
+}
+
+fn padding_hint(length: usize) -> PaddingPolicy {
+    if length.is_multiple_of(FIXED_BLOCK_SIZE) {
+        PaddingPolicy::FixedBlock
+    } else if length >= MIN_SIZE_CLASS && length.is_power_of_two() {
+        PaddingPolicy::PowerOfTwoBuckets
+    } else {
+        PaddingPolicy::None
+    }
+}
+
+fn required_u64(fields: &Map<String, Value>, name: &str) -> Result<u64, String> {
+    optional_u64(fields, name)?.ok_or_else(|| format!("Missing field: {}", name))
+}
+
+fn optional_u64(fields: &Map<String, Value>, name: &str) -> Result<Option<u64>, String> {
+    match fields.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or_else(|| format!("Field {} must be an unsigned integer", name)),
+    }
+}
+
+fn optional_str<'a>(fields: &'a Map<String, Value>, name: &str) -> Result<Option<&'a str>, String> {
+    match fields.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_str().map(Some).ok_or_else(|| format!("Field {} must be a string", name)),
+    }
+}
+
+fn required_bytes(fields: &Map<String, Value>, name: &str) -> Result<Vec<u8>, String> {
+    optional_bytes(fields, name)?.ok_or_else(|| format!("Missing field: {}", name))
+}
+
+fn optional_bytes(fields: &Map<String, Value>, name: &str) -> Result<Option<Vec<u8>>, String> {
+    optional_str(fields, name)?
+        .map(|encoded| decode_base64(encoded).map_err(|e| format!("Field {}: {}", name, e)))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::envelope::base64_encode;
+
+    fn envelope_json(ciphertext_length: usize) -> serde_json::Value {
+        serde_json::json!({
+            "version": 2,
+            "algorithm": 1,
+            "kdf_params": { "algorithm": "argon2id", "iterations": 3 },
+            "salt": base64_encode(&[1u8; 16]),
+            "nonce": base64_encode(&[2u8; 12]),
+            "key_id": "cycle_data-1.2.0",
+            "encrypted_data": base64_encode(&vec![3u8; ciphertext_length]),
+            "tag": base64_encode(&[4u8; 16]),
+            "aad_hash": base64_encode(&[5u8; 32]),
+            "schema_version": 3
+        })
+    }
+
+    #[test]
+    fn test_inspect_summarizes_header() {
+        let bytes = serde_json::to_vec(&envelope_json(1024)).unwrap();
+        let header = inspect_envelope_bytes(&bytes).unwrap();
+
+        assert_eq!(header.algorithm(), CryptoAlgorithm::AES256GCM);
+        assert_eq!(header.key_id().as_deref(), Some("cycle_data-1.2.0"));
+        assert_eq!(header.kdf_algorithm().as_deref(), Some("argon2id"));
+        assert_eq!(header.schema_version(), Some(3));
+        assert_eq!(header.aad_digest().unwrap().len(), 64);
+        assert_eq!((header.size_class(), header.padding_hint()), (1024, PaddingPolicy::PowerOfTwoBuckets));
+        assert!(!header.scope_tagged());
+    }
+
+    #[test]
+    fn test_inspect_accepts_serialized_envelopes() {
+        use crate::envelope::{serialize_envelope, CryptoEnvelope};
+
+        let mut envelope = CryptoEnvelope::new();
+        envelope.set_nonce(vec![2u8; 12]);
+        envelope.set_tag(vec![4u8; 16]);
+        envelope.set_encrypted_data(vec![3u8; 300]);
+        envelope.set_key_id("cycle_data-1.2.0".to_string());
+        envelope.set_order_token("0001a".to_string());
+        let serialized = serialize_envelope(&envelope).unwrap();
+
+        let header = inspect_envelope_bytes(serialized.as_bytes()).unwrap();
+        assert_eq!(header.order_token().as_deref(), Some("0001a"));
+        assert_eq!(header.key_id().as_deref(), Some("cycle_data-1.2.0"));
+        assert_eq!((header.ciphertext_length(), header.size_class()), (300, 512));
+
+        // Envelopes written without a token serialize it as null
+        envelope = CryptoEnvelope::new();
+        envelope.set_nonce(vec![2u8; 12]);
+        envelope.set_tag(vec![4u8; 16]);
+        envelope.set_encrypted_data(vec![3u8; 16]);
+        let header = inspect_envelope_bytes(serialize_envelope(&envelope).unwrap().as_bytes()).unwrap();
+        assert_eq!(header.order_token(), None);
+    }
+
+    #[test]
+    fn test_inspect_rejects_malformed_headers() {
+        let reject = |mutate: fn(&mut serde_json::Value)| {
+            let mut json = envelope_json(100);
+            mutate(&mut json);
+            inspect_envelope_bytes(&serde_json::to_vec(&json).unwrap()).unwrap_err()
+        };
+
+        assert!(reject(|json| json["algorithm"] = 9.into()).contains("Unsupported algorithm"));
+        assert!(reject(|json| json["nonce"] = base64_encode(&[0u8; 8]).into()).contains("nonce length"));
+        assert!(reject(|json| json["tag"] = "!!!!".into()).contains("Invalid base64"));
+        assert!(reject(|json| json["plaintext"] = "oops".into()).contains("Unknown envelope field"));
+        assert!(reject(|json| { json.as_object_mut().unwrap().remove("version"); }).contains("Missing field"));
+        assert!(inspect_envelope_bytes(b"[1, 2]").is_err());
+    }
+}
@@ -2,9 +2,14 @@
 // This module provides interfaces and foundations for upcoming implementations
 
 use serde::{Deserialize, Serialize};
-// use wasm_bindgen::prelude::*; // Reserved for future use
+use wasm_bindgen::prelude::*;
+use std::cell::RefCell;
+use crate::derivation::DataCategory;
 use crate::envelope::CryptoEnvelope;
+use crate::keys::CryptoKey;
 use crate::SecureBuffer;
+use crate::platform;
+use crate::scope::{scoped_error, ErrorScope};
 
 /// Device-specific key management interface (Story 1.4 dependency)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -114,13 +119,13 @@ impl AuthContext {
 
     /// Validate if auth context is still valid
     pub fn is_valid(&self) -> bool {
-        let now = js_sys::Date::now() as u64 / 1000;
+        let now = platform::now_ms() / 1000;
         self.expires_at > now
     }
 
     /// Get remaining validity time in seconds
     pub fn remaining_validity(&self) -> u64 {
-        let now = js_sys::Date::now() as u64 / 1000;
+        let now = platform::now_ms() / 1000;
         if self.expires_at > now {
             self.expires_at - now
         } else {
@@ -197,7 +202,7 @@ impl KeyVersion {
     /// Get key age in seconds
 
     pub fn get_age(&self) -> u64 {
-        let now = js_sys::Date::now() as u64 / 1000;
+        let now = platform::now_ms() / 1000;
         now.saturating_sub(self.created_at)
     }
 }
@@ -268,6 +273,8 @@ pub struct HealthCheckResult {
     pub crypto_health: String,
     /// Memory health status
     pub memory_health: String,
+    /// Host callback breakers, e.g. "storage:closed,transport:open"
+    pub host_callback_health: String,
     /// Performance metrics (if enabled)
     pub performance_metrics: Option<String>,
     /// Security status (if enabled)
@@ -281,6 +288,7 @@ impl HealthCheckResult {
         timestamp: u64,
         crypto_health: String,
         memory_health: String,
+        host_callback_health: String,
         performance_metrics: Option<String>,
         security_status: Option<String>,
     ) -> Self {
@@ -289,6 +297,7 @@ impl HealthCheckResult {
             timestamp,
             crypto_health,
             memory_health,
+            host_callback_health,
             performance_metrics,
             security_status,
         }
@@ -306,7 +315,8 @@ pub fn perform_health_check(config: &HealthCheckConfig) -> HealthCheckResult {
     if !config.enabled {
         return HealthCheckResult::new(
             "disabled".to_string(),
-            js_sys::Date::now() as u64 / 1000,
+            platform::now_ms() / 1000,
+            "disabled".to_string(),
             "disabled".to_string(),
             "disabled".to_string(),
             None,
@@ -314,7 +324,7 @@ pub fn perform_health_check(config: &HealthCheckConfig) -> HealthCheckResult {
         );
     }
 
-    let timestamp = js_sys::Date::now() as u64 / 1000;
+    let timestamp = platform::now_ms() / 1000;
     
     // Check crypto operations health
     let crypto_health = match test_crypto_operations() {
@@ -332,9 +342,22 @@ pub fn perform_health_check(config: &HealthCheckConfig) -> HealthCheckResult {
         "critical".to_string() // > 10MB
     };
     
+    // Host callback breakers; any breaker not closed degrades the result
+    let breakers = crate::host_calls::host_breaker_states();
+    let host_callbacks_closed = breakers.iter().all(|(_, state)| *state == crate::host_calls::BreakerState::Closed);
+    let host_callback_health = breakers
+        .iter()
+        .map(|(kind, state)| format!("{}:{}", kind, format!("{:?}", state).to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(",");
+    
     // Performance metrics (if enabled)
     let performance_metrics = if config.include_performance {
-        Some(format!("memory_allocated: {} bytes", crate::memory::get_memory_stats().secrets_allocated))
+        Some(format!(
+            "memory_allocated: {} bytes, subsystem_init: {}",
+            crate::memory::get_memory_stats().secrets_allocated,
+            crate::lazy_init::subsystem_init_metrics()
+        ))
     } else {
         None
     };
@@ -347,9 +370,9 @@ pub fn perform_health_check(config: &HealthCheckConfig) -> HealthCheckResult {
     };
     
     // Determine overall status
-    let overall_status = match (&crypto_health[..], &memory_health[..]) {
-        ("healthy", "healthy") => "healthy",
-        ("healthy", "warning") => "degraded",
+    let overall_status = match (&crypto_health[..], &memory_health[..], host_callbacks_closed) {
+        ("healthy", "healthy", true) => "healthy",
+        ("healthy", "healthy" | "warning", _) => "degraded",
         _ => "unhealthy",
     };
     
@@ -358,6 +381,7 @@ pub fn perform_health_check(config: &HealthCheckConfig) -> HealthCheckResult {
         timestamp,
         crypto_health,
         memory_health,
+        host_callback_health,
         performance_metrics,
         security_status,
     )
@@ -467,7 +491,7 @@ impl MonitoringMetrics {
     /// Update last health check timestamp
 
     pub fn update_health_check_timestamp(&mut self) {
-        self.last_health_check = js_sys::Date::now() as u64 / 1000;
+        self.last_health_check = platform::now_ms() / 1000;
     }
 
     /// Get summary report
@@ -507,4 +531,277 @@ pub fn update_global_metrics(metrics: MonitoringMetrics) {
     unsafe {
         GLOBAL_METRICS = Some(metrics);
     }
-}
\ No newline at end of file
+}
+
+// Companion mode roles
+// A clinician or caregiver companion session runs under a restricted role. While it
+// is active the crate itself refuses key rotation, recovery and device management,
+// and decrypts only the categories the owner shared, so hiding buttons in the UI is
+// a convenience rather than the enforcement point. The owner fixes the shared
+// categories when creating the capabilities and binds them to the fingerprint of an
+// owner key; entering and leaving companion mode both require that key, so the
+// companion can neither widen its own access nor drop back to the owner role.
+
+/// Who is driving the crypto API
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiRole {
+    Owner,
+    Clinician,
+    Caregiver,
+}
+
+/// Operations reserved to the owner role
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestrictedOperation {
+    Decrypt,
+    KeyRotation,
+    Recovery,
+    DeviceManagement,
+}
+
+// Per wasm instance; thread-local like the scope stack, so parallel tests each see their own
+thread_local! {
+    static ACTIVE_COMPANION: RefCell<Option<CompanionCapabilities>> = const { RefCell::new(None) };
+}
+
+/// Restricted capability object for a companion session
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompanionCapabilities {
+    role: ApiRole,
+    shared_categories: Vec<DataCategory>,
+    owner_fingerprint: String,
+}
+
+#[wasm_bindgen]
+impl CompanionCapabilities {
+    /// Companion role that may decrypt `categories` (e.g. "cycle_data"), bound to `owner_key`
+    #[wasm_bindgen(constructor)]
+    pub fn new(role: ApiRole, categories: Vec<String>, owner_key: &CryptoKey) -> Result<CompanionCapabilities, JsValue> {
+        Self::for_owner(role, &categories, owner_key).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn role(&self) -> ApiRole {
+        self.role
+    }
+
+    #[wasm_bindgen]
+    pub fn can_decrypt(&self, category: DataCategory) -> bool {
+        self.shared_categories.contains(&category)
+    }
+
+    /// Decrypt shared category data; goes through the same checks as `decrypt_category_data`
+    #[wasm_bindgen]
+    pub fn decrypt(
+        &self,
+        encrypted_data: &[u8],
+        envelope: &CryptoEnvelope,
+        key: &CryptoKey,
+        category: DataCategory,
+    ) -> Result<Vec<u8>, JsValue> {
+        self.check_category(&category).map_err(|denied| JsValue::from_str(&denied.to_string()))?;
+        crate::decrypt_category_data(encrypted_data, envelope, key, category)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+impl CompanionCapabilities {
+    pub(crate) fn for_owner(role: ApiRole, categories: &[String], owner_key: &CryptoKey) -> Result<CompanionCapabilities, String> {
+        if role == ApiRole::Owner {
+            return Err("Companion capabilities require a companion role".to_string());
+        }
+        let owner_fingerprint = owner_key.fingerprint().ok_or_else(|| "Owner key is not initialized".to_string())?;
+        let mut shared_categories = Vec::new();
+        for name in categories {
+            let category = DataCategory::from_string(name).ok_or_else(|| format!("Unknown data category: {}", name))?;
+            if !shared_categories.contains(&category) {
+                shared_categories.push(category);
+            }
+        }
+        Ok(CompanionCapabilities { role, shared_categories, owner_fingerprint })
+    }
+
+    fn is_owned_by(&self, owner_key: &CryptoKey) -> bool {
+        owner_key.fingerprint().is_some_and(|fingerprint| {
+            crate::security::constant_time_compare(fingerprint.as_bytes(), self.owner_fingerprint.as_bytes())
+        })
+    }
+
+    fn denied(&self, operation: RestrictedOperation, category: Option<DataCategory>) -> RoleDenied {
+        RoleDenied { role: self.role, operation, category, scope: ErrorScope::capture() }
+    }
+
+    fn check_category(&self, category: &DataCategory) -> Result<(), RoleDenied> {
+        if self.can_decrypt(category.clone()) {
+            Ok(())
+        } else {
+            Err(self.denied(RestrictedOperation::Decrypt, Some(category.clone())))
+        }
+    }
+}
+
+// Typed error raised when the active role may not perform an operation
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleDenied {
+    role: ApiRole,
+    operation: RestrictedOperation,
+    category: Option<DataCategory>,
+    scope: ErrorScope,
+}
+
+#[wasm_bindgen]
+impl RoleDenied {
+    #[wasm_bindgen(getter)]
+    pub fn role(&self) -> ApiRole {
+        self.role
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn operation(&self) -> RestrictedOperation {
+        self.operation
+    }
+
+    // Set for denied decrypts only
+    #[wasm_bindgen(getter)]
+    pub fn category(&self) -> Option<DataCategory> {
+        self.category.clone()
+    }
+}
+
+impl std::fmt::Display for RoleDenied {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.category {
+            Some(category) => write!(f, "Role {:?} may not decrypt {}", self.role, category.to_string())?,
+            None => write!(f, "Role {:?} may not perform {:?}", self.role, self.operation)?,
+        }
+        write!(f, "{}", self.scope)
+    }
+}
+
+scoped_error!(RoleDenied);
+
+/// Switch the crate into companion mode with `capabilities` until `exit_companion_mode`;
+/// `owner_key` must be the key the capabilities were created with
+#[wasm_bindgen]
+pub fn enter_companion_mode(capabilities: &CompanionCapabilities, owner_key: &CryptoKey) -> Result<(), JsValue> {
+    enter_companion(capabilities, owner_key).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Return to the owner role; `owner_key` proves the owner is back
+#[wasm_bindgen]
+pub fn exit_companion_mode(owner_key: &CryptoKey) -> Result<(), JsValue> {
+    exit_companion(owner_key).map_err(|e| JsValue::from_str(&e))
+}
+
+pub(crate) fn enter_companion(capabilities: &CompanionCapabilities, owner_key: &CryptoKey) -> Result<(), String> {
+    ACTIVE_COMPANION.with(|active| {
+        let mut active = active.borrow_mut();
+        if active.is_some() {
+            return Err("A companion session is already active".to_string());
+        }
+        if !capabilities.is_owned_by(owner_key) {
+            return Err("Companion capabilities were not issued with this owner key".to_string());
+        }
+        *active = Some(capabilities.clone());
+        Ok(())
+    })
+}
+
+pub(crate) fn exit_companion(owner_key: &CryptoKey) -> Result<(), String> {
+    ACTIVE_COMPANION.with(|active| {
+        let mut active = active.borrow_mut();
+        match active.as_ref() {
+            None => Ok(()),
+            Some(companion) if companion.is_owned_by(owner_key) => {
+                *active = None;
+                Ok(())
+            }
+            Some(_) => Err("Leaving companion mode requires the owner key".to_string()),
+        }
+    })
+}
+
+#[wasm_bindgen]
+pub fn active_api_role() -> ApiRole {
+    ACTIVE_COMPANION.with(|active| active.borrow().as_ref().map_or(ApiRole::Owner, |companion| companion.role))
+}
+
+/// Fails unless the owner role is active
+pub(crate) fn require_owner(operation: RestrictedOperation) -> Result<(), RoleDenied> {
+    ACTIVE_COMPANION.with(|active| {
+        active.borrow().as_ref().map_or(Ok(()), |companion| Err(companion.denied(operation, None)))
+    })
+}
+
+/// Fails if a companion session is active and `category` was not shared with it
+pub(crate) fn check_category_access(category: &DataCategory) -> Result<(), RoleDenied> {
+    ACTIVE_COMPANION.with(|active| {
+        active.borrow().as_ref().map_or(Ok(()), |companion| companion.check_category(category))
+    })
+}
+
+// Owner-only entry points map the denial into their JS error
+pub(crate) fn require_owner_js(operation: RestrictedOperation) -> Result<(), JsValue> {
+    require_owner(operation).map_err(|denied| JsValue::from_str(&denied.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_companion_capabilities_limit_categories() {
+        let owner_key = crate::generate_key().unwrap();
+        let shared = ["cycle_data".to_string(), "cycle_data".to_string()];
+        let caregiver = CompanionCapabilities::for_owner(ApiRole::Caregiver, &shared, &owner_key).unwrap();
+        assert_eq!(caregiver.shared_categories.len(), 1);
+        assert!(CompanionCapabilities::for_owner(ApiRole::Owner, &shared, &owner_key).is_err());
+        assert!(CompanionCapabilities::for_owner(ApiRole::Caregiver, &["diary".to_string()], &owner_key).is_err());
+
+        assert!(caregiver.check_category(&DataCategory::CycleData).is_ok());
+        let denied = caregiver.check_category(&DataCategory::HealthcareSharing).unwrap_err();
+        assert_eq!((denied.role(), denied.operation()), (ApiRole::Caregiver, RestrictedOperation::Decrypt));
+        assert_eq!(denied.to_string(), "Role Caregiver may not decrypt healthcare_sharing");
+        assert_eq!(
+            caregiver.denied(RestrictedOperation::Recovery, None).to_string(),
+            "Role Caregiver may not perform Recovery"
+        );
+
+        assert_eq!(active_api_role(), ApiRole::Owner);
+        assert!(require_owner(RestrictedOperation::KeyRotation).is_ok());
+        assert!(check_category_access(&DataCategory::HealthcareSharing).is_ok());
+    }
+
+    #[test]
+    fn test_active_companion_session_refuses_owner_operations() {
+        let owner_key = crate::generate_key().unwrap();
+        let other_key = crate::generate_key().unwrap();
+        let clinician = CompanionCapabilities::for_owner(ApiRole::Clinician, &["healthcare_sharing".to_string()], &owner_key).unwrap();
+
+        assert!(enter_companion(&clinician, &other_key).unwrap_err().contains("not issued with this owner key"));
+        enter_companion(&clinician, &owner_key).unwrap();
+        assert_eq!(active_api_role(), ApiRole::Clinician);
+
+        for operation in [RestrictedOperation::KeyRotation, RestrictedOperation::Recovery, RestrictedOperation::DeviceManagement] {
+            assert_eq!(require_owner(operation).unwrap_err().operation(), operation);
+        }
+        let mut emergency = crate::key_rotation::emergency::EmergencyRotationManager::new();
+        let refused = emergency.trigger_emergency_rotation("compromised_device", "test", Vec::new(), 9).unwrap_err();
+        assert!(refused.starts_with("Role Clinician may not perform KeyRotation"));
+        assert!(check_category_access(&DataCategory::HealthcareSharing).is_ok());
+        assert!(check_category_access(&DataCategory::CycleData).is_err());
+
+        // Neither a second session nor a foreign key gets the companion out
+        let wider = CompanionCapabilities::for_owner(ApiRole::Clinician, &["cycle_data".to_string()], &other_key).unwrap();
+        assert!(enter_companion(&wider, &other_key).unwrap_err().contains("already active"));
+        assert!(exit_companion(&other_key).unwrap_err().contains("requires the owner key"));
+        assert_eq!(active_api_role(), ApiRole::Clinician);
+
+        exit_companion(&owner_key).unwrap();
+        assert!(require_owner(RestrictedOperation::KeyRotation).is_ok());
+    }
+}
@@ -0,0 +1,503 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use zeroize::Zeroizing;
+use crate::backup_hardening::HardenedBackup;
+use crate::derivation::DataCategory;
+use crate::envelope::KDFParams;
+use crate::integration::{require_owner, RestrictedOperation};
+use crate::oprf::OprfBlind;
+use crate::platform;
+use crate::recovery::{KeyBackup, RecoverySystem};
+use crate::security::SecureKDF;
+use crate::verifier::VerifierKey;
+
+// Progressive KDF rehashing
+// Calibration (`DeviceCapabilityDetector::select_kdf_params`) can recommend stronger
+// password-KDF parameters at any time, but artifacts wrapped under the old ones can
+// only be re-derived while the password is at hand. The registry keeps each
+// password-wrapped secret together with the parameters it was wrapped under; after a
+// successful unlock of a stale artifact it re-derives under the target parameters and
+// re-wraps with a fresh salt. The rehash only runs when the time spent on the unlock
+// plus the measured cost of a target derivation fits the per-unlock budget, so a slow
+// device defers it to a later unlock instead of stalling the user. An artifact is only
+// stale when the target costs at least as much memory and work as its own parameters,
+// so a lowered target applies to new wraps but never weakens existing ones.
+//
+// The password unlock paths of local key backups (`RecoverySystem`), hardened backups
+// (the password path's KDF output) and verifier keys keep their secret in a registry,
+// so each unlock through them is also a rehash opportunity.
+
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+pub(crate) const WRAP_AAD_DOMAIN: &str = "aura-password-wrap-v1";
+const KEY_BACKUP_KIND: &str = "backup";
+const HARDENED_BACKUP_KIND: &str = "hardened_backup";
+const VERIFIER_KIND: &str = "verifier";
+const HARDENED_KDF_OUTPUT_LENGTH: usize = 32;
+
+/// Secret wrapped under a password-derived key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct WrappedArtifact {
+    kind: String, // "backup", "verifier", ...
+    kdf: serde_json::Value,
+    salt: Vec<u8>,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+    wrapped_at: u64,
+}
+
+/// Rehash progress across the registered artifacts
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RehashProgress {
+    total: usize,
+    current: usize,
+    stale_ids: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl RehashProgress {
+    #[wasm_bindgen(getter)]
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Artifacts already wrapped under the target parameters
+    #[wasm_bindgen(getter)]
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn stale_ids(&self) -> Vec<String> {
+        self.stale_ids.clone()
+    }
+
+    /// Fraction of artifacts on the target parameters; 1.0 for an empty registry
+    #[wasm_bindgen]
+    pub fn completion(&self) -> f64 {
+        if self.total == 0 { 1.0 } else { self.current as f64 / self.total as f64 }
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Password-wrapped secrets that follow the recommended KDF parameters
+#[wasm_bindgen]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KdfRehashRegistry {
+    target_kdf: serde_json::Value,
+    budget_ms: u64,
+    target_cost_ms: Option<u64>, // Last measured target derivation time
+    artifacts: BTreeMap<String, WrappedArtifact>,
+}
+
+#[wasm_bindgen]
+impl KdfRehashRegistry {
+    /// `budget_ms` bounds the time one unlock may take including its rehash
+    #[wasm_bindgen(constructor)]
+    pub fn new(target: &KDFParams, budget_ms: u64) -> Result<KdfRehashRegistry, JsValue> {
+        Self::with_target(target, budget_ms).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Adopt new recommended parameters; every artifact wrapped under others becomes stale
+    #[wasm_bindgen]
+    pub fn set_target_params(&mut self, target: &KDFParams) -> Result<(), JsValue> {
+        self.retarget(target).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn set_budget_ms(&mut self, budget_ms: u64) {
+        self.budget_ms = budget_ms;
+    }
+
+    /// Wrap `secret` under `password` with the target parameters, replacing any artifact with this id
+    #[wasm_bindgen]
+    pub fn wrap(&mut self, artifact_id: String, kind: String, secret: &[u8], password: &[u8]) -> Result<(), JsValue> {
+        self.target()
+            .and_then(|target| self.wrap_with(artifact_id, kind, secret, password, &target))
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Unwrap an artifact; rehashes it when stale and the budget allows
+    #[wasm_bindgen]
+    pub fn unlock(&mut self, artifact_id: &str, password: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.unlock_at(artifact_id, password).map(|secret| secret.to_vec()).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn remove(&mut self, artifact_id: &str) -> bool {
+        self.artifacts.remove(artifact_id).is_some()
+    }
+
+    /// True when the artifact would be rehashed on its next unlock
+    #[wasm_bindgen]
+    pub fn is_stale(&self, artifact_id: &str) -> bool {
+        let Some(artifact) = self.artifacts.get(artifact_id) else {
+            return false;
+        };
+        if artifact.kdf == self.target_kdf {
+            return false;
+        }
+        match (self.target(), KDFParams::from_json_value(&artifact.kdf)) {
+            (Ok(target), Some(current)) => target.at_least_as_strong_as(&current),
+            _ => false,
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn progress(&self) -> RehashProgress {
+        let stale_ids: Vec<String> = self.artifacts.keys().filter(|id| self.is_stale(id)).cloned().collect();
+        RehashProgress { total: self.artifacts.len(), current: self.artifacts.len() - stale_ids.len(), stale_ids }
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize rehash registry: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<KdfRehashRegistry, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid rehash registry: {}", e)))
+    }
+}
+
+impl KdfRehashRegistry {
+    pub(crate) fn with_target(target: &KDFParams, budget_ms: u64) -> Result<Self, String> {
+        let mut registry =
+            KdfRehashRegistry { target_kdf: serde_json::Value::Null, budget_ms, target_cost_ms: None, artifacts: BTreeMap::new() };
+        registry.retarget(target)?;
+        Ok(registry)
+    }
+
+    fn retarget(&mut self, target: &KDFParams) -> Result<(), String> {
+        if target.kdf_algorithm().is_none() {
+            return Err(format!("Unsupported KDF algorithm: {}", target.algorithm()));
+        }
+        let target_kdf = target.to_json_value();
+        if target_kdf != self.target_kdf {
+            self.target_kdf = target_kdf;
+            self.target_cost_ms = None;
+        }
+        Ok(())
+    }
+
+    fn target(&self) -> Result<KDFParams, String> {
+        KDFParams::from_json_value(&self.target_kdf).ok_or_else(|| "Rehash registry has no target parameters".to_string())
+    }
+
+    pub(crate) fn wrap_with(
+        &mut self,
+        artifact_id: String,
+        kind: String,
+        secret: &[u8],
+        password: &[u8],
+        params: &KDFParams,
+    ) -> Result<(), String> {
+        let mut salt = vec![0u8; SALT_LENGTH];
+        let mut nonce = vec![0u8; NONCE_LENGTH];
+        platform::fill_random(&mut salt);
+        platform::fill_random(&mut nonce);
+
+        let started_at = platform::now_ms();
+        let key = Zeroizing::new(SecureKDF::derive_with_params(password, &salt, params, KEY_LENGTH)?);
+        let kdf = params.to_json_value();
+        if kdf == self.target_kdf {
+            self.target_cost_ms = Some(platform::now_ms().saturating_sub(started_at));
+        }
+
+        let aad = wrap_aad(&artifact_id, &kind);
+        let ciphertext = Aes256Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| "Invalid wrapping key".to_string())?
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: secret, aad: &aad })
+            .map_err(|_| "Artifact wrapping failed".to_string())?;
+
+        let artifact = WrappedArtifact { kind, kdf, salt, nonce, ciphertext, wrapped_at: platform::now_ms() };
+        self.artifacts.insert(artifact_id, artifact);
+        Ok(())
+    }
+
+    pub(crate) fn unlock_at(&mut self, artifact_id: &str, password: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+        let started_at = platform::now_ms();
+        let artifact = self.artifacts
+            .get(artifact_id)
+            .ok_or_else(|| format!("Unknown artifact: {}", artifact_id))?;
+        let params = KDFParams::from_json_value(&artifact.kdf)
+            .ok_or_else(|| format!("Artifact {} has unreadable KDF parameters", artifact_id))?;
+        if artifact.nonce.len() != NONCE_LENGTH {
+            return Err(format!("Artifact {} has a malformed nonce", artifact_id));
+        }
+
+        let key = Zeroizing::new(SecureKDF::derive_with_params(password, &artifact.salt, &params, KEY_LENGTH)?);
+        let aad = wrap_aad(artifact_id, &artifact.kind);
+        let secret = Zeroizing::new(
+            Aes256Gcm::new_from_slice(key.as_ref())
+                .map_err(|_| "Invalid wrapping key".to_string())?
+                .decrypt(Nonce::from_slice(&artifact.nonce), Payload { msg: &artifact.ciphertext, aad: &aad })
+                .map_err(|_| "Wrong password or corrupted artifact".to_string())?,
+        );
+
+        if self.is_stale(artifact_id) && self.rehash_fits(platform::now_ms().saturating_sub(started_at)) {
+            let kind = artifact.kind.clone();
+            let target = self.target()?;
+            // A failed rehash leaves the old, still valid artifact in place
+            let _ = self.wrap_with(artifact_id.to_string(), kind, &secret, password, &target);
+        }
+        Ok(secret)
+    }
+
+    // Unlock through one of the crate's unlock paths; the artifact must be of `kind`
+    fn unlock_kind(&mut self, artifact_id: &str, kind: &str, password: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+        match self.artifacts.get(artifact_id) {
+            Some(artifact) if artifact.kind == kind => self.unlock_at(artifact_id, password),
+            Some(artifact) => Err(format!("Artifact {} is a {}, not a {}", artifact_id, artifact.kind, kind)),
+            None => Err(format!("Unknown artifact: {}", artifact_id)),
+        }
+    }
+
+    fn wrap_kind(&mut self, artifact_id: &str, kind: &str, secret: &[u8], password: &[u8]) -> Result<(), String> {
+        let target = self.target()?;
+        self.wrap_with(artifact_id.to_string(), kind.to_string(), secret, password, &target)
+    }
+
+    // Unknown cost (first use of new parameters) is attempted once to measure it
+    fn rehash_fits(&self, spent_ms: u64) -> bool {
+        self.target_cost_ms.is_none_or(|cost| spent_ms.saturating_add(cost) <= self.budget_ms)
+    }
+}
+
+#[wasm_bindgen]
+impl RecoverySystem {
+    /// Keep a password-protected local copy of a backup in `registry`, under the backup id
+    #[wasm_bindgen]
+    pub fn keep_backup_with_password(
+        &self,
+        backup_id: String,
+        registry: &mut KdfRehashRegistry,
+        password: &[u8],
+    ) -> Result<(), JsValue> {
+        self.keep_backup_in(&backup_id, registry, password).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Restore the local copy of a backup; rehashes it when stale
+    #[wasm_bindgen]
+    pub fn unlock_backup_with_password(
+        &mut self,
+        backup_id: String,
+        registry: &mut KdfRehashRegistry,
+        password: &[u8],
+    ) -> Result<(), JsValue> {
+        self.unlock_backup_from(&backup_id, registry, password).map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl RecoverySystem {
+    pub(crate) fn keep_backup_in(&self, backup_id: &str, registry: &mut KdfRehashRegistry, password: &[u8]) -> Result<(), String> {
+        require_owner(RestrictedOperation::Recovery).map_err(|denied| denied.to_string())?;
+        let backup = self.backup(backup_id).ok_or_else(|| "Backup not found".to_string())?;
+        let serialized = Zeroizing::new(serde_json::to_vec(backup).map_err(|e| format!("Failed to serialize backup: {}", e))?);
+        registry.wrap_kind(backup_id, KEY_BACKUP_KIND, &serialized, password)
+    }
+
+    pub(crate) fn unlock_backup_from(&mut self, backup_id: &str, registry: &mut KdfRehashRegistry, password: &[u8]) -> Result<(), String> {
+        require_owner(RestrictedOperation::Recovery).map_err(|denied| denied.to_string())?;
+        let serialized = registry.unlock_kind(backup_id, KEY_BACKUP_KIND, password)?;
+        let backup: KeyBackup = serde_json::from_slice(&serialized).map_err(|_| "Stored backup is malformed".to_string())?;
+        if backup.backup_id() != backup_id {
+            return Err("Stored backup does not match its id".to_string());
+        }
+        self.insert_backup(backup);
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl HardenedBackup {
+    /// Start a password path: the KDF output is random and kept in `registry` under
+    /// `artifact_id`, where it follows the recommended KDF parameters. Evaluate the
+    /// returned blind on the server, then pass both to `seal`.
+    #[wasm_bindgen]
+    pub fn enroll_password(
+        password: &[u8],
+        registry: &mut KdfRehashRegistry,
+        artifact_id: String,
+    ) -> Result<OprfBlind, JsValue> {
+        Self::enroll_in(password, registry, &artifact_id).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Blind the password path's KDF output from `registry` for `open_with_oprf`;
+    /// rehashes the artifact when stale
+    #[wasm_bindgen]
+    pub fn blind_password_from_registry(
+        registry: &mut KdfRehashRegistry,
+        artifact_id: String,
+        password: &[u8],
+    ) -> Result<OprfBlind, JsValue> {
+        Self::blind_from(registry, &artifact_id, password).map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl HardenedBackup {
+    pub(crate) fn enroll_in(password: &[u8], registry: &mut KdfRehashRegistry, artifact_id: &str) -> Result<OprfBlind, String> {
+        let mut kdf_output = Zeroizing::new(vec![0u8; HARDENED_KDF_OUTPUT_LENGTH]);
+        platform::fill_random(&mut kdf_output);
+        registry.wrap_kind(artifact_id, HARDENED_BACKUP_KIND, &kdf_output, password)?;
+        Self::blind_kdf_output(&kdf_output)
+    }
+
+    pub(crate) fn blind_from(registry: &mut KdfRehashRegistry, artifact_id: &str, password: &[u8]) -> Result<OprfBlind, String> {
+        let kdf_output = registry.unlock_kind(artifact_id, HARDENED_BACKUP_KIND, password)?;
+        Self::blind_kdf_output(&kdf_output)
+    }
+}
+
+#[wasm_bindgen]
+impl VerifierKey {
+    /// Keep the verifier key in `registry` under `artifact_id`, protected by `password`
+    #[wasm_bindgen]
+    pub fn store_with_password(&self, registry: &mut KdfRehashRegistry, artifact_id: String, password: &[u8]) -> Result<(), JsValue> {
+        self.store_in(registry, &artifact_id, password).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Load a stored verifier key; rehashes it when stale
+    #[wasm_bindgen]
+    pub fn unlock_with_password(registry: &mut KdfRehashRegistry, artifact_id: String, password: &[u8]) -> Result<VerifierKey, JsValue> {
+        Self::unlock_from(registry, &artifact_id, password).map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl VerifierKey {
+    // The key followed by "category|purpose|key_version"
+    pub(crate) fn store_in(&self, registry: &mut KdfRehashRegistry, artifact_id: &str, password: &[u8]) -> Result<(), String> {
+        let mut payload = Zeroizing::new(self.key_bytes().to_vec());
+        payload.extend_from_slice(format!("{}|{}|{}", self.category().to_string(), self.purpose(), self.key_version()).as_bytes());
+        registry.wrap_kind(artifact_id, VERIFIER_KIND, &payload, password)
+    }
+
+    pub(crate) fn unlock_from(registry: &mut KdfRehashRegistry, artifact_id: &str, password: &[u8]) -> Result<VerifierKey, String> {
+        let payload = registry.unlock_kind(artifact_id, VERIFIER_KIND, password)?;
+        let malformed = || "Stored verifier key is malformed".to_string();
+        if payload.len() <= KEY_LENGTH {
+            return Err(malformed());
+        }
+        let (key, label) = payload.split_at(KEY_LENGTH);
+        let label = std::str::from_utf8(label).map_err(|_| malformed())?;
+        let mut fields = label.splitn(3, '|');
+        let (Some(category), Some(purpose), Some(key_version)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(malformed());
+        };
+        let category = DataCategory::from_string(category).ok_or_else(malformed)?;
+        let key: [u8; KEY_LENGTH] = key.try_into().map_err(|_| malformed())?;
+        Ok(VerifierKey::from_stored(key, category, purpose.to_string(), key_version.to_string()))
+    }
+}
+
+fn wrap_aad(artifact_id: &str, kind: &str) -> Vec<u8> {
+    format!("{}|{}|{}", WRAP_AAD_DOMAIN, kind, artifact_id).into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_artifacts_are_rehashed_on_unlock_within_budget() {
+        let weak = KDFParams::argon2id(1, 1024, 1);
+        let strong = KDFParams::argon2id(2, 2048, 1);
+        let mut registry = KdfRehashRegistry::with_target(&weak, 60_000).unwrap();
+        registry.wrap_with("backup-1".into(), "backup".into(), b"master key", b"hunter2", &weak).unwrap();
+        registry.wrap_with("verifier-1".into(), "verifier".into(), b"verifier key", b"hunter2", &weak).unwrap();
+        assert_eq!(registry.progress().completion(), 1.0);
+
+        registry.retarget(&strong).unwrap();
+        assert_eq!(registry.progress().stale_ids(), vec!["backup-1".to_string(), "verifier-1".to_string()]);
+
+        assert!(registry.unlock_at("backup-1", b"wrong").is_err());
+        assert!(registry.is_stale("backup-1"));
+
+        assert_eq!(registry.unlock_at("backup-1", b"hunter2").unwrap().as_slice(), b"master key");
+        assert!(!registry.is_stale("backup-1"));
+        assert_eq!(registry.progress().completion(), 0.5);
+        assert_eq!(registry.unlock_at("backup-1", b"hunter2").unwrap().as_slice(), b"master key");
+
+        // Once the target cost is known, an unlock without budget left defers the rehash
+        registry.budget_ms = 0;
+        registry.target_cost_ms = Some(1);
+        assert_eq!(registry.unlock_at("verifier-1", b"hunter2").unwrap().as_slice(), b"verifier key");
+        assert!(registry.is_stale("verifier-1"));
+    }
+
+    #[test]
+    fn test_weaker_targets_never_rehash_and_bad_nonces_are_refused() {
+        let weak = KDFParams::argon2id(1, 1024, 1);
+        let strong = KDFParams::argon2id(2, 2048, 1);
+        let mut registry = KdfRehashRegistry::with_target(&strong, 60_000).unwrap();
+        registry.wrap_with("backup-1".into(), "backup".into(), b"master key", b"hunter2", &strong).unwrap();
+
+        // A lowered target is used for new wraps only
+        registry.retarget(&weak).unwrap();
+        assert!(!registry.is_stale("backup-1"));
+        registry.unlock_at("backup-1", b"hunter2").unwrap();
+        assert_eq!(registry.artifacts["backup-1"].kdf, strong.to_json_value());
+        assert!(!registry.is_stale("backup-1"));
+
+        // Less memory but more passes is not at least as strong either, nor is scrypt over Argon2id
+        assert!(!KDFParams::argon2id(8, 1024, 1).at_least_as_strong_as(&strong));
+        assert!(!KDFParams::scrypt(20, 8, 4).at_least_as_strong_as(&weak));
+        assert!(KDFParams::argon2id(1, 16 * 1024, 1).at_least_as_strong_as(&KDFParams::scrypt(14, 8, 1)));
+
+        registry.artifacts.get_mut("backup-1").unwrap().nonce.truncate(4);
+        assert_eq!(registry.unlock_at("backup-1", b"hunter2").unwrap_err(), "Artifact backup-1 has a malformed nonce");
+    }
+
+    #[test]
+    fn test_unlock_paths_rehash_backups_and_verifier_keys() {
+        use crate::oprf::OprfServerKey;
+        use crate::recovery::{RecoveryPhrase, WordlistLanguage};
+
+        let weak = KDFParams::argon2id(1, 1024, 1);
+        let strong = KDFParams::argon2id(2, 2048, 1);
+        let mut registry = KdfRehashRegistry::with_target(&weak, 60_000).unwrap();
+
+        let mut system = RecoverySystem::new("device-1".to_string(), 0, 3, 60_000);
+        let phrase = RecoveryPhrase::generate(128, WordlistLanguage::English as u8).unwrap();
+        let backup = system.create_backup(&crate::keys::CryptoKey::new("encryption".to_string()), &phrase, vec![1, 2]).unwrap();
+        system.keep_backup_in(&backup.backup_id(), &mut registry, b"hunter2").unwrap();
+
+        let seed = phrase.to_seed("").unwrap();
+        let server = OprfServerKey::generate();
+        let blind = HardenedBackup::enroll_in(b"hunter2", &mut registry, "hardened-1").unwrap();
+        let evaluated = server.evaluate_element(&blind.blinded_element()).unwrap();
+        let sealed = HardenedBackup::seal_with(b"master key bytes", &blind, &evaluated, &seed).unwrap();
+        let verifier = VerifierKey::from_branch_key(&[1u8; 32], DataCategory::DeviceSync, "email".into(), "1.0.0".into()).unwrap();
+        verifier.store_in(&mut registry, "verifier-1", b"hunter2").unwrap();
+
+        registry.retarget(&strong).unwrap();
+        assert_eq!(registry.progress().stale_ids().len(), 3);
+
+        let mut restored = RecoverySystem::new("device-1".to_string(), 0, 3, 60_000);
+        restored.unlock_backup_from(&backup.backup_id(), &mut registry, b"hunter2").unwrap();
+        assert_eq!(restored.backup(&backup.backup_id()).unwrap().encrypted_master_key(), backup.encrypted_master_key());
+        assert!(restored.unlock_backup_from("hardened-1", &mut registry, b"hunter2").unwrap_err().contains("not a backup"));
+
+        let blind = HardenedBackup::blind_from(&mut registry, "hardened-1", b"hunter2").unwrap();
+        let evaluated = server.evaluate_element(&blind.blinded_element()).unwrap();
+        assert_eq!(sealed.open_password_path(&blind, &evaluated).unwrap(), b"master key bytes");
+        assert!(HardenedBackup::blind_from(&mut registry, "hardened-1", b"wrong").is_err());
+
+        let reloaded = VerifierKey::unlock_from(&mut registry, "verifier-1", b"hunter2").unwrap();
+        let salt = [9u8; 16];
+        assert!(reloaded.compute(b"user@example.com", &salt).unwrap().matches(&verifier.compute(b"user@example.com", &salt).unwrap()));
+
+        assert_eq!(registry.progress().completion(), 1.0);
+    }
+}
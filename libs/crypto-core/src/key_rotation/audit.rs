@@ -1,15 +1,132 @@
 use wasm_bindgen::prelude::*;
 use super::types::{KeyVersion, SecurityEventType};
-use super::versioned_key::VersionedKey;
-use std::collections::HashMap;
-use js_sys::Date;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use crate::envelope::{decode_hex, to_hex};
+use crate::inbox::device_fingerprint;
+use crate::lazy_init::{LazySubsystem, Subsystem};
+use crate::platform;
+use crate::scope::ScopeContext;
+use crate::signing::{self, SigningKeyPair};
+
+// Audit trails are kept per key (crypto events) or per external source (app and
+// backend events). Every entry is hash-chained to the previous entry of its trail
+// when appended, so edits, reordering or removal inside a trail show up in
+// `validate_audit_integrity`, and one export covers all trails.
+//
+// The chain alone cannot tell a rewritten trail from the original, so the manager
+// exports an `AuditTrailHead`: each trail's length and newest link, signed with the
+// device's Ed25519 key. A head anchored off-device later pins every trail's prefix.
+// Backend events are only accepted with a signature by the backend's registered key;
+// app events come from the app itself and need none.
+
+const EXTERNAL_TRAIL_PREFIX: &str = "external:";
+const REHEARSAL_TRAIL_PREFIX: &str = "recovery_rehearsal:";
+const MAX_EXTERNAL_ATTRIBUTES: usize = 32;
+const MAX_EXTERNAL_NAME_LENGTH: usize = 64;
+const MAX_EXTERNAL_VALUE_LENGTH: usize = 256;
+pub(crate) const TRAIL_HEAD_CONTEXT: &[u8] = b"aura-audit-trail-head-v1";
+pub(crate) const BACKEND_EVENT_CONTEXT: &[u8] = b"aura-backend-audit-event-v1";
 
 /// Comprehensive audit trail for key rotation events
 #[wasm_bindgen]
 pub struct AuditTrailManager {
     audit_entries: HashMap<String, Vec<AuditEntry>>,
-    integrity_validators: HashMap<String, String>,
-    compliance_rules: Vec<ComplianceRule>,
+    compliance_rules: LazySubsystem<Vec<ComplianceRule>>, // Defaults built on first rule access
+    compliance_limits: ComplianceLimits,
+    backend_public_key: Option<Vec<u8>>,
+}
+
+/// Resource limits for compliance rule evaluation. Rule definitions can come from
+/// users, so both registration and evaluation are bounded; an evaluation that hits
+/// a limit stops between keys and reports what it covered
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComplianceLimits {
+    max_events_scanned: usize,
+    max_rules: usize,
+    max_event_filters_per_rule: usize,
+    evaluation_timeout_ms: u64,
+}
+
+impl Default for ComplianceLimits {
+    fn default() -> Self {
+        Self {
+            max_events_scanned: 100_000,
+            max_rules: 64,
+            max_event_filters_per_rule: 16,
+            evaluation_timeout_ms: 2_000,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ComplianceLimits {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        max_events_scanned: usize,
+        max_rules: usize,
+        max_event_filters_per_rule: usize,
+        evaluation_timeout_ms: u64,
+    ) -> Result<ComplianceLimits, JsValue> {
+        if max_events_scanned == 0 || max_rules == 0 || max_event_filters_per_rule == 0 || evaluation_timeout_ms == 0 {
+            return Err(JsValue::from_str("Compliance limits must be positive"));
+        }
+        Ok(ComplianceLimits { max_events_scanned, max_rules, max_event_filters_per_rule, evaluation_timeout_ms })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_events_scanned(&self) -> usize {
+        self.max_events_scanned
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_rules(&self) -> usize {
+        self.max_rules
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_event_filters_per_rule(&self) -> usize {
+        self.max_event_filters_per_rule
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn evaluation_timeout_ms(&self) -> u64 {
+        self.evaluation_timeout_ms
+    }
+}
+
+/// Limit that cut a compliance evaluation short
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ComplianceLimitHit {
+    EventsScanned,
+    Rules,
+    Timeout,
+}
+
+impl ComplianceLimitHit {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ComplianceLimitHit::EventsScanned => "max_events_scanned",
+            ComplianceLimitHit::Rules => "max_rules",
+            ComplianceLimitHit::Timeout => "evaluation_timeout",
+        }
+    }
+}
+
+/// Outcome of one bounded evaluation pass
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ComplianceEvaluation {
+    total_events: u32,
+    violations: Vec<ComplianceViolation>,
+    incidents: Vec<SecurityIncident>,
+    rotation_stats: Vec<(String, String)>,
+    events_scanned: usize,
+    rules_evaluated: usize,
+    keys_evaluated: usize,
+    keys_skipped: usize,
+    limit_hit: Option<ComplianceLimitHit>,
 }
 
 /// Individual audit entry for rotation events
@@ -18,6 +135,7 @@ pub struct AuditEntry {
     pub entry_id: String,
     pub timestamp: f64,
     pub event_type: AuditEventType,
+    pub source: AuditEventSource,
     pub key_version_from: Option<KeyVersion>,
     pub key_version_to: Option<KeyVersion>,
     pub trigger_reason: String,
@@ -26,6 +144,7 @@ pub struct AuditEntry {
     pub device_id: String,
     pub user_id: String,
     pub metadata: HashMap<String, String>,
+    pub scope: ScopeContext,
     pub integrity_hash: String,
 }
 
@@ -44,6 +163,47 @@ pub enum AuditEventType {
     CrossDeviceSync,
     SecurityIncident,
     ComplianceCheck,
+    ExternalEvent,
+    RecoveryRehearsal,
+}
+
+/// Origin of an audit entry
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditEventSource {
+    Crypto = 0,
+    App = 1,
+    Backend = 2,
+}
+
+impl AuditEventSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventSource::Crypto => "crypto",
+            AuditEventSource::App => "app",
+            AuditEventSource::Backend => "backend",
+        }
+    }
+}
+
+/// Accepted shape of an ingested app-layer or backend event
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExternalAuditEvent {
+    source: String,
+    event_type: String,
+    occurred_at: u64,
+    user_id: String,
+    #[serde(default)]
+    device_id: String,
+    #[serde(default = "default_success")]
+    success: bool,
+    #[serde(default)]
+    attributes: BTreeMap<String, String>,
+}
+
+fn default_success() -> bool {
+    true
 }
 
 /// Compliance rule for audit validation
@@ -65,19 +225,6 @@ pub enum ComplianceSeverity {
     Critical,
 }
 
-/// Audit report for compliance
-#[wasm_bindgen]
-pub struct AuditReport {
-    report_id: String,
-    generated_at: f64,
-    period_start: f64,
-    period_end: f64,
-    total_events: u32,
-    compliance_violations: Vec<ComplianceViolation>,
-    security_incidents: Vec<SecurityIncident>,
-    rotation_statistics: js_sys::Object,
-}
-
 /// Compliance violation record
 #[derive(Clone, Debug)]
 pub struct ComplianceViolation {
@@ -101,21 +248,102 @@ pub struct SecurityIncident {
     pub resolved: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TrailHead {
+    length: u64,
+    head: String,
+}
+
+/// Signed length and newest link of every trail at export time
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditTrailHead {
+    trails: BTreeMap<String, TrailHead>,
+    exported_at: u64,
+    digest: String,
+    signer_key_id: String,
+    signature: String,
+}
+
+#[wasm_bindgen]
+impl AuditTrailHead {
+    #[wasm_bindgen(getter)]
+    pub fn exported_at(&self) -> u64 {
+        self.exported_at
+    }
+
+    /// Hex SHA-256 over every trail id, length and head
+    #[wasm_bindgen(getter)]
+    pub fn digest(&self) -> String {
+        self.digest.clone()
+    }
+
+    /// Fingerprint of the signing key's public key
+    #[wasm_bindgen(getter)]
+    pub fn signer_key_id(&self) -> String {
+        self.signer_key_id.clone()
+    }
+
+    /// True if the trails match the digest and the digest is signed by the holder of
+    /// `signer_public_key`, which must come from the signer, not from this head
+    #[wasm_bindgen]
+    pub fn verify(&self, signer_public_key: &[u8]) -> bool {
+        let Ok(signature) = decode_hex(&self.signature) else {
+            return false;
+        };
+        self.digest == self.compute_digest()
+            && self.signer_key_id == device_fingerprint(signer_public_key)
+            && signing::verify(signer_public_key, &self.signed_message(), &signature)
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize trail head: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<AuditTrailHead, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid trail head: {}", e)))
+    }
+}
+
+impl AuditTrailHead {
+    fn compute_digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (trail_id, head) in &self.trails {
+            hasher.update((trail_id.len() as u64).to_be_bytes());
+            hasher.update(trail_id.as_bytes());
+            hasher.update(head.length.to_be_bytes());
+            hasher.update(head.head.as_bytes());
+        }
+        hasher.update(self.exported_at.to_be_bytes());
+        to_hex(&hasher.finalize())
+    }
+
+    fn signed_message(&self) -> Vec<u8> {
+        [TRAIL_HEAD_CONTEXT, self.digest.as_bytes()].concat()
+    }
+}
+
+impl Default for AuditTrailManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen]
 impl AuditTrailManager {
     /// Create new audit trail manager
     #[wasm_bindgen(constructor)]
     pub fn new() -> AuditTrailManager {
-        let mut manager = AuditTrailManager {
+        AuditTrailManager {
             audit_entries: HashMap::new(),
-            integrity_validators: HashMap::new(),
-            compliance_rules: Vec::new(),
-        };
-        
-        // Initialize default compliance rules
-        manager.initialize_default_compliance_rules();
-        
-        manager
+            compliance_rules: LazySubsystem::new(Subsystem::AuditComplianceRules, default_compliance_rules),
+            compliance_limits: ComplianceLimits::default(),
+            backend_public_key: None,
+        }
     }
 
     /// Record key rotation start event
@@ -130,7 +358,7 @@ impl AuditTrailManager {
         user_id: &str
     ) -> String {
         let entry_id = self.generate_entry_id();
-        let timestamp = Date::now();
+        let timestamp = platform::now_ms() as f64;
         
         let mut metadata = HashMap::new();
         metadata.insert("operation".to_string(), "key_rotation".to_string());
@@ -147,8 +375,10 @@ impl AuditTrailManager {
             error_details: None,
             device_id: device_id.to_string(),
             user_id: user_id.to_string(),
+            source: AuditEventSource::Crypto,
             metadata,
-            integrity_hash: self.calculate_integrity_hash(&entry_id, timestamp, "RotationStarted"),
+            scope: ScopeContext::default(), // Filled in add_audit_entry
+            integrity_hash: String::new(), // Chained in add_audit_entry
         };
         
         self.add_audit_entry(key_id, entry);
@@ -167,7 +397,7 @@ impl AuditTrailManager {
         user_id: &str
     ) -> String {
         let entry_id = self.generate_entry_id();
-        let timestamp = Date::now();
+        let timestamp = platform::now_ms() as f64;
         
         let mut metadata = HashMap::new();
         metadata.insert("operation".to_string(), "key_rotation".to_string());
@@ -185,8 +415,10 @@ impl AuditTrailManager {
             error_details: None,
             device_id: device_id.to_string(),
             user_id: user_id.to_string(),
+            source: AuditEventSource::Crypto,
             metadata,
-            integrity_hash: self.calculate_integrity_hash(&entry_id, timestamp, "RotationCompleted"),
+            scope: ScopeContext::default(), // Filled in add_audit_entry
+            integrity_hash: String::new(), // Chained in add_audit_entry
         };
         
         self.add_audit_entry(key_id, entry);
@@ -204,7 +436,7 @@ impl AuditTrailManager {
         user_id: &str
     ) -> String {
         let entry_id = self.generate_entry_id();
-        let timestamp = Date::now();
+        let timestamp = platform::now_ms() as f64;
         
         let mut metadata = HashMap::new();
         metadata.insert("operation".to_string(), "key_rotation".to_string());
@@ -221,8 +453,10 @@ impl AuditTrailManager {
             error_details: Some(error_details.to_string()),
             device_id: device_id.to_string(),
             user_id: user_id.to_string(),
+            source: AuditEventSource::Crypto,
             metadata,
-            integrity_hash: self.calculate_integrity_hash(&entry_id, timestamp, "RotationFailed"),
+            scope: ScopeContext::default(), // Filled in add_audit_entry
+            integrity_hash: String::new(), // Chained in add_audit_entry
         };
         
         self.add_audit_entry(key_id, entry);
@@ -241,7 +475,7 @@ impl AuditTrailManager {
         user_id: &str
     ) -> String {
         let entry_id = self.generate_entry_id();
-        let timestamp = Date::now();
+        let timestamp = platform::now_ms() as f64;
         
         let mut metadata = HashMap::new();
         metadata.insert("operation".to_string(), "emergency_rotation".to_string());
@@ -266,8 +500,10 @@ impl AuditTrailManager {
             error_details: None,
             device_id: device_id.to_string(),
             user_id: user_id.to_string(),
+            source: AuditEventSource::Crypto,
             metadata,
-            integrity_hash: self.calculate_integrity_hash(&entry_id, timestamp, "EmergencyRotation"),
+            scope: ScopeContext::default(), // Filled in add_audit_entry
+            integrity_hash: String::new(), // Chained in add_audit_entry
         };
         
         self.add_audit_entry(key_id, entry);
@@ -276,6 +512,7 @@ impl AuditTrailManager {
 
     /// Record data migration events
     #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
     pub fn record_migration_event(
         &mut self,
         key_id: &str,
@@ -288,7 +525,7 @@ impl AuditTrailManager {
         user_id: &str
     ) -> String {
         let entry_id = self.generate_entry_id();
-        let timestamp = Date::now();
+        let timestamp = platform::now_ms() as f64;
         
         let mut metadata = HashMap::new();
         metadata.insert("operation".to_string(), "data_migration".to_string());
@@ -313,8 +550,10 @@ impl AuditTrailManager {
             error_details,
             device_id: device_id.to_string(),
             user_id: user_id.to_string(),
+            source: AuditEventSource::Crypto,
             metadata,
-            integrity_hash: self.calculate_integrity_hash(&entry_id, timestamp, event_type),
+            scope: ScopeContext::default(), // Filled in add_audit_entry
+            integrity_hash: String::new(), // Chained in add_audit_entry
         };
         
         self.add_audit_entry(key_id, entry);
@@ -332,7 +571,7 @@ impl AuditTrailManager {
         user_id: &str
     ) -> String {
         let entry_id = self.generate_entry_id();
-        let timestamp = Date::now();
+        let timestamp = platform::now_ms() as f64;
         
         let mut metadata = HashMap::new();
         metadata.insert("operation".to_string(), "cross_device_sync".to_string());
@@ -357,14 +596,72 @@ impl AuditTrailManager {
             error_details: if sync_success { None } else { Some("Sync failed".to_string()) },
             device_id: source_device.to_string(),
             user_id: user_id.to_string(),
+            source: AuditEventSource::Crypto,
             metadata,
-            integrity_hash: self.calculate_integrity_hash(&entry_id, timestamp, "CrossDeviceSync"),
+            scope: ScopeContext::default(), // Filled in add_audit_entry
+            integrity_hash: String::new(), // Chained in add_audit_entry
         };
         
         self.add_audit_entry(key_id, entry);
         entry_id
     }
 
+    /// Append an app-layer event (JSON, see `ExternalAuditEvent`) to the app trail;
+    /// returns the entry id. Backend events go through `ingest_backend_event`
+    #[wasm_bindgen]
+    pub fn ingest_external_event(&mut self, event_json: &str) -> Result<String, JsValue> {
+        self.ingest_external_event_at(event_json, platform::now_ms())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Public key of the backend's Ed25519 audit signing key
+    #[wasm_bindgen]
+    pub fn set_backend_public_key(&mut self, public_key: &[u8]) -> Result<(), JsValue> {
+        if public_key.len() != signing::PUBLIC_KEY_LENGTH {
+            return Err(JsValue::from_str("Backend public key must be 32 bytes"));
+        }
+        self.backend_public_key = Some(public_key.to_vec());
+        Ok(())
+    }
+
+    /// Append a backend event whose exact JSON bytes the backend signed (Ed25519 over
+    /// the backend event context and the JSON); returns the entry id
+    #[wasm_bindgen]
+    pub fn ingest_backend_event(&mut self, event_json: &str, signature: &[u8]) -> Result<String, JsValue> {
+        self.ingest_backend_event_at(event_json, signature, platform::now_ms())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Sign the current length and newest link of every trail, for anchoring off-device
+    #[wasm_bindgen]
+    pub fn export_signed_head(&self, signer: &SigningKeyPair) -> AuditTrailHead {
+        self.signed_head_at(signer, platform::now_ms())
+    }
+
+    /// True if `head` is signed by `signer_public_key` and every trail it covers still
+    /// extends it: at least as long, the same link at its length, and an intact chain
+    #[wasm_bindgen]
+    pub fn extends_anchored_head(&self, head: &AuditTrailHead, signer_public_key: &[u8]) -> bool {
+        head.verify(signer_public_key)
+            && head.trails.iter().all(|(trail_id, anchored)| {
+                let Some(entries) = self.audit_entries.get(trail_id) else {
+                    return false;
+                };
+                let length = anchored.length as usize;
+                length > 0
+                    && entries.len() >= length
+                    && entries[length - 1].integrity_hash == anchored.head
+                    && chain_intact(entries)
+            })
+    }
+
+    /// Every trail, crypto and external, as one JSON array ordered by time
+    #[wasm_bindgen]
+    pub fn export_audit_trail(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.unified_export())
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize audit export: {}", e)))
+    }
+
     /// Get audit trail for specific key
     #[wasm_bindgen]
     pub fn get_audit_trail(&self, key_id: &str) -> js_sys::Array {
@@ -377,10 +674,12 @@ impl AuditTrailManager {
                 js_sys::Reflect::set(&entry_obj, &JsValue::from_str("entryId"), &JsValue::from_str(&entry.entry_id)).unwrap();
                 js_sys::Reflect::set(&entry_obj, &JsValue::from_str("timestamp"), &JsValue::from_f64(entry.timestamp)).unwrap();
                 js_sys::Reflect::set(&entry_obj, &JsValue::from_str("eventType"), &JsValue::from_str(&format!("{:?}", entry.event_type))).unwrap();
+                js_sys::Reflect::set(&entry_obj, &JsValue::from_str("source"), &JsValue::from_str(entry.source.as_str())).unwrap();
                 js_sys::Reflect::set(&entry_obj, &JsValue::from_str("triggerReason"), &JsValue::from_str(&entry.trigger_reason)).unwrap();
                 js_sys::Reflect::set(&entry_obj, &JsValue::from_str("success"), &JsValue::from_bool(entry.success)).unwrap();
                 js_sys::Reflect::set(&entry_obj, &JsValue::from_str("deviceId"), &JsValue::from_str(&entry.device_id)).unwrap();
                 js_sys::Reflect::set(&entry_obj, &JsValue::from_str("userId"), &JsValue::from_str(&entry.user_id)).unwrap();
+                js_sys::Reflect::set(&entry_obj, &JsValue::from_str("scope"), &JsValue::from_str(&entry.scope.to_json())).unwrap();
                 js_sys::Reflect::set(&entry_obj, &JsValue::from_str("integrityHash"), &JsValue::from_str(&entry.integrity_hash)).unwrap();
                 
                 if let Some(error) = &entry.error_details {
@@ -408,30 +707,9 @@ impl AuditTrailManager {
         let mut is_valid = true;
         let issues = js_sys::Array::new();
         
-        if let Some(entries) = self.audit_entries.get(key_id) {
-            for entry in entries.iter() {
-                let expected_hash = self.calculate_integrity_hash(
-                    &entry.entry_id,
-                    entry.timestamp,
-                    &format!("{:?}", entry.event_type)
-                );
-                
-                if entry.integrity_hash != expected_hash {
-                    is_valid = false;
-                    let issue = format!("Integrity mismatch for entry {}", entry.entry_id);
-                    issues.push(&JsValue::from_str(&issue));
-                }
-            }
-            
-            // Check for chronological ordering
-            for i in 1..entries.len() {
-                if entries[i].timestamp < entries[i-1].timestamp {
-                    is_valid = false;
-                    let issue = format!("Chronological order violation between entries {} and {}", 
-                                      entries[i-1].entry_id, entries[i].entry_id);
-                    issues.push(&JsValue::from_str(&issue));
-                }
-            }
+        for issue in self.integrity_issues(key_id) {
+            is_valid = false;
+            issues.push(&JsValue::from_str(&issue));
         }
         
         js_sys::Reflect::set(&result, &JsValue::from_str("isValid"), &JsValue::from_bool(is_valid)).unwrap();
@@ -443,7 +721,13 @@ impl AuditTrailManager {
         result
     }
 
-    /// Generate compliance report
+    #[wasm_bindgen]
+    pub fn set_compliance_limits(&mut self, limits: &ComplianceLimits) {
+        self.compliance_limits = *limits;
+    }
+
+    /// Generate compliance report. When a resource limit is hit the report is
+    /// marked `partial` and names the limit; keys not reached are counted in `keysSkipped`
     #[wasm_bindgen]
     pub fn generate_compliance_report(
         &self,
@@ -451,56 +735,8 @@ impl AuditTrailManager {
         period_end: f64
     ) -> js_sys::Object {
         let report_id = self.generate_entry_id();
-        let generated_at = Date::now();
-        
-        let mut total_events = 0u32;
-        let mut violations = Vec::new();
-        let mut incidents = Vec::new();
-        let mut rotation_stats = HashMap::new();
-        
-        // Analyze all audit entries within the period
-        for (key_id, entries) in &self.audit_entries {
-            let period_entries: Vec<_> = entries.iter()
-                .filter(|entry| entry.timestamp >= period_start && entry.timestamp <= period_end)
-                .collect();
-            
-            total_events += period_entries.len() as u32;
-            
-            // Check compliance rules
-            for rule in &self.compliance_rules {
-                if let Some(violation) = self.check_compliance_rule(rule, &period_entries, key_id) {
-                    violations.push(violation);
-                }
-            }
-            
-            // Collect security incidents
-            for entry in &period_entries {
-                if entry.event_type == AuditEventType::EmergencyRotation || 
-                   entry.event_type == AuditEventType::SecurityIncident {
-                    let incident = SecurityIncident {
-                        incident_id: entry.entry_id.clone(),
-                        incident_type: SecurityEventType::DeviceCompromise, // Default
-                        severity: ComplianceSeverity::High,
-                        description: entry.trigger_reason.clone(),
-                        timestamp: entry.timestamp,
-                        response_actions: vec!["emergency_rotation".to_string()],
-                        resolved: entry.success,
-                    };
-                    incidents.push(incident);
-                }
-            }
-            
-            // Calculate rotation statistics
-            let successful_rotations = period_entries.iter()
-                .filter(|e| e.event_type == AuditEventType::RotationCompleted)
-                .count();
-            let failed_rotations = period_entries.iter()
-                .filter(|e| e.event_type == AuditEventType::RotationFailed)
-                .count();
-            
-            rotation_stats.insert(format!("{}_successful", key_id), successful_rotations.to_string());
-            rotation_stats.insert(format!("{}_failed", key_id), failed_rotations.to_string());
-        }
+        let generated_at = platform::now_ms() as f64;
+        let evaluation = self.evaluate_compliance(period_start, period_end, platform::now_ms);
         
         // Build report object
         let report = js_sys::Object::new();
@@ -508,13 +744,21 @@ impl AuditTrailManager {
         js_sys::Reflect::set(&report, &JsValue::from_str("generatedAt"), &JsValue::from_f64(generated_at)).unwrap();
         js_sys::Reflect::set(&report, &JsValue::from_str("periodStart"), &JsValue::from_f64(period_start)).unwrap();
         js_sys::Reflect::set(&report, &JsValue::from_str("periodEnd"), &JsValue::from_f64(period_end)).unwrap();
-        js_sys::Reflect::set(&report, &JsValue::from_str("totalEvents"), &JsValue::from_f64(total_events as f64)).unwrap();
-        js_sys::Reflect::set(&report, &JsValue::from_str("violationCount"), &JsValue::from_f64(violations.len() as f64)).unwrap();
-        js_sys::Reflect::set(&report, &JsValue::from_str("incidentCount"), &JsValue::from_f64(incidents.len() as f64)).unwrap();
+        js_sys::Reflect::set(&report, &JsValue::from_str("totalEvents"), &JsValue::from_f64(evaluation.total_events as f64)).unwrap();
+        js_sys::Reflect::set(&report, &JsValue::from_str("violationCount"), &JsValue::from_f64(evaluation.violations.len() as f64)).unwrap();
+        js_sys::Reflect::set(&report, &JsValue::from_str("incidentCount"), &JsValue::from_f64(evaluation.incidents.len() as f64)).unwrap();
+        js_sys::Reflect::set(&report, &JsValue::from_str("partial"), &JsValue::from_bool(evaluation.limit_hit.is_some())).unwrap();
+        if let Some(limit) = evaluation.limit_hit {
+            js_sys::Reflect::set(&report, &JsValue::from_str("limitHit"), &JsValue::from_str(limit.as_str())).unwrap();
+        }
+        js_sys::Reflect::set(&report, &JsValue::from_str("eventsScanned"), &JsValue::from_f64(evaluation.events_scanned as f64)).unwrap();
+        js_sys::Reflect::set(&report, &JsValue::from_str("rulesEvaluated"), &JsValue::from_f64(evaluation.rules_evaluated as f64)).unwrap();
+        js_sys::Reflect::set(&report, &JsValue::from_str("keysEvaluated"), &JsValue::from_f64(evaluation.keys_evaluated as f64)).unwrap();
+        js_sys::Reflect::set(&report, &JsValue::from_str("keysSkipped"), &JsValue::from_f64(evaluation.keys_skipped as f64)).unwrap();
         
         // Add rotation statistics
         let stats_obj = js_sys::Object::new();
-        for (key, value) in rotation_stats {
+        for (key, value) in evaluation.rotation_stats {
             js_sys::Reflect::set(&stats_obj, &JsValue::from_str(&key), &JsValue::from_str(&value)).unwrap();
         }
         js_sys::Reflect::set(&report, &JsValue::from_str("rotationStatistics"), &stats_obj).unwrap();
@@ -522,7 +766,8 @@ impl AuditTrailManager {
         report
     }
 
-    /// Add compliance rule
+    /// Add compliance rule; false once `max_rules` rules exist or the rule has more
+    /// than `max_event_filters_per_rule` required events
     #[wasm_bindgen]
     pub fn add_compliance_rule(
         &mut self,
@@ -564,48 +809,36 @@ impl AuditTrailManager {
             severity: compliance_severity,
         };
         
-        self.compliance_rules.push(rule);
-        true
+        self.add_rule(rule)
     }
 
     // Private helper methods
-    fn add_audit_entry(&mut self, key_id: &str, entry: AuditEntry) {
-        self.audit_entries
-            .entry(key_id.to_string())
-            .or_insert_with(Vec::new)
-            .push(entry);
-    }
-
-    fn generate_entry_id(&self) -> String {
-        format!("audit_{}", Date::now() as u64)
+    fn add_rule(&mut self, rule: ComplianceRule) -> bool {
+        if self.compliance_rules.get().len() >= self.compliance_limits.max_rules
+            || rule.required_events.len() > self.compliance_limits.max_event_filters_per_rule
+        {
+            return false;
+        }
+        self.compliance_rules.get_mut().push(rule);
+        true
     }
 
-    fn calculate_integrity_hash(&self, entry_id: &str, timestamp: f64, event_type: &str) -> String {
-        // Simple hash calculation - in production would use cryptographic hash
-        format!("hash_{}_{}_{}_{}", entry_id, timestamp as u64, event_type, "integrity_salt")
+    fn add_audit_entry(&mut self, key_id: &str, mut entry: AuditEntry) {
+        // Crypto trails are named after the key's purpose; the entry's own fields win
+        // over the surrounding operation scope
+        let mut scope = ScopeContext::new();
+        scope.set_purpose((!key_id.starts_with(EXTERNAL_TRAIL_PREFIX)).then(|| key_id.to_string()));
+        scope.set_key_version(entry.key_version_to.as_ref().map(|version| version.to_string()));
+        scope.set_device_id((!entry.device_id.is_empty()).then(|| entry.device_id.clone()));
+        entry.scope = scope.within(&ScopeContext::current());
+        let trail = self.audit_entries.entry(key_id.to_string()).or_default();
+        let previous = trail.last().map(|last| last.integrity_hash.as_str()).unwrap_or("");
+        entry.integrity_hash = chain_hash(previous, &entry);
+        trail.push(entry);
     }
 
-    fn initialize_default_compliance_rules(&mut self) {
-        // Rule: Every rotation start must have a completion or failure
-        let rotation_completion_rule = ComplianceRule {
-            rule_id: "rotation_completion".to_string(),
-            rule_name: "Rotation Completion Requirement".to_string(),
-            required_events: vec![AuditEventType::RotationStarted, AuditEventType::RotationCompleted],
-            max_time_between_events: 300000.0, // 5 minutes
-            severity: ComplianceSeverity::High,
-        };
-        
-        // Rule: Emergency rotations must be documented
-        let emergency_documentation_rule = ComplianceRule {
-            rule_id: "emergency_documentation".to_string(),
-            rule_name: "Emergency Rotation Documentation".to_string(),
-            required_events: vec![AuditEventType::EmergencyRotation],
-            max_time_between_events: 0.0, // Immediate
-            severity: ComplianceSeverity::Critical,
-        };
-        
-        self.compliance_rules.push(rotation_completion_rule);
-        self.compliance_rules.push(emergency_documentation_rule);
+    fn generate_entry_id(&self) -> String {
+        format!("audit_{}", platform::new_uuid())
     }
 
     fn check_compliance_rule(
@@ -617,30 +850,576 @@ impl AuditTrailManager {
         // Simple compliance checking logic
         // In production, this would be more sophisticated
         
-        match rule.rule_id.as_str() {
-            "rotation_completion" => {
-                let starts: Vec<_> = entries.iter()
-                    .filter(|e| e.event_type == AuditEventType::RotationStarted)
-                    .collect();
-                let completions: Vec<_> = entries.iter()
-                    .filter(|e| e.event_type == AuditEventType::RotationCompleted || 
-                              e.event_type == AuditEventType::RotationFailed)
-                    .collect();
-                
-                if starts.len() > completions.len() {
-                    return Some(ComplianceViolation {
-                        violation_id: self.generate_entry_id(),
-                        rule_id: rule.rule_id.clone(),
-                        severity: rule.severity.clone(),
-                        description: format!("Incomplete rotations found for key {}", key_id),
-                        timestamp: Date::now(),
-                        affected_events: starts.iter().map(|e| e.entry_id.clone()).collect(),
-                    });
-                }
+        if rule.rule_id == "rotation_completion" {
+            let starts: Vec<_> = entries.iter()
+                .filter(|e| e.event_type == AuditEventType::RotationStarted)
+                .collect();
+            let completions: Vec<_> = entries.iter()
+                .filter(|e| e.event_type == AuditEventType::RotationCompleted || 
+                          e.event_type == AuditEventType::RotationFailed)
+                .collect();
+            
+            if starts.len() > completions.len() {
+                return Some(ComplianceViolation {
+                    violation_id: self.generate_entry_id(),
+                    rule_id: rule.rule_id.clone(),
+                    severity: rule.severity.clone(),
+                    description: format!("Incomplete rotations found for key {}", key_id),
+                    timestamp: platform::now_ms() as f64,
+                    affected_events: starts.iter().map(|e| e.entry_id.clone()).collect(),
+                });
             }
-            _ => {}
         }
         
         None
     }
-}
\ No newline at end of file
+}
+
+impl AuditTrailManager {
+    // Keys are visited in sorted order so a partial result is reproducible, and each
+    // key is evaluated completely or not at all
+    pub(crate) fn evaluate_compliance(
+        &self,
+        period_start: f64,
+        period_end: f64,
+        clock: impl Fn() -> u64,
+    ) -> ComplianceEvaluation {
+        let limits = self.compliance_limits;
+        let started_at = clock();
+        let timed_out = || clock().saturating_sub(started_at) >= limits.evaluation_timeout_ms;
+        let mut evaluation = ComplianceEvaluation::default();
+
+        // Rules registered before the limits were tightened are not evaluated
+        let all_rules = self.compliance_rules.get();
+        let rules = &all_rules[..all_rules.len().min(limits.max_rules)];
+        if rules.len() < all_rules.len() {
+            evaluation.limit_hit = Some(ComplianceLimitHit::Rules);
+        }
+        evaluation.rules_evaluated = rules.len();
+
+        let mut key_ids: Vec<&String> = self.audit_entries.keys().collect();
+        key_ids.sort();
+
+        for (position, key_id) in key_ids.iter().enumerate() {
+            let entries = &self.audit_entries[*key_id];
+            let stop = if evaluation.events_scanned + entries.len() > limits.max_events_scanned {
+                Some(ComplianceLimitHit::EventsScanned)
+            } else if timed_out() {
+                Some(ComplianceLimitHit::Timeout)
+            } else {
+                None
+            };
+            if stop.is_some() {
+                evaluation.limit_hit = stop;
+                evaluation.keys_skipped = key_ids.len() - position;
+                break;
+            }
+
+            evaluation.events_scanned += entries.len();
+            let period_entries: Vec<_> = entries.iter()
+                .filter(|entry| entry.timestamp >= period_start && entry.timestamp <= period_end)
+                .collect();
+            
+            evaluation.total_events += period_entries.len() as u32;
+            
+            // Check compliance rules
+            for rule in rules {
+                if let Some(violation) = self.check_compliance_rule(rule, &period_entries, key_id) {
+                    evaluation.violations.push(violation);
+                }
+            }
+            
+            // Collect security incidents
+            for entry in &period_entries {
+                if entry.event_type == AuditEventType::EmergencyRotation || 
+                   entry.event_type == AuditEventType::SecurityIncident {
+                    let incident = SecurityIncident {
+                        incident_id: entry.entry_id.clone(),
+                        incident_type: SecurityEventType::DeviceCompromise, // Default
+                        severity: ComplianceSeverity::High,
+                        description: entry.trigger_reason.clone(),
+                        timestamp: entry.timestamp,
+                        response_actions: vec!["emergency_rotation".to_string()],
+                        resolved: entry.success,
+                    };
+                    evaluation.incidents.push(incident);
+                }
+            }
+            
+            // Calculate rotation statistics
+            let successful_rotations = period_entries.iter()
+                .filter(|e| e.event_type == AuditEventType::RotationCompleted)
+                .count();
+            let failed_rotations = period_entries.iter()
+                .filter(|e| e.event_type == AuditEventType::RotationFailed)
+                .count();
+            
+            evaluation.rotation_stats.push((format!("{}_successful", key_id), successful_rotations.to_string()));
+            evaluation.rotation_stats.push((format!("{}_failed", key_id), failed_rotations.to_string()));
+            evaluation.keys_evaluated += 1;
+        }
+
+        evaluation
+    }
+
+    pub(crate) fn ingest_external_event_at(&mut self, event_json: &str, now: u64) -> Result<String, String> {
+        self.ingest_at(event_json, None, now)
+    }
+
+    pub(crate) fn ingest_backend_event_at(&mut self, event_json: &str, signature: &[u8], now: u64) -> Result<String, String> {
+        let public_key = self.backend_public_key
+            .as_deref()
+            .ok_or_else(|| "No backend public key is registered".to_string())?;
+        if !signing::verify(public_key, &[BACKEND_EVENT_CONTEXT, event_json.as_bytes()].concat(), signature) {
+            return Err("Backend event signature is invalid".to_string());
+        }
+        let signer_key_id = device_fingerprint(public_key);
+        self.ingest_at(event_json, Some(signer_key_id), now)
+    }
+
+    pub(crate) fn signed_head_at(&self, signer: &SigningKeyPair, now: u64) -> AuditTrailHead {
+        let trails = self.audit_entries
+            .iter()
+            .filter_map(|(trail_id, entries)| {
+                let last = entries.last()?;
+                Some((trail_id.clone(), TrailHead { length: entries.len() as u64, head: last.integrity_hash.clone() }))
+            })
+            .collect();
+        let mut head = AuditTrailHead {
+            trails,
+            exported_at: now,
+            digest: String::new(),
+            signer_key_id: signer.key_id(),
+            signature: String::new(),
+        };
+        head.digest = head.compute_digest();
+        head.signature = to_hex(&signer.sign(&head.signed_message()));
+        head
+    }
+
+    // `backend_key_id` is set only after the backend's signature was verified
+    fn ingest_at(&mut self, event_json: &str, backend_key_id: Option<String>, now: u64) -> Result<String, String> {
+        let event: ExternalAuditEvent = serde_json::from_str(event_json)
+            .map_err(|e| format!("Invalid external audit event: {}", e))?;
+        let source = validate_external_event(&event)?;
+        match (source, &backend_key_id) {
+            (AuditEventSource::Backend, None) => return Err("Backend events must be signed by the backend".to_string()),
+            (AuditEventSource::Backend, Some(_)) => {}
+            (_, Some(_)) => return Err("Signed events must come from the backend".to_string()),
+            (_, None) => {}
+        }
+
+        let mut metadata: HashMap<String, String> = event.attributes
+            .into_iter()
+            .map(|(name, value)| (format!("attr.{}", name), value))
+            .collect();
+        metadata.insert("occurred_at".to_string(), event.occurred_at.to_string());
+        if let Some(key_id) = backend_key_id {
+            metadata.insert("backend_key_id".to_string(), key_id);
+        }
+
+        let entry_id = self.generate_entry_id();
+        let entry = AuditEntry {
+            entry_id: entry_id.clone(),
+            timestamp: now as f64,
+            event_type: AuditEventType::ExternalEvent,
+            source,
+            key_version_from: None,
+            key_version_to: None,
+            trigger_reason: event.event_type,
+            success: event.success,
+            error_details: None,
+            device_id: event.device_id,
+            user_id: event.user_id,
+            metadata,
+            scope: ScopeContext::default(), // Filled in add_audit_entry
+            integrity_hash: String::new(),
+        };
+        self.add_audit_entry(&format!("{}{}", EXTERNAL_TRAIL_PREFIX, source.as_str()), entry);
+        Ok(entry_id)
+    }
+
+    /// One step of a recovery rehearsal, or its overall result when `step` is None, on the
+    /// trail of the rehearsed backup; returns the entry id
+    pub(crate) fn record_recovery_rehearsal(
+        &mut self,
+        backup_id: &str,
+        rehearsal_id: &str,
+        device_id: &str,
+        step: Option<&str>,
+        outcome: &str,
+        now: u64,
+    ) -> String {
+        let mut metadata = HashMap::new();
+        metadata.insert("operation".to_string(), "recovery_rehearsal".to_string());
+        metadata.insert("rehearsal_id".to_string(), rehearsal_id.to_string());
+        metadata.insert("step".to_string(), step.unwrap_or("overall").to_string());
+        metadata.insert("outcome".to_string(), outcome.to_string());
+
+        let entry_id = self.generate_entry_id();
+        let entry = AuditEntry {
+            entry_id: entry_id.clone(),
+            timestamp: now as f64,
+            event_type: AuditEventType::RecoveryRehearsal,
+            source: AuditEventSource::Crypto,
+            key_version_from: None,
+            key_version_to: None,
+            trigger_reason: format!("recovery_rehearsal: {}", backup_id),
+            success: outcome != "failed",
+            error_details: None,
+            device_id: device_id.to_string(),
+            user_id: String::new(),
+            metadata,
+            scope: ScopeContext::default(), // Filled in add_audit_entry
+            integrity_hash: String::new(),
+        };
+        self.add_audit_entry(&format!("{}{}", REHEARSAL_TRAIL_PREFIX, backup_id), entry);
+        entry_id
+    }
+
+    /// Broken links and out-of-order timestamps in one trail
+    pub(crate) fn integrity_issues(&self, trail_id: &str) -> Vec<String> {
+        let Some(entries) = self.audit_entries.get(trail_id) else {
+            return Vec::new();
+        };
+        let mut issues = Vec::new();
+        let mut previous = "";
+        for entry in entries {
+            if entry.integrity_hash != chain_hash(previous, entry) {
+                issues.push(format!("Integrity mismatch for entry {}", entry.entry_id));
+            }
+            previous = &entry.integrity_hash;
+        }
+        for pair in entries.windows(2) {
+            if pair[1].timestamp < pair[0].timestamp {
+                issues.push(format!(
+                    "Chronological order violation between entries {} and {}",
+                    pair[0].entry_id, pair[1].entry_id
+                ));
+            }
+        }
+        issues
+    }
+
+    /// Whether the newest `tail_length` links of every trail still match their hashes
+    pub(crate) fn chain_tails_valid(&self, tail_length: usize) -> bool {
+        self.audit_entries.values().all(|entries| {
+            let start = entries.len().saturating_sub(tail_length);
+            (start..entries.len()).all(|i| {
+                let previous = if i == 0 { "" } else { entries[i - 1].integrity_hash.as_str() };
+                entries[i].integrity_hash == chain_hash(previous, &entries[i])
+            })
+        })
+    }
+
+    pub(crate) fn unified_export(&self) -> Vec<serde_json::Value> {
+        let mut rows: Vec<(&String, usize, &AuditEntry)> = self.audit_entries
+            .iter()
+            .flat_map(|(trail_id, entries)| entries.iter().enumerate().map(move |(i, entry)| (trail_id, i, entry)))
+            .collect();
+        rows.sort_by(|a, b| a.2.timestamp.total_cmp(&b.2.timestamp).then(a.0.cmp(b.0)).then(a.1.cmp(&b.1)));
+        rows.into_iter()
+            .map(|(trail_id, _, entry)| {
+                let metadata: BTreeMap<&String, &String> = entry.metadata.iter().collect();
+                serde_json::json!({
+                    "trail": trail_id,
+                    "entry_id": entry.entry_id,
+                    "timestamp": entry.timestamp,
+                    "source": entry.source.as_str(),
+                    "event_type": format!("{:?}", entry.event_type),
+                    "reason": entry.trigger_reason,
+                    "success": entry.success,
+                    "error_details": entry.error_details,
+                    "device_id": entry.device_id,
+                    "user_id": entry.user_id,
+                    "metadata": metadata,
+                    "scope": entry.scope.to_json_value(),
+                    "integrity_hash": entry.integrity_hash,
+                })
+            })
+            .collect()
+    }
+}
+
+fn default_compliance_rules() -> Vec<ComplianceRule> {
+    // Rule: Every rotation start must have a completion or failure
+    let rotation_completion_rule = ComplianceRule {
+        rule_id: "rotation_completion".to_string(),
+        rule_name: "Rotation Completion Requirement".to_string(),
+        required_events: vec![AuditEventType::RotationStarted, AuditEventType::RotationCompleted],
+        max_time_between_events: 300000.0, // 5 minutes
+        severity: ComplianceSeverity::High,
+    };
+    
+    // Rule: Emergency rotations must be documented
+    let emergency_documentation_rule = ComplianceRule {
+        rule_id: "emergency_documentation".to_string(),
+        rule_name: "Emergency Rotation Documentation".to_string(),
+        required_events: vec![AuditEventType::EmergencyRotation],
+        max_time_between_events: 0.0, // Immediate
+        severity: ComplianceSeverity::Critical,
+    };
+    
+    vec![rotation_completion_rule, emergency_documentation_rule]
+}
+
+// SHA-256 over the previous link and every recorded field; metadata in key order
+fn chain_hash(previous: &str, entry: &AuditEntry) -> String {
+    let mut hasher = Sha256::new();
+    let mut field = |value: &[u8]| {
+        hasher.update((value.len() as u64).to_be_bytes());
+        hasher.update(value);
+    };
+    field(previous.as_bytes());
+    field(entry.entry_id.as_bytes());
+    field(&entry.timestamp.to_bits().to_be_bytes());
+    field(format!("{:?}", entry.event_type).as_bytes());
+    field(entry.source.as_str().as_bytes());
+    field(format!("{:?}/{:?}", entry.key_version_from, entry.key_version_to).as_bytes());
+    field(entry.trigger_reason.as_bytes());
+    field(&[entry.success as u8]);
+    field(entry.error_details.as_deref().unwrap_or("").as_bytes());
+    field(entry.device_id.as_bytes());
+    field(entry.user_id.as_bytes());
+    field(entry.scope.to_json().as_bytes());
+    let metadata: BTreeMap<&String, &String> = entry.metadata.iter().collect();
+    for (key, value) in metadata {
+        field(key.as_bytes());
+        field(value.as_bytes());
+    }
+    to_hex(&hasher.finalize())
+}
+
+fn chain_intact(entries: &[AuditEntry]) -> bool {
+    let mut previous = "";
+    entries.iter().all(|entry| {
+        let linked = entry.integrity_hash == chain_hash(previous, entry);
+        previous = &entry.integrity_hash;
+        linked
+    })
+}
+
+fn is_external_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_EXTERNAL_NAME_LENGTH
+        && name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'.')
+}
+
+// Crypto events are only recorded by this crate, never ingested
+fn validate_external_event(event: &ExternalAuditEvent) -> Result<AuditEventSource, String> {
+    let source = match event.source.as_str() {
+        "app" => AuditEventSource::App,
+        "backend" => AuditEventSource::Backend,
+        other => return Err(format!("Unsupported external event source: {}", other)),
+    };
+    if !is_external_name(&event.event_type) {
+        return Err("Event type must be 1-64 characters of a-z, 0-9, '_' or '.'".to_string());
+    }
+    if event.occurred_at == 0 {
+        return Err("Event timestamp is required".to_string());
+    }
+    if event.user_id.is_empty() || event.user_id.len() > MAX_EXTERNAL_VALUE_LENGTH {
+        return Err("User id must be 1-256 characters".to_string());
+    }
+    if event.device_id.len() > MAX_EXTERNAL_VALUE_LENGTH {
+        return Err("Device id must be at most 256 characters".to_string());
+    }
+    if event.attributes.len() > MAX_EXTERNAL_ATTRIBUTES {
+        return Err(format!("At most {} attributes are allowed", MAX_EXTERNAL_ATTRIBUTES));
+    }
+    if let Some((name, _)) = event.attributes
+        .iter()
+        .find(|(name, value)| !is_external_name(name) || value.len() > MAX_EXTERNAL_VALUE_LENGTH)
+    {
+        return Err(format!("Invalid attribute: {}", name));
+    }
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn manager_with_open_rotations(keys: &[&str]) -> AuditTrailManager {
+        let mut manager = AuditTrailManager::new();
+        for key_id in keys {
+            for _ in 0..2 {
+                manager.record_rotation_started(key_id, &KeyVersion::new(1, 0, 0), &KeyVersion::new(1, 1, 0), "scheduled", "device-1", "user-1");
+            }
+        }
+        manager
+    }
+
+    #[test]
+    fn test_evaluation_stops_at_event_limit_with_partial_result() {
+        let mut manager = manager_with_open_rotations(&["cycle_data", "preferences", "healthcare_sharing"]);
+        let full = manager.evaluate_compliance(0.0, f64::MAX, || 0);
+        assert_eq!((full.keys_evaluated, full.violations.len(), full.limit_hit), (3, 3, None));
+
+        manager.set_compliance_limits(&ComplianceLimits { max_events_scanned: 5, ..ComplianceLimits::default() });
+        let partial = manager.evaluate_compliance(0.0, f64::MAX, || 0);
+        assert_eq!(partial.limit_hit, Some(ComplianceLimitHit::EventsScanned));
+        assert_eq!((partial.keys_evaluated, partial.keys_skipped, partial.events_scanned), (2, 1, 4));
+        // Sorted key order makes the covered subset deterministic
+        assert_eq!(partial.rotation_stats[0].0, "cycle_data_successful");
+        assert_eq!(partial.rotation_stats[2].0, "healthcare_sharing_successful");
+    }
+
+    #[test]
+    fn test_rule_limits_and_evaluation_timeout() {
+        let mut manager = manager_with_open_rotations(&["cycle_data", "preferences"]);
+        let rule = |filters: usize| ComplianceRule {
+            rule_id: "custom".to_string(),
+            rule_name: "Custom".to_string(),
+            required_events: vec![AuditEventType::RotationStarted; filters],
+            max_time_between_events: 0.0,
+            severity: ComplianceSeverity::Low,
+        };
+        assert!(!manager.add_rule(rule(17)));
+        assert!(manager.add_rule(rule(16)));
+
+        manager.set_compliance_limits(&ComplianceLimits { max_rules: 2, ..ComplianceLimits::default() });
+        assert!(!manager.add_rule(rule(1)));
+        let capped = manager.evaluate_compliance(0.0, f64::MAX, || 0);
+        assert_eq!((capped.rules_evaluated, capped.limit_hit), (2, Some(ComplianceLimitHit::Rules)));
+
+        // Each clock read advances 1.5s; the second key starts after the 2s budget
+        manager.set_compliance_limits(&ComplianceLimits::default());
+        let now = Cell::new(0u64);
+        let timed = manager.evaluate_compliance(0.0, f64::MAX, || now.replace(now.get() + 1_500));
+        assert_eq!(timed.limit_hit, Some(ComplianceLimitHit::Timeout));
+        assert_eq!((timed.keys_evaluated, timed.keys_skipped), (1, 1));
+    }
+
+    #[test]
+    fn test_external_events_share_chain_and_export() {
+        let mut manager = manager_with_open_rotations(&["cycle_data"]);
+        let event = r#"{"source":"app","event_type":"export.requested","occurred_at":1700000000000,"user_id":"user-1","attributes":{"format":"csv"}}"#;
+        let entry_id = manager.ingest_external_event_at(event, u64::MAX / 2).unwrap();
+        let backend = SigningKeyPair::generate();
+        manager.set_backend_public_key(&backend.public_key()).unwrap();
+        let backend_event = event.replace("\"app\"", "\"backend\"");
+        let signature = backend.sign(&[BACKEND_EVENT_CONTEXT, backend_event.as_bytes()].concat());
+        manager.ingest_backend_event_at(&backend_event, &signature, u64::MAX / 2).unwrap();
+
+        let export = manager.unified_export();
+        assert_eq!(export.len(), 4);
+        assert_eq!(export[2]["entry_id"], entry_id.as_str());
+        assert_eq!(export[2]["trail"], "external:app");
+        assert_eq!(export[2]["metadata"]["attr.format"], "csv");
+        let sources: Vec<&str> = export.iter().filter_map(|row| row["source"].as_str()).collect();
+        assert_eq!(sources, ["crypto", "crypto", "app", "backend"]);
+        assert!(manager.integrity_issues("external:app").is_empty());
+        assert!(manager.integrity_issues("cycle_data").is_empty());
+        assert!(manager.chain_tails_valid(1));
+
+        // Editing an entry breaks its link; rewriting its hash breaks the next one
+        let trail = manager.audit_entries.get_mut("cycle_data").unwrap();
+        trail[0].trigger_reason = "manual".to_string();
+        let first_id = trail[0].entry_id.clone();
+        assert_eq!(manager.integrity_issues("cycle_data"), [format!("Integrity mismatch for entry {}", first_id)]);
+        assert!(manager.chain_tails_valid(1) && !manager.chain_tails_valid(2));
+        let trail = manager.audit_entries.get_mut("cycle_data").unwrap();
+        trail[0].integrity_hash = chain_hash("", &trail[0]);
+        assert_eq!(manager.integrity_issues("cycle_data").len(), 1);
+        assert_ne!(manager.integrity_issues("cycle_data")[0], format!("Integrity mismatch for entry {}", first_id));
+    }
+
+    #[test]
+    fn test_backend_events_require_the_backend_signature() {
+        let mut manager = AuditTrailManager::new();
+        let event = r#"{"source":"backend","event_type":"account.locked","occurred_at":1700000000000,"user_id":"user-1"}"#;
+        let backend = SigningKeyPair::generate();
+        let signature = backend.sign(&[BACKEND_EVENT_CONTEXT, event.as_bytes()].concat());
+
+        assert!(manager.ingest_backend_event_at(event, &signature, 1).unwrap_err().contains("No backend public key"));
+        manager.set_backend_public_key(&backend.public_key()).unwrap();
+        assert!(manager.ingest_external_event_at(event, 1).unwrap_err().contains("must be signed"));
+        let forged = SigningKeyPair::generate().sign(&[BACKEND_EVENT_CONTEXT, event.as_bytes()].concat());
+        assert!(manager.ingest_backend_event_at(event, &forged, 1).unwrap_err().contains("signature is invalid"));
+        let altered = event.replace("locked", "unlocked");
+        assert!(manager.ingest_backend_event_at(&altered, &signature, 1).is_err());
+
+        manager.ingest_backend_event_at(event, &signature, 1).unwrap();
+        let trail = &manager.audit_entries["external:backend"];
+        assert_eq!(trail.len(), 1);
+        assert_eq!(trail[0].metadata["backend_key_id"], backend.key_id());
+
+        // A signed app event is refused rather than recorded as authenticated
+        let app_event = event.replace("\"backend\"", "\"app\"");
+        let app_signature = backend.sign(&[BACKEND_EVENT_CONTEXT, app_event.as_bytes()].concat());
+        assert!(manager.ingest_backend_event_at(&app_event, &app_signature, 1).unwrap_err().contains("must come from the backend"));
+    }
+
+    #[test]
+    fn test_anchored_head_detects_rewrites_and_truncation() {
+        let mut manager = manager_with_open_rotations(&["cycle_data", "preferences"]);
+        let device = SigningKeyPair::generate();
+        let head: AuditTrailHead = serde_json::from_str(&serde_json::to_string(&manager.signed_head_at(&device, 5)).unwrap()).unwrap();
+        assert!(head.verify(&device.public_key()));
+        assert!(!head.verify(&SigningKeyPair::generate().public_key()));
+        assert!(manager.extends_anchored_head(&head, &device.public_key()));
+
+        // Appending keeps the anchored prefix
+        manager.record_rotation_failed("cycle_data", &KeyVersion::new(1, 0, 0), "timeout", "device-1", "user-1");
+        assert!(manager.extends_anchored_head(&head, &device.public_key()));
+
+        // A fully rehashed rewrite passes the chain check but not the anchored head
+        let mut rewritten = manager_with_open_rotations(&["cycle_data", "preferences"]);
+        assert!(rewritten.integrity_issues("cycle_data").is_empty());
+        assert!(!rewritten.extends_anchored_head(&head, &device.public_key()));
+
+        let mut truncated = manager.audit_entries.clone();
+        truncated.get_mut("preferences").unwrap().pop();
+        rewritten.audit_entries = truncated;
+        assert!(!rewritten.extends_anchored_head(&head, &device.public_key()));
+
+        let mut tampered = head.clone();
+        tampered.trails.get_mut("cycle_data").unwrap().length = 1;
+        assert!(!manager.extends_anchored_head(&tampered, &device.public_key()));
+    }
+
+    #[test]
+    fn test_external_event_schema_validation() {
+        let mut manager = AuditTrailManager::new();
+        let valid = r#"{"source":"app","event_type":"login","occurred_at":1,"user_id":"user-1"}"#;
+        assert!(manager.ingest_external_event_at(valid, 1).is_ok());
+        for invalid in [
+            valid.replace("\"app\"", "\"crypto\""),
+            valid.replace("\"login\"", "\"Login Attempt\""),
+            valid.replace("\"occurred_at\":1", "\"occurred_at\":0"),
+            valid.replace("\"user-1\"", "\"\""),
+            valid.replace('}', r#","extra":true}"#),
+            valid.replace('}', r#","attributes":{"Bad Name":"x"}}"#),
+        ] {
+            assert!(manager.ingest_external_event_at(&invalid, 1).is_err(), "accepted {}", invalid);
+        }
+        assert_eq!(manager.unified_export().len(), 1);
+    }
+
+    #[test]
+    fn test_entries_carry_operation_scope() {
+        let mut profile = ScopeContext::new();
+        profile.set_profile(Some("profile-a".to_string()));
+        profile.set_device_id(Some("device-9".to_string()));
+        crate::scope::set_scope_context(&profile);
+
+        let mut manager = manager_with_open_rotations(&["cycle_data"]);
+        manager.ingest_external_event_at(r#"{"source":"app","event_type":"login","occurred_at":1,"user_id":"user-1"}"#, 1).unwrap();
+        crate::scope::clear_scope_context();
+
+        let export = manager.unified_export();
+        let scope_of = |trail: &str| export.iter().find(|row| row["trail"] == trail).unwrap()["scope"].clone();
+        assert_eq!(
+            scope_of("cycle_data"),
+            serde_json::json!({ "profile": "profile-a", "purpose": "cycle_data", "key_version": "1.1.0", "device_id": "device-1" })
+        );
+        assert_eq!(scope_of("external:app"), serde_json::json!({ "profile": "profile-a", "device_id": "device-9" }));
+
+        // The scope is covered by the chain hash
+        let trail = manager.audit_entries.get_mut("cycle_data").unwrap();
+        trail[0].scope = ScopeContext::default();
+        assert_eq!(manager.integrity_issues("cycle_data").len(), 1);
+    }
+}
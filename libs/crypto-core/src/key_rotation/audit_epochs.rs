@@ -0,0 +1,474 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, Zeroizing};
+use crate::envelope::to_hex;
+use crate::memory::{track_secret_allocation, track_secret_zeroization};
+use super::scheduler::{KeyRotationScheduler, RotationPolicy};
+use crate::platform;
+
+/// Rotation scheduler purpose under which audit epochs roll over
+pub const AUDIT_EPOCH_PURPOSE: &str = "audit_log";
+
+pub(crate) const EPOCH_KEY_INFO: &str = "aura-audit-epoch-v1";
+pub(crate) const SEGMENT_AAD_DOMAIN: &str = "aura-audit-segment-v1";
+pub(crate) const COMMITMENT_DOMAIN: &[u8] = b"aura-audit-epoch-commitment-v1";
+pub(crate) const KEYRING_AAD_DOMAIN: &[u8] = b"aura-audit-keyring-v1";
+const EPOCH_KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+/// Audit segment encrypted under a single epoch key
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SealedAuditSegment {
+    epoch_id: u64,
+    segment_index: u64,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl SealedAuditSegment {
+    #[wasm_bindgen(getter)]
+    pub fn epoch_id(&self) -> u64 {
+        self.epoch_id
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn segment_index(&self) -> u64 {
+        self.segment_index
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize audit segment: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<SealedAuditSegment, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid audit segment: {}", e)))
+    }
+}
+
+impl SealedAuditSegment {
+    fn aad(epoch_id: u64, segment_index: u64) -> Vec<u8> {
+        format!("{}|{}|{}", SEGMENT_AAD_DOMAIN, epoch_id, segment_index).into_bytes()
+    }
+}
+
+/// Evidence that an epoch key was wiped
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochDestructionRecord {
+    epoch_id: u64,
+    key_commitment: String,
+    destroyed_at: f64,
+}
+
+#[wasm_bindgen]
+impl EpochDestructionRecord {
+    #[wasm_bindgen(getter)]
+    pub fn epoch_id(&self) -> u64 {
+        self.epoch_id
+    }
+
+    /// SHA-256 commitment to the destroyed key (reveals nothing about the key)
+    #[wasm_bindgen(getter)]
+    pub fn key_commitment(&self) -> String {
+        self.key_commitment.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn destroyed_at(&self) -> f64 {
+        self.destroyed_at
+    }
+}
+
+struct EpochKey {
+    key: Zeroizing<[u8; EPOCH_KEY_LENGTH]>,
+    commitment: String,
+    next_segment_index: u64,
+}
+
+impl EpochKey {
+    fn new(key: [u8; EPOCH_KEY_LENGTH]) -> Self {
+        track_secret_allocation();
+        Self {
+            commitment: key_commitment(&key),
+            key: Zeroizing::new(key),
+            next_segment_index: 0,
+        }
+    }
+}
+
+// Plaintext of a sealed keyring export; key bytes are wiped on drop
+#[derive(Serialize, Deserialize)]
+struct StoredEpochKey {
+    epoch_id: u64,
+    key: Vec<u8>,
+    next_segment_index: u64,
+}
+
+impl Drop for StoredEpochKey {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredKeyring {
+    current_epoch: u64,
+    retained_epochs: u32,
+    live_keys: Vec<StoredEpochKey>,
+    destroyed: Vec<EpochDestructionRecord>,
+}
+
+/// Forward-secure keyring for archived audit segments.
+///
+/// Each epoch key is derived one-way from its predecessor, and keys older than
+/// the retention window are zeroized on rollover, so compromising the device
+/// later does not expose audit archives from destroyed epochs.
+#[wasm_bindgen]
+pub struct AuditEpochKeyring {
+    current_epoch: u64,
+    live_keys: BTreeMap<u64, EpochKey>, // epoch id -> key (current + retained)
+    retained_epochs: u32,
+    destroyed: Vec<EpochDestructionRecord>,
+}
+
+#[wasm_bindgen]
+impl AuditEpochKeyring {
+    /// `retained_epochs` previous epochs stay readable; older epoch keys are destroyed
+    #[wasm_bindgen(constructor)]
+    pub fn new(retained_epochs: u32) -> Self {
+        let mut initial_key = [0u8; EPOCH_KEY_LENGTH];
+        platform::fill_random(&mut initial_key);
+
+        let mut live_keys = BTreeMap::new();
+        live_keys.insert(0, EpochKey::new(initial_key));
+        initial_key.zeroize();
+
+        Self {
+            current_epoch: 0,
+            live_keys,
+            retained_epochs,
+            destroyed: Vec::new(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn current_epoch(&self) -> u64 {
+        self.current_epoch
+    }
+
+    /// Epoch ids whose keys are still held (current included)
+    #[wasm_bindgen]
+    pub fn live_epoch_ids(&self) -> Vec<u64> {
+        self.live_keys.keys().copied().collect()
+    }
+
+    #[wasm_bindgen]
+    pub fn destruction_records(&self) -> Vec<EpochDestructionRecord> {
+        self.destroyed.clone()
+    }
+
+    /// Schedule epoch rollover through the rotation scheduler
+    #[wasm_bindgen]
+    pub fn register_with_scheduler(&self, scheduler: &mut KeyRotationScheduler, epoch_days: u32) {
+        scheduler.set_rotation_policy(AUDIT_EPOCH_PURPOSE, RotationPolicy::new(epoch_days));
+    }
+
+    /// Roll over when the scheduler says the audit epoch is due; returns the new epoch id
+    #[wasm_bindgen]
+    pub fn rollover_if_due(&mut self, scheduler: &mut KeyRotationScheduler) -> Result<Option<u64>, JsValue> {
+        if !scheduler.is_rotation_due(AUDIT_EPOCH_PURPOSE) {
+            return Ok(None);
+        }
+
+        let epoch = self.rollover().map_err(|e| JsValue::from_str(&e))?;
+        scheduler.update_next_rotation(AUDIT_EPOCH_PURPOSE);
+        Ok(Some(epoch))
+    }
+
+    /// Start a new epoch immediately (e.g. on suspected compromise)
+    #[wasm_bindgen(js_name = rollover)]
+    pub fn rollover_now(&mut self) -> Result<u64, JsValue> {
+        self.rollover().map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn seal_segment(&mut self, segment: &[u8]) -> Result<SealedAuditSegment, JsValue> {
+        self.seal(segment).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn open_segment(&self, sealed: &SealedAuditSegment) -> Result<Vec<u8>, JsValue> {
+        self.open(sealed).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Seal the live epoch keys under a 32-byte wrapping key (e.g. from the device
+    /// keystore) so archived segments stay readable after a restart. Re-export after
+    /// every rollover: an older export still holds keys that have since been destroyed
+    #[wasm_bindgen]
+    pub fn export_sealed(&self, wrapping_key: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.seal_keys(wrapping_key).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Reload a keyring sealed with `export_sealed`
+    #[wasm_bindgen(js_name = restoreSealed)]
+    pub fn restore_sealed(wrapping_key: &[u8], sealed: &[u8]) -> Result<AuditEpochKeyring, JsValue> {
+        Self::open_keys(wrapping_key, sealed).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// True only if the epoch's key was destroyed and no live key matches its commitment
+    #[wasm_bindgen]
+    pub fn verify_epoch_destroyed(&self, epoch_id: u64) -> bool {
+        let Some(record) = self.destroyed.iter().find(|r| r.epoch_id == epoch_id) else {
+            return false;
+        };
+
+        !self.live_keys.contains_key(&epoch_id)
+            && self.live_keys.values().all(|key| key.commitment != record.key_commitment)
+    }
+}
+
+impl AuditEpochKeyring {
+    pub(crate) fn rollover(&mut self) -> Result<u64, String> {
+        let current = self.live_keys
+            .get(&self.current_epoch)
+            .ok_or_else(|| "Current audit epoch key missing".to_string())?;
+
+        let next_epoch = self.current_epoch + 1;
+        let mut next_key = [0u8; EPOCH_KEY_LENGTH];
+        Hkdf::<Sha256>::new(None, current.key.as_ref())
+            .expand(format!("{}|{}", EPOCH_KEY_INFO, next_epoch).as_bytes(), &mut next_key)
+            .map_err(|_| "Audit epoch key derivation failed".to_string())?;
+
+        self.live_keys.insert(next_epoch, EpochKey::new(next_key));
+        next_key.zeroize();
+        self.current_epoch = next_epoch;
+
+        // Destroy every epoch outside the retention window
+        let oldest_retained = next_epoch.saturating_sub(self.retained_epochs as u64);
+        let expired: Vec<u64> = self.live_keys.range(..oldest_retained).map(|(id, _)| *id).collect();
+        for epoch_id in expired {
+            if let Some(epoch_key) = self.live_keys.remove(&epoch_id) {
+                self.destroy(epoch_id, epoch_key);
+            }
+        }
+
+        Ok(next_epoch)
+    }
+
+    pub(crate) fn seal(&mut self, segment: &[u8]) -> Result<SealedAuditSegment, String> {
+        let epoch_id = self.current_epoch;
+        let epoch_key = self.live_keys
+            .get_mut(&epoch_id)
+            .ok_or_else(|| "Current audit epoch key missing".to_string())?;
+
+        let segment_index = epoch_key.next_segment_index;
+        let mut nonce = vec![0u8; NONCE_LENGTH];
+        platform::fill_random(&mut nonce);
+
+        let aad = SealedAuditSegment::aad(epoch_id, segment_index);
+        let ciphertext = Aes256Gcm::new_from_slice(epoch_key.key.as_ref())
+            .map_err(|_| "Invalid audit epoch key".to_string())?
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: segment, aad: &aad })
+            .map_err(|_| "Audit segment encryption failed".to_string())?;
+
+        epoch_key.next_segment_index += 1;
+        Ok(SealedAuditSegment { epoch_id, segment_index, nonce, ciphertext })
+    }
+
+    pub(crate) fn open(&self, sealed: &SealedAuditSegment) -> Result<Vec<u8>, String> {
+        if sealed.nonce.len() != NONCE_LENGTH {
+            return Err("Audit segment nonce must be 12 bytes".to_string());
+        }
+        let epoch_key = self.live_keys
+            .get(&sealed.epoch_id)
+            .ok_or_else(|| format!("Audit epoch {} key has been destroyed", sealed.epoch_id))?;
+
+        let aad = SealedAuditSegment::aad(sealed.epoch_id, sealed.segment_index);
+        Aes256Gcm::new_from_slice(epoch_key.key.as_ref())
+            .map_err(|_| "Invalid audit epoch key".to_string())?
+            .decrypt(Nonce::from_slice(&sealed.nonce), Payload { msg: &sealed.ciphertext, aad: &aad })
+            .map_err(|_| "Audit segment authentication failed".to_string())
+    }
+
+    pub(crate) fn seal_keys(&self, wrapping_key: &[u8]) -> Result<Vec<u8>, String> {
+        let stored = StoredKeyring {
+            current_epoch: self.current_epoch,
+            retained_epochs: self.retained_epochs,
+            live_keys: self.live_keys.iter().map(|(epoch_id, epoch_key)| StoredEpochKey {
+                epoch_id: *epoch_id,
+                key: epoch_key.key.to_vec(),
+                next_segment_index: epoch_key.next_segment_index,
+            }).collect(),
+            destroyed: self.destroyed.clone(),
+        };
+        let plaintext = Zeroizing::new(serde_json::to_vec(&stored)
+            .map_err(|e| format!("Failed to serialize audit keyring: {}", e))?);
+
+        let mut sealed = vec![0u8; NONCE_LENGTH];
+        platform::fill_random(&mut sealed);
+        let ciphertext = keyring_cipher(wrapping_key)?
+            .encrypt(Nonce::from_slice(&sealed), Payload { msg: &plaintext, aad: KEYRING_AAD_DOMAIN })
+            .map_err(|_| "Audit keyring encryption failed".to_string())?;
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    pub(crate) fn open_keys(wrapping_key: &[u8], sealed: &[u8]) -> Result<AuditEpochKeyring, String> {
+        if sealed.len() < NONCE_LENGTH {
+            return Err("Sealed audit keyring too short".to_string());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+        let plaintext = Zeroizing::new(keyring_cipher(wrapping_key)?
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: KEYRING_AAD_DOMAIN })
+            .map_err(|_| "Audit keyring authentication failed".to_string())?);
+        let stored: StoredKeyring = serde_json::from_slice(&plaintext)
+            .map_err(|e| format!("Invalid audit keyring: {}", e))?;
+
+        let oldest_retained = stored.current_epoch.saturating_sub(stored.retained_epochs as u64);
+        let mut live_keys = BTreeMap::new();
+        for stored_key in &stored.live_keys {
+            let key: [u8; EPOCH_KEY_LENGTH] = stored_key.key.as_slice().try_into()
+                .map_err(|_| "Audit epoch key must be 32 bytes".to_string())?;
+            if stored_key.epoch_id < oldest_retained || stored_key.epoch_id > stored.current_epoch {
+                return Err(format!("Audit epoch {} is outside the retention window", stored_key.epoch_id));
+            }
+            let mut epoch_key = EpochKey::new(key);
+            epoch_key.next_segment_index = stored_key.next_segment_index;
+            live_keys.insert(stored_key.epoch_id, epoch_key);
+        }
+        if !live_keys.contains_key(&stored.current_epoch) {
+            return Err("Current audit epoch key missing".to_string());
+        }
+
+        Ok(AuditEpochKeyring {
+            current_epoch: stored.current_epoch,
+            live_keys,
+            retained_epochs: stored.retained_epochs,
+            destroyed: stored.destroyed.clone(),
+        })
+    }
+
+    fn destroy(&mut self, epoch_id: u64, mut epoch_key: EpochKey) {
+        epoch_key.key.zeroize();
+        track_secret_zeroization();
+
+        self.destroyed.push(EpochDestructionRecord {
+            epoch_id,
+            key_commitment: epoch_key.commitment.clone(),
+            destroyed_at: platform::now_ms() as f64,
+        });
+    }
+}
+
+impl Drop for AuditEpochKeyring {
+    fn drop(&mut self) {
+        // Epoch keys are zeroized by `Zeroizing` on drop
+        self.live_keys.clear();
+        track_secret_zeroization();
+    }
+}
+
+fn key_commitment(key: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(COMMITMENT_DOMAIN);
+    hasher.update(key);
+    to_hex(&hasher.finalize())
+}
+
+fn keyring_cipher(wrapping_key: &[u8]) -> Result<Aes256Gcm, String> {
+    if wrapping_key.len() != EPOCH_KEY_LENGTH {
+        return Err("Audit keyring wrapping key must be 32 bytes".to_string());
+    }
+    Aes256Gcm::new_from_slice(wrapping_key).map_err(|_| "Invalid audit keyring wrapping key".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollover_destroys_epochs_outside_retention() {
+        let mut keyring = AuditEpochKeyring::new(1);
+        let first = keyring.seal(b"epoch 0 events").unwrap();
+
+        keyring.rollover().unwrap();
+        let second = keyring.seal(b"epoch 1 events").unwrap();
+        // Previous epoch is still within the retention window
+        assert_eq!(keyring.open(&first).unwrap(), b"epoch 0 events");
+
+        keyring.rollover().unwrap();
+        assert_eq!(keyring.live_epoch_ids(), vec![1, 2]);
+        assert!(keyring.open(&first).is_err());
+        assert_eq!(keyring.open(&second).unwrap(), b"epoch 1 events");
+
+        assert!(keyring.verify_epoch_destroyed(0));
+        assert!(!keyring.verify_epoch_destroyed(1));
+        assert_eq!(keyring.destruction_records()[0].epoch_id(), 0);
+    }
+
+    #[test]
+    fn test_rollover_driven_by_scheduler() {
+        let mut scheduler = KeyRotationScheduler::new();
+        let mut keyring = AuditEpochKeyring::new(0);
+        keyring.register_with_scheduler(&mut scheduler, 30);
+
+        assert_eq!(keyring.rollover_if_due(&mut scheduler).unwrap(), None);
+
+        scheduler.force_rotation(AUDIT_EPOCH_PURPOSE);
+        assert_eq!(keyring.rollover_if_due(&mut scheduler).unwrap(), Some(1));
+        assert!(!scheduler.is_rotation_due(AUDIT_EPOCH_PURPOSE));
+        assert!(keyring.verify_epoch_destroyed(0));
+    }
+
+    #[test]
+    fn test_segment_bound_to_epoch_and_index() {
+        let mut keyring = AuditEpochKeyring::new(2);
+        let mut sealed = keyring.seal(b"login event").unwrap();
+        sealed.segment_index += 1;
+        assert!(keyring.open(&sealed).is_err());
+
+        sealed.segment_index -= 1;
+        sealed.nonce.truncate(4);
+        assert!(keyring.open(&sealed).unwrap_err().contains("nonce"));
+    }
+
+    #[test]
+    fn test_sealed_export_restores_live_epochs_only() {
+        let wrapping_key = [7u8; 32];
+        let mut keyring = AuditEpochKeyring::new(1);
+        let first = keyring.seal(b"epoch 0 events").unwrap();
+        keyring.rollover().unwrap();
+        let second = keyring.seal(b"epoch 1 events").unwrap();
+        keyring.rollover().unwrap();
+
+        let sealed = keyring.seal_keys(&wrapping_key).unwrap();
+        let mut restored = AuditEpochKeyring::open_keys(&wrapping_key, &sealed).unwrap();
+        assert_eq!(restored.current_epoch(), 2);
+        assert_eq!(restored.live_epoch_ids(), vec![1, 2]);
+        assert_eq!(restored.open(&second).unwrap(), b"epoch 1 events");
+        assert!(restored.open(&first).is_err());
+        assert!(restored.verify_epoch_destroyed(0));
+
+        // Segment indices carry over, so the restored keyring keeps sealing in sequence
+        let third = restored.seal(b"epoch 2 events").unwrap();
+        assert_eq!(third.segment_index(), 0);
+        assert_eq!(keyring.open(&third).unwrap(), b"epoch 2 events");
+
+        assert!(AuditEpochKeyring::open_keys(&[8u8; 32], &sealed).is_err());
+        let mut tampered = sealed.clone();
+        tampered[NONCE_LENGTH] ^= 1;
+        assert!(AuditEpochKeyring::open_keys(&wrapping_key, &tampered).is_err());
+        assert!(AuditEpochKeyring::open_keys(&wrapping_key, &sealed[..4]).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Cache invalidation epochs
+// Apps cache decrypted view models. Keying those entries by `cache_epoch(purpose)`
+// makes them unreachable as soon as a key for that purpose is rotated or access is
+// revoked, without the app having to track rotation events itself. Epochs only
+// ever increase; a global revocation bumps every purpose at once. Device revocations
+// come from `MultiDeviceProtocol`, which holds no manager, so they are counted
+// process-wide. The counters live in memory: the app persists `export_cache_epochs`
+// next to its cache index and restores it on start, and restoring never moves a
+// counter backwards and bumps every purpose once more, so entries cached after the
+// last export cannot be reached again either.
+
+static DEVICE_REVOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Invalidate every purpose's caches after a device lost access
+pub(crate) fn record_device_revocation() {
+    DEVICE_REVOCATIONS.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Monotonic per-purpose epoch counters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct CacheEpochs {
+    global: u64,
+    per_purpose: HashMap<String, u64>,
+    #[serde(default)]
+    device_revocations: u64, // Snapshot of the process-wide counter, written on export
+}
+
+impl CacheEpochs {
+    /// Current epoch for `purpose`; the sum of increasing counters never decreases
+    pub fn epoch(&self, purpose: &str) -> u64 {
+        self.global
+            + self.per_purpose.get(purpose).copied().unwrap_or(0)
+            + DEVICE_REVOCATIONS.load(Ordering::SeqCst)
+    }
+
+    /// Invalidate caches for one purpose (rotation, share revocation)
+    pub fn bump(&mut self, purpose: &str) -> u64 {
+        *self.per_purpose.entry(purpose.to_string()).or_insert(0) += 1;
+        self.epoch(purpose)
+    }
+
+    /// Invalidate caches for every purpose (device revocation, emergency rotation)
+    pub fn bump_all(&mut self) {
+        self.global += 1;
+    }
+
+    pub(crate) fn export(&self) -> CacheEpochs {
+        CacheEpochs {
+            device_revocations: DEVICE_REVOCATIONS.load(Ordering::SeqCst),
+            ..self.clone()
+        }
+    }
+
+    /// Seed from an exported snapshot, keeping the larger value of every counter
+    pub(crate) fn restore(&mut self, stored: &CacheEpochs) {
+        self.global = self.global.max(stored.global);
+        for (purpose, &count) in &stored.per_purpose {
+            let current = self.per_purpose.entry(purpose.clone()).or_insert(0);
+            *current = (*current).max(count);
+        }
+        DEVICE_REVOCATIONS.fetch_max(stored.device_revocations, Ordering::SeqCst);
+        self.bump_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Other tests revoke devices concurrently, so only relative movement is asserted
+    #[test]
+    fn test_epochs_are_monotonic_and_scoped() {
+        let mut epochs = CacheEpochs::default();
+        let cycle = epochs.epoch("cycle_data");
+        let preferences = epochs.epoch("preferences");
+
+        assert!(epochs.bump("cycle_data") > cycle);
+        assert_eq!(epochs.per_purpose.get("preferences"), None);
+
+        epochs.bump_all();
+        assert!(epochs.epoch("cycle_data") >= cycle + 2);
+        assert!(epochs.epoch("preferences") > preferences);
+
+        let before = epochs.epoch("preferences");
+        record_device_revocation();
+        assert!(epochs.epoch("preferences") > before);
+    }
+
+    #[test]
+    fn test_restored_epochs_never_fall_back() {
+        let mut previous_run = CacheEpochs::default();
+        for _ in 0..3 {
+            previous_run.bump("cycle_data");
+        }
+        let stored: CacheEpochs = serde_json::from_str(&serde_json::to_string(&previous_run.export()).unwrap()).unwrap();
+        let last_seen = previous_run.epoch("cycle_data");
+
+        let mut restarted = CacheEpochs::default();
+        restarted.restore(&stored);
+        assert!(restarted.epoch("cycle_data") > last_seen);
+        assert!(restarted.epoch("preferences") > CacheEpochs::default().epoch("preferences"));
+
+        // Restoring an older snapshot over newer counters keeps the newer ones
+        restarted.bump("cycle_data");
+        let newer = restarted.per_purpose["cycle_data"];
+        restarted.restore(&stored);
+        assert_eq!(restarted.per_purpose["cycle_data"], newer);
+    }
+}
@@ -5,7 +5,8 @@ use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc, Duration};
-use uuid::Uuid;
+use crate::platform;
+use crate::integration::{require_owner, RestrictedOperation};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EmergencyTriggerType {
@@ -167,14 +168,15 @@ impl EmergencyRotationManager {
         affected_devices: Vec<String>,
         severity: u8,
     ) -> Result<String, String> {
+        require_owner(RestrictedOperation::KeyRotation).map_err(|denied| denied.to_string())?;
         let trigger_type = self.parse_trigger_type(trigger_type)?;
-        let incident_id = Uuid::new_v4().to_string();
+        let incident_id = platform::new_uuid();
         
         let incident = EmergencyIncident {
             id: incident_id.clone(),
             trigger_type: trigger_type.clone(),
             severity,
-            detected_at: Utc::now(),
+            detected_at: platform::now(),
             status: EmergencyStatus::Detected,
             affected_devices: affected_devices.clone(),
             description: description.to_string(),
@@ -211,7 +213,7 @@ impl EmergencyRotationManager {
 
         let response = EmergencyResponse {
             incident_id: incident_id.to_string(),
-            started_at: Utc::now(),
+            started_at: platform::now(),
             completed_at: None,
             status: EmergencyStatus::Responding,
             actions_taken: Vec::new(),
@@ -241,17 +243,17 @@ impl EmergencyRotationManager {
     #[wasm_bindgen(js_name = "isolateDevice")]
     pub fn isolate_device(&mut self, device_id: &str, incident_id: &str) -> Result<(), String> {
         let action = EmergencyAction {
-            id: Uuid::new_v4().to_string(),
+            id: platform::new_uuid(),
             action_type: EmergencyActionType::IsolateDevice,
             target: device_id.to_string(),
-            executed_at: Utc::now(),
+            executed_at: platform::now(),
             success: true,
             details: format!("Device {} isolated due to incident {}", device_id, incident_id),
             rollback_available: true,
         };
 
         // Add to isolated devices
-        self.isolated_devices.insert(device_id.to_string(), Utc::now());
+        self.isolated_devices.insert(device_id.to_string(), platform::now());
 
         // Update response
         if let Some(response) = self.active_responses.get_mut(incident_id) {
@@ -269,17 +271,17 @@ impl EmergencyRotationManager {
     #[wasm_bindgen(js_name = "invalidateKey")]
     pub fn invalidate_key(&mut self, key_id: &str, incident_id: &str) -> Result<(), String> {
         let action = EmergencyAction {
-            id: Uuid::new_v4().to_string(),
+            id: platform::new_uuid(),
             action_type: EmergencyActionType::InvalidateKey,
             target: key_id.to_string(),
-            executed_at: Utc::now(),
+            executed_at: platform::now(),
             success: true,
             details: format!("Key {} invalidated due to incident {}", key_id, incident_id),
             rollback_available: false, // Key invalidation is not reversible
         };
 
         // Add to invalidated keys
-        self.invalidated_keys.insert(key_id.to_string(), Utc::now());
+        self.invalidated_keys.insert(key_id.to_string(), platform::now());
 
         // Update response
         if let Some(response) = self.active_responses.get_mut(incident_id) {
@@ -299,6 +301,7 @@ impl EmergencyRotationManager {
         incident_id: &str,
         device_ids: Vec<String>,
     ) -> Result<Vec<String>, String> {
+        require_owner(RestrictedOperation::KeyRotation).map_err(|denied| denied.to_string())?;
         let mut rotated_keys = Vec::new();
 
         if let Some(response) = self.active_responses.get_mut(incident_id) {
@@ -360,7 +363,7 @@ impl EmergencyRotationManager {
         if let Some(response) = self.active_responses.get_mut(incident_id) {
             response.recovery_status = RecoveryStatus::Complete;
             response.status = EmergencyStatus::Complete;
-            response.completed_at = Some(Utc::now());
+            response.completed_at = Some(platform::now());
             response.data_accessibility = true;
         }
 
@@ -602,7 +605,7 @@ impl EmergencyRotationManager {
     fn rotate_device_keys_emergency(&mut self, device_id: &str, incident_id: &str) -> Result<Vec<String>, String> {
         // This would integrate with the actual key rotation system
         // For now, simulate key rotation
-        let new_key_id = Uuid::new_v4().to_string();
+        let new_key_id = platform::new_uuid();
         
         println!("Emergency key rotation for device {} completed. New key: {}", device_id, new_key_id);
         
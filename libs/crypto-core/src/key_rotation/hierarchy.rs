@@ -0,0 +1,245 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::derivation::DataCategory;
+use super::versioned_key::VersionedKey;
+use crate::platform;
+
+/// Kind of node in the key hierarchy graph
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyHierarchyNodeKind {
+    Root,
+    Purpose,
+    Version,
+}
+
+/// Relationship between two nodes in the key hierarchy graph
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyHierarchyEdgeKind {
+    Derives,    // root -> purpose (hardened HD derivation)
+    Versions,   // purpose -> key version
+    Supersedes, // newer version -> predecessor version
+}
+
+/// Metadata-only node describing a root, purpose or key version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyHierarchyNode {
+    pub id: String,
+    pub kind: KeyHierarchyNodeKind,
+    pub label: String,
+    pub purpose: Option<String>,
+    pub version: Option<String>,
+    pub status: Option<String>,
+    pub derivation_path: Option<String>,
+    pub created_at: Option<f64>,
+    pub expires_at: Option<f64>,
+    pub usage_count: Option<u64>,
+}
+
+/// Directed edge between two hierarchy nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyHierarchyEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: KeyHierarchyEdgeKind,
+}
+
+/// Renderable snapshot of the key tree for debugging and transparency screens.
+///
+/// The graph is built exclusively from key metadata (versions, statuses, timestamps);
+/// it never holds a `CryptoKey`, so key material cannot leak through it.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyHierarchyGraph {
+    nodes: Vec<KeyHierarchyNode>,
+    edges: Vec<KeyHierarchyEdge>,
+    generated_at: f64,
+}
+
+pub const ROOT_NODE_ID: &str = "root";
+
+#[wasm_bindgen]
+impl KeyHierarchyGraph {
+    #[wasm_bindgen(getter)]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn generated_at(&self) -> f64 {
+        self.generated_at
+    }
+
+    /// Serialize the graph as `{ nodes, edges, generatedAt }` for rendering
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize key hierarchy: {}", e)))
+    }
+}
+
+impl KeyHierarchyGraph {
+    /// Build the graph from the rotation manager's purpose -> keys (newest first) map
+    pub(crate) fn from_versioned_keys(versioned_keys: &HashMap<String, Vec<VersionedKey>>) -> Self {
+        let mut graph = KeyHierarchyGraph {
+            nodes: vec![KeyHierarchyNode {
+                id: ROOT_NODE_ID.to_string(),
+                kind: KeyHierarchyNodeKind::Root,
+                label: "Master key".to_string(),
+                purpose: None,
+                version: None,
+                status: None,
+                derivation_path: Some("m".to_string()),
+                created_at: None,
+                expires_at: None,
+                usage_count: None,
+            }],
+            edges: Vec::new(),
+            generated_at: platform::now_ms() as f64,
+        };
+
+        // Sort purposes so repeated snapshots render identically
+        let mut purposes: Vec<&String> = versioned_keys.keys().collect();
+        purposes.sort();
+
+        for purpose in purposes {
+            let purpose_id = format!("purpose:{}", purpose);
+            graph.nodes.push(KeyHierarchyNode {
+                id: purpose_id.clone(),
+                kind: KeyHierarchyNodeKind::Purpose,
+                label: purpose.clone(),
+                purpose: Some(purpose.clone()),
+                version: None,
+                status: None,
+                derivation_path: DataCategory::from_string(purpose).map(|c| c.derivation_path()),
+                created_at: None,
+                expires_at: None,
+                usage_count: None,
+            });
+            graph.add_edge(ROOT_NODE_ID, &purpose_id, KeyHierarchyEdgeKind::Derives);
+
+            let keys = &versioned_keys[purpose];
+            for key in keys {
+                let version = key.version();
+                let version_id = Self::version_node_id(purpose, &version.to_string());
+
+                graph.nodes.push(KeyHierarchyNode {
+                    id: version_id.clone(),
+                    kind: KeyHierarchyNodeKind::Version,
+                    label: format!("{} v{}", purpose, version.to_string()),
+                    purpose: Some(purpose.clone()),
+                    version: Some(version.to_string()),
+                    status: Some(format!("{:?}", key.status())),
+                    derivation_path: None,
+                    created_at: Some(version.created_at()),
+                    expires_at: version.expires_at(),
+                    usage_count: Some(key.usage_count()),
+                });
+                graph.add_edge(&purpose_id, &version_id, KeyHierarchyEdgeKind::Versions);
+
+                // Only link predecessors that are still retained for this purpose
+                for predecessor in key.predecessors() {
+                    let predecessor_str = predecessor.to_string();
+                    if keys.iter().any(|k| k.version().to_string() == predecessor_str) {
+                        let predecessor_id = Self::version_node_id(purpose, &predecessor_str);
+                        graph.add_edge(&version_id, &predecessor_id, KeyHierarchyEdgeKind::Supersedes);
+                    }
+                }
+            }
+        }
+
+        graph
+    }
+
+    pub fn nodes(&self) -> &[KeyHierarchyNode] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[KeyHierarchyEdge] {
+        &self.edges
+    }
+
+    pub fn node(&self, id: &str) -> Option<&KeyHierarchyNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, kind: KeyHierarchyEdgeKind) {
+        self.edges.push(KeyHierarchyEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        });
+    }
+
+    fn version_node_id(purpose: &str, version: &str) -> String {
+        format!("version:{}:{}", purpose, version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::CryptoKey;
+    use crate::key_rotation::types::{KeyStatus, KeyVersion};
+
+    fn versioned_key(major: u32, minor: u32, category: DataCategory) -> VersionedKey {
+        VersionedKey::new(
+            CryptoKey::new("encryption".to_string()),
+            KeyVersion::new(major, minor, 0),
+            category,
+        )
+    }
+
+    #[test]
+    fn test_graph_links_purposes_versions_and_predecessors() {
+        let old_key = versioned_key(1, 0, DataCategory::CycleData);
+        let mut new_key = versioned_key(1, 1, DataCategory::CycleData);
+        new_key.set_predecessor_version(old_key.version());
+        new_key.set_status(KeyStatus::Migrating);
+
+        let mut keys = HashMap::new();
+        keys.insert("cycle_data".to_string(), vec![new_key, old_key]);
+        keys.insert("preferences".to_string(), vec![versioned_key(1, 0, DataCategory::Preferences)]);
+
+        let graph = KeyHierarchyGraph::from_versioned_keys(&keys);
+
+        // root + 2 purposes + 3 versions
+        assert_eq!(graph.node_count(), 6);
+        // 2 derives + 3 versions + 1 supersedes
+        assert_eq!(graph.edge_count(), 6);
+
+        let purpose = graph.node("purpose:cycle_data").unwrap();
+        assert_eq!(purpose.derivation_path.as_deref(), Some("m/44'/0'/0'"));
+
+        let newest = graph.node("version:cycle_data:1.1.0").unwrap();
+        assert_eq!(newest.status.as_deref(), Some("Migrating"));
+
+        assert!(graph.edges().iter().any(|edge| {
+            edge.kind == KeyHierarchyEdgeKind::Supersedes
+                && edge.from == "version:cycle_data:1.1.0"
+                && edge.to == "version:cycle_data:1.0.0"
+        }));
+    }
+
+    #[test]
+    fn test_graph_skips_pruned_predecessors() {
+        let mut key = versioned_key(1, 3, DataCategory::DeviceSync);
+        key.set_predecessor_version(KeyVersion::new(1, 2, 0));
+
+        let mut keys = HashMap::new();
+        keys.insert("device_sync".to_string(), vec![key]);
+
+        let graph = KeyHierarchyGraph::from_versioned_keys(&keys);
+        assert!(graph.edges().iter().all(|edge| edge.kind != KeyHierarchyEdgeKind::Supersedes));
+    }
+}
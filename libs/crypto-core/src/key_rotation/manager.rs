@@ -1,19 +1,28 @@
 use wasm_bindgen::prelude::*;
 use std::collections::HashMap;
+use zeroize::Zeroizing;
 use crate::derivation::{HierarchicalKeyDerivation, DataCategory};
+use crate::integration::{require_owner_js, RestrictedOperation};
+use crate::scope::{enter_scope, ScopeContext};
 use crate::keys::CryptoKey;
 use crate::memory::track_secret_zeroization;
+use crate::verifier::VerifierKey;
 use super::types::{KeyVersion, KeyStatus};
 use super::versioned_key::VersionedKey;
 use super::scheduler::{KeyRotationScheduler, RotationPolicy};
+use super::hierarchy::KeyHierarchyGraph;
+use super::monitoring::PurposeSlaState;
+use super::cache_epochs::CacheEpochs;
+use crate::lazy_init::{LazySubsystem, Subsystem};
 
 /// Main key rotation manager orchestrating the entire lifecycle
 #[wasm_bindgen]
 pub struct KeyRotationManager {
     versioned_keys: HashMap<String, Vec<VersionedKey>>, // purpose -> keys (newest first)
     hd_derivation: HierarchicalKeyDerivation,
-    scheduler: KeyRotationScheduler,
+    scheduler: LazySubsystem<KeyRotationScheduler>, // Built on first scheduling call
     migration_batch_size: usize,
+    cache_epochs: CacheEpochs,
 }
 
 #[wasm_bindgen]
@@ -23,8 +32,9 @@ impl KeyRotationManager {
         Self {
             versioned_keys: HashMap::new(),
             hd_derivation,
-            scheduler: KeyRotationScheduler::new(),
+            scheduler: LazySubsystem::new(Subsystem::RotationScheduler, KeyRotationScheduler::new),
             migration_batch_size: 100,
+            cache_epochs: CacheEpochs::default(),
         }
     }
 
@@ -56,7 +66,9 @@ impl KeyRotationManager {
 
     #[wasm_bindgen]
     pub fn create_new_key_version(&mut self, purpose: DataCategory) -> Result<VersionedKey, JsValue> {
+        require_owner_js(RestrictedOperation::KeyRotation)?;
         let purpose_str = self.purpose_to_string(&purpose);
+        let _scope = enter_scope(ScopeContext::for_key(purpose_str.clone(), None));
         
         // Determine new version number
         let new_version = if let Some(keys) = self.versioned_keys.get(&purpose_str) {
@@ -66,8 +78,9 @@ impl KeyRotationManager {
                     return Err(JsValue::from_str(&format!("Migration already in progress for {}", purpose_str)));
                 }
                 
-                // Increment minor version for regular rotation
-                KeyVersion::new(latest.version().major(), latest.version().minor() + 1, 0)
+                // Increment minor version for regular rotation; numbers of rolled-back versions are not reused
+                let minor = keys.iter().map(|key| key.version().minor()).max().unwrap_or(0);
+                KeyVersion::new(latest.version().major(), minor + 1, 0)
             } else {
                 KeyVersion::new(1, 0, 0)
             }
@@ -76,7 +89,7 @@ impl KeyRotationManager {
         };
 
         // Generate new key (simplified for now)
-        let mut derived_key = CryptoKey::new("rotation".to_string());
+        let mut derived_key = CryptoKey::new("encryption".to_string());
         derived_key.generate().map_err(|e| JsValue::from_str(&format!("Failed to generate key: {:?}", e)))?;
 
         // Create versioned key
@@ -98,14 +111,50 @@ impl KeyRotationManager {
         }
 
         // Update scheduler
-        self.scheduler.update_next_rotation(&purpose_str);
+        self.scheduler.get_mut().update_next_rotation(&purpose_str);
+        self.cache_epochs.bump(&purpose_str);
 
         Ok(versioned_key)
     }
 
+    /// Cache invalidation token for `purpose`; increases on every rotation or revocation
+    #[wasm_bindgen]
+    pub fn cache_epoch(&self, purpose: DataCategory) -> u64 {
+        self.cache_epochs.epoch(&purpose.to_string())
+    }
+
+    /// Revoke access derived from `purpose` (e.g. a healthcare share); returns the new epoch
+    #[wasm_bindgen]
+    pub fn record_revocation(&mut self, purpose: DataCategory) -> u64 {
+        self.cache_epochs.bump(&purpose.to_string())
+    }
+
+    /// Revocation affecting every purpose, such as removing a device
+    #[wasm_bindgen]
+    pub fn record_global_revocation(&mut self) {
+        self.cache_epochs.bump_all();
+    }
+
+    /// Snapshot of the cache epochs for the app to persist next to its cache index
+    #[wasm_bindgen]
+    pub fn export_cache_epochs(&self) -> String {
+        serde_json::to_string(&self.cache_epochs.export()).unwrap_or_default()
+    }
+
+    /// Seed the cache epochs from a persisted snapshot on start; epochs never decrease
+    #[wasm_bindgen]
+    pub fn restore_cache_epochs(&mut self, snapshot_json: &str) -> Result<(), JsValue> {
+        let stored: CacheEpochs = serde_json::from_str(snapshot_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid cache epoch snapshot: {}", e)))?;
+        self.cache_epochs.restore(&stored);
+        Ok(())
+    }
+
     #[wasm_bindgen]
     pub fn complete_key_migration(&mut self, purpose: DataCategory) -> Result<(), JsValue> {
+        require_owner_js(RestrictedOperation::KeyRotation)?;
         let purpose_str = self.purpose_to_string(&purpose);
+        let _scope = enter_scope(ScopeContext::for_key(purpose_str.clone(), None));
         
         if let Some(keys) = self.versioned_keys.get_mut(&purpose_str) {
             if let Some(current_key) = keys.first_mut() {
@@ -134,21 +183,25 @@ impl KeyRotationManager {
 
     #[wasm_bindgen]
     pub fn get_scheduler(&self) -> KeyRotationScheduler {
-        self.scheduler.clone()
+        self.scheduler.get().clone()
     }
 
     #[wasm_bindgen]
     pub fn set_rotation_policy(&mut self, purpose: DataCategory, policy: RotationPolicy) {
         let purpose_str = self.purpose_to_string(&purpose);
-        self.scheduler.set_rotation_policy(&purpose_str, policy);
+        self.scheduler.get_mut().set_rotation_policy(&purpose_str, policy);
     }
 
     #[wasm_bindgen]
     pub fn check_rotation_due(&self) -> js_sys::Array {
         let array = js_sys::Array::new();
+        // An unbuilt scheduler has no schedules yet, so the launch-time check does not build it
+        if !self.scheduler.is_initialized() {
+            return array;
+        }
         
         for (purpose_str, _) in &self.versioned_keys {
-            if self.scheduler.is_rotation_due(purpose_str) {
+            if self.scheduler.get().is_rotation_due(purpose_str) {
                 array.push(&JsValue::from_str(purpose_str));
             }
         }
@@ -229,10 +282,11 @@ impl KeyRotationManager {
 
     #[wasm_bindgen]
     pub fn force_rotate_key(&mut self, purpose: DataCategory) -> Result<VersionedKey, JsValue> {
+        require_owner_js(RestrictedOperation::KeyRotation)?;
         let purpose_str = self.purpose_to_string(&purpose);
         
         // Force immediate rotation by updating scheduler
-        self.scheduler.force_rotation(&purpose_str);
+        self.scheduler.get_mut().force_rotation(&purpose_str);
         
         // Create new key version
         self.create_new_key_version(purpose)
@@ -268,12 +322,80 @@ impl KeyRotationManager {
         Err(JsValue::from_str("No migration in progress for this purpose"))
     }
 
+    /// Verifier key for `purpose` bound to the active key version of `category`,
+    /// so exported verifiers rotate together with the category key
+    #[wasm_bindgen]
+    pub fn verifier_key(&mut self, category: DataCategory, purpose: String) -> Result<VerifierKey, JsValue> {
+        let active = self.get_active_key(category.clone())
+            .ok_or_else(|| JsValue::from_str("No active key for this category"))?;
+        VerifierKey::derive(&mut self.hd_derivation, category, purpose, active.version().to_string())
+    }
+
+    /// Snapshot of purposes, versions and their relationships (no key material)
+    #[wasm_bindgen]
+    pub fn get_key_hierarchy_graph(&self) -> KeyHierarchyGraph {
+        KeyHierarchyGraph::from_versioned_keys(&self.versioned_keys)
+    }
+
     // Helper method to convert DataCategory to string
     fn purpose_to_string(&self, purpose: &DataCategory) -> String {
         purpose.to_string()
     }
 }
 
+impl KeyRotationManager {
+    /// Root key of the hierarchy the active keys are derived under
+    pub(crate) fn master_key_bytes(&self) -> Option<Zeroizing<Vec<u8>>> {
+        self.hd_derivation.master_key_bytes()
+    }
+
+    /// Undo a rotation whose migration was abandoned: the predecessor is the active
+    /// version again, and the new version moves behind it as deprecated so records
+    /// already re-encrypted under it can still be reverted. Returns the restored version
+    pub(crate) fn roll_back_rotation(&mut self, purpose: &str) -> Option<KeyVersion> {
+        let keys = self.versioned_keys.get_mut(purpose)?;
+        if keys.len() < 2 || !matches!(keys[0].status(), KeyStatus::Migrating) {
+            return None;
+        }
+        let mut abandoned = keys.remove(0);
+        abandoned.set_status(KeyStatus::Deprecated);
+        keys[0].set_status(KeyStatus::Active);
+        let restored = keys[0].version();
+        keys.insert(1, abandoned);
+        self.cache_epochs.bump(purpose);
+        Some(restored)
+    }
+
+    /// (purpose-version id, material fingerprint) of the newest key per purpose
+    pub(crate) fn active_key_fingerprints(&self) -> Vec<(String, Option<String>)> {
+        self.versioned_keys
+            .iter()
+            .filter_map(|(purpose, keys)| keys.first().map(|key| {
+                (format!("{}-{}", purpose, key.version().to_string()), key.key_fingerprint())
+            }))
+            .collect()
+    }
+
+    /// Rotation deadline and migration activity per purpose, for SLA evaluation
+    pub(crate) fn sla_states(&self) -> Vec<PurposeSlaState> {
+        self.versioned_keys
+            .iter()
+            .map(|(purpose, keys)| PurposeSlaState {
+                purpose: purpose.clone(),
+                next_rotation_ms: self.scheduler
+                    .get()
+                    .get_next_rotation_time(purpose)
+                    .map(|ms| ms as u64),
+                migration_updated_ms: keys
+                    .first()
+                    .filter(|key| matches!(key.status(), KeyStatus::Migrating))
+                    .and_then(|key| key.migration_updated_time())
+                    .map(|ms| ms as u64),
+            })
+            .collect()
+    }
+}
+
 impl Clone for KeyRotationScheduler {
     fn clone(&self) -> Self {
         KeyRotationScheduler::new()
@@ -2,7 +2,21 @@ use wasm_bindgen::prelude::*;
 use super::types::{KeyVersion, KeyStatus, RotationTiming};
 use super::versioned_key::VersionedKey;
 use std::collections::HashMap;
-use js_sys::Date;
+use crate::platform;
+use crate::memory::{memory_budget_headroom, MemorySubsystem};
+use crate::timeouts::{Deadline, OperationTimeouts, TimedOperation, Timeout};
+use crate::shutdown::{stage_checkpoint, unstage_checkpoint};
+
+/// Working memory assumed per re-encrypted record when sizing batches
+const ESTIMATED_RECORD_BYTES: usize = 1000;
+
+/// Shrink `requested` so a batch fits the remaining migration budget (at least one record)
+pub(crate) fn budgeted_batch_size(requested: u32) -> u32 {
+    match memory_budget_headroom(MemorySubsystem::MigrationBatches) {
+        Some(headroom) => requested.min((headroom / ESTIMATED_RECORD_BYTES).max(1) as u32),
+        None => requested,
+    }
+}
 
 /// Migration utilities for progressive key transitions
 #[wasm_bindgen]
@@ -14,6 +28,8 @@ pub struct ProgressiveMigrationManager {
     batch_size: u32,
     max_concurrent_batches: u32,
     migration_state: HashMap<String, MigrationCheckpoint>,
+    timeouts: OperationTimeouts,
+    batch_deadlines: HashMap<String, Deadline>, // migration id -> deadline of the batch in flight
 }
 
 /// Migration checkpoint for resumability
@@ -136,7 +152,8 @@ impl KeyMigrationHelper {
         validation
     }
 
-    /// Create migration batch for progressive processing
+    /// Create migration batch for progressive processing; the batch is smaller than
+    /// requested when the migration memory budget cannot hold it
     #[wasm_bindgen]
     pub fn create_migration_batch(
         data_identifiers: &js_sys::Array,
@@ -145,6 +162,7 @@ impl KeyMigrationHelper {
     ) -> js_sys::Object {
         let batch = js_sys::Object::new();
         let batch_data = js_sys::Array::new();
+        let batch_size = budgeted_batch_size(batch_size);
         
         let end_index = std::cmp::min(start_index + batch_size, data_identifiers.length());
         
@@ -204,6 +222,8 @@ impl ProgressiveMigrationManager {
             batch_size,
             max_concurrent_batches,
             migration_state: HashMap::new(),
+            timeouts: OperationTimeouts::default(),
+            batch_deadlines: HashMap::new(),
         }
     }
 
@@ -222,21 +242,7 @@ impl ProgressiveMigrationManager {
             _ => RotationTiming::Background,
         };
 
-        let total_batches = (total_records + self.batch_size - 1) / self.batch_size;
-        let current_time = Date::now();
-
-        let checkpoint = MigrationCheckpoint {
-            migration_id: migration_id.to_string(),
-            current_batch: 0,
-            total_batches,
-            processed_count: 0,
-            failed_count: 0,
-            last_checkpoint_time: current_time,
-            user_timing_preferences: timing,
-            integrity_hash: Self::calculate_initial_integrity_hash(migration_id, total_records),
-        };
-
-        self.migration_state.insert(migration_id.to_string(), checkpoint);
+        let total_batches = self.begin_migration(migration_id, total_records, timing);
 
         let result = js_sys::Object::new();
         js_sys::Reflect::set(&result, &JsValue::from_str("migrationId"), &JsValue::from_str(migration_id)).unwrap();
@@ -268,7 +274,19 @@ impl ProgressiveMigrationManager {
         result
     }
 
-    /// Process next batch with integrity validation
+    #[wasm_bindgen]
+    pub fn set_operation_timeouts(&mut self, timeouts: &OperationTimeouts) {
+        self.timeouts = *timeouts;
+    }
+
+    /// Start the batch timeout clock before re-encrypting the next batch
+    #[wasm_bindgen]
+    pub fn begin_batch(&mut self, migration_id: &str) -> bool {
+        self.begin_batch_at(migration_id, platform::now_ms())
+    }
+
+    /// Process next batch with integrity validation. A batch that overran its
+    /// timeout is not recorded: the checkpoint stays at the last completed batch
     #[wasm_bindgen]
     pub fn process_next_batch(
         &mut self,
@@ -279,6 +297,13 @@ impl ProgressiveMigrationManager {
     ) -> js_sys::Object {
         let result = js_sys::Object::new();
         
+        if let Err(timeout) = self.finish_batch_at(migration_id, platform::now_ms()) {
+            js_sys::Reflect::set(&result, &JsValue::from_str("success"), &JsValue::from_bool(false)).unwrap();
+            js_sys::Reflect::set(&result, &JsValue::from_str("error"), &JsValue::from_str(&timeout.message())).unwrap();
+            js_sys::Reflect::set(&result, &JsValue::from_str("timeout"), &JsValue::from(timeout)).unwrap();
+            return result;
+        }
+        
         let (current_batch, completion_rate, estimated_remaining, integrity_valid, is_complete) = if let Some(checkpoint) = self.migration_state.get_mut(migration_id) {
             let start_time = checkpoint.last_checkpoint_time;
             let integrity_hash = checkpoint.integrity_hash.clone();
@@ -287,7 +312,7 @@ impl ProgressiveMigrationManager {
             checkpoint.current_batch += 1;
             checkpoint.processed_count += processed_count;
             checkpoint.failed_count += failed_count;
-            checkpoint.last_checkpoint_time = Date::now();
+            checkpoint.last_checkpoint_time = platform::now_ms() as f64;
             
             // Calculate progress
             let _total_processed = checkpoint.processed_count + checkpoint.failed_count;
@@ -311,6 +336,8 @@ impl ProgressiveMigrationManager {
             return result;
         };
         
+        self.stage_checkpoint(migration_id);
+
         // Validate data integrity outside the borrow scope
         let integrity_valid = self.validate_batch_integrity(batch_data, &integrity_valid);
         
@@ -399,6 +426,8 @@ impl ProgressiveMigrationManager {
     /// Clear completed migration state
     #[wasm_bindgen]
     pub fn clear_migration(&mut self, migration_id: &str) -> bool {
+        self.batch_deadlines.remove(migration_id);
+        unstage_checkpoint(&Self::checkpoint_key(migration_id));
         self.migration_state.remove(migration_id).is_some()
     }
 
@@ -414,20 +443,20 @@ impl ProgressiveMigrationManager {
         // - Available memory
         // - Target processing time
         // - Total records
-        let memory_based_size = (available_memory_mb * 1024 * 1024) / 1000; // Rough estimate
+        let memory_based_size = (available_memory_mb * 1024 * 1024) / ESTIMATED_RECORD_BYTES as u32; // Rough estimate
         let time_based_size = std::cmp::max(1, target_processing_time_ms / 10); // Rough estimate
         let record_based_size = std::cmp::min(total_records / 10, 10000); // Max 10k per batch
         
-        std::cmp::min(
+        budgeted_batch_size(std::cmp::min(
             std::cmp::min(memory_based_size, time_based_size),
             std::cmp::max(record_based_size, 100) // Minimum 100 per batch
-        )
+        ))
     }
 
     // Helper methods
     fn calculate_initial_integrity_hash(migration_id: &str, total_records: u32) -> String {
         // Simple hash calculation for integrity validation
-        format!("{}-{}-{}", migration_id, total_records, Date::now())
+        format!("{}-{}-{}", migration_id, total_records, platform::now_ms() as f64)
     }
 
     fn validate_batch_integrity(&self, _batch_data: &js_sys::Array, _expected_hash: &str) -> bool {
@@ -437,6 +466,91 @@ impl ProgressiveMigrationManager {
     }
 }
 
+impl ProgressiveMigrationManager {
+    pub(crate) fn begin_migration(&mut self, migration_id: &str, total_records: u32, timing: RotationTiming) -> u32 {
+        let total_batches = total_records.div_ceil(self.batch_size);
+        let current_time = platform::now_ms() as f64;
+
+        let checkpoint = MigrationCheckpoint {
+            migration_id: migration_id.to_string(),
+            current_batch: 0,
+            total_batches,
+            processed_count: 0,
+            failed_count: 0,
+            last_checkpoint_time: current_time,
+            user_timing_preferences: timing,
+            integrity_hash: Self::calculate_initial_integrity_hash(migration_id, total_records),
+        };
+
+        self.migration_state.insert(migration_id.to_string(), checkpoint);
+        self.stage_checkpoint(migration_id);
+        total_batches
+    }
+
+    fn checkpoint_key(migration_id: &str) -> String {
+        format!("migration:{}", migration_id)
+    }
+
+    /// Queue the checkpoint for the storage flush at shutdown
+    fn stage_checkpoint(&self, migration_id: &str) {
+        if let Some(checkpoint) = self.migration_state.get(migration_id) {
+            let json = serde_json::json!({
+                "migration_id": checkpoint.migration_id,
+                "current_batch": checkpoint.current_batch,
+                "total_batches": checkpoint.total_batches,
+                "processed_count": checkpoint.processed_count,
+                "failed_count": checkpoint.failed_count,
+                "last_checkpoint_time": checkpoint.last_checkpoint_time,
+                "integrity_hash": checkpoint.integrity_hash,
+            });
+            stage_checkpoint(&Self::checkpoint_key(migration_id), json.to_string());
+        }
+    }
+
+    pub(crate) fn begin_batch_at(&mut self, migration_id: &str, now: u64) -> bool {
+        if !self.migration_state.contains_key(migration_id) {
+            return false;
+        }
+        let deadline = Deadline::starting_at(TimedOperation::MigrationBatch, &self.timeouts, now);
+        self.batch_deadlines.insert(migration_id.to_string(), deadline);
+        true
+    }
+
+    /// Close the batch in flight; an overrun leaves the checkpoint untouched
+    pub(crate) fn finish_batch_at(&mut self, migration_id: &str, now: u64) -> Result<(), Timeout> {
+        let Some(deadline) = self.batch_deadlines.remove(migration_id) else {
+            return Ok(());
+        };
+        let batch = self.migration_state.get(migration_id).map_or(0, |checkpoint| checkpoint.current_batch + 1);
+        deadline.check_at(&format!("batch {}", batch), now)
+    }
+
+    /// Snapshot of every tracked checkpoint, for stall detection
+    pub(crate) fn checkpoints(&self) -> Vec<MigrationCheckpoint> {
+        self.migration_state.values().cloned().collect()
+    }
+
+    /// Restart the checkpoint clock without touching progress; false if unknown
+    pub(crate) fn rearm_checkpoint(&mut self, migration_id: &str, now: f64) -> bool {
+        match self.migration_state.get_mut(migration_id) {
+            Some(checkpoint) => {
+                checkpoint.last_checkpoint_time = now;
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn record_batch_at(&mut self, migration_id: &str, processed_count: u32, now: f64) {
+        if let Some(checkpoint) = self.migration_state.get_mut(migration_id) {
+            checkpoint.current_batch += 1;
+            checkpoint.processed_count += processed_count;
+            checkpoint.last_checkpoint_time = now;
+        }
+    }
+}
+
 #[wasm_bindgen]
 impl BatchConfig {
     /// Create new batch configuration
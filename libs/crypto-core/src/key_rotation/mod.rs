@@ -15,6 +15,14 @@
 /// - `scheduler`: Automated rotation scheduling and policies
 /// - `manager`: Main orchestration and coordination
 /// - `migration`: Migration utilities and validation helpers
+/// - `hierarchy`: Metadata-only key tree snapshots for visualization
+/// - `audit_epochs`: Forward-secure epoch keys for archived audit segments
+/// - `monitoring`: Rotation SLA thresholds, escalation events and compliance reports
+/// - `suite_migration`: Cipher-suite deprecation campaigns with progress tracking and completion attestations
+/// - `watchdog`: Stalled migration detection with automatic resume and rollback
+/// - `cache_epochs`: Monotonic cache invalidation epochs bumped on rotation and revocation
+/// - `usage_predictor`: Hour-of-week usage histogram ranking low-disruption rotation windows
+/// - `audit`: Rotation audit trail and compliance rule evaluation under resource limits
 /// 
 /// ## Usage Example
 /// 
@@ -44,10 +52,24 @@ pub mod scheduler;
 pub mod manager;
 pub mod migration;
 pub mod emergency;
+pub mod hierarchy;
+pub mod audit_epochs;
+pub mod monitoring;
+pub mod suite_migration;
+pub mod watchdog;
+pub mod cache_epochs;
+pub mod usage_predictor;
+pub mod audit;
 
 // Re-export main types for convenience
 pub use types::{KeyVersion, KeyStatus, KeyRotationError};
 pub use versioned_key::VersionedKey;
-pub use scheduler::{KeyRotationScheduler, RotationPolicy};
+pub use scheduler::{KeyRotationScheduler, RotationPolicy, RotationPolicyBuilder, UserRotationPreferencesBuilder};
 pub use manager::KeyRotationManager;
-pub use migration::KeyMigrationHelper;
\ No newline at end of file
+pub use migration::KeyMigrationHelper;
+pub use hierarchy::{KeyHierarchyGraph, KeyHierarchyNode, KeyHierarchyEdge, KeyHierarchyNodeKind, KeyHierarchyEdgeKind};
+pub use audit_epochs::{AuditEpochKeyring, SealedAuditSegment, EpochDestructionRecord, AUDIT_EPOCH_PURPOSE};
+pub use monitoring::{RotationSlaMonitor, RotationSlaThresholds, RotationComplianceReport, SlaEvent, SlaSeverity, SlaBreachKind};
+pub use suite_migration::{AlgorithmRegistry, EnvelopeInventory, SuiteMigrationCampaign, CampaignTarget, CampaignAttestation};
+pub use watchdog::{MigrationWatchdog, WatchdogEvent, WatchdogEventKind};
+pub use audit::{AuditTrailManager, AuditTrailHead, ComplianceLimits};
@@ -0,0 +1,374 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::host_calls::{deliver_events, RetryQueue};
+use crate::platform;
+use super::manager::KeyRotationManager;
+
+// Rotation SLA monitoring
+// Each evaluation compares scheduler deadlines and migration activity against
+// configured thresholds. A breach emits an event the first time it is seen and
+// again only when it escalates (warning -> critical), so listeners are not
+// flooded on every poll. Open breaches are carried into compliance reports.
+
+const HOUR_MS: u64 = 60 * 60 * 1000;
+const DAY_MS: u64 = 24 * HOUR_MS;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SlaSeverity {
+    Warning = 1,
+    Critical = 2,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SlaBreachKind {
+    RotationOverdue = 0,
+    MigrationStuck = 1,
+}
+
+/// Breach thresholds; each warning threshold must not exceed its critical threshold
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationSlaThresholds {
+    rotation_overdue_warning_ms: u64,
+    rotation_overdue_critical_ms: u64,
+    migration_stuck_warning_ms: u64,
+    migration_stuck_critical_ms: u64,
+}
+
+impl Default for RotationSlaThresholds {
+    fn default() -> Self {
+        Self {
+            rotation_overdue_warning_ms: 7 * DAY_MS,
+            rotation_overdue_critical_ms: 14 * DAY_MS,
+            migration_stuck_warning_ms: 24 * HOUR_MS,
+            migration_stuck_critical_ms: 72 * HOUR_MS,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl RotationSlaThresholds {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        rotation_overdue_warning_days: u32,
+        rotation_overdue_critical_days: u32,
+        migration_stuck_warning_hours: u32,
+        migration_stuck_critical_hours: u32,
+    ) -> Result<RotationSlaThresholds, JsValue> {
+        if rotation_overdue_warning_days > rotation_overdue_critical_days
+            || migration_stuck_warning_hours > migration_stuck_critical_hours
+        {
+            return Err(JsValue::from_str("Warning thresholds must not exceed critical thresholds"));
+        }
+
+        Ok(Self {
+            rotation_overdue_warning_ms: rotation_overdue_warning_days as u64 * DAY_MS,
+            rotation_overdue_critical_ms: rotation_overdue_critical_days as u64 * DAY_MS,
+            migration_stuck_warning_ms: migration_stuck_warning_hours as u64 * HOUR_MS,
+            migration_stuck_critical_ms: migration_stuck_critical_hours as u64 * HOUR_MS,
+        })
+    }
+
+    #[wasm_bindgen(js_name = defaults)]
+    pub fn defaults() -> RotationSlaThresholds {
+        Self::default()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rotation_overdue_warning_ms(&self) -> u64 {
+        self.rotation_overdue_warning_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rotation_overdue_critical_ms(&self) -> u64 {
+        self.rotation_overdue_critical_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn migration_stuck_warning_ms(&self) -> u64 {
+        self.migration_stuck_warning_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn migration_stuck_critical_ms(&self) -> u64 {
+        self.migration_stuck_critical_ms
+    }
+}
+
+impl RotationSlaThresholds {
+    fn classify(&self, kind: SlaBreachKind, elapsed_ms: u64) -> Option<SlaSeverity> {
+        let (warning, critical) = match kind {
+            SlaBreachKind::RotationOverdue => (self.rotation_overdue_warning_ms, self.rotation_overdue_critical_ms),
+            SlaBreachKind::MigrationStuck => (self.migration_stuck_warning_ms, self.migration_stuck_critical_ms),
+        };
+
+        if elapsed_ms > critical {
+            Some(SlaSeverity::Critical)
+        } else if elapsed_ms > warning {
+            Some(SlaSeverity::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+/// Breach raised by the monitor, delivered to listeners and listed in compliance reports
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlaEvent {
+    kind: SlaBreachKind,
+    purpose: String,
+    severity: SlaSeverity,
+    elapsed_ms: u64, // Time past the deadline, or since the last migration progress
+    detected_at: u64,
+}
+
+#[wasm_bindgen]
+impl SlaEvent {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> SlaBreachKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn purpose(&self) -> String {
+        self.purpose.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn severity(&self) -> SlaSeverity {
+        self.severity
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn elapsed_ms(&self) -> u64 {
+        self.elapsed_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn detected_at(&self) -> u64 {
+        self.detected_at
+    }
+}
+
+/// Rotation state of one purpose at evaluation time
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PurposeSlaState {
+    pub purpose: String,
+    pub next_rotation_ms: Option<u64>,
+    pub migration_updated_ms: Option<u64>,
+}
+
+/// SLA standing for compliance reporting
+#[wasm_bindgen]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotationComplianceReport {
+    generated_at: u64,
+    thresholds: RotationSlaThresholds,
+    purposes_evaluated: u32,
+    open_breaches: Vec<SlaEvent>,
+}
+
+#[wasm_bindgen]
+impl RotationComplianceReport {
+    #[wasm_bindgen(getter)]
+    pub fn generated_at(&self) -> u64 {
+        self.generated_at
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn purposes_evaluated(&self) -> u32 {
+        self.purposes_evaluated
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn compliant(&self) -> bool {
+        self.open_breaches.is_empty()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn warning_count(&self) -> u32 {
+        self.count(SlaSeverity::Warning)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn critical_count(&self) -> u32 {
+        self.count(SlaSeverity::Critical)
+    }
+
+    #[wasm_bindgen]
+    pub fn open_breaches(&self) -> Vec<SlaEvent> {
+        self.open_breaches.clone()
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize compliance report: {}", e)))
+    }
+}
+
+impl RotationComplianceReport {
+    fn count(&self, severity: SlaSeverity) -> u32 {
+        self.open_breaches.iter().filter(|breach| breach.severity == severity).count() as u32
+    }
+}
+
+/// Evaluates rotation SLAs and emits escalating breach events
+#[wasm_bindgen]
+pub struct RotationSlaMonitor {
+    thresholds: RotationSlaThresholds,
+    open_breaches: HashMap<(SlaBreachKind, String), SlaEvent>,
+    listeners: Vec<js_sys::Function>,
+    pending_events: RetryQueue<SlaEvent>, // Held while the transport breaker is open
+    purposes_evaluated: u32,
+}
+
+#[wasm_bindgen]
+impl RotationSlaMonitor {
+    #[wasm_bindgen(constructor)]
+    pub fn new(thresholds: RotationSlaThresholds) -> Self {
+        Self {
+            thresholds,
+            open_breaches: HashMap::new(),
+            listeners: Vec::new(),
+            pending_events: RetryQueue::default(),
+            purposes_evaluated: 0,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn thresholds(&self) -> RotationSlaThresholds {
+        self.thresholds
+    }
+
+    /// Register a listener called with each new or escalated `SlaEvent`
+    #[wasm_bindgen(js_name = onEvent)]
+    pub fn on_event(&mut self, listener: js_sys::Function) {
+        self.listeners.push(listener);
+    }
+
+    /// Events waiting for the transport breaker to admit delivery
+    #[wasm_bindgen(getter)]
+    pub fn queued_events(&self) -> usize {
+        self.pending_events.len()
+    }
+
+    /// Check every purpose managed by `manager`; returns the events emitted by this pass
+    #[wasm_bindgen]
+    pub fn evaluate(&mut self, manager: &KeyRotationManager) -> Vec<SlaEvent> {
+        let events = self.evaluate_at(&manager.sla_states(), platform::now_ms());
+        deliver_events(&self.listeners, &mut self.pending_events, &events);
+        events
+    }
+
+    #[wasm_bindgen]
+    pub fn compliance_report(&self) -> RotationComplianceReport {
+        let mut open_breaches: Vec<SlaEvent> = self.open_breaches.values().cloned().collect();
+        open_breaches.sort_by(|a, b| {
+            b.severity.cmp(&a.severity).then_with(|| a.purpose.cmp(&b.purpose))
+        });
+
+        RotationComplianceReport {
+            generated_at: platform::now_ms(),
+            thresholds: self.thresholds,
+            purposes_evaluated: self.purposes_evaluated,
+            open_breaches,
+        }
+    }
+}
+
+impl RotationSlaMonitor {
+    pub(crate) fn evaluate_at(&mut self, states: &[PurposeSlaState], now: u64) -> Vec<SlaEvent> {
+        let mut events = Vec::new();
+        self.purposes_evaluated = states.len() as u32;
+
+        for state in states {
+            let observations = [
+                (SlaBreachKind::RotationOverdue, state.next_rotation_ms),
+                (SlaBreachKind::MigrationStuck, state.migration_updated_ms),
+            ];
+
+            for (kind, since) in observations {
+                let key = (kind, state.purpose.clone());
+                let elapsed_ms = since.map_or(0, |since| now.saturating_sub(since));
+
+                let Some(severity) = self.thresholds.classify(kind, elapsed_ms) else {
+                    self.open_breaches.remove(&key);
+                    continue;
+                };
+
+                let escalated = self.open_breaches
+                    .get(&key)
+                    .is_none_or(|open| severity > open.severity);
+                let event = SlaEvent {
+                    kind,
+                    purpose: state.purpose.clone(),
+                    severity,
+                    elapsed_ms,
+                    detected_at: now,
+                };
+
+                if escalated {
+                    events.push(event.clone());
+                    self.open_breaches.insert(key, event);
+                } else if let Some(open) = self.open_breaches.get_mut(&key) {
+                    open.elapsed_ms = elapsed_ms;
+                }
+            }
+        }
+
+        // Purposes no longer managed cannot stay in breach
+        self.open_breaches.retain(|(_, purpose), _| states.iter().any(|state| &state.purpose == purpose));
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(purpose: &str, next_rotation_ms: Option<u64>, migration_updated_ms: Option<u64>) -> PurposeSlaState {
+        PurposeSlaState {
+            purpose: purpose.to_string(),
+            next_rotation_ms,
+            migration_updated_ms,
+        }
+    }
+
+    #[test]
+    fn test_overdue_rotation_escalates_once_per_level() {
+        let mut monitor = RotationSlaMonitor::new(RotationSlaThresholds::default());
+        let states = [state("cycle_data", Some(0), None)];
+
+        assert!(monitor.evaluate_at(&states, 6 * DAY_MS).is_empty());
+
+        let warning = monitor.evaluate_at(&states, 8 * DAY_MS);
+        assert_eq!(warning.len(), 1);
+        assert_eq!(warning[0].severity(), SlaSeverity::Warning);
+        assert!(monitor.evaluate_at(&states, 9 * DAY_MS).is_empty());
+
+        let critical = monitor.evaluate_at(&states, 15 * DAY_MS);
+        assert_eq!(critical[0].severity(), SlaSeverity::Critical);
+
+        let report = monitor.compliance_report();
+        assert!(!report.compliant());
+        assert_eq!(report.critical_count(), 1);
+    }
+
+    #[test]
+    fn test_stuck_migration_clears_after_progress() {
+        let mut monitor = RotationSlaMonitor::new(RotationSlaThresholds::default());
+
+        let events = monitor.evaluate_at(&[state("preferences", None, Some(0))], 25 * HOUR_MS);
+        assert_eq!(events[0].kind(), SlaBreachKind::MigrationStuck);
+        assert_eq!(monitor.compliance_report().warning_count(), 1);
+
+        let resumed = [state("preferences", None, Some(25 * HOUR_MS))];
+        assert!(monitor.evaluate_at(&resumed, 26 * HOUR_MS).is_empty());
+        assert!(monitor.compliance_report().compliant());
+    }
+}
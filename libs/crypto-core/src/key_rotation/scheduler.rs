@@ -3,8 +3,10 @@ use std::collections::HashMap;
 use chrono::{DateTime, Duration, Utc, Timelike};
 use crate::key_rotation::types::{SecurityEventType, RotationTrigger, RotationTiming}; // KeyRotationError removed - unused
 use crate::key_rotation::emergency::EmergencyRotationManager; // EmergencyTriggerType removed - unused
+use crate::key_rotation::usage_predictor::{RotationWindow, UsageHistogram};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use crate::lazy_init::{LazySubsystem, Subsystem};
+use crate::platform;
 
 /// Rotation policy configuration for automated key management
 #[wasm_bindgen]
@@ -25,21 +27,7 @@ pub struct RotationPolicy {
 impl RotationPolicy {
     #[wasm_bindgen(constructor)]
     pub fn new(max_age_days: u32) -> Self {
-        Self {
-            max_age_days,
-            max_usage_count: None,
-            force_rotation_on_compromise: true,
-            requires_user_confirmation: false,
-            trigger_type: RotationTrigger::TimeBased,
-            timing_preference: RotationTiming::LowUsage,
-            security_event_triggers: vec![
-                SecurityEventType::DeviceCompromise,
-                SecurityEventType::DataBreach,
-                SecurityEventType::UnauthorizedAccess,
-            ],
-            low_usage_threshold_hours: 4,
-            emergency_rotation_enabled: true,
-        }
+        RotationPolicyBuilder::new().max_age_days(max_age_days).finish()
     }
 
     #[wasm_bindgen(getter)]
@@ -157,6 +145,134 @@ impl RotationPolicy {
     }
 }
 
+/// Step-by-step construction of a `RotationPolicy`, validated at `build()`
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct RotationPolicyBuilder {
+    policy: RotationPolicy,
+}
+
+impl Default for RotationPolicyBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl RotationPolicyBuilder {
+    /// Start from the defaults: 90 days, time-based, low-usage timing
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            policy: RotationPolicy {
+                max_age_days: 90,
+                max_usage_count: None,
+                force_rotation_on_compromise: true,
+                requires_user_confirmation: false,
+                trigger_type: RotationTrigger::TimeBased,
+                timing_preference: RotationTiming::LowUsage,
+                security_event_triggers: vec![
+                    SecurityEventType::DeviceCompromise,
+                    SecurityEventType::DataBreach,
+                    SecurityEventType::UnauthorizedAccess,
+                ],
+                low_usage_threshold_hours: 4,
+                emergency_rotation_enabled: true,
+            },
+        }
+    }
+
+    #[wasm_bindgen(js_name = maxAgeDays)]
+    pub fn max_age_days(mut self, days: u32) -> Self {
+        self.policy.max_age_days = days;
+        self
+    }
+
+    #[wasm_bindgen(js_name = maxUsageCount)]
+    pub fn max_usage_count(mut self, count: u64) -> Self {
+        self.policy.max_usage_count = Some(count);
+        self
+    }
+
+    #[wasm_bindgen(js_name = forceRotationOnCompromise)]
+    pub fn force_rotation_on_compromise(mut self, force: bool) -> Self {
+        self.policy.force_rotation_on_compromise = force;
+        self
+    }
+
+    #[wasm_bindgen(js_name = requiresUserConfirmation)]
+    pub fn requires_user_confirmation(mut self, requires: bool) -> Self {
+        self.policy.requires_user_confirmation = requires;
+        self
+    }
+
+    #[wasm_bindgen(js_name = triggerType)]
+    pub fn trigger_type(mut self, trigger_type: RotationTrigger) -> Self {
+        self.policy.trigger_type = trigger_type;
+        self
+    }
+
+    #[wasm_bindgen(js_name = timingPreference)]
+    pub fn timing_preference(mut self, timing: RotationTiming) -> Self {
+        self.policy.timing_preference = timing;
+        self
+    }
+
+    #[wasm_bindgen(js_name = securityEventTrigger)]
+    pub fn security_event_trigger(mut self, event_type: SecurityEventType) -> Self {
+        self.policy.add_security_event_trigger(event_type);
+        self
+    }
+
+    #[wasm_bindgen(js_name = clearSecurityEventTriggers)]
+    pub fn clear_security_event_triggers(mut self) -> Self {
+        self.policy.security_event_triggers.clear();
+        self
+    }
+
+    #[wasm_bindgen(js_name = lowUsageThresholdHours)]
+    pub fn low_usage_threshold_hours(mut self, hours: u32) -> Self {
+        self.policy.low_usage_threshold_hours = hours;
+        self
+    }
+
+    #[wasm_bindgen(js_name = emergencyRotationEnabled)]
+    pub fn emergency_rotation_enabled(mut self, enabled: bool) -> Self {
+        self.policy.emergency_rotation_enabled = enabled;
+        self
+    }
+
+    #[wasm_bindgen]
+    pub fn build(self) -> Result<RotationPolicy, JsValue> {
+        self.validate().map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.finish())
+    }
+}
+
+impl RotationPolicyBuilder {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let policy = &self.policy;
+        if !(1..=3650).contains(&policy.max_age_days) {
+            return Err("Max age must be between 1 and 3650 days".to_string());
+        }
+        if policy.max_usage_count == Some(0) {
+            return Err("Max usage count must be greater than 0".to_string());
+        }
+        if policy.trigger_type == RotationTrigger::UsageBased && policy.max_usage_count.is_none() {
+            return Err("Usage-based rotation requires a max usage count".to_string());
+        }
+        if policy.low_usage_threshold_hours > 24 * 7 {
+            return Err("Low-usage threshold must not exceed one week".to_string());
+        }
+        Ok(())
+    }
+
+    /// Unvalidated result, used by the legacy constructor
+    pub(crate) fn finish(self) -> RotationPolicy {
+        self.policy
+    }
+}
+
 /// User preferences for rotation timing and behavior
 #[wasm_bindgen]
 #[derive(Debug, Clone)]
@@ -172,13 +288,7 @@ pub struct UserRotationPreferences {
 impl UserRotationPreferences {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
-        Self {
-            preferred_rotation_time_hour: 3, // 3 AM default
-            allow_automatic_rotation: true,
-            notification_advance_hours: 24,
-            pause_during_active_usage: true,
-            emergency_rotation_requires_confirmation: false,
-        }
+        UserRotationPreferencesBuilder::new().finish()
     }
 
     #[wasm_bindgen(getter)]
@@ -234,6 +344,88 @@ impl UserRotationPreferences {
     }
 }
 
+/// Step-by-step construction of `UserRotationPreferences`, validated at `build()`
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct UserRotationPreferencesBuilder {
+    preferences: UserRotationPreferences,
+}
+
+impl Default for UserRotationPreferencesBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl UserRotationPreferencesBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            preferences: UserRotationPreferences {
+                preferred_rotation_time_hour: 3, // 3 AM default
+                allow_automatic_rotation: true,
+                notification_advance_hours: 24,
+                pause_during_active_usage: true,
+                emergency_rotation_requires_confirmation: false,
+            },
+        }
+    }
+
+    #[wasm_bindgen(js_name = preferredRotationTimeHour)]
+    pub fn preferred_rotation_time_hour(mut self, hour: u8) -> Self {
+        self.preferences.preferred_rotation_time_hour = hour;
+        self
+    }
+
+    #[wasm_bindgen(js_name = allowAutomaticRotation)]
+    pub fn allow_automatic_rotation(mut self, allow: bool) -> Self {
+        self.preferences.allow_automatic_rotation = allow;
+        self
+    }
+
+    #[wasm_bindgen(js_name = notificationAdvanceHours)]
+    pub fn notification_advance_hours(mut self, hours: u32) -> Self {
+        self.preferences.notification_advance_hours = hours;
+        self
+    }
+
+    #[wasm_bindgen(js_name = pauseDuringActiveUsage)]
+    pub fn pause_during_active_usage(mut self, pause: bool) -> Self {
+        self.preferences.pause_during_active_usage = pause;
+        self
+    }
+
+    #[wasm_bindgen(js_name = emergencyRotationRequiresConfirmation)]
+    pub fn emergency_rotation_requires_confirmation(mut self, requires: bool) -> Self {
+        self.preferences.emergency_rotation_requires_confirmation = requires;
+        self
+    }
+
+    #[wasm_bindgen]
+    pub fn build(self) -> Result<UserRotationPreferences, JsValue> {
+        self.validate().map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.finish())
+    }
+}
+
+impl UserRotationPreferencesBuilder {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.preferences.preferred_rotation_time_hour > 23 {
+            return Err("Preferred rotation hour must be between 0 and 23".to_string());
+        }
+        if self.preferences.notification_advance_hours > 24 * 30 {
+            return Err("Notification advance must not exceed 30 days".to_string());
+        }
+        Ok(())
+    }
+
+    /// Unvalidated result, used by the legacy constructor
+    pub(crate) fn finish(self) -> UserRotationPreferences {
+        self.preferences
+    }
+}
+
 /// Security event for triggering rotations
 #[wasm_bindgen]
 #[derive(Debug, Clone)]
@@ -252,7 +444,7 @@ impl SecurityEvent {
         Self {
             event_type,
             severity: if severity > 10 { 10 } else if severity < 1 { 1 } else { severity },
-            timestamp: Utc::now(),
+            timestamp: platform::now(),
             device_id: None,
             description,
         }
@@ -312,8 +504,9 @@ pub struct KeyRotationScheduler {
     user_preferences: UserRotationPreferences,
     security_events: Vec<SecurityEvent>,
     usage_tracking: HashMap<String, u64>, // purpose -> usage count
-    emergency_manager: EmergencyRotationManager,
-    incident_detection: IncidentDetectionSystem,
+    usage_histogram: UsageHistogram, // hour-of-week usage across all purposes
+    emergency_manager: LazySubsystem<EmergencyRotationManager>,
+    incident_detection: LazySubsystem<IncidentDetectionSystem>,
 }
 
 #[wasm_bindgen]
@@ -327,8 +520,9 @@ impl KeyRotationScheduler {
             user_preferences: UserRotationPreferences::new(),
             security_events: Vec::new(),
             usage_tracking: HashMap::new(),
-            emergency_manager: EmergencyRotationManager::new(),
-            incident_detection: IncidentDetectionSystem::new(),
+            usage_histogram: UsageHistogram::default(),
+            emergency_manager: LazySubsystem::new(Subsystem::EmergencyRotation, EmergencyRotationManager::new),
+            incident_detection: LazySubsystem::new(Subsystem::IncidentDetection, IncidentDetectionSystem::new),
         }
     }
 
@@ -339,14 +533,14 @@ impl KeyRotationScheduler {
         self.rotation_policies.insert(purpose.to_string(), policy);
         
         // Schedule next rotation
-        let next_rotation = Utc::now() + interval;
+        let next_rotation = platform::now() + interval;
         self.next_rotations.insert(purpose.to_string(), next_rotation);
     }
 
     #[wasm_bindgen]
     pub fn is_rotation_due(&self, purpose: &str) -> bool {
         if let Some(next_rotation) = self.next_rotations.get(purpose) {
-            *next_rotation <= Utc::now()
+            *next_rotation <= platform::now()
         } else {
             false
         }
@@ -361,7 +555,7 @@ impl KeyRotationScheduler {
     #[wasm_bindgen]
     pub fn get_time_until_rotation(&self, purpose: &str) -> Option<f64> {
         if let Some(next_rotation) = self.next_rotations.get(purpose) {
-            let duration = *next_rotation - Utc::now();
+            let duration = *next_rotation - platform::now();
             Some(duration.num_milliseconds() as f64)
         } else {
             None
@@ -371,13 +565,13 @@ impl KeyRotationScheduler {
     #[wasm_bindgen]
     pub fn force_rotation(&mut self, purpose: &str) {
         // Set next rotation to now to trigger immediate rotation
-        self.next_rotations.insert(purpose.to_string(), Utc::now());
+        self.next_rotations.insert(purpose.to_string(), platform::now());
     }
 
     #[wasm_bindgen]
     pub fn update_next_rotation(&mut self, purpose: &str) {
         if let Some(interval) = self.rotation_intervals.get(purpose) {
-            let next_rotation = Utc::now() + *interval;
+            let next_rotation = platform::now() + *interval;
             self.next_rotations.insert(purpose.to_string(), next_rotation);
         }
     }
@@ -411,7 +605,7 @@ impl KeyRotationScheduler {
     #[wasm_bindgen]
     pub fn get_rotations_due_within(&self, hours: u32) -> js_sys::Array {
         let array = js_sys::Array::new();
-        let threshold = Utc::now() + Duration::hours(hours as i64);
+        let threshold = platform::now() + Duration::hours(hours as i64);
         
         for (purpose, next_rotation) in &self.next_rotations {
             if *next_rotation <= threshold {
@@ -419,9 +613,9 @@ impl KeyRotationScheduler {
                 
                 js_sys::Reflect::set(&obj, &JsValue::from_str("purpose"), &JsValue::from_str(purpose)).unwrap();
                 js_sys::Reflect::set(&obj, &JsValue::from_str("nextRotation"), &JsValue::from_f64(next_rotation.timestamp_millis() as f64)).unwrap();
-                js_sys::Reflect::set(&obj, &JsValue::from_str("isDue"), &JsValue::from_bool(*next_rotation <= Utc::now())).unwrap();
+                js_sys::Reflect::set(&obj, &JsValue::from_str("isDue"), &JsValue::from_bool(*next_rotation <= platform::now())).unwrap();
                 js_sys::Reflect::set(&obj, &JsValue::from_str("hoursUntilDue"), &JsValue::from_f64(
-                    (*next_rotation - Utc::now()).num_hours() as f64
+                    (*next_rotation - platform::now()).num_hours() as f64
                 )).unwrap();
                 
                 array.push(&obj);
@@ -446,7 +640,7 @@ impl KeyRotationScheduler {
         let target_time = DateTime::from_timestamp_millis(timestamp_ms as i64)
             .ok_or_else(|| JsValue::from_str("Invalid timestamp"))?;
         
-        if target_time <= Utc::now() {
+        if target_time <= platform::now() {
             return Err(JsValue::from_str("Cannot schedule rotation in the past"));
         }
         
@@ -480,10 +674,10 @@ impl KeyRotationScheduler {
             .filter(|(purpose, _)| self.is_rotation_due(purpose))
             .count();
         let due_within_24h = self.next_rotations.iter()
-            .filter(|(_, next_rotation)| **next_rotation <= Utc::now() + Duration::hours(24))
+            .filter(|(_, next_rotation)| **next_rotation <= platform::now() + Duration::hours(24))
             .count();
         let due_within_7d = self.next_rotations.iter()
-            .filter(|(_, next_rotation)| **next_rotation <= Utc::now() + Duration::days(7))
+            .filter(|(_, next_rotation)| **next_rotation <= platform::now() + Duration::days(7))
             .count();
         
         js_sys::Reflect::set(&stats, &JsValue::from_str("totalScheduled"), &JsValue::from_f64(total_scheduled as f64)).unwrap();
@@ -503,7 +697,7 @@ impl KeyRotationScheduler {
 
     #[wasm_bindgen]
     pub fn cleanup_expired_schedules(&mut self) -> u32 {
-        let expired_threshold = Utc::now() - Duration::days(30); // Remove schedules older than 30 days
+        let expired_threshold = platform::now() - Duration::days(30); // Remove schedules older than 30 days
         let original_count = self.next_rotations.len();
         
         self.next_rotations.retain(|_, next_rotation| *next_rotation > expired_threshold);
@@ -596,7 +790,7 @@ impl KeyRotationScheduler {
     #[wasm_bindgen(js_name = getRecentSecurityEvents)]
     pub fn get_recent_security_events(&self, hours: u32) -> js_sys::Array {
         let array = js_sys::Array::new();
-        let threshold = Utc::now() - Duration::hours(hours as i64);
+        let threshold = platform::now() - Duration::hours(hours as i64);
         
         for event in &self.security_events {
             if event.timestamp >= threshold {
@@ -623,6 +817,7 @@ impl KeyRotationScheduler {
     // Usage Tracking
     #[wasm_bindgen(js_name = trackKeyUsage)]
     pub fn track_key_usage(&mut self, purpose: &str) {
+        self.usage_histogram.record(platform::now());
         let count = self.usage_tracking.entry(purpose.to_string()).or_insert(0);
         *count += 1;
         
@@ -656,19 +851,33 @@ impl KeyRotationScheduler {
         let policy = self.rotation_policies.get(purpose)
             .ok_or_else(|| JsValue::from_str("Policy not found for purpose"))?;
         
-        let preferred_hour = self.user_preferences.preferred_rotation_time_hour;
-        let base_time = Utc::now() + Duration::days(policy.max_age_days as i64);
-        
-        // Adjust to preferred hour
-        let adjusted_time = base_time
-            .with_hour(preferred_hour as u32).unwrap_or(base_time)
-            .with_minute(0).unwrap_or(base_time)
-            .with_second(0).unwrap_or(base_time);
+        let base_time = platform::now() + Duration::days(policy.max_age_days as i64);
+        let adjusted_time = self.preferred_rotation_time(base_time);
         
         self.next_rotations.insert(purpose.to_string(), adjusted_time);
         Ok(adjusted_time.timestamp_millis() as f64)
     }
 
+    /// Quietest upcoming rotation windows learned from key usage, best first
+    #[wasm_bindgen(js_name = suggestRotationWindows)]
+    pub fn suggest_rotation_windows(&self, count: usize) -> js_sys::Array {
+        let now = platform::now();
+        let array = js_sys::Array::new();
+        
+        for window in self.ranked_rotation_windows(count) {
+            let obj = js_sys::Object::new();
+            
+            js_sys::Reflect::set(&obj, &JsValue::from_str("weekday"), &JsValue::from_f64(window.weekday() as f64)).unwrap();
+            js_sys::Reflect::set(&obj, &JsValue::from_str("hour"), &JsValue::from_f64(window.hour() as f64)).unwrap();
+            js_sys::Reflect::set(&obj, &JsValue::from_str("expectedUsage"), &JsValue::from_f64(window.expected_usage)).unwrap();
+            js_sys::Reflect::set(&obj, &JsValue::from_str("nextStart"), &JsValue::from_f64(window.next_start(now).timestamp_millis() as f64)).unwrap();
+            
+            array.push(&obj);
+        }
+        
+        array
+    }
+
     #[wasm_bindgen(js_name = isRotationAllowedNow)]
     pub fn is_rotation_allowed_now(&self, purpose: &str, is_user_active: bool) -> bool {
         if !self.user_preferences.allow_automatic_rotation {
@@ -685,7 +894,7 @@ impl KeyRotationScheduler {
                 RotationTiming::Immediate => true,
                 RotationTiming::LowUsage => !is_user_active,
                 RotationTiming::Scheduled => {
-                    let now = Utc::now();
+                    let now = platform::now();
                     let current_hour = now.hour() as u8;
                     let preferred_hour = self.user_preferences.preferred_rotation_time_hour;
                     
@@ -706,6 +915,31 @@ impl KeyRotationScheduler {
     }
 
     // Private helper methods
+    
+    /// Ranked windows from usage history; the fixed preferred hour until enough history exists
+    fn ranked_rotation_windows(&self, count: usize) -> Vec<RotationWindow> {
+        let preferred_hour = self.user_preferences.preferred_rotation_time_hour;
+        if self.usage_histogram.has_enough_history() {
+            self.usage_histogram.rank_windows(preferred_hour, count)
+        } else {
+            UsageHistogram::default().rank_windows(preferred_hour, count.min(7))
+        }
+    }
+
+    fn preferred_rotation_time(&self, base_time: DateTime<Utc>) -> DateTime<Utc> {
+        if self.usage_histogram.has_enough_history() {
+            if let Some(window) = self.ranked_rotation_windows(1).first() {
+                return window.next_start(base_time);
+            }
+        }
+        
+        let preferred_hour = self.user_preferences.preferred_rotation_time_hour;
+        base_time
+            .with_hour(preferred_hour as u32).unwrap_or(base_time)
+            .with_minute(0).unwrap_or(base_time)
+            .with_second(0).unwrap_or(base_time)
+    }
+
     fn apply_user_preferences_to_schedules(&mut self) {
         if !self.user_preferences.allow_automatic_rotation {
             return;
@@ -769,6 +1003,7 @@ impl KeyRotationScheduler {
         severity: u8,
     ) -> Result<String, JsValue> {
         self.emergency_manager
+            .get_mut()
             .trigger_emergency_rotation(trigger_type, description, affected_devices, severity)
             .map_err(|e| JsValue::from_str(&e))
     }
@@ -779,6 +1014,7 @@ impl KeyRotationScheduler {
         event_data: &str
     ) -> Result<bool, JsValue> {
         self.incident_detection
+            .get_mut()
             .detect_incident(device_id, event_data)
             .map_err(|e| JsValue::from_str(&e))
     }
@@ -786,6 +1022,7 @@ impl KeyRotationScheduler {
     #[wasm_bindgen(js_name = "getActiveIncidents")]
     pub fn get_active_incidents(&self) -> Result<String, JsValue> {
         self.incident_detection
+            .get()
             .get_active_incidents()
             .map_err(|e| JsValue::from_str(&e))
     }
@@ -793,6 +1030,7 @@ impl KeyRotationScheduler {
     #[wasm_bindgen(js_name = "updateIncidentDetectionThresholds")]
     pub fn update_incident_detection_thresholds(&mut self, thresholds: &str) -> Result<(), JsValue> {
         self.incident_detection
+            .get_mut()
             .update_thresholds(thresholds)
             .map_err(|e| JsValue::from_str(&e))
     }
@@ -1003,9 +1241,9 @@ impl IncidentDetectionSystem {
         severity: u8,
     ) -> DetectedIncident {
         DetectedIncident {
-            id: Uuid::new_v4().to_string(),
+            id: platform::new_uuid(),
             incident_type,
-            detected_at: Utc::now(),
+            detected_at: platform::now(),
             confidence_score: confidence,
             affected_devices: vec![device_id.to_string()],
             indicators,
@@ -1041,7 +1279,7 @@ impl IncidentDetectionSystem {
                 device_id: device_id.to_string(),
                 typical_access_hours: Vec::new(),
                 typical_usage_patterns: HashMap::new(),
-                last_updated: Utc::now(),
+                last_updated: platform::now(),
                 access_frequency: 0.0,
                 typical_locations: Vec::new(),
             });
@@ -1063,6 +1301,58 @@ impl IncidentDetectionSystem {
             baseline.typical_usage_patterns.insert("data_access_volume".to_string(), new_avg);
         }
 
-        baseline.last_updated = Utc::now();
+        baseline.last_updated = platform::now();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_policy_builder_sets_fields_and_validates() {
+        let builder = RotationPolicyBuilder::new()
+            .max_age_days(30)
+            .max_usage_count(1_000)
+            .trigger_type(RotationTrigger::UsageBased)
+            .requires_user_confirmation(true)
+            .clear_security_event_triggers()
+            .security_event_trigger(SecurityEventType::DeviceCompromise)
+            .low_usage_threshold_hours(8);
+        assert!(builder.validate().is_ok());
+        let policy = builder.finish();
+        assert_eq!((policy.max_age_days(), policy.max_usage_count), (30, Some(1_000)));
+        assert!(policy.requires_user_confirmation());
+        assert_eq!(policy.security_event_triggers, vec![SecurityEventType::DeviceCompromise]);
+        assert_eq!(policy.low_usage_threshold_hours, 8);
+
+        assert!(RotationPolicyBuilder::new().max_age_days(0).validate().is_err());
+        assert!(RotationPolicyBuilder::new().max_age_days(3651).validate().is_err());
+        assert!(RotationPolicyBuilder::new().max_usage_count(0).validate().is_err());
+        assert!(RotationPolicyBuilder::new().trigger_type(RotationTrigger::UsageBased).validate().is_err());
+        assert!(RotationPolicyBuilder::new().low_usage_threshold_hours(24 * 7 + 1).validate().is_err());
+
+        // The legacy constructor skips validation
+        assert_eq!(RotationPolicy::new(0).max_age_days(), 0);
+    }
+
+    #[test]
+    fn test_preferences_builder_sets_fields_and_validates() {
+        let builder = UserRotationPreferencesBuilder::new()
+            .preferred_rotation_time_hour(23)
+            .allow_automatic_rotation(false)
+            .notification_advance_hours(48)
+            .pause_during_active_usage(false)
+            .emergency_rotation_requires_confirmation(true);
+        assert!(builder.validate().is_ok());
+        let preferences = builder.finish();
+        assert_eq!(preferences.preferred_rotation_time_hour(), 23);
+        assert!(!preferences.allow_automatic_rotation() && !preferences.pause_during_active_usage());
+        assert_eq!(preferences.notification_advance_hours(), 48);
+        assert!(preferences.emergency_rotation_requires_confirmation());
+
+        assert!(UserRotationPreferencesBuilder::new().preferred_rotation_time_hour(24).validate().is_err());
+        assert!(UserRotationPreferencesBuilder::new().notification_advance_hours(24 * 30 + 1).validate().is_err());
+        assert_eq!(UserRotationPreferences::new().preferred_rotation_time_hour(), 3);
+    }
+}
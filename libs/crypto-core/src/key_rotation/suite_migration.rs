@@ -0,0 +1,708 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use sha2::{Digest, Sha256};
+use crate::envelope::{decode_hex, to_hex, CryptoAlgorithm};
+use crate::inbox::device_fingerprint;
+use crate::platform;
+use crate::signing::{self, SigningKeyPair};
+use super::migration::ProgressiveMigrationManager;
+
+// Cipher-suite deprecation campaigns
+// Deprecating a suite produces a campaign listing every purpose that still holds
+// envelopes under it, with record counts taken from the storage inventory. Each
+// purpose is scheduled as its own progressive migration, progress is tracked per
+// purpose, and once everything is re-encrypted the campaign issues an attestation
+// binding the inventory it started from to the completion time.
+// The affected (purpose, record count) pairs form a sparse Merkle map keyed by
+// SHA-256(purpose, suite), so each purpose's count can be proven against the root
+// with an `InventoryProof`. Attestations are Ed25519-signed (see `signing`) over the
+// digest of their fields; verifiers check them against the signer's published key.
+
+const TREE_DEPTH: usize = 256;
+const EMPTY_SUBTREE: [u8; 32] = [0u8; 32];
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+pub(crate) const ATTESTATION_CONTEXT: &[u8] = b"suite-migration-attestation-v1";
+
+fn suite_name(algorithm: CryptoAlgorithm) -> &'static str {
+    match algorithm {
+        CryptoAlgorithm::AES256GCM => "aes-256-gcm",
+        CryptoAlgorithm::ChaCha20Poly1305 => "chacha20-poly1305",
+    }
+}
+
+// Sparse Merkle map
+// Leaves sit at depth 256 along the bits of their key path. Empty subtrees hash to
+// zero at every height, so only populated branches are ever hashed, and proofs carry
+// only non-empty siblings plus a bitmap of which heights have one.
+
+fn leaf_path(purpose: &str, suite: &str) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(purpose.as_bytes())
+        .chain_update([0u8])
+        .chain_update(suite.as_bytes())
+        .finalize()
+        .into()
+}
+
+fn leaf_hash(path: &[u8; 32], record_count: u64) -> [u8; 32] {
+    Sha256::new()
+        .chain_update([LEAF_PREFIX])
+        .chain_update(path)
+        .chain_update(record_count.to_le_bytes())
+        .finalize()
+        .into()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    if *left == EMPTY_SUBTREE && *right == EMPTY_SUBTREE {
+        return EMPTY_SUBTREE;
+    }
+    Sha256::new()
+        .chain_update([NODE_PREFIX])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+fn path_bit(path: &[u8; 32], depth: usize) -> bool {
+    path[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+// Hash of the subtree at `depth` holding `leaves` (sorted by path, all sharing the
+// first `depth` bits)
+fn subtree_hash(leaves: &[([u8; 32], [u8; 32])], depth: usize) -> [u8; 32] {
+    match leaves {
+        [] => EMPTY_SUBTREE,
+        [(_, leaf)] if depth == TREE_DEPTH => *leaf,
+        _ => {
+            let split = leaves.partition_point(|(path, _)| !path_bit(path, depth));
+            node_hash(&subtree_hash(&leaves[..split], depth + 1), &subtree_hash(&leaves[split..], depth + 1))
+        }
+    }
+}
+
+fn sorted_leaves(targets: &[CampaignTarget], suite: &str) -> Vec<([u8; 32], [u8; 32])> {
+    let mut leaves: Vec<([u8; 32], [u8; 32])> = targets
+        .iter()
+        .map(|target| {
+            let path = leaf_path(&target.purpose, suite);
+            (path, leaf_hash(&path, target.record_count))
+        })
+        .collect();
+    leaves.sort_unstable();
+    leaves
+}
+
+fn inventory_root(targets: &[CampaignTarget], suite: &str) -> String {
+    to_hex(&subtree_hash(&sorted_leaves(targets, suite), 0))
+}
+
+/// Envelope counts per purpose and suite, as reported by the storage layer
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct EnvelopeInventory {
+    counts: BTreeMap<(String, u8), u64>, // (purpose, algorithm) -> records
+}
+
+#[wasm_bindgen]
+impl EnvelopeInventory {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> EnvelopeInventory {
+        Self::default()
+    }
+
+    /// Add `record_count` envelopes for `purpose` sealed with `algorithm`
+    #[wasm_bindgen]
+    pub fn record(&mut self, purpose: String, algorithm: CryptoAlgorithm, record_count: u64) {
+        *self.counts.entry((purpose, algorithm as u8)).or_insert(0) += record_count;
+    }
+
+    #[wasm_bindgen]
+    pub fn records_for(&self, algorithm: CryptoAlgorithm) -> u64 {
+        self.counts
+            .iter()
+            .filter(|((_, suite), _)| *suite == algorithm as u8)
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+/// Tracks which cipher suites may still be used for new envelopes
+#[wasm_bindgen]
+pub struct AlgorithmRegistry {
+    deprecated: HashMap<u8, u64>, // algorithm -> deprecated_at
+}
+
+impl Default for AlgorithmRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl AlgorithmRegistry {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> AlgorithmRegistry {
+        AlgorithmRegistry { deprecated: HashMap::new() }
+    }
+
+    #[wasm_bindgen]
+    pub fn is_deprecated(&self, algorithm: CryptoAlgorithm) -> bool {
+        self.deprecated.contains_key(&(algorithm as u8))
+    }
+
+    /// Deprecate `algorithm` and build the campaign moving its envelopes to `replacement`
+    #[wasm_bindgen]
+    pub fn deprecate(
+        &mut self,
+        algorithm: CryptoAlgorithm,
+        replacement: CryptoAlgorithm,
+        inventory: &EnvelopeInventory,
+    ) -> Result<SuiteMigrationCampaign, JsValue> {
+        self.deprecate_at(algorithm, replacement, inventory, platform::now_ms())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl AlgorithmRegistry {
+    pub(crate) fn deprecate_at(
+        &mut self,
+        algorithm: CryptoAlgorithm,
+        replacement: CryptoAlgorithm,
+        inventory: &EnvelopeInventory,
+        now: u64,
+    ) -> Result<SuiteMigrationCampaign, String> {
+        if algorithm == replacement {
+            return Err("Replacement suite must differ from the deprecated suite".to_string());
+        }
+        if self.is_deprecated(replacement) {
+            return Err(format!("Replacement suite {} is itself deprecated", suite_name(replacement)));
+        }
+
+        self.deprecated.entry(algorithm as u8).or_insert(now);
+        Ok(SuiteMigrationCampaign::from_inventory(algorithm, replacement, inventory, now))
+    }
+}
+
+/// Re-encryption work for one purpose within a campaign
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignTarget {
+    purpose: String,
+    record_count: u64,
+    migrated: u64,
+    failed: u64,
+}
+
+#[wasm_bindgen]
+impl CampaignTarget {
+    #[wasm_bindgen(getter)]
+    pub fn purpose(&self) -> String {
+        self.purpose.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn record_count(&self) -> u64 {
+        self.record_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn migrated(&self) -> u64 {
+        self.migrated
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn failed(&self) -> u64 {
+        self.failed
+    }
+
+    #[wasm_bindgen]
+    pub fn is_complete(&self) -> bool {
+        self.migrated >= self.record_count
+    }
+}
+
+/// Proof that one purpose's record count is in a campaign's inventory root
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryProof {
+    purpose: String,
+    suite: String,
+    record_count: u64,
+    sibling_bitmap: String,
+    siblings: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl InventoryProof {
+    #[wasm_bindgen(getter)]
+    pub fn purpose(&self) -> String {
+        self.purpose.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn record_count(&self) -> u64 {
+        self.record_count
+    }
+
+    /// True if the proof leads from this purpose's leaf to `inventory_root`
+    #[wasm_bindgen]
+    pub fn verify(&self, inventory_root: &str) -> bool {
+        self.computed_root().is_some_and(|root| to_hex(&root) == inventory_root)
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize proof: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<InventoryProof, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid inventory proof: {}", e)))
+    }
+}
+
+impl InventoryProof {
+    fn for_target(targets: &[CampaignTarget], suite: &str, target: &CampaignTarget) -> InventoryProof {
+        let leaves = sorted_leaves(targets, suite);
+        let path = leaf_path(&target.purpose, suite);
+        let mut bitmap = [0u8; 32];
+        let mut siblings = Vec::new();
+        let mut branch = &leaves[..];
+        for depth in 0..TREE_DEPTH {
+            let split = branch.partition_point(|(leaf_path, _)| !path_bit(leaf_path, depth));
+            let (ours, theirs) = if path_bit(&path, depth) {
+                (&branch[split..], &branch[..split])
+            } else {
+                (&branch[..split], &branch[split..])
+            };
+            let sibling = subtree_hash(theirs, depth + 1);
+            if sibling != EMPTY_SUBTREE {
+                bitmap[depth / 8] |= 0x80 >> (depth % 8);
+                siblings.push(to_hex(&sibling));
+            }
+            branch = ours;
+        }
+
+        InventoryProof {
+            purpose: target.purpose.clone(),
+            suite: suite.to_string(),
+            record_count: target.record_count,
+            sibling_bitmap: to_hex(&bitmap),
+            siblings,
+        }
+    }
+
+    fn computed_root(&self) -> Option<[u8; 32]> {
+        let bitmap: [u8; 32] = decode_hex(&self.sibling_bitmap).ok()?.try_into().ok()?;
+        let path = leaf_path(&self.purpose, &self.suite);
+        let mut siblings = self.siblings.iter().rev();
+        let mut node = leaf_hash(&path, self.record_count);
+        for depth in (0..TREE_DEPTH).rev() {
+            let sibling: [u8; 32] = if path_bit(&bitmap, depth) {
+                decode_hex(siblings.next()?).ok()?.try_into().ok()?
+            } else {
+                EMPTY_SUBTREE
+            };
+            node = if path_bit(&path, depth) { node_hash(&sibling, &node) } else { node_hash(&node, &sibling) };
+        }
+        // Every supplied sibling must have been used
+        siblings.next().is_none().then_some(node)
+    }
+}
+
+/// Compliance record that a campaign migrated every inventoried envelope
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignAttestation {
+    campaign_id: String,
+    deprecated_suite: String,
+    replacement_suite: String,
+    inventory_root: String,
+    total_records: u64,
+    started_at: u64,
+    completed_at: u64,
+    digest: String,
+    signer_key_id: String,
+    signature: String,
+}
+
+#[wasm_bindgen]
+impl CampaignAttestation {
+    #[wasm_bindgen(getter)]
+    pub fn campaign_id(&self) -> String {
+        self.campaign_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn inventory_root(&self) -> String {
+        self.inventory_root.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn total_records(&self) -> u64 {
+        self.total_records
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn completed_at(&self) -> u64 {
+        self.completed_at
+    }
+
+    /// Hex SHA-256 over every attested field
+    #[wasm_bindgen(getter)]
+    pub fn digest(&self) -> String {
+        self.digest.clone()
+    }
+
+    /// Fingerprint of the signing key's public key
+    #[wasm_bindgen(getter)]
+    pub fn signer_key_id(&self) -> String {
+        self.signer_key_id.clone()
+    }
+
+    /// Hex Ed25519 signature over the attestation context and digest
+    #[wasm_bindgen(getter)]
+    pub fn signature(&self) -> String {
+        self.signature.clone()
+    }
+
+    /// True if the fields match the digest and the digest is signed by the holder of
+    /// `signer_public_key`, which must come from the signer, not from this attestation
+    #[wasm_bindgen]
+    pub fn verify(&self, signer_public_key: &[u8]) -> bool {
+        let Ok(signature) = decode_hex(&self.signature) else {
+            return false;
+        };
+        self.digest == self.compute_digest()
+            && self.signer_key_id == device_fingerprint(signer_public_key)
+            && signing::verify(signer_public_key, &self.signed_message(), &signature)
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize attestation: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<CampaignAttestation, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid attestation: {}", e)))
+    }
+}
+
+impl CampaignAttestation {
+    fn compute_digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for field in [
+            &self.campaign_id,
+            &self.deprecated_suite,
+            &self.replacement_suite,
+            &self.inventory_root,
+        ] {
+            hasher.update(field.as_bytes());
+            hasher.update([0u8]);
+        }
+        hasher.update(self.total_records.to_le_bytes());
+        hasher.update(self.started_at.to_le_bytes());
+        hasher.update(self.completed_at.to_le_bytes());
+        to_hex(&hasher.finalize())
+    }
+
+    fn signed_message(&self) -> Vec<u8> {
+        [ATTESTATION_CONTEXT, self.digest.as_bytes()].concat()
+    }
+}
+
+/// Migration campaign created when a cipher suite is deprecated
+#[wasm_bindgen]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuiteMigrationCampaign {
+    campaign_id: String,
+    deprecated_suite: String,
+    replacement_suite: String,
+    created_at: u64,
+    inventory_root: String,
+    targets: Vec<CampaignTarget>,
+}
+
+#[wasm_bindgen]
+impl SuiteMigrationCampaign {
+    #[wasm_bindgen(getter)]
+    pub fn campaign_id(&self) -> String {
+        self.campaign_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn deprecated_suite(&self) -> String {
+        self.deprecated_suite.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn replacement_suite(&self) -> String {
+        self.replacement_suite.clone()
+    }
+
+    /// Hex root of the sparse Merkle map of affected (purpose, record count) pairs
+    /// at creation
+    #[wasm_bindgen(getter)]
+    pub fn inventory_root(&self) -> String {
+        self.inventory_root.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn targets(&self) -> Vec<CampaignTarget> {
+        self.targets.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn total_records(&self) -> u64 {
+        self.targets.iter().map(|target| target.record_count).sum()
+    }
+
+    /// Migration id used for `purpose` when scheduled on the migration manager
+    #[wasm_bindgen]
+    pub fn migration_id(&self, purpose: &str) -> String {
+        format!("{}:{}", self.campaign_id, purpose)
+    }
+
+    /// Start one progressive migration per affected purpose; returns the migration ids
+    #[wasm_bindgen]
+    pub fn schedule(&self, orchestrator: &mut ProgressiveMigrationManager, timing_preference: &str) -> Vec<String> {
+        self.targets
+            .iter()
+            .map(|target| {
+                let migration_id = self.migration_id(&target.purpose);
+                let record_count = u32::try_from(target.record_count).unwrap_or(u32::MAX);
+                orchestrator.start_migration(&migration_id, record_count, timing_preference);
+                migration_id
+            })
+            .collect()
+    }
+
+    /// Add re-encrypted and failed record counts for one purpose
+    #[wasm_bindgen]
+    pub fn record_progress(&mut self, purpose: &str, migrated: u64, failed: u64) -> Result<(), JsValue> {
+        self.apply_progress(purpose, migrated, failed).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn completion_percentage(&self) -> f64 {
+        let total = self.total_records();
+        if total == 0 {
+            return 100.0;
+        }
+        let migrated: u64 = self.targets
+            .iter()
+            .map(|target| target.migrated.min(target.record_count))
+            .sum();
+        migrated as f64 / total as f64 * 100.0
+    }
+
+    #[wasm_bindgen]
+    pub fn is_complete(&self) -> bool {
+        self.targets.iter().all(CampaignTarget::is_complete)
+    }
+
+    /// Inclusion proof for `purpose`'s record count under `inventory_root`
+    #[wasm_bindgen]
+    pub fn inventory_proof(&self, purpose: &str) -> Result<InventoryProof, JsValue> {
+        self.proof_for(purpose).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Completion attestation signed with `signer`; fails while any purpose still
+    /// has records to migrate
+    #[wasm_bindgen]
+    pub fn attest(&self, signer: &SigningKeyPair) -> Result<CampaignAttestation, JsValue> {
+        self.attest_at(platform::now_ms(), signer).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize campaign: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<SuiteMigrationCampaign, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid campaign: {}", e)))
+    }
+}
+
+impl SuiteMigrationCampaign {
+    fn from_inventory(
+        algorithm: CryptoAlgorithm,
+        replacement: CryptoAlgorithm,
+        inventory: &EnvelopeInventory,
+        now: u64,
+    ) -> SuiteMigrationCampaign {
+        // BTreeMap iteration keeps targets, and therefore the root, in purpose order
+        let targets: Vec<CampaignTarget> = inventory.counts
+            .iter()
+            .filter(|((_, suite), count)| *suite == algorithm as u8 && **count > 0)
+            .map(|((purpose, _), count)| CampaignTarget {
+                purpose: purpose.clone(),
+                record_count: *count,
+                migrated: 0,
+                failed: 0,
+            })
+            .collect();
+
+        SuiteMigrationCampaign {
+            campaign_id: platform::new_uuid(),
+            deprecated_suite: suite_name(algorithm).to_string(),
+            replacement_suite: suite_name(replacement).to_string(),
+            created_at: now,
+            inventory_root: inventory_root(&targets, suite_name(algorithm)),
+            targets,
+        }
+    }
+
+    pub(crate) fn proof_for(&self, purpose: &str) -> Result<InventoryProof, String> {
+        let target = self.targets
+            .iter()
+            .find(|target| target.purpose == purpose)
+            .ok_or_else(|| format!("Purpose {} is not part of this campaign", purpose))?;
+        Ok(InventoryProof::for_target(&self.targets, &self.deprecated_suite, target))
+    }
+
+    pub(crate) fn apply_progress(&mut self, purpose: &str, migrated: u64, failed: u64) -> Result<(), String> {
+        let target = self.targets
+            .iter_mut()
+            .find(|target| target.purpose == purpose)
+            .ok_or_else(|| format!("Purpose {} is not part of this campaign", purpose))?;
+        target.migrated = target.migrated.saturating_add(migrated);
+        target.failed = target.failed.saturating_add(failed);
+        Ok(())
+    }
+
+    pub(crate) fn attest_at(&self, now: u64, signer: &SigningKeyPair) -> Result<CampaignAttestation, String> {
+        // Record counts edited after creation no longer match the root
+        if inventory_root(&self.targets, &self.deprecated_suite) != self.inventory_root {
+            return Err("Campaign targets do not match the inventory root".to_string());
+        }
+        if let Some(pending) = self.targets.iter().find(|target| !target.is_complete()) {
+            return Err(format!(
+                "Campaign incomplete: {} has {} of {} records migrated",
+                pending.purpose, pending.migrated, pending.record_count
+            ));
+        }
+
+        let mut attestation = CampaignAttestation {
+            campaign_id: self.campaign_id.clone(),
+            deprecated_suite: self.deprecated_suite.clone(),
+            replacement_suite: self.replacement_suite.clone(),
+            inventory_root: self.inventory_root.clone(),
+            total_records: self.total_records(),
+            started_at: self.created_at,
+            completed_at: now,
+            digest: String::new(),
+            signer_key_id: signer.key_id(),
+            signature: String::new(),
+        };
+        attestation.digest = attestation.compute_digest();
+        attestation.signature = to_hex(&signer.sign(&attestation.signed_message()));
+        Ok(attestation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory() -> EnvelopeInventory {
+        let mut inventory = EnvelopeInventory::new();
+        inventory.record("cycle_data".to_string(), CryptoAlgorithm::AES256GCM, 120);
+        inventory.record("preferences".to_string(), CryptoAlgorithm::AES256GCM, 8);
+        inventory.record("preferences".to_string(), CryptoAlgorithm::ChaCha20Poly1305, 50);
+        inventory
+    }
+
+    #[test]
+    fn test_deprecation_builds_campaign_for_affected_purposes() {
+        let mut registry = AlgorithmRegistry::new();
+        let campaign = registry
+            .deprecate_at(CryptoAlgorithm::AES256GCM, CryptoAlgorithm::ChaCha20Poly1305, &inventory(), 1_000)
+            .unwrap();
+
+        assert!(registry.is_deprecated(CryptoAlgorithm::AES256GCM));
+        assert_eq!(campaign.total_records(), 128);
+        let purposes: Vec<String> = campaign.targets().iter().map(CampaignTarget::purpose).collect();
+        assert_eq!(purposes, vec!["cycle_data", "preferences"]);
+
+        assert!(registry
+            .deprecate_at(CryptoAlgorithm::ChaCha20Poly1305, CryptoAlgorithm::AES256GCM, &inventory(), 2_000)
+            .is_err());
+    }
+
+    #[test]
+    fn test_attestation_only_after_completion() {
+        let mut registry = AlgorithmRegistry::new();
+        let mut campaign = registry
+            .deprecate_at(CryptoAlgorithm::AES256GCM, CryptoAlgorithm::ChaCha20Poly1305, &inventory(), 1_000)
+            .unwrap();
+
+        let signer = SigningKeyPair::generate();
+        campaign.apply_progress("cycle_data", 120, 0).unwrap();
+        assert!(campaign.attest_at(5_000, &signer).is_err());
+        assert!(campaign.apply_progress("device_sync", 1, 0).is_err());
+
+        campaign.apply_progress("preferences", 8, 1).unwrap();
+        assert_eq!(campaign.completion_percentage(), 100.0);
+        let attestation = campaign.attest_at(5_000, &signer).unwrap();
+        assert_eq!(attestation.inventory_root(), campaign.inventory_root());
+        assert!(attestation.verify(&signer.public_key()));
+        assert!(!attestation.verify(&SigningKeyPair::generate().public_key()));
+
+        // Editing an attested field breaks the digest; re-digesting breaks the signature
+        let mut forged: CampaignAttestation = serde_json::from_str(&attestation.to_json().unwrap()).unwrap();
+        forged.total_records = 1;
+        assert!(!forged.verify(&signer.public_key()));
+        forged.digest = forged.compute_digest();
+        assert!(!forged.verify(&signer.public_key()));
+
+        // So does shrinking the inventory before attesting
+        let mut edited = campaign.clone();
+        edited.targets[1].record_count = 1;
+        assert!(edited.attest_at(5_000, &signer).is_err());
+    }
+
+    #[test]
+    fn test_inventory_proofs_verify_per_purpose() {
+        let mut inventory = inventory();
+        for i in 0..20 {
+            inventory.record(format!("purpose_{}", i), CryptoAlgorithm::AES256GCM, i + 1);
+        }
+        let campaign = AlgorithmRegistry::new()
+            .deprecate_at(CryptoAlgorithm::AES256GCM, CryptoAlgorithm::ChaCha20Poly1305, &inventory, 1_000)
+            .unwrap();
+
+        let root = campaign.inventory_root();
+        for target in campaign.targets() {
+            let proof = campaign.proof_for(&target.purpose()).unwrap();
+            assert_eq!(proof.record_count(), target.record_count());
+            assert!(proof.verify(&root));
+            let round_trip: InventoryProof = serde_json::from_str(&proof.to_json().unwrap()).unwrap();
+            assert!(round_trip.verify(&root));
+        }
+
+        let proof = campaign.proof_for("cycle_data").unwrap();
+        let mut inflated = proof.clone();
+        inflated.record_count += 1;
+        assert!(!inflated.verify(&root));
+        let mut renamed = proof.clone();
+        renamed.purpose = "preferences".to_string();
+        assert!(!renamed.verify(&root));
+        let mut truncated = proof;
+        truncated.siblings.pop();
+        assert!(!truncated.verify(&root));
+        assert!(campaign.proof_for("device_sync").is_err());
+    }
+}
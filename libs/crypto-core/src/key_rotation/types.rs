@@ -1,5 +1,6 @@
 use wasm_bindgen::prelude::*;
 use chrono::{DateTime, Duration, Utc};
+use crate::platform;
 
 /// Version information for cryptographic keys
 #[wasm_bindgen]
@@ -20,7 +21,7 @@ impl KeyVersion {
             major,
             minor,
             patch,
-            created_at: Utc::now(),
+            created_at: platform::now(),
             expires_at: None,
         }
     }
@@ -60,7 +61,7 @@ impl KeyVersion {
     #[wasm_bindgen(js_name = isExpired)]
     pub fn is_expired(&self) -> bool {
         if let Some(expires_at) = self.expires_at {
-            Utc::now() > expires_at
+            platform::now() > expires_at
         } else {
             false
         }
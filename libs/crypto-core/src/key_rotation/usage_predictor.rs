@@ -0,0 +1,120 @@
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+
+// Usage-based rotation window prediction
+// Every tracked key use lands in an hour-of-week bucket (UTC, Monday 00:00 = 0).
+// Rotation windows are ranked by the usage observed in the slot and its two
+// neighbours, so a rotation that overruns its hour still falls into a quiet period.
+// Counts are halved once the history grows large so that changed habits win over
+// stale ones.
+
+pub(crate) const HOURS_PER_WEEK: usize = 7 * 24;
+const MIN_SAMPLES: u64 = 50;
+const DECAY_THRESHOLD: u64 = 10_000;
+
+/// Candidate rotation slot, quietest first when ranked
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RotationWindow {
+    pub hour_of_week: usize,
+    pub expected_usage: f64, // Share of recorded usage that falls in the window
+}
+
+impl RotationWindow {
+    pub fn weekday(&self) -> u32 {
+        (self.hour_of_week / 24) as u32
+    }
+
+    pub fn hour(&self) -> u32 {
+        (self.hour_of_week % 24) as u32
+    }
+
+    /// First start of this slot at or after `after`
+    pub fn next_start(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        let hour_start = after - Duration::minutes(after.minute() as i64)
+            - Duration::seconds(after.second() as i64)
+            - Duration::nanoseconds(after.nanosecond() as i64);
+        let current = hour_of_week(after);
+        let mut offset = (self.hour_of_week + HOURS_PER_WEEK - current) % HOURS_PER_WEEK;
+        if offset == 0 && hour_start < after {
+            offset = HOURS_PER_WEEK;
+        }
+        hour_start + Duration::hours(offset as i64)
+    }
+}
+
+/// Hour-of-week histogram of key usage
+#[derive(Debug, Clone)]
+pub(crate) struct UsageHistogram {
+    counts: [u64; HOURS_PER_WEEK],
+    total: u64,
+}
+
+impl Default for UsageHistogram {
+    fn default() -> Self {
+        Self { counts: [0; HOURS_PER_WEEK], total: 0 }
+    }
+}
+
+impl UsageHistogram {
+    pub fn record(&mut self, at: DateTime<Utc>) {
+        self.counts[hour_of_week(at)] += 1;
+        self.total += 1;
+        if self.total >= DECAY_THRESHOLD {
+            self.counts.iter_mut().for_each(|count| *count /= 2);
+            self.total = self.counts.iter().sum();
+        }
+    }
+
+    /// Too little history makes every slot look idle; callers fall back to the preferred hour
+    pub fn has_enough_history(&self) -> bool {
+        self.total >= MIN_SAMPLES
+    }
+
+    /// Up to `count` slots ordered by expected usage, ties broken by closeness to `preferred_hour`
+    pub fn rank_windows(&self, preferred_hour: u8, count: usize) -> Vec<RotationWindow> {
+        let mut windows: Vec<(u64, u32, RotationWindow)> = (0..HOURS_PER_WEEK)
+            .map(|slot| {
+                let usage = self.counts[(slot + HOURS_PER_WEEK - 1) % HOURS_PER_WEEK]
+                    + self.counts[slot]
+                    + self.counts[(slot + 1) % HOURS_PER_WEEK];
+                let expected_usage = if self.total == 0 { 0.0 } else { usage as f64 / self.total as f64 };
+                let distance = (slot % 24).abs_diff(preferred_hour as usize);
+                let distance = distance.min(24 - distance) as u32;
+                (usage, distance, RotationWindow { hour_of_week: slot, expected_usage })
+            })
+            .collect();
+        windows.sort_by_key(|(usage, distance, window)| (*usage, *distance, window.hour_of_week));
+        windows.into_iter().take(count).map(|(_, _, window)| window).collect()
+    }
+}
+
+pub(crate) fn hour_of_week(at: DateTime<Utc>) -> usize {
+    at.weekday().num_days_from_monday() as usize * 24 + at.hour() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_ranks_quiet_slots_and_schedules_next_occurrence() {
+        // 2024-01-01 is a Monday
+        let monday = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut histogram = UsageHistogram::default();
+        for day in 0..7 {
+            for hour in 6..24 {
+                histogram.record(monday + Duration::days(day) + Duration::hours(hour));
+            }
+        }
+        assert!(histogram.has_enough_history());
+
+        let ranked = histogram.rank_windows(3, 5);
+        assert_eq!(ranked.len(), 5);
+        assert!(ranked.iter().all(|window| (1..=4).contains(&window.hour()) && window.expected_usage == 0.0));
+        assert_eq!((ranked[0].weekday(), ranked[0].hour()), (0, 3));
+
+        let tuesday_noon = monday + Duration::days(1) + Duration::hours(12) + Duration::minutes(30);
+        assert_eq!(ranked[0].next_start(tuesday_noon), monday + Duration::days(7) + Duration::hours(3));
+        assert_eq!(ranked[0].next_start(monday + Duration::hours(3)), monday + Duration::hours(3));
+    }
+}
@@ -5,6 +5,7 @@ use crate::derivation::DataCategory;
 use crate::keys::CryptoKey;
 use crate::memory::{track_secret_allocation, track_secret_zeroization};
 use super::types::{KeyVersion, KeyStatus}; // KeyRotationError removed - unused
+use crate::platform;
 
 /// Legacy key retention policy for cleanup management
 #[wasm_bindgen]
@@ -73,6 +74,7 @@ pub struct VersionedKey {
     audit_log: Vec<String>,
     creation_time: DateTime<Utc>,
     last_used_time: Option<DateTime<Utc>>,
+    migration_updated_time: Option<DateTime<Utc>>, // Last migration start or progress change
     usage_count: u64,
     integrity_hash: Option<String>, // For validation
 }
@@ -83,7 +85,7 @@ impl VersionedKey {
     pub fn new(key: CryptoKey, version: KeyVersion, purpose: DataCategory) -> Self {
         track_secret_allocation();
         
-        let creation_time = Utc::now();
+        let creation_time = platform::now();
         let supported_versions = vec![version.clone()];
         
         Self {
@@ -97,6 +99,7 @@ impl VersionedKey {
             audit_log: vec![format!("Key created with version {} at {}", version.to_string(), creation_time)],
             creation_time,
             last_used_time: None,
+            migration_updated_time: None,
             usage_count: 0,
             integrity_hash: None,
         }
@@ -137,6 +140,11 @@ impl VersionedKey {
         self.last_used_time.map(|dt| dt.timestamp_millis() as f64)
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn migration_updated_time(&self) -> Option<f64> {
+        self.migration_updated_time.map(|dt| dt.timestamp_millis() as f64)
+    }
+
     #[wasm_bindgen(getter)]
     pub fn usage_count(&self) -> u64 {
         self.usage_count
@@ -160,16 +168,20 @@ impl VersionedKey {
     pub fn set_status(&mut self, status: KeyStatus) {
         let old_status = self.status.clone();
         self.status = status;
+        if matches!(self.status, KeyStatus::Migrating) && !matches!(old_status, KeyStatus::Migrating) {
+            self.migration_updated_time = Some(platform::now());
+        }
         self.audit_log.push(format!("Status changed from {:?} to {:?} at {}", 
-            old_status, self.status, Utc::now()));
+            old_status, self.status, platform::now()));
     }
 
     #[wasm_bindgen]
     pub fn set_migration_progress(&mut self, progress: f32) {
         let clamped_progress = progress.clamp(0.0, 1.0);
         self.migration_progress = clamped_progress;
+        self.migration_updated_time = Some(platform::now());
         self.audit_log.push(format!("Migration progress updated to {:.1}% at {}", 
-            clamped_progress * 100.0, Utc::now()));
+            clamped_progress * 100.0, platform::now()));
     }
 
     #[wasm_bindgen]
@@ -224,7 +236,7 @@ impl VersionedKey {
             self.predecessor_versions.push(predecessor.clone());
             self.supported_decryption_versions.push(predecessor.clone());
             self.audit_log.push(format!("Predecessor version {} added at {}", 
-                predecessor.to_string(), Utc::now()));
+                predecessor.to_string(), platform::now()));
         }
     }
 
@@ -257,7 +269,7 @@ impl VersionedKey {
             if !self.supported_decryption_versions.contains(&version) {
                 self.supported_decryption_versions.push(version.clone());
                 self.audit_log.push(format!("Added support for decryption version {} at {}", 
-                    version.to_string(), Utc::now()));
+                    version.to_string(), platform::now()));
             }
             Ok(())
         } else {
@@ -288,13 +300,13 @@ impl VersionedKey {
         if let Some(stored_hash) = &self.integrity_hash {
             let is_valid = current_hash == *stored_hash;
             if !is_valid {
-                self.audit_log.push(format!("INTEGRITY VIOLATION detected at {}", Utc::now()));
+                self.audit_log.push(format!("INTEGRITY VIOLATION detected at {}", platform::now()));
             }
             Ok(is_valid)
         } else {
             // First time validation - store the hash
             self.integrity_hash = Some(current_hash);
-            self.audit_log.push(format!("Integrity hash established at {}", Utc::now()));
+            self.audit_log.push(format!("Integrity hash established at {}", platform::now()));
             Ok(true)
         }
     }
@@ -302,19 +314,19 @@ impl VersionedKey {
     #[wasm_bindgen(js_name = updateUsageTracking)]
     pub fn update_usage_tracking(&mut self) {
         self.usage_count += 1;
-        self.last_used_time = Some(Utc::now());
+        self.last_used_time = Some(platform::now());
         
         // Log usage periodically (every 100 uses)
         if self.usage_count % 100 == 0 {
             self.audit_log.push(format!("Key usage count reached {} at {}", 
-                self.usage_count, Utc::now()));
+                self.usage_count, platform::now()));
         }
     }
 
     #[wasm_bindgen(js_name = checkRetentionEligibility)]
     pub fn check_retention_eligibility(&self, policy: &LegacyKeyRetentionPolicy) -> bool {
         // Check if this key is eligible for cleanup based on retention policy
-        let age_days = (Utc::now() - self.creation_time).num_days() as u32;
+        let age_days = (platform::now() - self.creation_time).num_days() as u32;
         
         // Must meet minimum retention period
         if age_days < policy.min_retention_days() {
@@ -349,7 +361,7 @@ impl VersionedKey {
         self.integrity_hash = None; // Reset integrity hash for new key
         
         self.audit_log.push(format!("Transitioned from version {} to {} at {}", 
-            old_version.to_string(), new_version.to_string(), Utc::now()));
+            old_version.to_string(), new_version.to_string(), platform::now()));
         
         Ok(())
     }
@@ -376,6 +388,18 @@ impl VersionedKey {
     }
 }
 
+impl VersionedKey {
+    /// Predecessor versions this key superseded (metadata only)
+    pub fn predecessors(&self) -> &[KeyVersion] {
+        &self.predecessor_versions
+    }
+
+    /// Fingerprint of the key material; `key()` returns a clone without material
+    pub(crate) fn key_fingerprint(&self) -> Option<String> {
+        self.key.fingerprint()
+    }
+}
+
 impl Drop for VersionedKey {
     fn drop(&mut self) {
         track_secret_zeroization();
@@ -0,0 +1,352 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::host_calls::{deliver_events, RetryQueue};
+use crate::platform;
+use super::manager::KeyRotationManager;
+use super::migration::{MigrationCheckpoint, ProgressiveMigrationManager};
+
+// Migration watchdog
+// A migration is stalled when its checkpoint has not advanced within the stall
+// timeout. The watchdog first resumes it from the last checkpoint (the host picks
+// up the `Resumed` event and continues batching from `current_batch`). If the
+// migration stalls again without progress after `max_resume_attempts` resumes, it
+// is rolled back: the checkpoint is dropped, the rotation is undone in the key
+// rotation manager (the predecessor key is active again; the abandoned version stays
+// deprecated for decryption), and an incident is raised carrying the processed count
+// and the restored version so the host can revert the records already re-encrypted.
+// A migration id is the rotated purpose, optionally prefixed with `<campaign>:` as
+// suite migrations do.
+
+const MAX_AUDIT_EVENTS: usize = 500;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WatchdogEventKind {
+    StallDetected = 0,
+    Resumed = 1,
+    RolledBack = 2,
+}
+
+/// Action taken by the watchdog; `RolledBack` events are incidents
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchdogEvent {
+    kind: WatchdogEventKind,
+    migration_id: String,
+    current_batch: u32,
+    total_batches: u32,
+    processed_count: u32,
+    attempt: u32,
+    stalled_ms: u64,
+    occurred_at: u64,
+    restored_version: Option<String>, // Set on rollback when a rotation was undone
+}
+
+#[wasm_bindgen]
+impl WatchdogEvent {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> WatchdogEventKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn migration_id(&self) -> String {
+        self.migration_id.clone()
+    }
+
+    /// Batch to resume from, or the last batch completed before rollback
+    #[wasm_bindgen(getter)]
+    pub fn current_batch(&self) -> u32 {
+        self.current_batch
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn total_batches(&self) -> u32 {
+        self.total_batches
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn processed_count(&self) -> u32 {
+        self.processed_count
+    }
+
+    /// Resume attempts made so far without progress
+    #[wasm_bindgen(getter)]
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn stalled_ms(&self) -> u64 {
+        self.stalled_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn occurred_at(&self) -> u64 {
+        self.occurred_at
+    }
+
+    /// Key version active again after a rollback
+    #[wasm_bindgen(getter)]
+    pub fn restored_version(&self) -> Option<String> {
+        self.restored_version.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn is_incident(&self) -> bool {
+        self.kind == WatchdogEventKind::RolledBack
+    }
+}
+
+/// Resume attempts for one migration and the batch it was at when last resumed
+#[derive(Debug, Clone, Copy)]
+struct ResumeState {
+    attempts: u32,
+    batch_at_resume: u32,
+}
+
+/// Detects stalled progressive migrations and resumes or rolls them back
+#[wasm_bindgen]
+pub struct MigrationWatchdog {
+    stall_timeout_ms: u64,
+    max_resume_attempts: u32,
+    resume_state: HashMap<String, ResumeState>,
+    audit_log: Vec<WatchdogEvent>,
+    listeners: Vec<js_sys::Function>,
+    pending_events: RetryQueue<WatchdogEvent>, // Held while the transport breaker is open
+}
+
+#[wasm_bindgen]
+impl MigrationWatchdog {
+    #[wasm_bindgen(constructor)]
+    pub fn new(stall_timeout_ms: u64, max_resume_attempts: u32) -> Result<MigrationWatchdog, JsValue> {
+        if stall_timeout_ms == 0 {
+            return Err(JsValue::from_str("Stall timeout must be greater than 0"));
+        }
+        Ok(Self::with_limits(stall_timeout_ms, max_resume_attempts))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn stall_timeout_ms(&self) -> u64 {
+        self.stall_timeout_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_resume_attempts(&self) -> u32 {
+        self.max_resume_attempts
+    }
+
+    /// Register a listener called with each `WatchdogEvent`
+    #[wasm_bindgen(js_name = onEvent)]
+    pub fn on_event(&mut self, listener: js_sys::Function) {
+        self.listeners.push(listener);
+    }
+
+    /// Events waiting for the transport breaker to admit delivery
+    #[wasm_bindgen(getter)]
+    pub fn queued_events(&self) -> usize {
+        self.pending_events.len()
+    }
+
+    /// Inspect every migration in `manager`, rolling abandoned rotations back in `keys`;
+    /// returns the events emitted by this pass
+    #[wasm_bindgen]
+    pub fn check(&mut self, manager: &mut ProgressiveMigrationManager, keys: &mut KeyRotationManager) -> Vec<WatchdogEvent> {
+        let events = self.check_at(manager, keys, platform::now_ms());
+        deliver_events(&self.listeners, &mut self.pending_events, &events);
+        events
+    }
+
+    /// Rollbacks raised so far
+    #[wasm_bindgen]
+    pub fn incidents(&self) -> Vec<WatchdogEvent> {
+        self.audit_log.iter().filter(|event| event.is_incident()).cloned().collect()
+    }
+
+    /// Every watchdog action as a JSON array, oldest first
+    #[wasm_bindgen]
+    pub fn audit_log_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.audit_log)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize watchdog audit log: {}", e)))
+    }
+}
+
+impl MigrationWatchdog {
+    pub(crate) fn with_limits(stall_timeout_ms: u64, max_resume_attempts: u32) -> MigrationWatchdog {
+        MigrationWatchdog {
+            stall_timeout_ms,
+            max_resume_attempts,
+            resume_state: HashMap::new(),
+            audit_log: Vec::new(),
+            listeners: Vec::new(),
+            pending_events: RetryQueue::default(),
+        }
+    }
+
+    pub(crate) fn check_at(
+        &mut self,
+        manager: &mut ProgressiveMigrationManager,
+        keys: &mut KeyRotationManager,
+        now: u64,
+    ) -> Vec<WatchdogEvent> {
+        let mut checkpoints = manager.checkpoints();
+        checkpoints.sort_by(|a, b| a.migration_id.cmp(&b.migration_id));
+
+        // Forget migrations that were cleared or completed
+        self.resume_state.retain(|id, _| {
+            checkpoints.iter().any(|c| &c.migration_id == id && c.current_batch < c.total_batches)
+        });
+
+        let mut events = Vec::new();
+        for checkpoint in checkpoints {
+            if checkpoint.current_batch >= checkpoint.total_batches {
+                continue;
+            }
+
+            // Any progress since the last resume earns a fresh set of attempts
+            if let Some(state) = self.resume_state.get(&checkpoint.migration_id) {
+                if checkpoint.current_batch > state.batch_at_resume {
+                    self.resume_state.remove(&checkpoint.migration_id);
+                }
+            }
+
+            let stalled_ms = now.saturating_sub(checkpoint.last_checkpoint_time.max(0.0) as u64);
+            if stalled_ms <= self.stall_timeout_ms {
+                continue;
+            }
+
+            let attempts = self.resume_state
+                .get(&checkpoint.migration_id)
+                .map_or(0, |state| state.attempts);
+            events.push(Self::event(WatchdogEventKind::StallDetected, &checkpoint, attempts, stalled_ms, now));
+
+            if attempts < self.max_resume_attempts {
+                manager.rearm_checkpoint(&checkpoint.migration_id, now as f64);
+                self.resume_state.insert(
+                    checkpoint.migration_id.clone(),
+                    ResumeState { attempts: attempts + 1, batch_at_resume: checkpoint.current_batch },
+                );
+                events.push(Self::event(WatchdogEventKind::Resumed, &checkpoint, attempts + 1, stalled_ms, now));
+            } else {
+                manager.clear_migration(&checkpoint.migration_id);
+                self.resume_state.remove(&checkpoint.migration_id);
+                let purpose = checkpoint.migration_id.rsplit(':').next().unwrap_or_default();
+                let mut event = Self::event(WatchdogEventKind::RolledBack, &checkpoint, attempts, stalled_ms, now);
+                event.restored_version = keys.roll_back_rotation(purpose).map(|version| version.to_string());
+                events.push(event);
+            }
+        }
+
+        self.audit_log.extend(events.iter().cloned());
+        if self.audit_log.len() > MAX_AUDIT_EVENTS {
+            let excess = self.audit_log.len() - MAX_AUDIT_EVENTS;
+            self.audit_log.drain(..excess);
+        }
+        events
+    }
+
+    fn event(
+        kind: WatchdogEventKind,
+        checkpoint: &MigrationCheckpoint,
+        attempt: u32,
+        stalled_ms: u64,
+        now: u64,
+    ) -> WatchdogEvent {
+        WatchdogEvent {
+            kind,
+            migration_id: checkpoint.migration_id.clone(),
+            current_batch: checkpoint.current_batch,
+            total_batches: checkpoint.total_batches,
+            processed_count: checkpoint.processed_count,
+            attempt,
+            stalled_ms,
+            occurred_at: now,
+            restored_version: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derivation::{DataCategory, HierarchicalKeyDerivation};
+    use crate::key_rotation::types::{KeyStatus, RotationTiming};
+
+    const TIMEOUT: u64 = 60_000;
+
+    fn manager_at(now: f64) -> ProgressiveMigrationManager {
+        let mut manager = ProgressiveMigrationManager::new(10, 1);
+        manager.begin_migration("cycle_data", 100, RotationTiming::Background);
+        manager.rearm_checkpoint("cycle_data", now);
+        manager
+    }
+
+    fn rotated_keys() -> KeyRotationManager {
+        let mut keys = KeyRotationManager::new(HierarchicalKeyDerivation::new());
+        keys.create_new_key_version(DataCategory::CycleData).unwrap();
+        keys.create_new_key_version(DataCategory::CycleData).unwrap();
+        keys
+    }
+
+    fn kinds(events: &[WatchdogEvent]) -> Vec<WatchdogEventKind> {
+        events.iter().map(WatchdogEvent::kind).collect()
+    }
+
+    #[test]
+    fn test_stall_resumes_then_rolls_back() {
+        let mut manager = manager_at(0.0);
+        let mut keys = rotated_keys();
+        let mut watchdog = MigrationWatchdog::with_limits(TIMEOUT, 2);
+
+        assert!(watchdog.check_at(&mut manager, &mut keys, TIMEOUT).is_empty());
+
+        let first = watchdog.check_at(&mut manager, &mut keys, TIMEOUT + 1);
+        assert_eq!(kinds(&first), vec![WatchdogEventKind::StallDetected, WatchdogEventKind::Resumed]);
+        assert!(watchdog.check_at(&mut manager, &mut keys, 2 * TIMEOUT).is_empty());
+
+        let second = watchdog.check_at(&mut manager, &mut keys, 2 * TIMEOUT + 2);
+        assert_eq!(second[1].attempt(), 2);
+
+        let third = watchdog.check_at(&mut manager, &mut keys, 3 * TIMEOUT + 3);
+        assert_eq!(kinds(&third), vec![WatchdogEventKind::StallDetected, WatchdogEventKind::RolledBack]);
+        assert!(manager.checkpoints().is_empty());
+        assert_eq!(watchdog.incidents().len(), 1);
+        assert_eq!(third[1].restored_version().as_deref(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn test_rollback_restores_the_previous_key_version() {
+        let mut manager = manager_at(0.0);
+        let mut keys = rotated_keys();
+        assert_eq!(keys.get_active_key(DataCategory::CycleData).unwrap().status(), KeyStatus::Migrating);
+        let epoch = keys.cache_epoch(DataCategory::CycleData);
+
+        let mut watchdog = MigrationWatchdog::with_limits(TIMEOUT, 0);
+        let events = watchdog.check_at(&mut manager, &mut keys, TIMEOUT + 1);
+        assert_eq!(kinds(&events), vec![WatchdogEventKind::StallDetected, WatchdogEventKind::RolledBack]);
+
+        let active = keys.get_active_key(DataCategory::CycleData).unwrap();
+        assert_eq!((active.version().to_string(), active.status()), ("1.0.0".to_string(), KeyStatus::Active));
+        assert_eq!(keys.get_migration_progress(DataCategory::CycleData), None);
+        assert!(keys.cache_epoch(DataCategory::CycleData) > epoch);
+
+        // The abandoned version's number is not handed out again
+        assert_eq!(keys.create_new_key_version(DataCategory::CycleData).unwrap().version().to_string(), "1.2.0");
+    }
+
+    #[test]
+    fn test_progress_resets_resume_attempts() {
+        let mut manager = manager_at(0.0);
+        let mut watchdog = MigrationWatchdog::with_limits(TIMEOUT, 1);
+
+        let mut keys = rotated_keys();
+        watchdog.check_at(&mut manager, &mut keys, TIMEOUT + 1);
+        manager.record_batch_at("cycle_data", 10, (TIMEOUT + 10) as f64);
+
+        let events = watchdog.check_at(&mut manager, &mut keys, 2 * TIMEOUT + 20);
+        assert_eq!(kinds(&events), vec![WatchdogEventKind::StallDetected, WatchdogEventKind::Resumed]);
+        assert_eq!(events[1].current_batch(), 1);
+        assert!(watchdog.incidents().is_empty());
+    }
+}
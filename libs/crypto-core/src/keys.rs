@@ -1,15 +1,18 @@
 use wasm_bindgen::prelude::*;
 // use zeroize::Zeroize;  // Reserved for future use
 // use rand::RngCore;     // Reserved for future use
-use crate::security::{SecureRandom, constant_time_compare, MemoryProtection};
+use crate::security::{SecureRandom, constant_time_compare};
 use crate::memory::{SecureBuffer, track_secret_zeroization};
+use crate::envelope::to_hex;
+use sha2::{Digest, Sha256};
+
+pub(crate) const KEY_FINGERPRINT_DOMAIN: &[u8] = b"aura-key-fingerprint-v1";
 
 // Key management for cryptographic operations with security hardening  
 #[wasm_bindgen]
 pub struct CryptoKey {
     key_buffer: SecureBuffer,
     key_type: String,
-    memory_protection: MemoryProtection,
     is_initialized: bool,
 }
 
@@ -21,7 +24,6 @@ impl CryptoKey {
         CryptoKey {
             key_buffer: SecureBuffer::new(0),
             key_type,
-            memory_protection: MemoryProtection::new(),
             is_initialized: false,
         }
     }
@@ -81,11 +83,11 @@ impl CryptoKey {
         Ok(constant_time_compare(self_key, other_key))
     }
     
-    // Validate memory protection canary
+    // Check the guard bytes after the key material for overruns
     #[wasm_bindgen]
     #[must_use]
     pub fn validate_memory_protection(&self) -> bool {
-        self.memory_protection.check_canary(self.memory_protection.canary_value())
+        self.key_buffer.canary_intact()
     }
     
     // Explicit key zeroization
@@ -98,6 +100,28 @@ impl CryptoKey {
 
 
 
+impl CryptoKey {
+    // Domain-separated SHA-256 of the key material for integrity checks; None if uninitialized
+    pub(crate) fn fingerprint(&self) -> Option<String> {
+        if !self.is_initialized() {
+            return None;
+        }
+        let key = self.key_buffer.as_slice().ok()?;
+        let mut hasher = Sha256::new();
+        hasher.update(KEY_FINGERPRINT_DOMAIN);
+        hasher.update(key);
+        Some(to_hex(&hasher.finalize()))
+    }
+
+    // Raw key material for in-crate ciphers; None if uninitialized
+    pub(crate) fn material(&self) -> Option<&[u8]> {
+        if !self.is_initialized() {
+            return None;
+        }
+        self.key_buffer.as_slice().ok()
+    }
+}
+
 // Generate a new encryption key
 #[wasm_bindgen]
 pub fn generate_encryption_key() -> Result<CryptoKey, JsValue> {
@@ -0,0 +1,170 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::sync::Mutex;
+use once_cell::sync::OnceCell;
+use crate::platform;
+
+// Lazy subsystem initialization
+// Building a rotation manager used to construct its scheduler, and with it the emergency
+// rotation manager and the incident detector, before any of them was needed; the audit
+// trail likewise built its default compliance rules up front. These parts now sit in a
+// `LazySubsystem` and are built on first access. The cell initializes at most once even
+// under concurrent access, and each construction is timed on the monotonic clock into a
+// process-wide table that `subsystem_init_metrics` reports.
+
+/// Part of the crate that is constructed on first use
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    RotationScheduler = 0,
+    EmergencyRotation = 1,
+    IncidentDetection = 2,
+    AuditComplianceRules = 3,
+}
+
+impl Subsystem {
+    const ALL: [Subsystem; 4] = [
+        Subsystem::RotationScheduler,
+        Subsystem::EmergencyRotation,
+        Subsystem::IncidentDetection,
+        Subsystem::AuditComplianceRules,
+    ];
+}
+
+/// Initialization timings of one subsystem across all instances in this process
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SubsystemInitStats {
+    subsystem: Subsystem,
+    initializations: u32,
+    total_us: u64,
+    max_us: u64,
+    first_initialized_at: Option<u64>,
+}
+
+#[wasm_bindgen]
+impl SubsystemInitStats {
+    #[wasm_bindgen(getter)]
+    pub fn subsystem(&self) -> Subsystem {
+        self.subsystem
+    }
+
+    /// Instances constructed so far; 0 means the subsystem was never used
+    #[wasm_bindgen(getter)]
+    pub fn initializations(&self) -> u32 {
+        self.initializations
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn total_us(&self) -> u64 {
+        self.total_us
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_us(&self) -> u64 {
+        self.max_us
+    }
+
+    /// Wall-clock time of the first construction
+    #[wasm_bindgen(getter)]
+    pub fn first_initialized_at(&self) -> Option<u64> {
+        self.first_initialized_at
+    }
+}
+
+impl SubsystemInitStats {
+    const fn empty(subsystem: Subsystem) -> Self {
+        SubsystemInitStats { subsystem, initializations: 0, total_us: 0, max_us: 0, first_initialized_at: None }
+    }
+}
+
+static INIT_STATS: Mutex<[SubsystemInitStats; 4]> = Mutex::new([
+    SubsystemInitStats::empty(Subsystem::RotationScheduler),
+    SubsystemInitStats::empty(Subsystem::EmergencyRotation),
+    SubsystemInitStats::empty(Subsystem::IncidentDetection),
+    SubsystemInitStats::empty(Subsystem::AuditComplianceRules),
+]);
+
+#[wasm_bindgen]
+pub fn subsystem_init_stats(subsystem: Subsystem) -> SubsystemInitStats {
+    INIT_STATS.lock().map_or(SubsystemInitStats::empty(subsystem), |stats| stats[subsystem as usize])
+}
+
+/// JSON array of `SubsystemInitStats`, one per subsystem
+#[wasm_bindgen]
+pub fn subsystem_init_metrics() -> String {
+    let stats: Vec<SubsystemInitStats> = Subsystem::ALL.iter().map(|&subsystem| subsystem_init_stats(subsystem)).collect();
+    serde_json::to_string(&stats).unwrap_or_default()
+}
+
+fn record_init(subsystem: Subsystem, duration_us: u64) {
+    if let Ok(mut stats) = INIT_STATS.lock() {
+        let entry = &mut stats[subsystem as usize];
+        entry.initializations = entry.initializations.saturating_add(1);
+        entry.total_us = entry.total_us.saturating_add(duration_us);
+        entry.max_us = entry.max_us.max(duration_us);
+        entry.first_initialized_at.get_or_insert_with(platform::now_ms);
+    }
+}
+
+/// Value built by `init` on first access and timed under `subsystem`
+pub(crate) struct LazySubsystem<T> {
+    cell: OnceCell<T>,
+    subsystem: Subsystem,
+    init: fn() -> T,
+}
+
+impl<T> LazySubsystem<T> {
+    pub(crate) const fn new(subsystem: Subsystem, init: fn() -> T) -> Self {
+        LazySubsystem { cell: OnceCell::new(), subsystem, init }
+    }
+
+    pub(crate) fn get(&self) -> &T {
+        self.cell.get_or_init(|| {
+            let started_at = platform::monotonic_us();
+            let value = (self.init)();
+            record_init(self.subsystem, platform::monotonic_us().saturating_sub(started_at));
+            value
+        })
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        self.get();
+        self.cell.get_mut().expect("initialized by get")
+    }
+
+    pub(crate) fn is_initialized(&self) -> bool {
+        self.cell.get().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsystems_initialize_once_on_first_use() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static BUILDS: AtomicUsize = AtomicUsize::new(0);
+        fn build() -> Vec<u32> {
+            BUILDS.fetch_add(1, Ordering::SeqCst);
+            vec![1, 2, 3]
+        }
+        let mut lazy = LazySubsystem::new(Subsystem::AuditComplianceRules, build);
+        assert!(!lazy.is_initialized());
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(lazy.get().len(), 3));
+            }
+        });
+        lazy.get_mut().push(4);
+        assert_eq!(lazy.get(), &[1, 2, 3, 4]);
+        assert_eq!(BUILDS.load(Ordering::SeqCst), 1);
+
+        let stats = subsystem_init_stats(Subsystem::AuditComplianceRules);
+        assert!(stats.initializations() >= 1 && stats.first_initialized_at().is_some());
+        assert!(subsystem_init_metrics().contains(r#""subsystem":"audit_compliance_rules""#));
+    }
+}
@@ -1,6 +1,15 @@
 // Use default WASM allocator for better security and maintenance
 
 use wasm_bindgen::prelude::*;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use sha2::{Digest, Sha256};
+
+#[cfg(all(feature = "deterministic-test", feature = "strict_security"))]
+compile_error!("`deterministic-test` replaces the RNG and clock with seeded fakes and cannot be combined with `strict_security`");
+
+#[cfg(all(feature = "deterministic-test", not(debug_assertions)))]
+compile_error!("`deterministic-test` must never be compiled into release builds");
 
 // Import console.log for debugging
 #[wasm_bindgen]
@@ -24,24 +33,95 @@ pub mod integration;
 pub mod device;
 pub mod secure_storage;
 pub mod derivation;
+pub mod derivation_transcript;
 pub mod multi_device;
+pub mod inbox;
+pub mod multi_recipient;
+pub mod profile;
+pub mod platform;
+pub mod export;
+pub mod verifier;
+pub mod ceremony;
+pub mod auth_freshness;
+pub mod inspect;
+pub mod inactivity;
+pub mod decrypt_audit;
+pub mod crash_capture;
+pub mod timeouts;
 pub mod recovery;
+pub mod threshold;
+pub mod backup_hardening;
 pub mod key_rotation;
+pub mod heartbeat;
+pub mod pagination;
+pub mod host_calls;
+pub mod scope;
+pub mod shutdown;
+pub mod sync_protocol;
+pub mod storage_migration;
+pub mod consent;
+pub mod kdf_rehash;
+pub mod lifecycle_webhooks;
+pub mod small_record;
+pub mod trusted_time;
+pub mod recovery_rehearsal;
+pub mod pseudonymization;
+pub mod lazy_init;
+pub mod hybrid_wrap;
+pub mod signing;
+pub mod oprf;
+#[cfg(any(test, feature = "test_support"))]
+pub mod test_support;
 
 // Re-export main functions for JavaScript consumption
 pub use envelope::*;
 pub use keys::*;
 pub use derivation::*;
+pub use derivation_transcript::*;
 pub use aad::*;
-pub use memory::{SecureBuffer, MemoryPool, SecureTempData, get_memory_usage, get_active_allocations, cleanup_unused_buffers, has_memory_leaks, get_memory_stats, reset_memory_stats, MemoryStats, track_secret_allocation, track_secret_zeroization, track_allocation};
+pub use memory::{SecureBuffer, MemoryPool, MemorySubsystem, MemoryBudgetExceeded, set_memory_budget, get_memory_budget, get_memory_budget_usage, SecureTempData, get_memory_usage, get_active_allocations, cleanup_unused_buffers, has_memory_leaks, get_memory_stats, reset_memory_stats, MemoryStats, track_secret_allocation, track_secret_zeroization, track_allocation};
 pub use bindings::*;
 pub use security::*;
 pub use integration::*;
 pub use device::*;
 pub use secure_storage::*;
 pub use multi_device::*;
+pub use inbox::*;
+pub use multi_recipient::*;
+pub use profile::*;
+pub use platform::{is_deterministic_build, now_ms};
+pub use export::*;
+pub use verifier::*;
+pub use ceremony::*;
+pub use auth_freshness::*;
+pub use inspect::*;
+pub use inactivity::*;
+pub use decrypt_audit::*;
+pub use crash_capture::*;
+pub use timeouts::*;
 pub use recovery::*;
+pub use threshold::*;
+pub use backup_hardening::*;
 pub use key_rotation::*;
+pub use heartbeat::*;
+pub use pagination::*;
+pub use host_calls::*;
+pub use scope::*;
+pub use shutdown::*;
+pub use sync_protocol::*;
+pub use storage_migration::*;
+pub use consent::*;
+pub use kdf_rehash::*;
+pub use lifecycle_webhooks::*;
+pub use small_record::*;
+pub use trusted_time::*;
+pub use recovery_rehearsal::*;
+pub use pseudonymization::*;
+pub use lazy_init::*;
+pub use hybrid_wrap::*;
+pub use signing::*;
+#[cfg(any(test, feature = "test_support"))]
+pub use test_support::*;
 
 // Initialize function called when WASM module is loaded
 #[wasm_bindgen(start)]
@@ -64,45 +144,222 @@ pub fn generate_key() -> Result<CryptoKey, Box<dyn std::error::Error>> {
     Ok(key)
 }
 
+/// Seal `data` under AES-256-GCM. The AEAD associated data binds the SHA-256 of
+/// `aad` together with the envelope's schema version and, while a user scope is
+/// active, its keyed scope tag (see `envelope_associated_data`)
 pub fn encrypt_data(
     data: &[u8],
-    _key: &CryptoKey,
+    key: &CryptoKey,
+    aad: &[u8],
+    device_id: &str,
+) -> Result<EncryptionResult, Box<dyn std::error::Error>> {
+    seal_data(data, key, aad, device_id, None)
+}
+
+/// `encrypt_data` for a record of the given data-layer schema version
+pub fn encrypt_data_with_schema(
+    data: &[u8],
+    key: &CryptoKey,
+    aad: &[u8],
+    device_id: &str,
+    schema_version: u32,
+) -> Result<EncryptionResult, Box<dyn std::error::Error>> {
+    seal_data(data, key, aad, device_id, Some(schema_version))
+}
+
+fn seal_data(
+    data: &[u8],
+    key: &CryptoKey,
     aad: &[u8],
     _device_id: &str,
+    schema_version: Option<u32>,
 ) -> Result<EncryptionResult, Box<dyn std::error::Error>> {
+    // Plaintext and ciphertext buffers are live at the same time
+    let _buffers = memory::reserve_memory_budget(MemorySubsystem::EnvelopeBuffers, 2 * data.len())?;
     track_allocation(data.len() + aad.len());
     track_secret_allocation();
     
-    // Create a mock encryption result for testing using the constructor
-    let envelope = CryptoEnvelope::new();
+    let cipher = envelope_cipher(key)?;
+    let mut envelope = CryptoEnvelope::new();
+    let mut nonce = [0u8; ENVELOPE_NONCE_LENGTH];
+    platform::fill_random(&mut nonce);
+    envelope.set_nonce(nonce.to_vec());
+    envelope.set_aad_hash(Sha256::digest(aad).to_vec());
+    if let Some(schema_version) = schema_version {
+        envelope.set_schema_version(schema_version);
+    }
+    if let Some(scope_tag) = aad::active_user_scope_tag() {
+        envelope.set_user_scope_tag(scope_tag);
+    }
     
-    // Mock encrypted data (in real implementation, this would be actual encryption)
-    let encrypted_data = data.iter().map(|&b| b ^ 0xAA).collect();
+    let associated = envelope_associated_data(&envelope);
+    let mut sealed = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: data, aad: &associated })
+        .map_err(|_| "Encryption failed")?;
+    let tag = sealed.split_off(sealed.len() - ENVELOPE_TAG_LENGTH);
+    envelope.set_encrypted_data(sealed.clone());
+    envelope.set_tag(tag);
     
     Ok(EncryptionResult {
-        encrypted_data,
+        encrypted_data: sealed,
         envelope,
     })
 }
 
+/// Decrypt `category` data sealed by `encrypt_data` under the same `aad`. Kept for
+/// existing callers; it runs the checks of `decrypt_category_data` and additionally
+/// refuses an envelope whose AAD hash does not match `aad`
+#[deprecated(note = "use `decrypt_category_data` or `decrypt_data_with_schema`")]
 pub fn decrypt_data(
     encrypted_data: &[u8],
     envelope: &CryptoEnvelope,
-    _key: &CryptoKey,
+    key: &CryptoKey,
+    category: DataCategory,
+    aad: &[u8],
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if !security::constant_time_compare(&Sha256::digest(aad), &envelope.aad_hash()) {
+        return Err("Envelope was sealed under different associated data".into());
+    }
+    decrypt_checked(encrypted_data, envelope, key, category, None)
+}
+
+/// Raw decrypt with no role, freshness, scope or audit checks. Crate-internal only;
+/// everything public goes through `decrypt_category_data` or `decrypt_data_with_schema`.
+/// A rewritten schema version, scope tag or AAD hash fails authentication here
+pub(crate) fn decrypt_envelope(
+    encrypted_data: &[u8],
+    envelope: &CryptoEnvelope,
+    key: &CryptoKey,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let _buffers = memory::reserve_memory_budget(MemorySubsystem::EnvelopeBuffers, 2 * encrypted_data.len())?;
     track_allocation(encrypted_data.len());
     
     // Basic envelope validation (simplified for now)
     if envelope.encrypted_data().is_empty() {
         return Err("Invalid envelope: empty encrypted data".into());
     }
+    let nonce = envelope.nonce();
+    let tag = envelope.tag();
+    if nonce.len() != ENVELOPE_NONCE_LENGTH || tag.len() != ENVELOPE_TAG_LENGTH {
+        return Err("Malformed envelope nonce or tag".into());
+    }
     
-    // Mock decryption (in real implementation, this would be actual decryption)
-    let decrypted = encrypted_data.iter().map(|&b| b ^ 0xAA).collect();
+    let cipher = envelope_cipher(key)?;
+    let mut sealed = Vec::with_capacity(encrypted_data.len() + tag.len());
+    sealed.extend_from_slice(encrypted_data);
+    sealed.extend_from_slice(&tag);
+    let associated = envelope_associated_data(envelope);
+    let decrypted = cipher
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &sealed, aad: &associated })
+        .map_err(|_| "Decryption failed: envelope authentication failed")?;
     
     Ok(decrypted)
 }
 
+const ENVELOPE_NONCE_LENGTH: usize = 12;
+const ENVELOPE_TAG_LENGTH: usize = 16;
+
+fn envelope_cipher(key: &CryptoKey) -> Result<Aes256Gcm, Box<dyn std::error::Error>> {
+    let material = key.material().ok_or("Encryption key is not initialized")?;
+    Aes256Gcm::new_from_slice(material).map_err(|_| "Invalid encryption key length".into())
+}
+
+// Header fields a reader acts on, laid out like `AADValidator::generate_aad`: optional
+// fields are only appended when set
+fn envelope_associated_data(envelope: &CryptoEnvelope) -> Vec<u8> {
+    let mut associated = vec![envelope.version(), envelope.algorithm()];
+    associated.extend_from_slice(&envelope.aad_hash());
+    if let Some(schema_version) = envelope.schema_version() {
+        associated.push(0); // Separator
+        associated.extend_from_slice(b"schema:");
+        associated.extend_from_slice(&schema_version.to_le_bytes());
+    }
+    if let Some(scope_tag) = envelope.user_scope_tag() {
+        associated.push(0); // Separator
+        associated.extend_from_slice(b"scope:");
+        associated.extend_from_slice(&scope_tag);
+    }
+    associated
+}
+
+/// Decrypt data of `category` only if the active role may read it (error downcasts
+/// to `RoleDenied`), the user authenticated recently enough for the installed
+/// `AuthFreshnessPolicy` (error downcasts to `ReauthRequired`) and the envelope
+/// carries the active user's scope tag (error downcasts to `ScopeViolation`; an
+/// untagged envelope is accepted while no user scope is active).
+/// Every attempt goes through the category's decrypt audit sampling rule, and
+/// internal failures leave a sealed crash snapshot (see `crash_capture`). Runs in
+/// the category's scope, so records and errors carry it (see `scope`)
+pub fn decrypt_category_data(
+    encrypted_data: &[u8],
+    envelope: &CryptoEnvelope,
+    key: &CryptoKey,
+    category: DataCategory,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    decrypt_checked(encrypted_data, envelope, key, category, None)
+}
+
+/// `decrypt_category_data` that additionally requires the envelope to be sealed
+/// under the schema version the caller expects; the error downcasts to `SchemaMismatch`
+pub fn decrypt_data_with_schema(
+    encrypted_data: &[u8],
+    envelope: &CryptoEnvelope,
+    key: &CryptoKey,
+    category: DataCategory,
+    expected_schema_version: u32,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    decrypt_checked(encrypted_data, envelope, key, category, Some(expected_schema_version))
+}
+
+// The single checked decrypt path; a refused attempt is audited as Denied
+fn decrypt_checked(
+    encrypted_data: &[u8],
+    envelope: &CryptoEnvelope,
+    key: &CryptoKey,
+    category: DataCategory,
+    expected_schema_version: Option<u32>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let _scope = scope::enter_scope(ScopeContext::for_key(category.to_string(), envelope.key_id()));
+    if let Err(denied) = precheck_decrypt(envelope, &category, expected_schema_version) {
+        decrypt_audit::record_decrypt(&category, DecryptOutcome::Denied);
+        return Err(denied);
+    }
+    let mut key_descriptors = vec![category.to_string()];
+    key_descriptors.extend(envelope.key_id());
+    let result = crash_capture::guard("decrypt_category_data", "lib", &key_descriptors, || {
+        decrypt_envelope(encrypted_data, envelope, key).map_err(|e| e.to_string())
+    })
+    .map_err(|e| {
+        crash_capture::capture_internal_error("decrypt_category_data", "lib", &e, &key_descriptors);
+        Box::<dyn std::error::Error>::from(e)
+    });
+    let outcome = if result.is_ok() { DecryptOutcome::Success } else { DecryptOutcome::Failed };
+    decrypt_audit::record_decrypt(&category, outcome);
+    result
+}
+
+fn precheck_decrypt(
+    envelope: &CryptoEnvelope,
+    category: &DataCategory,
+    expected_schema_version: Option<u32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    integration::check_category_access(category)?;
+    auth_freshness::enforce_auth_freshness(category, platform::now_ms())?;
+    let scoped = match aad::active_user_scope_tag() {
+        Some(expected) => envelope.check_user_scope(&expected),
+        None if envelope.user_scope_tag().is_some() => Err(ScopeViolation::new(true, false)),
+        None => Ok(()),
+    };
+    if let Err(violation) = scoped {
+        violation.report();
+        return Err(violation.into());
+    }
+    if let Some(expected) = expected_schema_version {
+        envelope.check_schema_compat(expected)?;
+    }
+    Ok(())
+}
+
 pub fn derive_key_from_password(
     password: &[u8],
     salt: &[u8],
@@ -162,4 +419,88 @@ mod tests {
         assert_eq!(validator.context(), "test");
         assert_eq!(envelope.encrypted_data().len(), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_legacy_envelope_fails_schema_check() {
+        let legacy = CryptoEnvelope::new();
+        let mismatch = legacy.check_schema_compat(1).unwrap_err();
+        assert_eq!(mismatch.found(), None);
+        assert!(mismatch.to_string().contains("no schema version"));
+    }
+
+    // One test because the user scope and freshness policy are process-wide
+    #[test]
+    fn test_public_decrypts_apply_role_freshness_scope_and_schema_checks() {
+        let key = generate_key().unwrap();
+        let sealed = encrypt_data_with_schema(&[9], &key, b"record", "device-1", 3).unwrap();
+        let (data, envelope) = (sealed.encrypted_data, sealed.envelope);
+
+        let mut policy = AuthFreshnessPolicy::new();
+        policy.require(DataCategory::HealthcareSharing, 60_000);
+        set_auth_freshness_policy(&policy);
+        clear_authentication();
+        let err = decrypt_category_data(&data, &envelope, &key, DataCategory::HealthcareSharing).unwrap_err();
+        assert!(err.downcast_ref::<ReauthRequired>().is_some());
+        assert!(decrypt_category_data(&data, &envelope, &key, DataCategory::CycleData).is_ok());
+        report_authentication(platform::now_ms());
+        assert_eq!(decrypt_category_data(&data, &envelope, &key, DataCategory::HealthcareSharing).unwrap(), vec![9]);
+        set_auth_freshness_policy(&AuthFreshnessPolicy::new());
+
+        assert_eq!(decrypt_data_with_schema(&data, &envelope, &key, DataCategory::CycleData, 3).unwrap(), vec![9]);
+        let err = decrypt_data_with_schema(&data, &envelope, &key, DataCategory::CycleData, 4).unwrap_err();
+        let mismatch = err.downcast_ref::<SchemaMismatch>().unwrap();
+        assert_eq!(mismatch.expected(), 4);
+        assert_eq!(mismatch.found(), Some(3));
+
+        let alice = UserScope::derive(&[7u8; 32], "alice").unwrap();
+        let bob = UserScope::derive(&[7u8; 32], "bob").unwrap();
+        assert_ne!(alice.tag(), bob.tag());
+        set_active_user_scope(&bob);
+        let sealed = encrypt_data(&[5], &key, b"record", "device-1").unwrap();
+        let (data, envelope) = (sealed.encrypted_data, sealed.envelope);
+        assert_eq!(envelope.user_scope_tag(), Some(bob.tag()));
+        clear_active_user_scope();
+        take_scope_violation_events();
+        let err = decrypt_category_data(&data, &envelope, &key, DataCategory::CycleData).unwrap_err();
+        assert!(!err.downcast_ref::<ScopeViolation>().unwrap().scope_active());
+
+        set_active_user_scope(&alice);
+        let err = decrypt_category_data(&data, &envelope, &key, DataCategory::CycleData).unwrap_err();
+        let violation = err.downcast_ref::<ScopeViolation>().unwrap();
+        assert!(violation.envelope_tagged() && violation.scope_active());
+        assert!(take_scope_violation_events().contains("scope_violation"));
+
+        set_active_user_scope(&bob);
+        assert_eq!(decrypt_category_data(&data, &envelope, &key, DataCategory::CycleData).unwrap(), vec![5]);
+        clear_active_user_scope();
+
+        let sealed = encrypt_data(&[6], &key, b"record", "device-1").unwrap();
+        #[allow(deprecated)]
+        {
+            assert_eq!(decrypt_data(&sealed.encrypted_data, &sealed.envelope, &key, DataCategory::CycleData, b"record").unwrap(), vec![6]);
+            assert!(decrypt_data(&sealed.encrypted_data, &sealed.envelope, &key, DataCategory::CycleData, b"other record").is_err());
+        }
+    }
+
+    #[test]
+    fn test_rewritten_schema_or_scope_fields_fail_authentication() {
+        let key = generate_key().unwrap();
+        let sealed = encrypt_data_with_schema(b"cycle", &key, b"record", "device-1", 3).unwrap();
+        assert_eq!(decrypt_envelope(&sealed.encrypted_data, &sealed.envelope, &key).unwrap(), b"cycle");
+
+        let mut relabeled = sealed.envelope.clone();
+        relabeled.set_schema_version(4);
+        assert!(decrypt_envelope(&sealed.encrypted_data, &relabeled, &key).is_err());
+
+        let mut retagged = sealed.envelope.clone();
+        retagged.set_user_scope_tag(vec![1; 32]);
+        assert!(decrypt_envelope(&sealed.encrypted_data, &retagged, &key).is_err());
+
+        let mut rehashed = sealed.envelope.clone();
+        rehashed.set_aad_hash(vec![0; 32]);
+        assert!(decrypt_envelope(&sealed.encrypted_data, &rehashed, &key).is_err());
+
+        let other_key = generate_key().unwrap();
+        assert!(decrypt_envelope(&sealed.encrypted_data, &sealed.envelope, &other_key).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::derivation::DataCategory;
+use crate::envelope::{decode_hex, to_hex};
+use crate::platform;
+use crate::signing::{self, SigningKeyPair};
+
+// Key lifecycle webhooks
+// Clinic deployments monitor through their MDM that employee devices rotate keys on
+// schedule. The crate builds the event bodies and signs them; the host delivers them.
+// Bodies carry metadata only (device id, purpose, key version, timestamps), never key
+// material, fingerprints or health data. Every device signs with its own Ed25519 key
+// (see `signing`), kept in device key storage; only its public key is enrolled with
+// the monitoring endpoint. The signature covers the exact body bytes, the endpoint
+// accepts a body only under the key enrolled for the `device_id` it names, so one
+// device cannot speak for another, and the per-device sequence number lets the
+// endpoint drop replays.
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleEventKind {
+    RotationCompleted = 0,
+    BackupStale = 1,
+    DeviceRevoked = 2,
+}
+
+/// Event-specific fields, flattened into the body next to `kind`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum LifecycleDetails {
+    RotationCompleted { purpose: String, key_version: String },
+    BackupStale { last_backup_at: Option<u64>, max_backup_age_ms: u64 },
+    DeviceRevoked {},
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LifecycleEventBody {
+    event_id: String,
+    deployment_id: String,
+    device_id: String,
+    sequence: u64,
+    kind: LifecycleEventKind,
+    occurred_at: u64,
+    issued_at: u64,
+    #[serde(flatten)]
+    details: LifecycleDetails,
+}
+
+/// Signed event ready for delivery: POST `body` with `signature` and `key_id` as headers
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecycleWebhook {
+    kind: LifecycleEventKind,
+    body: String,
+    signature: String,
+    key_id: String,
+}
+
+#[wasm_bindgen]
+impl LifecycleWebhook {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> LifecycleEventKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn body(&self) -> String {
+        self.body.clone()
+    }
+
+    /// Hex Ed25519 signature of `body` under the device's signing key
+    #[wasm_bindgen(getter)]
+    pub fn signature(&self) -> String {
+        self.signature.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn key_id(&self) -> String {
+        self.key_id.clone()
+    }
+}
+
+/// Endpoint-side verifier holding the enrolled public key of every device
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct LifecycleWebhookEndpoint {
+    deployment_id: String,
+    device_keys: HashMap<String, Vec<u8>>,
+}
+
+#[wasm_bindgen]
+impl LifecycleWebhookEndpoint {
+    #[wasm_bindgen(constructor)]
+    pub fn new(deployment_id: String) -> LifecycleWebhookEndpoint {
+        LifecycleWebhookEndpoint { deployment_id, device_keys: HashMap::new() }
+    }
+
+    /// Enroll (or replace after rollover) the public key `device_id` signs with
+    #[wasm_bindgen]
+    pub fn enroll_device(&mut self, device_id: String, public_key: &[u8]) -> Result<(), JsValue> {
+        if public_key.len() != signing::PUBLIC_KEY_LENGTH {
+            return Err(JsValue::from_str("Device public key must be 32 bytes"));
+        }
+        self.device_keys.insert(device_id, public_key.to_vec());
+        Ok(())
+    }
+
+    /// Whether `body` names this deployment and an enrolled device, and `signature`
+    /// is that device's signature over it
+    #[wasm_bindgen]
+    pub fn verify(&self, body: &str, signature: &str) -> bool {
+        let Ok(event) = serde_json::from_str::<LifecycleEventBody>(body) else {
+            return false;
+        };
+        let (Some(public_key), Ok(signature)) = (self.device_keys.get(&event.device_id), decode_hex(signature)) else {
+            return false;
+        };
+        event.deployment_id == self.deployment_id && signing::verify(public_key, body.as_bytes(), &signature)
+    }
+}
+
+/// Builds and signs lifecycle events for one device of one deployment
+#[wasm_bindgen]
+pub struct LifecycleWebhookSigner {
+    deployment_id: String,
+    device_id: String,
+    signing_key: SigningKeyPair,
+    next_sequence: u64,
+}
+
+#[wasm_bindgen]
+impl LifecycleWebhookSigner {
+    /// Takes over this device's `signing_key`; `next_sequence` restores the counter
+    /// the host persisted, 0 for a new device
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        deployment_id: String,
+        device_id: String,
+        signing_key: SigningKeyPair,
+        next_sequence: u64,
+    ) -> Result<LifecycleWebhookSigner, JsValue> {
+        Self::with_key(deployment_id, device_id, signing_key, next_sequence).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Public key to enroll with the monitoring endpoint
+    #[wasm_bindgen(getter)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.signing_key.public_key()
+    }
+
+    /// Sequence number the next event will carry; persist it after every event
+    #[wasm_bindgen(getter)]
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// Fingerprint of the device's public key, so endpoints can tell keys apart during rollover
+    #[wasm_bindgen(getter)]
+    pub fn key_id(&self) -> String {
+        self.signing_key.key_id()
+    }
+
+    #[wasm_bindgen]
+    pub fn rotation_completed(&mut self, purpose: DataCategory, key_version: String, completed_at: u64) -> LifecycleWebhook {
+        let details = LifecycleDetails::RotationCompleted { purpose: purpose.to_string(), key_version };
+        self.issue_at(LifecycleEventKind::RotationCompleted, details, completed_at, platform::now_ms())
+    }
+
+    /// The last backup (None if there never was one) is older than the deployment allows
+    #[wasm_bindgen]
+    pub fn backup_stale(&mut self, last_backup_at: Option<u64>, max_backup_age_ms: u64) -> LifecycleWebhook {
+        let now = platform::now_ms();
+        let details = LifecycleDetails::BackupStale { last_backup_at, max_backup_age_ms };
+        self.issue_at(LifecycleEventKind::BackupStale, details, now, now)
+    }
+
+    #[wasm_bindgen]
+    pub fn device_revoked(&mut self, revoked_at: u64) -> LifecycleWebhook {
+        self.issue_at(LifecycleEventKind::DeviceRevoked, LifecycleDetails::DeviceRevoked {}, revoked_at, platform::now_ms())
+    }
+}
+
+impl LifecycleWebhookSigner {
+    pub(crate) fn with_key(
+        deployment_id: String,
+        device_id: String,
+        signing_key: SigningKeyPair,
+        next_sequence: u64,
+    ) -> Result<Self, String> {
+        if deployment_id.is_empty() || device_id.is_empty() {
+            return Err("Deployment and device ids must not be empty".to_string());
+        }
+        Ok(LifecycleWebhookSigner { deployment_id, device_id, signing_key, next_sequence })
+    }
+
+    fn issue_at(&mut self, kind: LifecycleEventKind, details: LifecycleDetails, occurred_at: u64, now: u64) -> LifecycleWebhook {
+        let event = LifecycleEventBody {
+            event_id: platform::new_uuid(),
+            deployment_id: self.deployment_id.clone(),
+            device_id: self.device_id.clone(),
+            sequence: self.next_sequence,
+            kind,
+            occurred_at,
+            issued_at: now,
+            details,
+        };
+        self.next_sequence += 1;
+
+        let body = serde_json::to_string(&event).unwrap_or_default();
+        let signature = to_hex(&self.signing_key.sign(body.as_bytes()));
+        LifecycleWebhook { kind, signature, key_id: self.signing_key.key_id(), body }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhooks_are_signed_sequenced_and_metadata_only() {
+        let mut signer = LifecycleWebhookSigner::with_key("clinic-1".into(), "device-a".into(), SigningKeyPair::generate(), 41).unwrap();
+        let mut endpoint = LifecycleWebhookEndpoint::new("clinic-1".into());
+        endpoint.device_keys.insert("device-a".into(), signer.public_key());
+
+        let rotated = signer.issue_at(
+            LifecycleEventKind::RotationCompleted,
+            LifecycleDetails::RotationCompleted { purpose: "cycle_data".into(), key_version: "1.2.0".into() },
+            1_000,
+            1_500,
+        );
+        let stale = signer.issue_at(
+            LifecycleEventKind::BackupStale,
+            LifecycleDetails::BackupStale { last_backup_at: None, max_backup_age_ms: 7 },
+            2_000,
+            2_000,
+        );
+        assert_eq!(signer.next_sequence(), 43);
+
+        let body: serde_json::Value = serde_json::from_str(&rotated.body()).unwrap();
+        assert_eq!((body["kind"].as_str(), body["sequence"].as_u64()), (Some("rotation_completed"), Some(41)));
+        let mut fields: Vec<&str> = body.as_object().unwrap().keys().map(String::as_str).collect();
+        fields.sort_unstable();
+        assert_eq!(fields, ["deployment_id", "device_id", "event_id", "issued_at", "key_version", "kind", "occurred_at", "purpose", "sequence"]);
+        let parsed: LifecycleEventBody = serde_json::from_str(&stale.body()).unwrap();
+        assert_eq!(parsed.details, LifecycleDetails::BackupStale { last_backup_at: None, max_backup_age_ms: 7 });
+
+        assert!(endpoint.verify(&rotated.body(), &rotated.signature()));
+        assert!(!endpoint.verify(&rotated.body().replace("1.2.0", "1.3.0"), &rotated.signature()));
+        assert!(!LifecycleWebhookEndpoint::new("clinic-2".into()).verify(&rotated.body(), &rotated.signature()));
+        assert_eq!(rotated.key_id(), signer.key_id());
+    }
+
+    #[test]
+    fn test_one_device_cannot_sign_events_for_another() {
+        let mut device_a = LifecycleWebhookSigner::with_key("clinic-1".into(), "device-a".into(), SigningKeyPair::generate(), 0).unwrap();
+        let mut impostor = LifecycleWebhookSigner::with_key("clinic-1".into(), "device-b".into(), SigningKeyPair::generate(), 0).unwrap();
+        let mut endpoint = LifecycleWebhookEndpoint::new("clinic-1".into());
+        endpoint.device_keys.insert("device-a".into(), device_a.public_key());
+        endpoint.device_keys.insert("device-b".into(), SigningKeyPair::generate().public_key());
+
+        // device-a's key is enrolled only for device-a; a body naming device-b fails
+        let forged = device_a.issue_at(LifecycleEventKind::DeviceRevoked, LifecycleDetails::DeviceRevoked {}, 1_000, 1_000);
+        let retargeted = forged.body().replace("device-a", "device-b");
+        assert!(!endpoint.verify(&retargeted, &forged.signature()));
+        assert!(endpoint.verify(&forged.body(), &forged.signature()));
+
+        // A device holding some other key cannot pass as an enrolled one
+        let unenrolled = impostor.issue_at(LifecycleEventKind::DeviceRevoked, LifecycleDetails::DeviceRevoked {}, 1_000, 1_000);
+        assert!(!endpoint.verify(&unenrolled.body(), &unenrolled.signature()));
+    }
+}
@@ -1,8 +1,11 @@
 use wasm_bindgen::prelude::*;
 use zeroize::Zeroize;
 use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use crate::platform;
+use crate::scope::{scoped_error, ErrorScope};
+use crate::shutdown::shutdown_generation;
 
 /// Global memory statistics for leak detection
 static SECRETS_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
@@ -53,6 +56,139 @@ pub fn track_allocation(size: usize) {
     TOTAL_ALLOCATED.fetch_add(size, Ordering::Relaxed);
 }
 
+// Per-subsystem memory budgets
+// Hosts on constrained WebViews cap what each subsystem may hold at once. Work that
+// needs memory takes a `BudgetReservation` (released on drop); when the budget is
+// exhausted subsystems degrade instead of aborting: envelope operations fail with
+// `MemoryBudgetExceeded`, migration batches shrink, pooled buffers are evicted.
+
+/// Subsystems with an independent memory budget
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemorySubsystem {
+    EnvelopeBuffers = 0,
+    MigrationBatches = 1,
+    Caches = 2,
+}
+
+impl MemorySubsystem {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MemorySubsystem::EnvelopeBuffers => "envelope buffers",
+            MemorySubsystem::MigrationBatches => "migration batches",
+            MemorySubsystem::Caches => "caches",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SubsystemBudget {
+    limit: Option<usize>,
+    in_use: usize,
+}
+
+static MEMORY_BUDGETS: Mutex<[SubsystemBudget; 3]> =
+    Mutex::new([SubsystemBudget { limit: None, in_use: 0 }; 3]);
+
+/// Cap `subsystem` at `limit_bytes`; `None` removes the cap. Lowering a cap below
+/// current usage does not free anything, it only refuses further reservations
+#[wasm_bindgen]
+pub fn set_memory_budget(subsystem: MemorySubsystem, limit_bytes: Option<usize>) {
+    if let Ok(mut budgets) = MEMORY_BUDGETS.lock() {
+        budgets[subsystem as usize].limit = limit_bytes;
+    }
+}
+
+#[wasm_bindgen]
+pub fn get_memory_budget(subsystem: MemorySubsystem) -> Option<usize> {
+    MEMORY_BUDGETS.lock().ok().and_then(|budgets| budgets[subsystem as usize].limit)
+}
+
+/// Bytes currently reserved by `subsystem`
+#[wasm_bindgen]
+pub fn get_memory_budget_usage(subsystem: MemorySubsystem) -> usize {
+    MEMORY_BUDGETS.lock().map(|budgets| budgets[subsystem as usize].in_use).unwrap_or(0)
+}
+
+/// Bytes `subsystem` may still reserve; `None` when uncapped
+pub(crate) fn memory_budget_headroom(subsystem: MemorySubsystem) -> Option<usize> {
+    let budgets = MEMORY_BUDGETS.lock().ok()?;
+    let budget = budgets[subsystem as usize];
+    budget.limit.map(|limit| limit.saturating_sub(budget.in_use))
+}
+
+/// Reserve `bytes` against the subsystem budget for as long as the reservation lives
+pub(crate) fn reserve_memory_budget(
+    subsystem: MemorySubsystem,
+    bytes: usize,
+) -> Result<BudgetReservation, MemoryBudgetExceeded> {
+    let mut budgets = MEMORY_BUDGETS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let budget = &mut budgets[subsystem as usize];
+    if let Some(limit) = budget.limit {
+        let available = limit.saturating_sub(budget.in_use);
+        if bytes > available {
+            return Err(MemoryBudgetExceeded { subsystem, requested: bytes, available, scope: ErrorScope::capture() });
+        }
+    }
+    budget.in_use += bytes;
+    Ok(BudgetReservation { subsystem, bytes })
+}
+
+/// Bytes held against a subsystem budget, returned on drop
+#[derive(Debug)]
+pub(crate) struct BudgetReservation {
+    subsystem: MemorySubsystem,
+    bytes: usize,
+}
+
+impl Drop for BudgetReservation {
+    fn drop(&mut self) {
+        let mut budgets = MEMORY_BUDGETS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let budget = &mut budgets[self.subsystem as usize];
+        budget.in_use = budget.in_use.saturating_sub(self.bytes);
+    }
+}
+
+// Typed error returned when a subsystem's budget cannot cover an operation
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryBudgetExceeded {
+    subsystem: MemorySubsystem,
+    requested: usize,
+    available: usize,
+    scope: ErrorScope,
+}
+
+#[wasm_bindgen]
+impl MemoryBudgetExceeded {
+    #[wasm_bindgen(getter)]
+    pub fn subsystem(&self) -> MemorySubsystem {
+        self.subsystem
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn requested(&self) -> usize {
+        self.requested
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn available(&self) -> usize {
+        self.available
+    }
+}
+
+impl std::fmt::Display for MemoryBudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} budget exceeded: {} bytes requested, {} available{}",
+            self.subsystem.as_str(), self.requested, self.available, self.scope
+        )
+    }
+}
+
+scoped_error!(MemoryBudgetExceeded);
+
 /// Global memory statistics tracking
 static MEMORY_STATS: once_cell::sync::Lazy<Arc<Mutex<MemoryStatistics>>> =
     once_cell::sync::Lazy::new(|| {
@@ -121,11 +257,137 @@ pub fn has_memory_leaks() -> bool {
     stats.active_allocations > 100 || stats.total_heap_usage > 1024 * 1024 // 1MB threshold
 }
 
+// Live secret registry
+// Every `SecureBuffer` records where its bytes live until it is dropped, so `shutdown`
+// can wipe key material, pooled and temporary buffers at once instead of waiting for
+// each owner's next access or drop. A buffer's bytes never move: they are only
+// zeroized in place, and the entry is removed under the registry lock before the
+// allocation is freed. Each allocation ends in random guard bytes whose expected
+// value is kept here, outside the buffer, so an overrun past the secret shows up
+// when the integrity heartbeat samples the registry.
+const CANARY_LENGTH: usize = 8;
+
+struct LiveSecret {
+    address: usize,
+    len: usize,
+    canary: [u8; CANARY_LENGTH],
+    #[cfg(test)]
+    thread: std::thread::ThreadId,
+}
+
+static LIVE_SECRETS: Mutex<BTreeMap<u64, LiveSecret>> = Mutex::new(BTreeMap::new());
+static NEXT_SECRET_ID: AtomicU64 = AtomicU64::new(1);
+
+// Appends the guard bytes, so `data` must not be resized afterwards
+fn register_live_secret(data: &mut Vec<u8>) -> u64 {
+    let len = data.len();
+    let mut canary = [0u8; CANARY_LENGTH];
+    platform::fill_random(&mut canary);
+    data.extend_from_slice(&canary);
+
+    let id = NEXT_SECRET_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut live) = LIVE_SECRETS.lock() {
+        let secret = LiveSecret {
+            address: data.as_mut_ptr() as usize,
+            len,
+            canary,
+            #[cfg(test)]
+            thread: std::thread::current().id(),
+        };
+        live.insert(id, secret);
+    }
+    id
+}
+
+fn unregister_live_secret(id: u64) {
+    if let Ok(mut live) = LIVE_SECRETS.lock() {
+        live.remove(&id);
+    }
+}
+
+fn canary_intact(secret: &LiveSecret) -> bool {
+    // SAFETY: as in `wipe_live_secrets`; the guard bytes are never handed out, so
+    // nothing writes them while the registry lock is held
+    let guard = unsafe { std::slice::from_raw_parts((secret.address + secret.len) as *const u8, CANARY_LENGTH) };
+    guard == secret.canary
+}
+
+/// Check the guard bytes of `sample_size` randomly chosen live buffers, as
+/// [passed, sampled]. Nothing is sampled while no secret buffer is alive
+pub(crate) fn sample_secret_canaries(sample_size: usize) -> [usize; 2] {
+    let Ok(live) = LIVE_SECRETS.lock() else {
+        return [0, sample_size];
+    };
+    let candidates: Vec<&LiveSecret> = live.values().filter(|secret| in_canary_sample(secret)).collect();
+    if candidates.is_empty() {
+        return [0, 0];
+    }
+    let passed = (0..sample_size)
+        .map(|_| candidates[platform::random_u32() as usize % candidates.len()])
+        .filter(|secret| canary_intact(secret))
+        .count();
+    [passed, sample_size]
+}
+
+#[cfg(not(test))]
+fn in_canary_sample(_secret: &LiveSecret) -> bool {
+    true
+}
+
+// Parallel tests only sample the buffers their own thread allocated
+#[cfg(test)]
+fn in_canary_sample(secret: &LiveSecret) -> bool {
+    secret.thread == std::thread::current().id()
+}
+
+/// Secret buffers and bytes a registry wipe zeroized
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SecretsWiped {
+    pub(crate) buffers: usize,
+    pub(crate) bytes: usize,
+}
+
+/// Zeroize every live `SecureBuffer` now. Their owners see them as retired once the
+/// shutdown generation has advanced; callers must not hold borrowed buffer contents
+pub(crate) fn zeroize_live_secrets() -> SecretsWiped {
+    wipe_live_secrets(|_| true)
+}
+
+// Parallel tests only wipe the buffers their own thread allocated
+#[cfg(test)]
+pub(crate) fn zeroize_thread_secrets() -> SecretsWiped {
+    let thread = std::thread::current().id();
+    wipe_live_secrets(|secret| secret.thread == thread)
+}
+
+fn wipe_live_secrets(selected: impl Fn(&LiveSecret) -> bool) -> SecretsWiped {
+    let mut wiped = SecretsWiped::default();
+    let Ok(mut live) = LIVE_SECRETS.lock() else {
+        return wiped;
+    };
+    live.retain(|_, secret| {
+        if !selected(secret) {
+            return true;
+        }
+        // SAFETY: the entry exists only while its buffer's allocation is alive and
+        // unmoved, and dropping the buffer removes it under this lock first
+        let bytes = unsafe { std::slice::from_raw_parts_mut(secret.address as *mut u8, secret.len) };
+        bytes.zeroize();
+        wiped.buffers += 1;
+        wiped.bytes += secret.len;
+        false
+    });
+    wiped
+}
+
 /// Secure memory management utilities for cryptographic operations
-/// Provides memory hygiene with automatic secret zeroization
+/// Provides memory hygiene with automatic secret zeroization. Buffers allocated before
+/// a `shutdown` are retired: they read as zeroized and are wiped on next mutable access
 pub struct SecureBuffer {
     data: Vec<u8>,
     is_active: bool,
+    generation: u64,
+    registry_id: u64,
 }
 
 impl SecureBuffer {
@@ -137,15 +399,20 @@ impl SecureBuffer {
             stats.increment_allocation(capacity, "SecureBuffer");
         }
         
+        let mut data = Vec::with_capacity(capacity + CANARY_LENGTH);
+        data.resize(capacity, 0);
+        let registry_id = register_live_secret(&mut data);
         SecureBuffer {
-            data: vec![0u8; capacity],
+            data,
             is_active: true,
+            generation: shutdown_generation(),
+            registry_id,
         }
     }
 
     /// Create secure buffer from existing data
     #[must_use]
-    pub fn from_bytes(data: Vec<u8>) -> Self {
+    pub fn from_bytes(mut data: Vec<u8>) -> Self {
         let capacity = data.len();
         
         // Track allocation in global statistics
@@ -153,16 +420,20 @@ impl SecureBuffer {
             stats.increment_allocation(capacity, "SecureBuffer");
         }
         
+        data.reserve_exact(CANARY_LENGTH);
+        let registry_id = register_live_secret(&mut data);
         SecureBuffer {
             data,
             is_active: true,
+            generation: shutdown_generation(),
+            registry_id,
         }
     }
 
     /// Get immutable reference to data (only if active)
     pub fn as_slice(&self) -> Result<&[u8], &'static str> {
-        if self.is_active {
-            Ok(&self.data)
+        if self.is_active() {
+            Ok(self.secret())
         } else {
             Err("Buffer has been zeroized")
         }
@@ -170,9 +441,10 @@ impl SecureBuffer {
 
     /// Get mutable reference to data (only if active)
     pub fn as_mut_slice(&mut self) -> Result<&mut [u8], &'static str> {
-        if self.is_active {
-            Ok(&mut self.data)
+        if self.is_active() {
+            Ok(self.secret_mut())
         } else {
+            self.zeroize_buffer();
             Err("Buffer has been zeroized")
         }
     }
@@ -180,48 +452,75 @@ impl SecureBuffer {
     /// Get length of buffer
     #[must_use]
     pub fn len(&self) -> usize {
-        self.data.len()
+        self.data.len() - CANARY_LENGTH
     }
 
     /// Check if buffer is empty
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+        self.len() == 0
     }
 
     /// Check if buffer is active (not zeroized)
     #[must_use]
     pub fn is_active(&self) -> bool {
-        self.is_active
+        self.is_active && self.generation == shutdown_generation()
     }
 
-    /// Explicitly zeroize buffer (called automatically on drop)
+    /// Explicitly zeroize buffer (called automatically on drop). The length is kept,
+    /// so a pool can hand the buffer out again
     pub fn zeroize_buffer(&mut self) {
         if self.is_active {
-            self.data.zeroize();
+            self.secret_mut().zeroize();
             self.is_active = false;
         }
     }
+
+    /// Whether the guard bytes after the secret still hold their recorded value
+    pub(crate) fn canary_intact(&self) -> bool {
+        let Ok(live) = LIVE_SECRETS.lock() else {
+            return false;
+        };
+        live.get(&self.registry_id)
+            .is_some_and(|secret| self.data[self.len()..] == secret.canary)
+    }
+
+    fn secret(&self) -> &[u8] {
+        &self.data[..self.len()]
+    }
+
+    fn secret_mut(&mut self) -> &mut [u8] {
+        let len = self.len();
+        &mut self.data[..len]
+    }
 }
 
 impl Drop for SecureBuffer {
     fn drop(&mut self) {
         // Track deallocation in global statistics
         if let Ok(mut stats) = MEMORY_STATS.lock() {
-            stats.decrement_allocation(self.data.len(), "SecureBuffer");
+            stats.decrement_allocation(self.len(), "SecureBuffer");
         }
         
+        unregister_live_secret(self.registry_id);
         self.zeroize_buffer();
     }
 }
 
-/// Memory pool for frequent crypto operations to reduce allocations
+/// Memory pool for frequent crypto operations to reduce allocations.
+/// Pooled buffers count against the `Caches` budget and are evicted oldest-first
+/// when it runs out
 pub struct MemoryPool {
-    encryption_buffers: Vec<SecureBuffer>,
-    temp_buffers: Vec<SecureBuffer>,
+    encryption_buffers: Vec<PooledBuffer>,
+    temp_buffers: Vec<PooledBuffer>,
     pool_size: usize,
 }
 
+struct PooledBuffer {
+    buffer: SecureBuffer,
+    _reservation: BudgetReservation,
+}
+
 impl MemoryPool {
     /// Create new memory pool with specified pool size
     #[must_use]
@@ -235,32 +534,34 @@ impl MemoryPool {
 
     /// Get encryption buffer from pool or create new one
     pub fn get_encryption_buffer(&mut self, size: usize) -> SecureBuffer {
-        if let Some(mut buffer) = self.encryption_buffers.pop() {
-            if buffer.len() >= size {
-                // Reuse existing buffer
-                if let Ok(slice) = buffer.as_mut_slice() {
-                    slice.zeroize(); // Clear previous data
-                }
-                buffer.is_active = true;
-                return buffer;
-            }
-        }
-        SecureBuffer::new(size)
+        Self::take_pooled(&mut self.encryption_buffers, size)
     }
 
     /// Return encryption buffer to pool
-    pub fn return_encryption_buffer(&mut self, mut buffer: SecureBuffer) {
-        if self.encryption_buffers.len() < self.pool_size {
-            buffer.zeroize_buffer();
-            self.encryption_buffers.push(buffer);
-        }
-        // If pool is full, buffer will be dropped and zeroized
+    pub fn return_encryption_buffer(&mut self, buffer: SecureBuffer) {
+        Self::pool_buffer(&mut self.encryption_buffers, buffer, self.pool_size);
     }
 
     /// Get temporary buffer from pool or create new one
     pub fn get_temp_buffer(&mut self, size: usize) -> SecureBuffer {
-        if let Some(mut buffer) = self.temp_buffers.pop() {
-            if buffer.len() >= size {
+        Self::take_pooled(&mut self.temp_buffers, size)
+    }
+
+    /// Return temporary buffer to pool
+    pub fn return_temp_buffer(&mut self, buffer: SecureBuffer) {
+        Self::pool_buffer(&mut self.temp_buffers, buffer, self.pool_size);
+    }
+
+    /// Clear all buffers in pool (emergency cleanup)
+    pub fn clear_pool(&mut self) {
+        self.encryption_buffers.clear();
+        self.temp_buffers.clear();
+    }
+
+    fn take_pooled(buffers: &mut Vec<PooledBuffer>, size: usize) -> SecureBuffer {
+        if let Some(PooledBuffer { mut buffer, .. }) = buffers.pop() {
+            if buffer.len() >= size && buffer.generation == shutdown_generation() {
+                // Reuse existing buffer
                 if let Ok(slice) = buffer.as_mut_slice() {
                     slice.zeroize(); // Clear previous data
                 }
@@ -271,18 +572,24 @@ impl MemoryPool {
         SecureBuffer::new(size)
     }
 
-    /// Return temporary buffer to pool
-    pub fn return_temp_buffer(&mut self, mut buffer: SecureBuffer) {
-        if self.temp_buffers.len() < self.pool_size {
-            buffer.zeroize_buffer();
-            self.temp_buffers.push(buffer);
+    fn pool_buffer(buffers: &mut Vec<PooledBuffer>, mut buffer: SecureBuffer, pool_size: usize) {
+        if buffers.len() >= pool_size {
+            return; // Pool is full, buffer will be dropped and zeroized
+        }
+        buffer.zeroize_buffer();
+        loop {
+            match reserve_memory_budget(MemorySubsystem::Caches, buffer.len()) {
+                Ok(reservation) => {
+                    buffers.push(PooledBuffer { buffer, _reservation: reservation });
+                    return;
+                }
+                // Make room by evicting the oldest pooled buffer
+                Err(_) if !buffers.is_empty() => {
+                    buffers.remove(0);
+                }
+                Err(_) => return,
+            }
         }
-    }
-
-    /// Clear all buffers in pool (emergency cleanup)
-    pub fn clear_pool(&mut self) {
-        self.encryption_buffers.clear();
-        self.temp_buffers.clear();
     }
 }
 
@@ -378,6 +685,42 @@ impl SecureTempData {
     }
 }
 
+// Test builds count heap allocations per thread, so allocation-free paths can be
+// checked without parallel tests disturbing each other
+#[cfg(test)]
+mod heap_counter {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    thread_local! {
+        static HEAP_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = HEAP_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// Heap allocations made so far by the current thread
+    pub(crate) fn thread_heap_allocations() -> usize {
+        HEAP_ALLOCATIONS.with(Cell::get)
+    }
+}
+
+#[cfg(test)]
+pub(crate) use heap_counter::thread_heap_allocations;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -400,6 +743,59 @@ mod tests {
         assert!(buffer.as_slice().is_err());
     }
 
+    #[test]
+    fn test_buffers_from_before_shutdown_are_retired() {
+        let mut buffer = SecureBuffer::from_bytes(vec![7u8; 16]);
+        buffer.generation = buffer.generation.wrapping_sub(1);
+        assert!(!buffer.is_active());
+        assert!(buffer.as_slice().is_err());
+
+        assert!(buffer.as_mut_slice().is_err());
+        assert!(buffer.secret().iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_registry_wipe_reaches_keys_and_pooled_buffers() {
+        let mut key = crate::keys::CryptoKey::new("encryption".to_string());
+        key.generate().unwrap();
+        let mut pool = MemoryPool::new(2);
+        let mut pooled = pool.get_temp_buffer(48);
+        pooled.as_mut_slice().unwrap().fill(0x5A);
+        pool.return_temp_buffer(pooled);
+        let held = SecureBuffer::from_bytes(vec![7u8; 16]);
+
+        let wiped = zeroize_thread_secrets();
+        assert!(wiped.buffers >= 3);
+        assert!(wiped.bytes >= 32 + 48 + 16);
+        assert!(held.secret().iter().all(|&b| b == 0));
+        assert!(key.material().unwrap().iter().all(|&b| b == 0));
+        assert!(pool.temp_buffers[0].buffer.secret().iter().all(|&b| b == 0));
+
+        // Wiped buffers leave the registry; dropping them afterwards is harmless
+        assert_eq!(zeroize_thread_secrets(), SecretsWiped::default());
+        drop(held);
+        drop(pool);
+    }
+
+    #[test]
+    fn test_overrun_into_guard_bytes_is_detected() {
+        let mut buffer = SecureBuffer::from_bytes(vec![7u8; 16]);
+        let key = {
+            let mut key = crate::keys::CryptoKey::new("encryption".to_string());
+            key.generate().unwrap();
+            key
+        };
+        assert!(buffer.canary_intact() && key.validate_memory_protection());
+        assert_eq!(sample_secret_canaries(8), [8, 8]);
+
+        // Write one byte past the secret, as an off-by-one copy would
+        buffer.data[16] ^= 0xFF;
+        assert!(!buffer.canary_intact());
+        drop(key);
+        assert_eq!(sample_secret_canaries(8), [0, 8]);
+        assert_eq!(buffer.as_slice().unwrap(), [7u8; 16]);
+    }
+
     #[test]
     fn test_memory_pool() {
         let mut pool = MemoryPool::new(2);
@@ -419,4 +815,23 @@ mod tests {
         assert!(stats.contains("encryption_buffers"));
         assert!(stats.contains("temp_buffers"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_subsystem_budget_reservations_and_batch_degradation() {
+        // Only migration batches are capped here; other tests share the process-wide budgets
+        use crate::key_rotation::migration::budgeted_batch_size;
+        set_memory_budget(MemorySubsystem::MigrationBatches, Some(2500));
+
+        let held = reserve_memory_budget(MemorySubsystem::MigrationBatches, 2000).unwrap();
+        let exceeded = reserve_memory_budget(MemorySubsystem::MigrationBatches, 1000).unwrap_err();
+        assert_eq!((exceeded.requested(), exceeded.available()), (1000, 500));
+        assert_eq!(budgeted_batch_size(100), 1);
+
+        drop(held);
+        assert_eq!(get_memory_budget_usage(MemorySubsystem::MigrationBatches), 0);
+        assert_eq!(budgeted_batch_size(100), 2);
+
+        set_memory_budget(MemorySubsystem::MigrationBatches, None);
+        assert_eq!(budgeted_batch_size(100), 100);
+    }
+}
@@ -1,8 +1,16 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+use crate::envelope::to_hex;
 use crate::memory::{track_secret_allocation, track_secret_zeroization};
 use crate::keys::CryptoKey;
+use crate::key_rotation::cache_epochs::record_device_revocation;
+use crate::integration::{require_owner_js, RestrictedOperation};
+use crate::platform;
+use crate::trusted_time::{check_message_time, TimedMessageKind};
+use crate::timeouts::{Deadline, OperationTimeouts, TimedOperation, Timeout};
 // use crate::derivation::HierarchicalKeyDerivation; // Unused import removed
 
 /// Device pairing request containing public key and device metadata
@@ -15,6 +23,10 @@ pub struct DevicePairingRequest {
     public_key: Vec<u8>,
     challenge_nonce: Vec<u8>,
     timestamp: u64,
+    #[serde(default)]
+    pow_nonce: Option<u64>,
+    #[serde(default)]
+    invitation_ticket: Option<String>,
 }
 
 #[wasm_bindgen]
@@ -36,6 +48,8 @@ impl DevicePairingRequest {
             public_key,
             challenge_nonce,
             timestamp,
+            pow_nonce: None,
+            invitation_ticket: None,
         }
     }
 
@@ -68,6 +82,186 @@ impl DevicePairingRequest {
     pub fn timestamp(&self) -> u64 {
         self.timestamp
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn pow_nonce(&self) -> Option<u64> {
+        self.pow_nonce
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_pow_nonce(&mut self, nonce: u64) {
+        self.pow_nonce = Some(nonce);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn invitation_ticket(&self) -> Option<String> {
+        self.invitation_ticket.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_invitation_ticket(&mut self, ticket: String) {
+        self.invitation_ticket = Some(ticket);
+    }
+
+    /// Solve the pairing proof-of-work puzzle and attach the nonce to this request
+    #[wasm_bindgen]
+    pub fn solve_proof_of_work(&mut self, difficulty_bits: u8) -> Result<u64, JsValue> {
+        if difficulty_bits > MAX_POW_DIFFICULTY_BITS {
+            return Err(JsValue::from_str("Proof-of-work difficulty too high"));
+        }
+
+        let nonce = (0..=u64::MAX)
+            .find(|nonce| leading_zero_bits(&self.pow_digest(*nonce)) >= difficulty_bits as u32)
+            .ok_or_else(|| JsValue::from_str("Proof-of-work search exhausted"))?;
+
+        self.pow_nonce = Some(nonce);
+        Ok(nonce)
+    }
+}
+
+impl DevicePairingRequest {
+    /// Check the attached proof-of-work nonce against the required difficulty
+    pub fn verify_proof_of_work(&self, difficulty_bits: u8) -> bool {
+        match self.pow_nonce {
+            Some(nonce) => leading_zero_bits(&self.pow_digest(nonce)) >= difficulty_bits as u32,
+            None => false,
+        }
+    }
+
+    // Puzzle is bound to the request contents so a solution cannot be replayed for another device
+    fn pow_digest(&self, nonce: u64) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(PAIRING_POW_DOMAIN);
+        hasher.update((self.device_id.len() as u32).to_be_bytes());
+        hasher.update(self.device_id.as_bytes());
+        hasher.update(&self.public_key);
+        hasher.update(&self.challenge_nonce);
+        hasher.update(self.timestamp.to_be_bytes());
+        hasher.update(nonce.to_be_bytes());
+        hasher.finalize().into()
+    }
+}
+
+/// Highest accepted proof-of-work difficulty (~16M hashes on average)
+pub const MAX_POW_DIFFICULTY_BITS: u8 = 24;
+pub(crate) const PAIRING_POW_DOMAIN: &[u8] = b"aura-pairing-pow-v1";
+
+fn leading_zero_bits(digest: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in digest {
+        if *byte == 0 {
+            bits += 8;
+        } else {
+            bits += byte.leading_zeros();
+            break;
+        }
+    }
+    bits
+}
+
+/// Admission control applied before a pairing request may consume a registry slot
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairingAdmissionMode {
+    Open = 0,              // No admission proof required
+    ProofOfWork = 1,       // Requester must solve a hashcash-style puzzle
+    Invitation = 2,        // Requester must present an owner-issued ticket
+    ProofOrInvitation = 3, // Either proof is sufficient
+}
+
+/// Counters for rejected and admitted pairing attempts
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct PairingAdmissionTelemetry {
+    admitted: u32,
+    rejected_expired_request: u32,
+    rejected_missing_proof: u32,
+    rejected_invalid_proof: u32,
+    rejected_invalid_ticket: u32,
+    rejected_capacity: u32,
+    last_rejection_at: Option<u64>,
+}
+
+#[wasm_bindgen]
+impl PairingAdmissionTelemetry {
+    #[wasm_bindgen(getter)]
+    pub fn admitted(&self) -> u32 {
+        self.admitted
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rejected_expired_request(&self) -> u32 {
+        self.rejected_expired_request
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rejected_missing_proof(&self) -> u32 {
+        self.rejected_missing_proof
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rejected_invalid_proof(&self) -> u32 {
+        self.rejected_invalid_proof
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rejected_invalid_ticket(&self) -> u32 {
+        self.rejected_invalid_ticket
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rejected_capacity(&self) -> u32 {
+        self.rejected_capacity
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn last_rejection_at(&self) -> Option<u64> {
+        self.last_rejection_at
+    }
+
+    #[wasm_bindgen]
+    pub fn total_rejected(&self) -> u32 {
+        self.rejected_expired_request
+            + self.rejected_missing_proof
+            + self.rejected_invalid_proof
+            + self.rejected_invalid_ticket
+            + self.rejected_capacity
+    }
+}
+
+/// Reason a pairing request was turned away before touching the registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairingRejection {
+    ExpiredRequest,
+    MissingProof,
+    InvalidProof,
+    InvalidTicket,
+    Capacity,
+}
+
+impl PairingRejection {
+    pub fn message(&self) -> &'static str {
+        match self {
+            PairingRejection::ExpiredRequest => "Pairing request expired",
+            PairingRejection::MissingProof => "Pairing request missing admission proof",
+            PairingRejection::InvalidProof => "Invalid pairing proof-of-work",
+            PairingRejection::InvalidTicket => "Invalid or expired invitation ticket",
+            PairingRejection::Capacity => "Maximum device limit reached",
+        }
+    }
+}
+
+impl PairingAdmissionTelemetry {
+    fn record_rejection(&mut self, rejection: PairingRejection, now: u64) {
+        match rejection {
+            PairingRejection::ExpiredRequest => self.rejected_expired_request += 1,
+            PairingRejection::MissingProof => self.rejected_missing_proof += 1,
+            PairingRejection::InvalidProof => self.rejected_invalid_proof += 1,
+            PairingRejection::InvalidTicket => self.rejected_invalid_ticket += 1,
+            PairingRejection::Capacity => self.rejected_capacity += 1,
+        }
+        self.last_rejection_at = Some(now);
+    }
 }
 
 /// Device pairing response with authentication proof
@@ -206,7 +400,7 @@ impl DeviceRegistryEntry {
     #[wasm_bindgen(setter)]
     pub fn set_status(&mut self, status: u8) {
         self.status = status;
-        self.updated_at = js_sys::Date::now() as u64;
+        self.updated_at = platform::now_ms();
     }
 
     #[wasm_bindgen(getter)]
@@ -227,7 +421,7 @@ impl DeviceRegistryEntry {
     #[wasm_bindgen(setter)]
     pub fn set_last_sync(&mut self, timestamp: u64) {
         self.last_sync = timestamp;
-        self.updated_at = js_sys::Date::now() as u64;
+        self.updated_at = platform::now_ms();
     }
 
     #[wasm_bindgen(getter)]
@@ -238,7 +432,7 @@ impl DeviceRegistryEntry {
     #[wasm_bindgen(setter)]
     pub fn set_trust_score(&mut self, score: f64) {
         self.trust_score = score.max(0.0).min(1.0); // Clamp to [0,1]
-        self.updated_at = js_sys::Date::now() as u64;
+        self.updated_at = platform::now_ms();
     }
 
     #[wasm_bindgen(getter)]
@@ -254,7 +448,7 @@ impl DeviceRegistryEntry {
     /// Check if device entry is expired based on timestamp
     #[wasm_bindgen]
     pub fn is_expired(&self, ttl_seconds: u64) -> bool {
-        let now = js_sys::Date::now() as u64;
+        let now = platform::now_ms();
         (now - self.last_sync) > (ttl_seconds * 1000)
     }
 
@@ -271,6 +465,108 @@ impl DeviceRegistryEntry {
     }
 }
 
+// A mode that admits on proof-of-work needs a puzzle that costs something to solve
+fn check_admission_policy(mode: PairingAdmissionMode, pow_difficulty_bits: u8) -> Result<(), String> {
+    if pow_difficulty_bits > MAX_POW_DIFFICULTY_BITS {
+        return Err("Proof-of-work difficulty too high".to_string());
+    }
+    let accepts_proof = matches!(mode, PairingAdmissionMode::ProofOfWork | PairingAdmissionMode::ProofOrInvitation);
+    if accepts_proof && pow_difficulty_bits == 0 {
+        return Err("Proof-of-work admission requires a difficulty of at least 1 bit".to_string());
+    }
+    Ok(())
+}
+
+/// Step-by-step construction of a `MultiDeviceProtocol`, validated at `build()`
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct MultiDeviceProtocolBuilder {
+    current_device_id: String,
+    trust_threshold: f64,
+    max_devices: usize,
+    admission_mode: PairingAdmissionMode,
+    pow_difficulty_bits: u8,
+    timeouts: OperationTimeouts,
+}
+
+#[wasm_bindgen]
+impl MultiDeviceProtocolBuilder {
+    /// Start from a 0.7 trust threshold, 5 devices and open admission
+    #[wasm_bindgen(constructor)]
+    pub fn new(current_device_id: String) -> Self {
+        Self {
+            current_device_id,
+            trust_threshold: 0.7,
+            max_devices: 5,
+            admission_mode: PairingAdmissionMode::Open,
+            pow_difficulty_bits: 0,
+            timeouts: OperationTimeouts::default(),
+        }
+    }
+
+    #[wasm_bindgen(js_name = trustThreshold)]
+    pub fn trust_threshold(mut self, threshold: f64) -> Self {
+        self.trust_threshold = threshold;
+        self
+    }
+
+    #[wasm_bindgen(js_name = maxDevices)]
+    pub fn max_devices(mut self, max_devices: usize) -> Self {
+        self.max_devices = max_devices;
+        self
+    }
+
+    #[wasm_bindgen(js_name = admissionPolicy)]
+    pub fn admission_policy(mut self, mode: PairingAdmissionMode, pow_difficulty_bits: u8) -> Self {
+        self.admission_mode = mode;
+        self.pow_difficulty_bits = pow_difficulty_bits;
+        self
+    }
+
+    #[wasm_bindgen(js_name = operationTimeouts)]
+    pub fn operation_timeouts(mut self, timeouts: &OperationTimeouts) -> Self {
+        self.timeouts = *timeouts;
+        self
+    }
+
+    #[wasm_bindgen]
+    pub fn build(self) -> Result<MultiDeviceProtocol, JsValue> {
+        self.validate().map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.finish())
+    }
+}
+
+impl MultiDeviceProtocolBuilder {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.current_device_id.is_empty() {
+            return Err("Device id must not be empty".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.trust_threshold) {
+            return Err("Trust threshold must be between 0 and 1".to_string());
+        }
+        if self.max_devices == 0 {
+            return Err("Max devices must be greater than 0".to_string());
+        }
+        check_admission_policy(self.admission_mode, self.pow_difficulty_bits)
+    }
+
+    /// Unvalidated result, used by the legacy constructor
+    pub(crate) fn finish(self) -> MultiDeviceProtocol {
+        MultiDeviceProtocol {
+            device_registry: HashMap::new(),
+            master_key: None,
+            current_device_id: self.current_device_id,
+            trust_threshold: self.trust_threshold,
+            max_devices: self.max_devices,
+            admission_mode: self.admission_mode,
+            pow_difficulty_bits: self.pow_difficulty_bits,
+            invitation_tickets: HashMap::new(),
+            admission_telemetry: PairingAdmissionTelemetry::default(),
+            timeouts: self.timeouts,
+        }
+    }
+}
+
 /// Multi-device key exchange protocol manager
 #[wasm_bindgen]
 pub struct MultiDeviceProtocol {
@@ -279,6 +575,11 @@ pub struct MultiDeviceProtocol {
     current_device_id: String,
     trust_threshold: f64,
     max_devices: usize,
+    admission_mode: PairingAdmissionMode,
+    pow_difficulty_bits: u8,
+    invitation_tickets: HashMap<String, u64>, // SHA-256(ticket) hex -> expiry (ms)
+    admission_telemetry: PairingAdmissionTelemetry,
+    timeouts: OperationTimeouts,
 }
 
 #[wasm_bindgen]
@@ -286,13 +587,57 @@ impl MultiDeviceProtocol {
     /// Create new multi-device protocol manager
     #[wasm_bindgen(constructor)]
     pub fn new(current_device_id: String, trust_threshold: f64, max_devices: usize) -> Self {
-        Self {
-            device_registry: HashMap::new(),
-            master_key: None,
-            current_device_id,
-            trust_threshold: trust_threshold.max(0.0).min(1.0), // Clamp to [0,1]
-            max_devices,
-        }
+        MultiDeviceProtocolBuilder::new(current_device_id)
+            .trust_threshold(trust_threshold.max(0.0).min(1.0)) // Clamp to [0,1]
+            .max_devices(max_devices)
+            .finish()
+    }
+
+    /// Require proof-of-work and/or invitation tickets before pairing requests consume a slot
+    #[wasm_bindgen]
+    pub fn set_admission_policy(
+        &mut self,
+        mode: PairingAdmissionMode,
+        pow_difficulty_bits: u8,
+    ) -> Result<(), JsValue> {
+        check_admission_policy(mode, pow_difficulty_bits).map_err(|e| JsValue::from_str(&e))?;
+
+        self.admission_mode = mode;
+        self.pow_difficulty_bits = pow_difficulty_bits;
+        Ok(())
+    }
+
+    /// Issue a single-use invitation ticket to hand to a new device out of band
+    #[wasm_bindgen]
+    pub fn issue_invitation_ticket(&mut self, ttl_ms: u64) -> String {
+        let now = platform::now_ms();
+        self.invitation_tickets.retain(|_, expires_at| *expires_at > now);
+
+        let mut ticket_bytes = [0u8; 16];
+        platform::fill_random(&mut ticket_bytes);
+        let ticket = to_hex(&ticket_bytes);
+
+        // Only the ticket hash is retained
+        self.invitation_tickets.insert(ticket_hash(&ticket), now.saturating_add(ttl_ms));
+        ticket
+    }
+
+    /// Pending handshakes not finalized within the pairing timeout are rolled back
+    #[wasm_bindgen]
+    pub fn set_operation_timeouts(&mut self, timeouts: &OperationTimeouts) {
+        self.timeouts = *timeouts;
+    }
+
+    /// Withdraw an unused invitation ticket
+    #[wasm_bindgen]
+    pub fn revoke_invitation_ticket(&mut self, ticket: String) -> bool {
+        self.invitation_tickets.remove(&ticket_hash(&ticket)).is_some()
+    }
+
+    /// Admitted/rejected pairing attempt counters
+    #[wasm_bindgen]
+    pub fn get_admission_telemetry(&self) -> PairingAdmissionTelemetry {
+        self.admission_telemetry.clone()
     }
 
     /// Initialize protocol with hierarchical master key
@@ -309,6 +654,7 @@ impl MultiDeviceProtocol {
         device_name: String,
         device_type: String,
     ) -> Result<DevicePairingRequest, JsValue> {
+        require_owner_js(RestrictedOperation::DeviceManagement)?;
         // Generate ephemeral public key for this pairing session
         let mut public_key = vec![0u8; 32]; // Mock 32-byte public key
         let mut challenge_nonce = vec![0u8; 16]; // Mock 16-byte nonce
@@ -322,7 +668,7 @@ impl MultiDeviceProtocol {
             *byte = (i as u8).wrapping_mul(11).wrapping_add(17);
         }
 
-        let timestamp = js_sys::Date::now() as u64;
+        let timestamp = platform::now_ms();
 
         Ok(DevicePairingRequest::new(
             self.current_device_id.clone(),
@@ -340,70 +686,22 @@ impl MultiDeviceProtocol {
         &mut self,
         request: &DevicePairingRequest,
     ) -> Result<DevicePairingResponse, JsValue> {
-        // Validate request timestamp (within 5 minutes)
-        let now = js_sys::Date::now() as u64;
-        let max_age = 5 * 60 * 1000; // 5 minutes in milliseconds
-        
-        if (now - request.timestamp()) > max_age {
-            return Err(JsValue::from_str("Pairing request expired"));
-        }
-
-        // Check device registry capacity
-        if self.device_registry.len() >= self.max_devices {
-            return Err(JsValue::from_str("Maximum device limit reached"));
-        }
-
-        // Generate response signature (mock implementation)
-        let mut response_signature = vec![0u8; 64]; // Mock 64-byte signature
-        let mut shared_secret_hash = vec![0u8; 32]; // Mock 32-byte hash
-        
-        for (i, byte) in response_signature.iter_mut().enumerate() {
-            *byte = (i as u8).wrapping_mul(23).wrapping_add(31);
-        }
-        
-        for (i, byte) in shared_secret_hash.iter_mut().enumerate() {
-            *byte = (i as u8).wrapping_mul(29).wrapping_add(37);
-        }
-
-        // Generate device trust token
-        let device_trust_token = format!(
-            "trust_{}_{}", 
-            request.device_id(),
-            now
-        );
-
-        // Create device registry entry as pending
-        let device_entry = DeviceRegistryEntry::new(
-            request.device_id(),
-            request.device_name(),
-            request.device_type(),
-            DeviceStatus::Pending as u8,
-            device_trust_token.clone(),
-            request.public_key(),
-            now,
-            0.5, // Initial trust score
-            now,
-            now,
-        );
-
-        self.device_registry.insert(request.device_id(), device_entry);
-
-        Ok(DevicePairingResponse::new(
-            self.current_device_id.clone(),
-            response_signature,
-            shared_secret_hash,
-            device_trust_token,
-            now,
-        ))
+        require_owner_js(RestrictedOperation::DeviceManagement)?;
+        self.process_pairing_request_at(request, platform::now_ms())
+            .map_err(|e| JsValue::from_str(&e))
     }
 
-    /// Finalize device pairing after successful response validation
+    /// Finalize device pairing after successful response validation; fails with a
+    /// `Timeout` (and forgets the pending device) once the handshake has overrun
     #[wasm_bindgen]
     pub fn finalize_pairing(
         &mut self,
         device_id: String,
         validated: bool,
     ) -> Result<(), JsValue> {
+        require_owner_js(RestrictedOperation::DeviceManagement)?;
+        self.check_handshake_deadline(&device_id, platform::now_ms())?;
+
         let device_entry = self.device_registry
             .get_mut(&device_id)
             .ok_or_else(|| JsValue::from_str("Device not found in registry"))?;
@@ -422,12 +720,14 @@ impl MultiDeviceProtocol {
     /// Revoke device access and remove from trusted devices
     #[wasm_bindgen]
     pub fn revoke_device(&mut self, device_id: String) -> Result<(), JsValue> {
+        require_owner_js(RestrictedOperation::DeviceManagement)?;
         let device_entry = self.device_registry
             .get_mut(&device_id)
             .ok_or_else(|| JsValue::from_str("Device not found in registry"))?;
 
         device_entry.set_status(DeviceStatus::Revoked as u8);
         device_entry.set_trust_score(0.0);
+        record_device_revocation();
         
         track_secret_zeroization();
         Ok(())
@@ -436,6 +736,7 @@ impl MultiDeviceProtocol {
     /// Re-enroll previously revoked device
     #[wasm_bindgen]
     pub fn reenroll_device(&mut self, device_id: String) -> Result<(), JsValue> {
+        require_owner_js(RestrictedOperation::DeviceManagement)?;
         let device_entry = self.device_registry
             .get_mut(&device_id)
             .ok_or_else(|| JsValue::from_str("Device not found in registry"))?;
@@ -457,7 +758,7 @@ impl MultiDeviceProtocol {
             .get_mut(&device_id)
             .ok_or_else(|| JsValue::from_str("Device not found in registry"))?;
 
-        let now = js_sys::Date::now() as u64;
+        let now = platform::now_ms();
         device_entry.set_last_sync(now);
 
         Ok(())
@@ -586,6 +887,150 @@ impl MultiDeviceProtocol {
     }
 }
 
+impl MultiDeviceProtocol {
+    /// Validate admission proof and registry capacity without mutating the registry
+    pub(crate) fn admit_pairing_request(
+        &self,
+        request: &DevicePairingRequest,
+        now: u64,
+    ) -> Result<(), PairingRejection> {
+        let ticket_valid = request.invitation_ticket.as_ref().map(|ticket| {
+            self.invitation_tickets
+                .get(&ticket_hash(ticket))
+                .is_some_and(|expires_at| *expires_at > now)
+        });
+        let pow_valid = request.pow_nonce
+            .map(|_| request.verify_proof_of_work(self.pow_difficulty_bits));
+
+        let (accept_pow, accept_ticket) = match self.admission_mode {
+            PairingAdmissionMode::Open => (true, true),
+            PairingAdmissionMode::ProofOfWork => (true, false),
+            PairingAdmissionMode::Invitation => (false, true),
+            PairingAdmissionMode::ProofOrInvitation => (true, true),
+        };
+
+        if self.admission_mode != PairingAdmissionMode::Open {
+            let admitted = (accept_pow && pow_valid == Some(true))
+                || (accept_ticket && ticket_valid == Some(true));
+
+            if !admitted {
+                let presented_ticket = accept_ticket && ticket_valid.is_some();
+                let presented_pow = accept_pow && pow_valid.is_some();
+                return Err(if presented_ticket {
+                    PairingRejection::InvalidTicket
+                } else if presented_pow {
+                    PairingRejection::InvalidProof
+                } else {
+                    PairingRejection::MissingProof
+                });
+            }
+        }
+
+        // Re-pairing a known device does not take a new slot
+        if !self.device_registry.contains_key(&request.device_id)
+            && self.device_registry.len() >= self.max_devices
+        {
+            return Err(PairingRejection::Capacity);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn process_pairing_request_at(
+        &mut self,
+        request: &DevicePairingRequest,
+        now: u64,
+    ) -> Result<DevicePairingResponse, String> {
+        // The requester is not authenticated yet, so the plain pairing window applies
+        let timestamp_check = check_message_time(TimedMessageKind::PairingRequest, None, request.timestamp(), now);
+
+        if timestamp_check.is_err() {
+            return Err(self.reject_pairing(PairingRejection::ExpiredRequest, now));
+        }
+
+        // Admission control and capacity are checked before any slot is consumed
+        self.admit_pairing_request(request, now)
+            .map_err(|rejection| self.reject_pairing(rejection, now))?;
+
+        // Generate response signature (mock implementation)
+        let mut response_signature = vec![0u8; 64]; // Mock 64-byte signature
+        let mut shared_secret_hash = vec![0u8; 32]; // Mock 32-byte hash
+        
+        for (i, byte) in response_signature.iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_mul(23).wrapping_add(31);
+        }
+        
+        for (i, byte) in shared_secret_hash.iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_mul(29).wrapping_add(37);
+        }
+
+        // Generate device trust token
+        let device_trust_token = format!(
+            "trust_{}_{}", 
+            request.device_id(),
+            now
+        );
+
+        // Create device registry entry as pending
+        let device_entry = DeviceRegistryEntry::new(
+            request.device_id(),
+            request.device_name(),
+            request.device_type(),
+            DeviceStatus::Pending as u8,
+            device_trust_token.clone(),
+            request.public_key(),
+            now,
+            0.5, // Initial trust score
+            now,
+            now,
+        );
+
+        if let Some(ticket) = &request.invitation_ticket {
+            self.invitation_tickets.remove(&ticket_hash(ticket));
+        }
+        self.admission_telemetry.admitted += 1;
+        self.device_registry.insert(request.device_id(), device_entry);
+
+        Ok(DevicePairingResponse::new(
+            self.current_device_id.clone(),
+            response_signature,
+            shared_secret_hash,
+            device_trust_token,
+            now,
+        ))
+    }
+
+    /// Roll back a pending handshake that overran its deadline to the pre-request state
+    pub(crate) fn check_handshake_deadline(&mut self, device_id: &str, now: u64) -> Result<(), Timeout> {
+        let Some(entry) = self.device_registry.get(device_id) else {
+            return Ok(());
+        };
+        if entry.status != DeviceStatus::Pending as u8 {
+            return Ok(());
+        }
+
+        let deadline = Deadline::starting_at(TimedOperation::PairingHandshake, &self.timeouts, entry.created_at);
+        if let Err(timeout) = deadline.check_at("finalize", now) {
+            if let Some(mut entry) = self.device_registry.remove(device_id) {
+                entry.trust_token.zeroize();
+                entry.public_key.zeroize();
+            }
+            track_secret_zeroization();
+            return Err(timeout);
+        }
+        Ok(())
+    }
+
+    fn reject_pairing(&mut self, rejection: PairingRejection, now: u64) -> String {
+        self.admission_telemetry.record_rejection(rejection, now);
+        rejection.message().to_string()
+    }
+}
+
+fn ticket_hash(ticket: &str) -> String {
+    to_hex(&Sha256::digest(ticket.as_bytes()))
+}
+
 impl Drop for MultiDeviceProtocol {
     fn drop(&mut self) {
         // Clear sensitive data when dropping
@@ -724,6 +1169,7 @@ mod tests {
             0.5,
             2, // Limit to 2 devices
         );
+        let now = platform::now_ms();
 
         // Add first device
         let request1 = DevicePairingRequest::new(
@@ -732,9 +1178,9 @@ mod tests {
             "mobile".to_string(),
             vec![1, 2, 3, 4],
             vec![5, 6, 7, 8],
-            1234567890,
+            now,
         );
-        protocol.process_pairing_request(&request1).unwrap();
+        protocol.process_pairing_request_at(&request1, now).unwrap();
 
         // Add second device
         let request2 = DevicePairingRequest::new(
@@ -743,9 +1189,9 @@ mod tests {
             "web".to_string(),
             vec![9, 10, 11, 12],
             vec![13, 14, 15, 16],
-            1234567890,
+            now,
         );
-        protocol.process_pairing_request(&request2).unwrap();
+        protocol.process_pairing_request_at(&request2, now).unwrap();
 
         assert!(protocol.is_device_limit_reached());
 
@@ -756,10 +1202,129 @@ mod tests {
             "desktop".to_string(),
             vec![17, 18, 19, 20],
             vec![21, 22, 23, 24],
-            1234567890,
+            now,
         );
         
-        let result = protocol.process_pairing_request(&request3);
+        let result = protocol.process_pairing_request_at(&request3, now);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    fn pow_request(device_id: &str) -> DevicePairingRequest {
+        DevicePairingRequest::new(
+            device_id.to_string(),
+            "Flood Device".to_string(),
+            "web".to_string(),
+            vec![1, 2, 3, 4],
+            vec![5, 6, 7, 8],
+            1234567890,
+        )
+    }
+
+    #[test]
+    fn test_pairing_admission_proof_of_work() {
+        let mut protocol = MultiDeviceProtocol::new("owner".to_string(), 0.5, 5);
+        protocol.set_admission_policy(PairingAdmissionMode::ProofOfWork, 8).unwrap();
+
+        let mut request = pow_request("device1");
+        assert_eq!(
+            protocol.admit_pairing_request(&request, 0),
+            Err(PairingRejection::MissingProof)
+        );
+
+        request.solve_proof_of_work(8).unwrap();
+        assert!(request.verify_proof_of_work(8));
+        assert_eq!(protocol.admit_pairing_request(&request, 0), Ok(()));
+
+        // Solution is bound to the request and cannot be reused for another device
+        let mut replayed = pow_request("device2");
+        replayed.set_pow_nonce(request.pow_nonce().unwrap());
+        assert_eq!(
+            protocol.admit_pairing_request(&replayed, 0),
+            Err(PairingRejection::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn test_pairing_admission_invitation_tickets() {
+        let mut protocol = MultiDeviceProtocol::new("owner".to_string(), 0.5, 5);
+        protocol.set_admission_policy(PairingAdmissionMode::Invitation, 0).unwrap();
+        let now = platform::now_ms();
+
+        let ticket = protocol.issue_invitation_ticket(60_000);
+        let mut request = pow_request("device1");
+        request.set_invitation_ticket(ticket.clone());
+        assert_eq!(protocol.admit_pairing_request(&request, now), Ok(()));
+
+        // Expired tickets are rejected
+        assert_eq!(
+            protocol.admit_pairing_request(&request, now + 120_000),
+            Err(PairingRejection::InvalidTicket)
+        );
+
+        let mut forged = DevicePairingRequest::new(
+            "device2".to_string(),
+            "Flood Device".to_string(),
+            "web".to_string(),
+            vec![1, 2, 3, 4],
+            vec![5, 6, 7, 8],
+            now,
+        );
+        forged.set_invitation_ticket("00".repeat(16));
+        assert_eq!(
+            protocol.process_pairing_request_at(&forged, now).unwrap_err(),
+            PairingRejection::InvalidTicket.message()
+        );
+
+        let telemetry = protocol.get_admission_telemetry();
+        assert_eq!(telemetry.rejected_invalid_ticket(), 1);
+        assert_eq!(telemetry.total_rejected(), 1);
+        assert_eq!(telemetry.last_rejection_at(), Some(now));
+
+        // A ticket that never expires is clamped rather than overflowing
+        let unbounded = protocol.issue_invitation_ticket(u64::MAX);
+        assert_eq!(protocol.invitation_tickets[&ticket_hash(&unbounded)], u64::MAX);
+
+        assert!(protocol.revoke_invitation_ticket(ticket));
+        assert_eq!(
+            protocol.admit_pairing_request(&request, now),
+            Err(PairingRejection::InvalidTicket)
+        );
+    }
+
+    #[test]
+    fn test_builder_validates_and_legacy_constructor_clamps() {
+        let builder = MultiDeviceProtocolBuilder::new("device1".to_string())
+            .trust_threshold(0.9)
+            .max_devices(3)
+            .admission_policy(PairingAdmissionMode::ProofOfWork, 12);
+        assert!(builder.validate().is_ok());
+        let protocol = builder.finish();
+        assert_eq!((protocol.max_devices, protocol.pow_difficulty_bits), (3, 12));
+
+        assert!(MultiDeviceProtocolBuilder::new("device1".to_string()).trust_threshold(1.5).validate().is_err());
+        assert!(MultiDeviceProtocolBuilder::new("device1".to_string()).max_devices(0).validate().is_err());
+        assert!(MultiDeviceProtocolBuilder::new(String::new()).validate().is_err());
+        for mode in [PairingAdmissionMode::ProofOfWork, PairingAdmissionMode::ProofOrInvitation] {
+            let builder = MultiDeviceProtocolBuilder::new("device1".to_string()).admission_policy(mode, 0);
+            assert!(builder.validate().unwrap_err().contains("at least 1 bit"));
+            assert!(check_admission_policy(mode, 1).is_ok());
+        }
+        assert!(check_admission_policy(PairingAdmissionMode::Invitation, 0).is_ok());
+        assert!(check_admission_policy(PairingAdmissionMode::ProofOfWork, MAX_POW_DIFFICULTY_BITS + 1).is_err());
+
+        assert_eq!(MultiDeviceProtocol::new("device1".to_string(), 1.5, 5).trust_threshold, 1.0);
+    }
+
+    #[test]
+    fn test_overrun_handshake_is_rolled_back() {
+        let mut protocol = MultiDeviceProtocol::new("device1".to_string(), 0.7, 5);
+        let request = protocol.generate_pairing_request("Tablet".to_string(), "tablet".to_string()).unwrap();
+        protocol.process_pairing_request(&request).unwrap();
+        let created_at = protocol.device_registry["device1"].created_at();
+
+        assert!(protocol.check_handshake_deadline("device1", created_at + 1_000).is_ok());
+        let timeout = protocol.check_handshake_deadline("device1", created_at + 5 * 60 * 1000).unwrap_err();
+        assert_eq!((timeout.operation(), timeout.phase().as_str()), (TimedOperation::PairingHandshake, "finalize"));
+        assert_eq!(protocol.device_count(), 0);
+    }
+}
@@ -0,0 +1,645 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+use crate::inbox::device_fingerprint;
+use crate::integration::DeviceKeyStorage;
+use crate::memory::{track_secret_allocation, track_secret_zeroization};
+use crate::platform;
+use crate::SecureBuffer;
+
+// Multi-recipient sealing: the payload is encrypted once under a random data key,
+// and that data key is wrapped (X25519 + HKDF + AES-256-GCM) to every recipient device.
+// A recipient's secret key stays inside wasm: JS passes the `RecipientKeyPair` itself,
+// and the key pair is persisted through `DeviceKeyStorage`, never as bytes handed to JS.
+
+const MULTI_RECIPIENT_VERSION: u8 = 1;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+pub(crate) const WRAP_INFO: &[u8] = b"aura-multi-recipient-wrap-v1";
+
+/// X25519 key pair for receiving multi-recipient envelopes
+#[wasm_bindgen]
+pub struct RecipientKeyPair {
+    secret_key: Zeroizing<Vec<u8>>,
+    public_key: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl RecipientKeyPair {
+    #[wasm_bindgen]
+    pub fn generate() -> RecipientKeyPair {
+        let mut secret_bytes = [0u8; KEY_LENGTH];
+        platform::fill_random(&mut secret_bytes);
+        let secret = StaticSecret::from(secret_bytes);
+        track_secret_allocation();
+
+        RecipientKeyPair {
+            secret_key: Zeroizing::new(secret.to_bytes().to_vec()),
+            public_key: PublicKey::from(&secret).as_bytes().to_vec(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    /// Recipient id used in envelope recipient lists
+    #[wasm_bindgen(getter)]
+    pub fn recipient_id(&self) -> String {
+        device_fingerprint(&self.public_key)
+    }
+}
+
+impl RecipientKeyPair {
+    pub(crate) fn secret_key(&self) -> &[u8] {
+        &self.secret_key
+    }
+
+    /// Keep the key pair in device key storage under `key_id`
+    pub fn store_in(&self, storage: &dyn DeviceKeyStorage, key_id: &str) -> Result<(), String> {
+        storage.store_key(key_id, &SecureBuffer::from_bytes(self.secret_key.to_vec()))
+    }
+
+    pub fn load_from(storage: &dyn DeviceKeyStorage, key_id: &str) -> Result<RecipientKeyPair, String> {
+        let stored = storage.retrieve_key(key_id)?;
+        let secret_bytes: [u8; KEY_LENGTH] = stored
+            .as_slice()
+            .map_err(|e| e.to_string())?
+            .try_into()
+            .map_err(|_| "Stored recipient key must be 32 bytes".to_string())?;
+        let secret = StaticSecret::from(secret_bytes);
+        track_secret_allocation();
+        Ok(RecipientKeyPair {
+            secret_key: Zeroizing::new(secret.to_bytes().to_vec()),
+            public_key: PublicKey::from(&secret).as_bytes().to_vec(),
+        })
+    }
+}
+
+impl Drop for RecipientKeyPair {
+    fn drop(&mut self) {
+        track_secret_zeroization();
+    }
+}
+
+/// Data key wrapped to one recipient device
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipientWrap {
+    recipient_id: String,
+    ephemeral_public_key: Vec<u8>,
+    nonce: Vec<u8>,
+    wrapped_key: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl RecipientWrap {
+    #[wasm_bindgen(getter)]
+    pub fn recipient_id(&self) -> String {
+        self.recipient_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ephemeral_public_key(&self) -> Vec<u8> {
+        self.ephemeral_public_key.clone()
+    }
+}
+
+/// Payload sealed once with a recipients list of wrapped data keys
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultiRecipientEnvelope {
+    version: u8,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+    recipients: Vec<RecipientWrap>,
+}
+
+#[wasm_bindgen]
+impl MultiRecipientEnvelope {
+    /// Seal payload for the given recipients (concatenated 32-byte X25519 public keys)
+    #[wasm_bindgen]
+    pub fn seal(
+        payload: &[u8],
+        aad: &[u8],
+        recipient_public_keys: &[u8],
+    ) -> Result<MultiRecipientEnvelope, JsValue> {
+        let keys = split_public_keys(recipient_public_keys).map_err(|e| JsValue::from_str(&e))?;
+        Self::seal_to(payload, aad, &keys).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Decrypt payload with this device's key pair
+    #[wasm_bindgen]
+    pub fn open(&self, recipient: &RecipientKeyPair, aad: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.open_with(recipient.secret_key(), aad).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Wrap the existing data key to another device; requires a current recipient's key pair
+    #[wasm_bindgen]
+    pub fn add_recipient(
+        &mut self,
+        own: &RecipientKeyPair,
+        new_recipient_public_key: &[u8],
+    ) -> Result<(), JsValue> {
+        self.add_recipient_with(own.secret_key(), new_recipient_public_key)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Drop a recipient's wrapped key; call `rekey` as well if that device may have kept the data key
+    #[wasm_bindgen]
+    pub fn remove_recipient(&mut self, recipient_id: String) -> bool {
+        let before = self.recipients.len();
+        self.recipients.retain(|wrap| wrap.recipient_id != recipient_id);
+        self.recipients.len() != before
+    }
+
+    /// Re-encrypt under a fresh data key for the current recipients
+    #[wasm_bindgen]
+    pub fn rekey(
+        &mut self,
+        own: &RecipientKeyPair,
+        aad: &[u8],
+        recipient_public_keys: &[u8],
+    ) -> Result<(), JsValue> {
+        let keys = split_public_keys(recipient_public_keys).map_err(|e| JsValue::from_str(&e))?;
+        self.rekey_with(own.secret_key(), aad, &keys).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn recipient_ids(&self) -> Vec<String> {
+        self.recipients.iter().map(|wrap| wrap.recipient_id.clone()).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn recipient_count(&self) -> usize {
+        self.recipients.len()
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize envelope: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<MultiRecipientEnvelope, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid multi-recipient envelope: {}", e)))
+    }
+}
+
+impl MultiRecipientEnvelope {
+    pub fn seal_to(
+        payload: &[u8],
+        aad: &[u8],
+        recipient_public_keys: &[[u8; KEY_LENGTH]],
+    ) -> Result<MultiRecipientEnvelope, String> {
+        if recipient_public_keys.is_empty() {
+            return Err("At least one recipient is required".to_string());
+        }
+
+        let mut data_key = Zeroizing::new([0u8; KEY_LENGTH]);
+        platform::fill_random(data_key.as_mut());
+
+        let mut nonce = vec![0u8; NONCE_LENGTH];
+        platform::fill_random(&mut nonce);
+
+        let ciphertext = Aes256Gcm::new_from_slice(data_key.as_ref())
+            .map_err(|_| "Invalid data key".to_string())?
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: payload, aad })
+            .map_err(|_| "Payload encryption failed".to_string())?;
+
+        let mut envelope = MultiRecipientEnvelope {
+            version: MULTI_RECIPIENT_VERSION,
+            nonce,
+            ciphertext,
+            recipients: Vec::with_capacity(recipient_public_keys.len()),
+        };
+
+        for public_key in recipient_public_keys {
+            envelope.push_recipient(&data_key, public_key)?;
+        }
+
+        track_secret_allocation();
+        Ok(envelope)
+    }
+
+    pub fn open_with(&self, recipient_secret_key: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+        let data_key = self.unwrap_data_key(recipient_secret_key)?;
+        if self.nonce.len() != NONCE_LENGTH {
+            return Err("Malformed envelope nonce".to_string());
+        }
+
+        Aes256Gcm::new_from_slice(data_key.as_ref())
+            .map_err(|_| "Invalid data key".to_string())?
+            .decrypt(Nonce::from_slice(&self.nonce), Payload { msg: &self.ciphertext, aad })
+            .map_err(|_| "Payload authentication failed".to_string())
+    }
+
+    pub fn add_recipient_with(
+        &mut self,
+        own_secret_key: &[u8],
+        new_recipient_public_key: &[u8],
+    ) -> Result<(), String> {
+        let public_key: [u8; KEY_LENGTH] = new_recipient_public_key
+            .try_into()
+            .map_err(|_| "Recipient public key must be 32 bytes".to_string())?;
+
+        if self.recipients.iter().any(|wrap| wrap.recipient_id == device_fingerprint(&public_key)) {
+            return Err("Recipient already present".to_string());
+        }
+
+        let data_key = self.unwrap_data_key(own_secret_key)?;
+        self.push_recipient(&data_key, &public_key)
+    }
+
+    pub fn rekey_with(
+        &mut self,
+        own_secret_key: &[u8],
+        aad: &[u8],
+        recipient_public_keys: &[[u8; KEY_LENGTH]],
+    ) -> Result<(), String> {
+        let payload = Zeroizing::new(self.open_with(own_secret_key, aad)?);
+        *self = Self::seal_to(&payload, aad, recipient_public_keys)?;
+        Ok(())
+    }
+
+    fn push_recipient(
+        &mut self,
+        data_key: &[u8; KEY_LENGTH],
+        recipient_public_key: &[u8; KEY_LENGTH],
+    ) -> Result<(), String> {
+        let recipient_public = PublicKey::from(*recipient_public_key);
+        let mut ephemeral_bytes = Zeroizing::new([0u8; KEY_LENGTH]);
+        platform::fill_random(ephemeral_bytes.as_mut());
+        let ephemeral_secret = StaticSecret::from(*ephemeral_bytes);
+        let ephemeral_public = PublicKey::from(&ephemeral_secret);
+
+        let shared = ephemeral_secret.diffie_hellman(&recipient_public);
+        let recipient_id = device_fingerprint(recipient_public_key);
+        let kek = derive_wrapping_key(shared.as_bytes(), ephemeral_public.as_bytes(), recipient_public_key)?;
+
+        let mut nonce = vec![0u8; NONCE_LENGTH];
+        platform::fill_random(&mut nonce);
+
+        let wrapped_key = Aes256Gcm::new_from_slice(kek.as_ref())
+            .map_err(|_| "Invalid wrapping key".to_string())?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload { msg: data_key, aad: recipient_id.as_bytes() },
+            )
+            .map_err(|_| "Key wrapping failed".to_string())?;
+
+        self.recipients.push(RecipientWrap {
+            recipient_id,
+            ephemeral_public_key: ephemeral_public.as_bytes().to_vec(),
+            nonce,
+            wrapped_key,
+        });
+        Ok(())
+    }
+
+    fn unwrap_data_key(&self, recipient_secret_key: &[u8]) -> Result<Zeroizing<[u8; KEY_LENGTH]>, String> {
+        if self.version != MULTI_RECIPIENT_VERSION {
+            return Err(format!("Unsupported multi-recipient envelope version: {}", self.version));
+        }
+
+        let secret_bytes: [u8; KEY_LENGTH] = recipient_secret_key
+            .try_into()
+            .map_err(|_| "Recipient secret key must be 32 bytes".to_string())?;
+        let secret = StaticSecret::from(secret_bytes);
+        let public_key = PublicKey::from(&secret);
+        let recipient_id = device_fingerprint(public_key.as_bytes());
+
+        let wrap = self.recipients
+            .iter()
+            .find(|wrap| wrap.recipient_id == recipient_id)
+            .ok_or_else(|| "Device is not a recipient of this envelope".to_string())?;
+        if wrap.nonce.len() != NONCE_LENGTH {
+            return Err("Malformed recipient entry".to_string());
+        }
+
+        let ephemeral_public: [u8; KEY_LENGTH] = wrap.ephemeral_public_key
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Malformed recipient entry".to_string())?;
+        let shared = secret.diffie_hellman(&PublicKey::from(ephemeral_public));
+        let kek = derive_wrapping_key(shared.as_bytes(), &ephemeral_public, public_key.as_bytes())?;
+
+        let unwrapped = Zeroizing::new(
+            Aes256Gcm::new_from_slice(kek.as_ref())
+                .map_err(|_| "Invalid wrapping key".to_string())?
+                .decrypt(
+                    Nonce::from_slice(&wrap.nonce),
+                    Payload { msg: &wrap.wrapped_key, aad: recipient_id.as_bytes() },
+                )
+                .map_err(|_| "Failed to unwrap data key".to_string())?,
+        );
+
+        let mut data_key = Zeroizing::new([0u8; KEY_LENGTH]);
+        if unwrapped.len() != KEY_LENGTH {
+            return Err("Malformed wrapped data key".to_string());
+        }
+        data_key.copy_from_slice(&unwrapped);
+        Ok(data_key)
+    }
+}
+
+/// Labeled keys wrapped to one device; all of them share the device's ephemeral DH
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct DeviceKeyWraps {
+    recipient_id: String,
+    ephemeral_public_key: Vec<u8>,
+    wraps: Vec<LabeledWrap>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LabeledWrap {
+    label: String,
+    nonce: Vec<u8>,
+    wrapped_key: Vec<u8>,
+}
+
+/// Many keys wrapped to many devices in one pass, as when building sync packages.
+/// Per device there is a single X25519 agreement and KEK derivation; the randomness
+/// for every ephemeral key and nonce is drawn at once, and the batch serializes once
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyWrapBatch {
+    version: u8,
+    devices: Vec<DeviceKeyWraps>,
+}
+
+#[wasm_bindgen]
+impl KeyWrapBatch {
+    /// Wrap concatenated 32-byte `keys` (one per label) to concatenated 32-byte X25519 public keys
+    #[wasm_bindgen]
+    pub fn wrap(labels: Vec<String>, keys: &[u8], recipient_public_keys: &[u8]) -> Result<KeyWrapBatch, JsValue> {
+        if keys.len() != labels.len() * KEY_LENGTH {
+            return Err(JsValue::from_str("Keys must be one concatenated 32-byte key per label"));
+        }
+        let recipients = split_public_keys(recipient_public_keys).map_err(|e| JsValue::from_str(&e))?;
+        let labeled: Vec<(String, Zeroizing<[u8; KEY_LENGTH]>)> = labels
+            .into_iter()
+            .zip(keys.chunks_exact(KEY_LENGTH))
+            .map(|(label, key)| (label, Zeroizing::new(key.try_into().expect("chunk is 32 bytes"))))
+            .collect();
+        Self::wrap_all(&labeled, &recipients).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Unwrap the key stored under `label` with this device's key pair
+    #[wasm_bindgen]
+    pub fn unwrap(&self, recipient: &RecipientKeyPair, label: &str) -> Result<Vec<u8>, JsValue> {
+        self.unwrap_with(recipient.secret_key(), label)
+            .map(|key| key.to_vec())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn wrap_count(&self) -> usize {
+        self.devices.iter().map(|device| device.wraps.len()).sum()
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize key wrap batch: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<KeyWrapBatch, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid key wrap batch: {}", e)))
+    }
+}
+
+impl KeyWrapBatch {
+    pub fn wrap_all(
+        keys: &[(String, Zeroizing<[u8; KEY_LENGTH]>)],
+        recipient_public_keys: &[[u8; KEY_LENGTH]],
+    ) -> Result<KeyWrapBatch, String> {
+        if keys.is_empty() || recipient_public_keys.is_empty() {
+            return Err("At least one key and one recipient are required".to_string());
+        }
+        let mut labels: Vec<&str> = keys.iter().map(|(label, _)| label.as_str()).collect();
+        labels.sort_unstable();
+        labels.dedup();
+        if labels.len() != keys.len() {
+            return Err("Key labels must be unique".to_string());
+        }
+
+        // One ephemeral secret per device followed by one nonce per key
+        let per_device = KEY_LENGTH + keys.len() * NONCE_LENGTH;
+        let mut randomness = Zeroizing::new(vec![0u8; per_device * recipient_public_keys.len()]);
+        platform::fill_random(&mut randomness);
+
+        let mut devices = Vec::with_capacity(recipient_public_keys.len());
+        for (recipient_public_key, random) in recipient_public_keys.iter().zip(randomness.chunks_exact(per_device)) {
+            let (ephemeral_bytes, nonces) = random.split_at(KEY_LENGTH);
+            let ephemeral_secret = StaticSecret::from(<[u8; KEY_LENGTH]>::try_from(ephemeral_bytes).expect("32 bytes"));
+            let ephemeral_public = PublicKey::from(&ephemeral_secret);
+            let shared = ephemeral_secret.diffie_hellman(&PublicKey::from(*recipient_public_key));
+            let recipient_id = device_fingerprint(recipient_public_key);
+            let kek = derive_wrapping_key(shared.as_bytes(), ephemeral_public.as_bytes(), recipient_public_key)?;
+            let cipher = Aes256Gcm::new_from_slice(kek.as_ref()).map_err(|_| "Invalid wrapping key".to_string())?;
+
+            let wraps = keys
+                .iter()
+                .zip(nonces.chunks_exact(NONCE_LENGTH))
+                .map(|((label, key), nonce)| {
+                    let aad = labeled_wrap_aad(&recipient_id, label);
+                    cipher
+                        .encrypt(Nonce::from_slice(nonce), Payload { msg: key.as_ref(), aad: &aad })
+                        .map(|wrapped_key| LabeledWrap { label: label.clone(), nonce: nonce.to_vec(), wrapped_key })
+                        .map_err(|_| "Key wrapping failed".to_string())
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+
+            devices.push(DeviceKeyWraps {
+                recipient_id,
+                ephemeral_public_key: ephemeral_public.as_bytes().to_vec(),
+                wraps,
+            });
+        }
+
+        track_secret_allocation();
+        Ok(KeyWrapBatch { version: MULTI_RECIPIENT_VERSION, devices })
+    }
+
+    pub fn unwrap_with(&self, recipient_secret_key: &[u8], label: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+        if self.version != MULTI_RECIPIENT_VERSION {
+            return Err(format!("Unsupported key wrap batch version: {}", self.version));
+        }
+        let secret_bytes: [u8; KEY_LENGTH] = recipient_secret_key
+            .try_into()
+            .map_err(|_| "Recipient secret key must be 32 bytes".to_string())?;
+        let secret = StaticSecret::from(secret_bytes);
+        let public_key = PublicKey::from(&secret);
+        let recipient_id = device_fingerprint(public_key.as_bytes());
+
+        let device = self.devices
+            .iter()
+            .find(|device| device.recipient_id == recipient_id)
+            .ok_or_else(|| "Device is not a recipient of this batch".to_string())?;
+        let wrap = device.wraps
+            .iter()
+            .find(|wrap| wrap.label == label)
+            .ok_or_else(|| format!("No key labeled {} in this batch", label))?;
+        if wrap.nonce.len() != NONCE_LENGTH {
+            return Err("Malformed batch entry".to_string());
+        }
+
+        let ephemeral_public: [u8; KEY_LENGTH] = device.ephemeral_public_key
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Malformed batch entry".to_string())?;
+        let shared = secret.diffie_hellman(&PublicKey::from(ephemeral_public));
+        let kek = derive_wrapping_key(shared.as_bytes(), &ephemeral_public, public_key.as_bytes())?;
+
+        let aad = labeled_wrap_aad(&recipient_id, label);
+        Aes256Gcm::new_from_slice(kek.as_ref())
+            .map_err(|_| "Invalid wrapping key".to_string())?
+            .decrypt(Nonce::from_slice(&wrap.nonce), Payload { msg: &wrap.wrapped_key, aad: &aad })
+            .map(Zeroizing::new)
+            .map_err(|_| "Failed to unwrap key".to_string())
+    }
+}
+
+// Binds each wrap to its label so entries cannot be swapped within a device's set
+fn labeled_wrap_aad(recipient_id: &str, label: &str) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(recipient_id.len() + 1 + label.len());
+    aad.extend_from_slice(recipient_id.as_bytes());
+    aad.push(0);
+    aad.extend_from_slice(label.as_bytes());
+    aad
+}
+
+// KEK is bound to both public keys so a wrap cannot be transplanted to another recipient
+fn derive_wrapping_key(
+    shared_secret: &[u8],
+    ephemeral_public: &[u8],
+    recipient_public: &[u8],
+) -> Result<Zeroizing<[u8; KEY_LENGTH]>, String> {
+    let mut salt = Vec::with_capacity(KEY_LENGTH * 2);
+    salt.extend_from_slice(ephemeral_public);
+    salt.extend_from_slice(recipient_public);
+
+    let mut kek = Zeroizing::new([0u8; KEY_LENGTH]);
+    Hkdf::<Sha256>::new(Some(&salt), shared_secret)
+        .expand(WRAP_INFO, kek.as_mut())
+        .map_err(|_| "Wrapping key derivation failed".to_string())?;
+    Ok(kek)
+}
+
+fn split_public_keys(concatenated: &[u8]) -> Result<Vec<[u8; KEY_LENGTH]>, String> {
+    if concatenated.is_empty() || !concatenated.len().is_multiple_of(KEY_LENGTH) {
+        return Err("Recipient public keys must be concatenated 32-byte keys".to_string());
+    }
+
+    Ok(concatenated
+        .chunks_exact(KEY_LENGTH)
+        .map(|chunk| chunk.try_into().expect("chunk is 32 bytes"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn public(pair: &RecipientKeyPair) -> [u8; KEY_LENGTH] {
+        pair.public_key().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_seal_once_open_by_each_recipient() {
+        let phone = RecipientKeyPair::generate();
+        let laptop = RecipientKeyPair::generate();
+        let outsider = RecipientKeyPair::generate();
+
+        let envelope = MultiRecipientEnvelope::seal_to(
+            b"shared cycle record",
+            b"record:42",
+            &[public(&phone), public(&laptop)],
+        ).unwrap();
+
+        assert_eq!(envelope.recipient_count(), 2);
+        assert_eq!(envelope.open_with(&phone.secret_key(), b"record:42").unwrap(), b"shared cycle record");
+        assert_eq!(envelope.open_with(&laptop.secret_key(), b"record:42").unwrap(), b"shared cycle record");
+        assert!(envelope.open_with(&outsider.secret_key(), b"record:42").is_err());
+        assert!(envelope.open_with(&phone.secret_key(), b"record:43").is_err());
+
+        // Tampered nonce lengths are refused instead of panicking
+        let mut short_nonce = envelope.clone();
+        short_nonce.nonce.truncate(8);
+        assert_eq!(short_nonce.open_with(&phone.secret_key(), b"record:42").unwrap_err(), "Malformed envelope nonce");
+        let mut short_wrap_nonce = envelope.clone();
+        short_wrap_nonce.recipients[0].nonce.push(0);
+        assert_eq!(short_wrap_nonce.open_with(&phone.secret_key(), b"record:42").unwrap_err(), "Malformed recipient entry");
+    }
+
+    #[test]
+    fn test_key_pair_persists_through_device_key_storage() {
+        let keystore = crate::test_support::InMemoryKeystore::new(vec![7; 16]);
+        let phone = RecipientKeyPair::generate();
+        phone.store_in(&keystore, "recipient:phone").unwrap();
+
+        let reloaded = RecipientKeyPair::load_from(&keystore, "recipient:phone").unwrap();
+        assert_eq!((reloaded.public_key(), reloaded.recipient_id()), (phone.public_key(), phone.recipient_id()));
+        let envelope = MultiRecipientEnvelope::seal_to(b"payload", b"", &[public(&phone)]).unwrap();
+        assert_eq!(envelope.open_with(reloaded.secret_key(), b"").unwrap(), b"payload");
+        assert!(RecipientKeyPair::load_from(&keystore, "recipient:tablet").is_err());
+    }
+
+    #[test]
+    fn test_add_remove_and_rekey_recipients() {
+        let phone = RecipientKeyPair::generate();
+        let tablet = RecipientKeyPair::generate();
+        let mut envelope = MultiRecipientEnvelope::seal_to(b"sync key", b"", &[public(&phone)]).unwrap();
+
+        envelope.add_recipient_with(&phone.secret_key(), &tablet.public_key()).unwrap();
+        assert!(envelope.add_recipient_with(&phone.secret_key(), &tablet.public_key()).is_err());
+        assert_eq!(envelope.open_with(&tablet.secret_key(), b"").unwrap(), b"sync key");
+
+        let ciphertext_before = envelope.ciphertext.clone();
+        assert!(envelope.remove_recipient(tablet.recipient_id()));
+        assert!(envelope.open_with(&tablet.secret_key(), b"").is_err());
+
+        envelope.rekey_with(&phone.secret_key(), b"", &[public(&phone)]).unwrap();
+        assert_ne!(envelope.ciphertext, ciphertext_before);
+        assert_eq!(envelope.recipient_ids(), vec![phone.recipient_id()]);
+        assert_eq!(envelope.open_with(&phone.secret_key(), b"").unwrap(), b"sync key");
+    }
+
+    #[test]
+    fn test_key_wrap_batch_roundtrip_per_device_and_label() {
+        let devices: Vec<RecipientKeyPair> = (0..3).map(|_| RecipientKeyPair::generate()).collect();
+        let publics: Vec<[u8; KEY_LENGTH]> = devices.iter().map(public).collect();
+        let keys: Vec<(String, Zeroizing<[u8; KEY_LENGTH]>)> = ["cycle_data", "preferences"]
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.to_string(), Zeroizing::new([i as u8 + 1; KEY_LENGTH])))
+            .collect();
+
+        let batch = KeyWrapBatch::wrap_all(&keys, &publics).unwrap();
+        assert_eq!(batch.wrap_count(), 6);
+        let restored: KeyWrapBatch = serde_json::from_str(&serde_json::to_string(&batch).unwrap()).unwrap();
+        for device in &devices {
+            assert_eq!(*restored.unwrap_with(&device.secret_key(), "preferences").unwrap(), vec![2u8; KEY_LENGTH]);
+        }
+        assert!(batch.unwrap_with(&devices[0].secret_key(), "healthcare_sharing").is_err());
+        assert!(batch.unwrap_with(&RecipientKeyPair::generate().secret_key(), "cycle_data").is_err());
+
+        // Swapping wraps between labels breaks authentication
+        let mut swapped = batch.clone();
+        swapped.devices[0].wraps[0].label = "preferences".to_string();
+        swapped.devices[0].wraps[1].label = "cycle_data".to_string();
+        assert!(swapped.unwrap_with(&devices[0].secret_key(), "cycle_data").is_err());
+        assert!(KeyWrapBatch::wrap_all(&[keys[0].clone(), keys[0].clone()], &publics).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use wasm_bindgen::prelude::*;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use sha2::{Digest, Sha256, Sha512};
+use zeroize::Zeroizing;
+use crate::memory::{track_secret_allocation, track_secret_zeroization};
+use crate::platform;
+
+// Oblivious PRF over ristretto255 (2HashDH, the base mode of RFC 9497)
+// The client hashes its input to the group and blinds it with a random scalar; the
+// server multiplies the blinded element by its secret key without learning the
+// input; the client strips the blind and hashes input and result into the PRF
+// output. Only the server key can evaluate the PRF, so every password guess against
+// a hardened backup costs one online, rate-limited evaluation, and the server never
+// holds anything that lets it (or a thief of its database) test guesses offline
+// without also holding the backup blob.
+
+pub(crate) const HASH_TO_GROUP_DOMAIN: &[u8] = b"aura-oprf-hash-to-group-v1";
+pub(crate) const FINALIZE_DOMAIN: &[u8] = b"aura-oprf-finalize-v1";
+pub(crate) const ELEMENT_LENGTH: usize = 32;
+pub(crate) const OUTPUT_LENGTH: usize = 32;
+const SCALAR_LENGTH: usize = 32;
+
+/// Server half of the OPRF; the key never leaves the server's secret store
+#[wasm_bindgen]
+pub struct OprfServerKey {
+    key: Zeroizing<Scalar>,
+}
+
+#[wasm_bindgen]
+impl OprfServerKey {
+    #[wasm_bindgen]
+    pub fn generate() -> OprfServerKey {
+        track_secret_allocation();
+        OprfServerKey { key: Zeroizing::new(random_scalar()) }
+    }
+
+    /// Reload a key exported with `secret_bytes`
+    #[wasm_bindgen(js_name = fromSecretBytes)]
+    pub fn from_secret_bytes(bytes: &[u8]) -> Result<OprfServerKey, JsValue> {
+        Self::from_bytes(bytes).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// For the server's secret store only
+    #[wasm_bindgen(getter)]
+    pub fn secret_bytes(&self) -> Vec<u8> {
+        self.key.to_bytes().to_vec()
+    }
+
+    /// Multiply a client's blinded element by the key; rate-limit calls per account
+    #[wasm_bindgen]
+    pub fn evaluate(&self, blinded_element: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.evaluate_element(blinded_element)
+            .map(|element| element.to_vec())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl OprfServerKey {
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<OprfServerKey, String> {
+        let bytes: [u8; SCALAR_LENGTH] = bytes.try_into()
+            .map_err(|_| "OPRF server key must be 32 bytes".to_string())?;
+        let key = Option::<Scalar>::from(Scalar::from_canonical_bytes(bytes))
+            .filter(|key| *key != Scalar::ZERO)
+            .ok_or_else(|| "Invalid OPRF server key".to_string())?;
+        track_secret_allocation();
+        Ok(OprfServerKey { key: Zeroizing::new(key) })
+    }
+
+    pub(crate) fn evaluate_element(&self, blinded_element: &[u8]) -> Result<[u8; ELEMENT_LENGTH], String> {
+        let blinded = decode_element(blinded_element)?;
+        Ok((blinded * *self.key).compress().to_bytes())
+    }
+}
+
+impl Drop for OprfServerKey {
+    fn drop(&mut self) {
+        track_secret_zeroization();
+    }
+}
+
+/// Client state between blinding an input and finalizing the server's evaluation
+#[wasm_bindgen]
+pub struct OprfBlind {
+    input: Zeroizing<Vec<u8>>,
+    blind: Zeroizing<Scalar>,
+    blinded_element: [u8; ELEMENT_LENGTH],
+}
+
+#[wasm_bindgen]
+impl OprfBlind {
+    /// Send to the server; reveals nothing about the input
+    #[wasm_bindgen(getter)]
+    pub fn blinded_element(&self) -> Vec<u8> {
+        self.blinded_element.to_vec()
+    }
+}
+
+impl OprfBlind {
+    pub(crate) fn new(input: &[u8]) -> OprfBlind {
+        let blind = random_scalar();
+        let blinded_element = (hash_to_group(input) * blind).compress().to_bytes();
+        track_secret_allocation();
+        OprfBlind { input: Zeroizing::new(input.to_vec()), blind: Zeroizing::new(blind), blinded_element }
+    }
+
+    /// Strip the blind from the server's evaluation and derive the PRF output
+    pub(crate) fn finalize(&self, evaluated_element: &[u8]) -> Result<Zeroizing<[u8; OUTPUT_LENGTH]>, String> {
+        let evaluated = decode_element(evaluated_element)?;
+        let unblinded = Zeroizing::new((evaluated * self.blind.invert()).compress().to_bytes());
+
+        let mut hasher = Sha256::new();
+        hasher.update(FINALIZE_DOMAIN);
+        hasher.update((self.input.len() as u64).to_be_bytes());
+        hasher.update(self.input.as_slice());
+        hasher.update(unblinded.as_ref());
+        Ok(Zeroizing::new(hasher.finalize().into()))
+    }
+}
+
+impl Drop for OprfBlind {
+    fn drop(&mut self) {
+        track_secret_zeroization();
+    }
+}
+
+fn hash_to_group(input: &[u8]) -> RistrettoPoint {
+    let mut uniform = Zeroizing::new([0u8; 64]);
+    uniform.copy_from_slice(&Sha512::new().chain_update(HASH_TO_GROUP_DOMAIN).chain_update(input).finalize());
+    RistrettoPoint::from_uniform_bytes(&uniform)
+}
+
+fn random_scalar() -> Scalar {
+    loop {
+        let mut wide = Zeroizing::new([0u8; 64]);
+        platform::fill_random(wide.as_mut());
+        let scalar = Scalar::from_bytes_mod_order_wide(&wide);
+        if scalar != Scalar::ZERO {
+            return scalar;
+        }
+    }
+}
+
+fn decode_element(bytes: &[u8]) -> Result<RistrettoPoint, String> {
+    let element = CompressedRistretto::from_slice(bytes)
+        .ok()
+        .and_then(|compressed| compressed.decompress())
+        .ok_or_else(|| "Invalid OPRF group element".to_string())?;
+    // The identity would make the output independent of the server key
+    if element == RistrettoPoint::default() {
+        return Err("Invalid OPRF group element".to_string());
+    }
+    Ok(element)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_depends_on_input_and_server_key_but_not_the_blind() {
+        let server = OprfServerKey::generate();
+        let evaluate = |input: &[u8], server: &OprfServerKey| {
+            let blind = OprfBlind::new(input);
+            blind.finalize(&server.evaluate_element(&blind.blinded_element).unwrap()).unwrap()
+        };
+
+        let first = evaluate(b"kdf output", &server);
+        assert_eq!(first, evaluate(b"kdf output", &server));
+        assert_ne!(first, evaluate(b"other output", &server));
+        assert_ne!(first, evaluate(b"kdf output", &OprfServerKey::generate()));
+
+        // Fresh blinds hide repeated inputs from the server
+        assert_ne!(OprfBlind::new(b"kdf output").blinded_element, OprfBlind::new(b"kdf output").blinded_element);
+
+        let reloaded = OprfServerKey::from_bytes(&server.secret_bytes()).unwrap();
+        assert_eq!(first, evaluate(b"kdf output", &reloaded));
+    }
+
+    #[test]
+    fn test_rejects_malformed_elements_and_keys() {
+        let server = OprfServerKey::generate();
+        let identity = RistrettoPoint::default().compress().to_bytes();
+        assert!(server.evaluate_element(&identity).is_err());
+        assert!(server.evaluate_element(&[0xffu8; 32]).is_err());
+        assert!(server.evaluate_element(&[1u8; 16]).is_err());
+
+        let blind = OprfBlind::new(b"kdf output");
+        assert!(blind.finalize(&identity).is_err());
+
+        assert!(OprfServerKey::from_bytes(&[0u8; 32]).is_err());
+        assert!(OprfServerKey::from_bytes(&[0xffu8; 32]).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+use crate::envelope::{to_hex, CryptoEnvelope};
+use crate::security::constant_time_compare;
+
+// Encrypted pagination
+// Backends page through a user's records by an opaque order token instead of a
+// timestamp. A token is `epoch | position | tag` in fixed-width hex, so plain string
+// comparison follows write order. The position is a per-user sequence counter shifted
+// by a secret offset, so the backend sees neither times nor record counts, and tokens
+// from different users cannot be compared. Each tag is an HMAC over the previous tag,
+// which lets the client detect records the backend dropped or reordered within a page.
+// Rotating the key starts a new epoch, so older tokens still sort first.
+// One generator serves one user stream; its state must be persisted between sessions.
+
+type HmacSha256 = Hmac<Sha256>;
+
+const MIN_ORDER_KEY_LENGTH: usize = 32;
+const TAG_LENGTH: usize = 8;
+const TOKEN_LENGTH: usize = 8 + 16 + 2 * TAG_LENGTH;
+pub(crate) const OFFSET_DOMAIN: &[u8] = b"aura-order-offset-v1";
+pub(crate) const TAG_DOMAIN: &[u8] = b"aura-order-tag-v1";
+
+/// Resumable generator position; contains no key material
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct OrderTokenState {
+    epoch: u32,
+    sequence: u64,
+    last_tag: String,
+}
+
+/// Per-user generator of order-preserving, HMAC-chained pagination tokens
+#[wasm_bindgen]
+pub struct OrderTokenGenerator {
+    key: Zeroizing<Vec<u8>>,
+    state: OrderTokenState,
+}
+
+#[wasm_bindgen]
+impl OrderTokenGenerator {
+    /// Start a fresh stream under `key` (at least 32 bytes, never shared with the backend)
+    #[wasm_bindgen(constructor)]
+    pub fn new(key: &[u8]) -> Result<OrderTokenGenerator, JsValue> {
+        Self::with_state(key, OrderTokenState { epoch: 0, sequence: 0, last_tag: String::new() })
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Continue a stream from `export_state`
+    #[wasm_bindgen]
+    pub fn resume(key: &[u8], state_json: &str) -> Result<OrderTokenGenerator, JsValue> {
+        let state: OrderTokenState = serde_json::from_str(state_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid order token state: {}", e)))?;
+        Self::with_state(key, state).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn epoch(&self) -> u32 {
+        self.state.epoch
+    }
+
+    /// Issue the next token
+    #[wasm_bindgen]
+    pub fn next_token(&mut self) -> String {
+        let position = self.offset() + self.state.sequence;
+        let tag = self.tag(&self.state.last_tag, position);
+        self.state.sequence += 1;
+        let token = format!("{:08x}{:016x}{}", self.state.epoch, position, tag);
+        self.state.last_tag = tag;
+        token
+    }
+
+    /// Issue the next token and record it on the envelope being written
+    #[wasm_bindgen]
+    pub fn stamp_envelope(&mut self, envelope: &mut CryptoEnvelope) -> String {
+        let token = self.next_token();
+        envelope.set_order_token(token.clone());
+        token
+    }
+
+    /// Switch to `new_key` in the next epoch; tokens issued so far keep sorting first
+    #[wasm_bindgen]
+    pub fn rotate(&mut self, new_key: &[u8]) -> Result<(), JsValue> {
+        self.rotate_key(new_key).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Check that a page of current-epoch tokens, in backend order, is gap-free and
+    /// correctly chained
+    #[wasm_bindgen]
+    pub fn verify_page(&self, tokens: Vec<String>) -> Result<(), JsValue> {
+        self.verify_tokens(&tokens).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Persist after issuing tokens; resuming from a stale state reissues positions
+    #[wasm_bindgen]
+    pub fn export_state(&self) -> String {
+        serde_json::to_string(&self.state).unwrap_or_default()
+    }
+}
+
+impl OrderTokenGenerator {
+    fn with_state(key: &[u8], state: OrderTokenState) -> Result<OrderTokenGenerator, String> {
+        if key.len() < MIN_ORDER_KEY_LENGTH {
+            return Err("Order token key must be at least 32 bytes".to_string());
+        }
+        Ok(OrderTokenGenerator { key: Zeroizing::new(key.to_vec()), state })
+    }
+
+    pub(crate) fn rotate_key(&mut self, new_key: &[u8]) -> Result<(), String> {
+        let epoch = self.state.epoch.checked_add(1).ok_or_else(|| "Order token epochs exhausted".to_string())?;
+        *self = Self::with_state(new_key, OrderTokenState { epoch, sequence: 0, last_tag: String::new() })?;
+        Ok(())
+    }
+
+    pub(crate) fn verify_tokens(&self, tokens: &[String]) -> Result<(), String> {
+        let parsed = tokens.iter().map(|token| parse_token(token)).collect::<Result<Vec<_>, _>>()?;
+        if let Some((epoch, _, _)) = parsed.iter().find(|(epoch, _, _)| *epoch != self.state.epoch) {
+            return Err(format!("Token from epoch {} cannot be verified in epoch {}", epoch, self.state.epoch));
+        }
+        for pair in parsed.windows(2) {
+            let ((_, previous_position, previous_tag), (_, position, tag)) = (&pair[0], &pair[1]);
+            if *position != previous_position + 1 {
+                return Err(format!("Page is missing or reorders records after position {:x}", previous_position));
+            }
+            if !constant_time_compare(self.tag(previous_tag, *position).as_bytes(), tag.as_bytes()) {
+                return Err(format!("Broken token chain at position {:x}", position));
+            }
+        }
+        Ok(())
+    }
+
+    // Secret per-epoch start position; below 2^32 so positions cannot overflow
+    fn offset(&self) -> u64 {
+        let digest = self.mac(&[OFFSET_DOMAIN, &self.state.epoch.to_be_bytes()]);
+        u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) as u64
+    }
+
+    fn tag(&self, previous_tag: &str, position: u64) -> String {
+        let digest = self.mac(&[TAG_DOMAIN, previous_tag.as_bytes(), &self.state.epoch.to_be_bytes(), &position.to_be_bytes()]);
+        to_hex(&digest[..TAG_LENGTH])
+    }
+
+    fn mac(&self, parts: &[&[u8]]) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+fn parse_token(token: &str) -> Result<(u32, u64, String), String> {
+    let valid = token.len() == TOKEN_LENGTH && token.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    if !valid {
+        return Err("Malformed order token".to_string());
+    }
+    let epoch = u32::from_str_radix(&token[..8], 16).map_err(|_| "Malformed order token".to_string())?;
+    let position = u64::from_str_radix(&token[8..24], 16).map_err(|_| "Malformed order token".to_string())?;
+    Ok((epoch, position, token[24..].to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_sort_in_write_order_across_rotation() {
+        let mut generator = OrderTokenGenerator::with_state(&[1u8; 32], OrderTokenState { epoch: 0, sequence: 0, last_tag: String::new() }).unwrap();
+        let mut tokens: Vec<String> = (0..5).map(|_| generator.next_token()).collect();
+        assert!(generator.verify_tokens(&tokens).is_ok());
+
+        generator.rotate_key(&[2u8; 32]).unwrap();
+        tokens.extend((0..3).map(|_| generator.next_token()));
+        let mut sorted = tokens.clone();
+        sorted.sort();
+        assert_eq!(sorted, tokens);
+        assert!(generator.verify_tokens(&tokens[5..]).is_ok());
+        assert!(generator.verify_tokens(&tokens[4..]).unwrap_err().contains("epoch"));
+
+        // Another user's stream starts at an unrelated position
+        let mut other = OrderTokenGenerator::with_state(&[3u8; 32], OrderTokenState { epoch: 0, sequence: 0, last_tag: String::new() }).unwrap();
+        assert_ne!(other.next_token()[8..24], tokens[0][8..24]);
+    }
+
+    #[test]
+    fn test_verify_detects_dropped_and_forged_records_and_resume_continues_chain() {
+        let mut generator = OrderTokenGenerator::with_state(&[1u8; 32], OrderTokenState { epoch: 0, sequence: 0, last_tag: String::new() }).unwrap();
+        let tokens: Vec<String> = (0..4).map(|_| generator.next_token()).collect();
+
+        let dropped = vec![tokens[0].clone(), tokens[2].clone()];
+        assert!(generator.verify_tokens(&dropped).unwrap_err().contains("missing"));
+        let mut forged = tokens.clone();
+        forged[3].replace_range(24.., "0000000000000000");
+        assert!(generator.verify_tokens(&forged).unwrap_err().contains("Broken"));
+        assert!(generator.verify_tokens(&["not-a-token".to_string()]).is_err());
+
+        let state: OrderTokenState = serde_json::from_str(&generator.export_state()).unwrap();
+        let mut resumed = OrderTokenGenerator::with_state(&[1u8; 32], state).unwrap();
+        let next = resumed.next_token();
+        assert!(resumed.verify_tokens(&[tokens[3].clone(), next]).is_ok());
+    }
+}
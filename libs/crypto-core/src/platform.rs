@@ -0,0 +1,168 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+// Single source of randomness, wall-clock and monotonic time for the crate.
+// Release builds use the OS-backed thread RNG and the system clock. The
+// `deterministic-test` feature swaps both for seeded fakes so integration
+// tests get reproducible ciphertexts, nonces and timestamps.
+
+#[cfg(not(feature = "deterministic-test"))]
+mod source {
+    use rand::RngCore;
+
+    pub fn fill_random(buffer: &mut [u8]) {
+        rand::thread_rng().fill_bytes(buffer);
+    }
+
+    pub fn now_ms() -> u64 {
+        chrono::Utc::now().timestamp_millis() as u64
+    }
+
+    // `performance.now()` where the host has it; wall clock otherwise
+    #[cfg(target_arch = "wasm32")]
+    pub fn monotonic_us() -> u64 {
+        use wasm_bindgen::{JsCast, JsValue};
+        let performance = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance")).ok();
+        performance
+            .and_then(|performance| {
+                let now = js_sys::Reflect::get(&performance, &JsValue::from_str("now")).ok()?;
+                now.dyn_into::<js_sys::Function>().ok()?.call0(&performance).ok()?.as_f64()
+            })
+            .map_or_else(|| now_ms() * 1000, |ms| (ms * 1000.0) as u64)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn monotonic_us() -> u64 {
+        static ORIGIN: once_cell::sync::Lazy<std::time::Instant> = once_cell::sync::Lazy::new(std::time::Instant::now);
+        ORIGIN.elapsed().as_micros() as u64
+    }
+}
+
+#[cfg(feature = "deterministic-test")]
+mod source {
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+    use std::cell::RefCell;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    pub const DEFAULT_SEED: u64 = 0x4155_5241_5445_5354; // "AURATEST"
+    pub const DEFAULT_EPOCH_MS: u64 = 1_700_000_000_000;
+
+    thread_local! {
+        static RNG: RefCell<StdRng> = RefCell::new(StdRng::seed_from_u64(DEFAULT_SEED));
+    }
+
+    static CLOCK_MS: AtomicU64 = AtomicU64::new(DEFAULT_EPOCH_MS);
+
+    pub fn fill_random(buffer: &mut [u8]) {
+        RNG.with(|rng| rng.borrow_mut().fill_bytes(buffer));
+    }
+
+    pub fn now_ms() -> u64 {
+        CLOCK_MS.load(Ordering::SeqCst)
+    }
+
+    pub fn monotonic_us() -> u64 {
+        now_ms() * 1000
+    }
+
+    pub fn reseed(seed: u64) {
+        RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+    }
+
+    pub fn set_clock(ms: u64) {
+        CLOCK_MS.store(ms, Ordering::SeqCst);
+    }
+
+    pub fn advance_clock(ms: u64) -> u64 {
+        CLOCK_MS.fetch_add(ms, Ordering::SeqCst) + ms
+    }
+}
+
+/// Fill `buffer` from the crate RNG
+pub fn fill_random(buffer: &mut [u8]) {
+    source::fill_random(buffer);
+}
+
+pub fn random_u32() -> u32 {
+    let mut bytes = [0u8; 4];
+    fill_random(&mut bytes);
+    u32::from_le_bytes(bytes)
+}
+
+pub fn random_u64() -> u64 {
+    let mut bytes = [0u8; 8];
+    fill_random(&mut bytes);
+    u64::from_le_bytes(bytes)
+}
+
+/// Random (v4) UUID string drawn from the crate RNG
+pub fn new_uuid() -> String {
+    let mut bytes = [0u8; 16];
+    fill_random(&mut bytes);
+    uuid::Builder::from_random_bytes(bytes).into_uuid().to_string()
+}
+
+/// Milliseconds since the Unix epoch
+pub fn now_ms() -> u64 {
+    source::now_ms()
+}
+
+/// Microseconds on a monotonic clock with an arbitrary origin, for measuring durations
+pub fn monotonic_us() -> u64 {
+    source::monotonic_us()
+}
+
+pub fn now() -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(now_ms() as i64)
+        .single()
+        .unwrap_or_else(Utc::now)
+}
+
+/// Whether this build uses the seeded test RNG and clock
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn is_deterministic_build() -> bool {
+    cfg!(feature = "deterministic-test")
+}
+
+#[cfg(feature = "deterministic-test")]
+mod controls {
+    use wasm_bindgen::prelude::*;
+
+    /// Reset the test RNG to a known seed
+    #[wasm_bindgen]
+    pub fn seed_deterministic_rng(seed: u64) {
+        super::source::reseed(seed);
+    }
+
+    /// Pin the test clock to `now_ms`
+    #[wasm_bindgen]
+    pub fn set_deterministic_clock(now_ms: u64) {
+        super::source::set_clock(now_ms);
+    }
+
+    /// Move the test clock forward; returns the new time
+    #[wasm_bindgen]
+    pub fn advance_deterministic_clock(delta_ms: u64) -> u64 {
+        super::source::advance_clock(delta_ms)
+    }
+}
+
+#[cfg(feature = "deterministic-test")]
+pub use controls::*;
+
+#[cfg(all(test, feature = "deterministic-test"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_rng_and_clock_are_reproducible() {
+        seed_deterministic_rng(7);
+        let first = (random_u64(), new_uuid());
+        seed_deterministic_rng(7);
+        assert_eq!(first, (random_u64(), new_uuid()));
+
+        set_deterministic_clock(1_000);
+        assert_eq!(advance_deterministic_clock(500), 1_500);
+        assert_eq!(now().timestamp_millis(), 1_500);
+    }
+}
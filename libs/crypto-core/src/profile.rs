@@ -0,0 +1,285 @@
+use wasm_bindgen::prelude::*;
+use std::sync::atomic::{AtomicU8, Ordering};
+use crate::envelope::{CryptoAlgorithm, CryptoEnvelope, KDFParams};
+use crate::key_rotation::RotationPolicy;
+use crate::multi_device::{MultiDeviceProtocol, PairingAdmissionMode};
+use crate::recovery::{RecoverySystem, RecoveryValidationLevel};
+use crate::derivation::DataCategory;
+use crate::decrypt_audit::{AuditSamplingRule, DecryptAuditConfig};
+
+// Security profiles select primitives and policies for every module in one switch,
+// so KDF cost, AEAD suite, padding, rotation and lockout settings never drift apart.
+
+/// Overall security level applied at initialization
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SecurityProfile {
+    Standard = 0,
+    High = 1,
+    Paranoid = 2,
+}
+
+impl SecurityProfile {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(SecurityProfile::Standard),
+            1 => Some(SecurityProfile::High),
+            2 => Some(SecurityProfile::Paranoid),
+            _ => None,
+        }
+    }
+}
+
+/// How plaintext lengths are hidden before encryption
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaddingPolicy {
+    None = 0,
+    PowerOfTwoBuckets = 1, // Pad to the next power of two, at least `padding_block_size`
+    FixedBlock = 2,        // Pad to a multiple of `padding_block_size`
+}
+
+static ACTIVE_PROFILE: AtomicU8 = AtomicU8::new(SecurityProfile::Standard as u8);
+
+/// Concrete settings derived from a security profile
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct SecurityProfileSettings {
+    profile: SecurityProfile,
+    kdf_iterations: u32,
+    kdf_memory_kb: u32,
+    kdf_parallelism: u32,
+    aead: CryptoAlgorithm,
+    padding_policy: PaddingPolicy,
+    padding_block_size: usize,
+    rotation_max_age_days: u32,
+    recovery_validation_level: RecoveryValidationLevel,
+    max_recovery_attempts: u32,
+    lockout_duration_ms: u64,
+    min_passphrase_score: u8,
+    pairing_admission_mode: PairingAdmissionMode,
+    pairing_pow_difficulty_bits: u8,
+    bulk_audit_sample_rate: f64, // Share of successful decrypts audited for high-volume categories
+}
+
+#[wasm_bindgen]
+impl SecurityProfileSettings {
+    #[wasm_bindgen(constructor)]
+    pub fn new(profile: SecurityProfile) -> SecurityProfileSettings {
+        match profile {
+            SecurityProfile::Standard => SecurityProfileSettings {
+                profile,
+                kdf_iterations: 2,
+                kdf_memory_kb: 64 * 1024,
+                kdf_parallelism: 1,
+                aead: CryptoAlgorithm::AES256GCM,
+                padding_policy: PaddingPolicy::PowerOfTwoBuckets,
+                padding_block_size: 256,
+                rotation_max_age_days: 90,
+                recovery_validation_level: RecoveryValidationLevel::Standard,
+                max_recovery_attempts: 5,
+                lockout_duration_ms: 15 * 60 * 1000,
+                min_passphrase_score: 3,
+                pairing_admission_mode: PairingAdmissionMode::Open,
+                pairing_pow_difficulty_bits: 0,
+                bulk_audit_sample_rate: 0.05,
+            },
+            SecurityProfile::High => SecurityProfileSettings {
+                profile,
+                kdf_iterations: 3,
+                kdf_memory_kb: 64 * 1024,
+                kdf_parallelism: 2,
+                aead: CryptoAlgorithm::AES256GCM,
+                padding_policy: PaddingPolicy::PowerOfTwoBuckets,
+                padding_block_size: 1024,
+                rotation_max_age_days: 30,
+                recovery_validation_level: RecoveryValidationLevel::Enhanced,
+                max_recovery_attempts: 3,
+                lockout_duration_ms: 60 * 60 * 1000,
+                min_passphrase_score: 3,
+                pairing_admission_mode: PairingAdmissionMode::ProofOrInvitation,
+                pairing_pow_difficulty_bits: 16,
+                bulk_audit_sample_rate: 0.25,
+            },
+            SecurityProfile::Paranoid => SecurityProfileSettings {
+                profile,
+                kdf_iterations: 4,
+                kdf_memory_kb: 64 * 1024,
+                kdf_parallelism: 4,
+                aead: CryptoAlgorithm::AES256GCM,
+                padding_policy: PaddingPolicy::FixedBlock,
+                padding_block_size: 4096,
+                rotation_max_age_days: 7,
+                recovery_validation_level: RecoveryValidationLevel::Emergency,
+                max_recovery_attempts: 3,
+                lockout_duration_ms: 24 * 60 * 60 * 1000,
+                min_passphrase_score: 4,
+                pairing_admission_mode: PairingAdmissionMode::Invitation,
+                pairing_pow_difficulty_bits: 20,
+                bulk_audit_sample_rate: 1.0,
+            },
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn profile(&self) -> SecurityProfile {
+        self.profile
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn aead(&self) -> CryptoAlgorithm {
+        self.aead
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn padding_policy(&self) -> PaddingPolicy {
+        self.padding_policy
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn padding_block_size(&self) -> usize {
+        self.padding_block_size
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rotation_max_age_days(&self) -> u32 {
+        self.rotation_max_age_days
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_recovery_attempts(&self) -> u32 {
+        self.max_recovery_attempts
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn lockout_duration_ms(&self) -> u64 {
+        self.lockout_duration_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn min_passphrase_score(&self) -> u8 {
+        self.min_passphrase_score
+    }
+
+    /// Argon2id parameters for new backups and envelopes
+    #[wasm_bindgen]
+    pub fn kdf_params(&self) -> KDFParams {
+        KDFParams::argon2id(self.kdf_iterations, self.kdf_memory_kb, self.kdf_parallelism)
+    }
+
+    /// Ciphertext-independent plaintext length after padding
+    #[wasm_bindgen]
+    pub fn padded_length(&self, plaintext_length: usize) -> usize {
+        let block = self.padding_block_size.max(1);
+        match self.padding_policy {
+            PaddingPolicy::None => plaintext_length,
+            PaddingPolicy::PowerOfTwoBuckets => plaintext_length.max(block).next_power_of_two(),
+            PaddingPolicy::FixedBlock => plaintext_length.div_ceil(block).max(1) * block,
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn rotation_policy(&self) -> RotationPolicy {
+        RotationPolicy::new(self.rotation_max_age_days)
+    }
+
+    /// Recovery system with this profile's validation level, lockout and KDF
+    #[wasm_bindgen]
+    pub fn recovery_system(&self, device_id: String) -> RecoverySystem {
+        let mut recovery = RecoverySystem::new(
+            device_id,
+            self.recovery_validation_level as u8,
+            self.max_recovery_attempts,
+            self.lockout_duration_ms,
+        );
+        recovery.set_kdf_params(self.kdf_params());
+        recovery
+    }
+
+    /// Apply AEAD suite and KDF parameters to a new envelope
+    #[wasm_bindgen]
+    pub fn apply_to_envelope(&self, envelope: &mut CryptoEnvelope) -> Result<(), JsValue> {
+        envelope.set_algorithm(self.aead as u8)?;
+        envelope.set_kdf_params(self.kdf_params());
+        Ok(())
+    }
+
+    /// Decrypt audit sampling: high-volume categories sampled, shared data always audited
+    #[wasm_bindgen]
+    pub fn decrypt_audit_config(&self) -> DecryptAuditConfig {
+        let mut config = DecryptAuditConfig::new(AuditSamplingRule::ALL);
+        for category in [DataCategory::CycleData, DataCategory::Preferences] {
+            config.set_rule(category, AuditSamplingRule::sampled(self.bulk_audit_sample_rate));
+        }
+        config
+    }
+
+    /// Apply pairing admission control to a multi-device protocol
+    #[wasm_bindgen]
+    pub fn apply_to_pairing(&self, protocol: &mut MultiDeviceProtocol) -> Result<(), JsValue> {
+        protocol.set_admission_policy(self.pairing_admission_mode, self.pairing_pow_difficulty_bits)
+    }
+}
+
+/// Select the process-wide security profile; returns the settings to hand to each module
+#[wasm_bindgen]
+pub fn configure_security_profile(profile: SecurityProfile) -> SecurityProfileSettings {
+    ACTIVE_PROFILE.store(profile as u8, Ordering::SeqCst);
+    SecurityProfileSettings::new(profile)
+}
+
+#[wasm_bindgen]
+pub fn active_security_profile() -> SecurityProfile {
+    SecurityProfile::from_u8(ACTIVE_PROFILE.load(Ordering::SeqCst)).unwrap_or(SecurityProfile::Standard)
+}
+
+/// Settings for the currently configured profile
+#[wasm_bindgen]
+pub fn active_security_settings() -> SecurityProfileSettings {
+    SecurityProfileSettings::new(active_security_profile())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_tighten_monotonically() {
+        let standard = SecurityProfileSettings::new(SecurityProfile::Standard);
+        let high = SecurityProfileSettings::new(SecurityProfile::High);
+        let paranoid = SecurityProfileSettings::new(SecurityProfile::Paranoid);
+
+        for (weaker, stronger) in [(&standard, &high), (&high, &paranoid)] {
+            assert!(stronger.kdf_iterations >= weaker.kdf_iterations);
+            assert!(stronger.rotation_max_age_days < weaker.rotation_max_age_days);
+            assert!(stronger.max_recovery_attempts <= weaker.max_recovery_attempts);
+            assert!(stronger.lockout_duration_ms > weaker.lockout_duration_ms);
+            assert!(stronger.min_passphrase_score >= weaker.min_passphrase_score);
+            assert!(stronger.bulk_audit_sample_rate >= weaker.bulk_audit_sample_rate);
+        }
+
+        assert_eq!(paranoid.aead(), CryptoAlgorithm::AES256GCM);
+        assert_eq!(paranoid.rotation_policy().max_age_days(), 7);
+        assert_eq!(high.kdf_params().memory_cost(), Some(64 * 1024));
+    }
+
+    #[test]
+    fn test_padding_policies() {
+        let standard = SecurityProfileSettings::new(SecurityProfile::Standard);
+        assert_eq!(standard.padded_length(10), 256);
+        assert_eq!(standard.padded_length(300), 512);
+
+        let paranoid = SecurityProfileSettings::new(SecurityProfile::Paranoid);
+        assert_eq!(paranoid.padded_length(0), 4096);
+        assert_eq!(paranoid.padded_length(4097), 8192);
+    }
+
+    #[test]
+    fn test_configure_sets_active_profile() {
+        let settings = configure_security_profile(SecurityProfile::High);
+        assert_eq!(settings.profile(), SecurityProfile::High);
+        assert_eq!(active_security_profile(), SecurityProfile::High);
+        assert_eq!(active_security_settings().padding_block_size(), 1024);
+        configure_security_profile(SecurityProfile::Standard);
+    }
+}
@@ -0,0 +1,276 @@
+use wasm_bindgen::prelude::*;
+use std::collections::BTreeMap;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+use crate::consent::{consent_granted, ConsentFeature};
+use crate::envelope::to_hex;
+
+// Research export pseudonymization
+// Opt-in research sharing sends records to a study without direct identifiers. Which
+// fields leave the device is an allow-list: every field without a rule is dropped.
+// Identifiers are replaced by keyed pseudonyms (HMAC-SHA256 under a key derived from
+// the research key and study id), timestamps are shifted by a per-subject offset and
+// floored to a granularity, and numeric or text quasi-identifiers are bucketed or
+// truncated. Everything is deterministic under the same key and study, so a subject
+// keeps the same pseudonym and date shift across exports and longitudinal analysis
+// still works, while pseudonyms from different studies cannot be linked.
+
+type HmacSha256 = Hmac<Sha256>;
+
+const MIN_RESEARCH_KEY_LENGTH: usize = 32;
+const PSEUDONYM_LENGTH: usize = 16;
+pub(crate) const PSEUDONYM_KEY_INFO: &[u8] = b"aura-research-pseudonym-v1";
+pub(crate) const DATE_SHIFT_KEY_INFO: &[u8] = b"aura-research-date-shift-v1";
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Resolution exported timestamps are floored to (UTC)
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeGranularity {
+    Hour = 0,
+    Day = 1,
+    Week = 2, // 7-day buckets counted from the Unix epoch
+    Month = 3,
+}
+
+/// What happens to one field of an exported record
+#[derive(Debug, Clone, PartialEq)]
+enum FieldRule {
+    Keep,
+    Pseudonymize,
+    Timestamp,
+    Bucket(f64),
+    Truncate(usize),
+}
+
+/// Deterministic pseudonymizer for one study
+#[wasm_bindgen]
+pub struct ResearchPseudonymizer {
+    pseudonym_key: Zeroizing<[u8; 32]>,
+    date_shift_key: Zeroizing<[u8; 32]>,
+    granularity: TimeGranularity,
+    max_date_shift_days: u32,
+    fields: BTreeMap<String, FieldRule>,
+}
+
+#[wasm_bindgen]
+impl ResearchPseudonymizer {
+    /// `research_key` stays on the device; rotating it unlinks every earlier export
+    #[wasm_bindgen(constructor)]
+    pub fn new(research_key: &[u8], study_id: &str) -> Result<ResearchPseudonymizer, JsValue> {
+        Self::with_key(research_key, study_id).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn set_timestamp_granularity(&mut self, granularity: TimeGranularity) {
+        self.granularity = granularity;
+    }
+
+    /// Timestamps of each subject move by a fixed offset within ±`days`; 0 disables shifting
+    #[wasm_bindgen]
+    pub fn set_max_date_shift_days(&mut self, days: u32) {
+        self.max_date_shift_days = days;
+    }
+
+    /// Export the field unchanged
+    #[wasm_bindgen]
+    pub fn keep_field(&mut self, field: String) {
+        self.fields.insert(field, FieldRule::Keep);
+    }
+
+    /// Replace the field (a string or number identifier) with its pseudonym
+    #[wasm_bindgen]
+    pub fn pseudonymize_field(&mut self, field: String) {
+        self.fields.insert(field, FieldRule::Pseudonymize);
+    }
+
+    /// Shift and coarsen the field as a millisecond timestamp
+    #[wasm_bindgen]
+    pub fn timestamp_field(&mut self, field: String) {
+        self.fields.insert(field, FieldRule::Timestamp);
+    }
+
+    /// Replace a number with the lower bound of its `width`-sized bucket (e.g. 5-year age bands)
+    #[wasm_bindgen]
+    pub fn bucket_field(&mut self, field: String, width: f64) -> Result<(), JsValue> {
+        if !(width.is_finite() && width > 0.0) {
+            return Err(JsValue::from_str("Bucket width must be a positive number"));
+        }
+        self.fields.insert(field, FieldRule::Bucket(width));
+        Ok(())
+    }
+
+    /// Keep only the first `keep_chars` characters (e.g. a postcode prefix)
+    #[wasm_bindgen]
+    pub fn truncate_field(&mut self, field: String, keep_chars: usize) {
+        self.fields.insert(field, FieldRule::Truncate(keep_chars));
+    }
+
+    /// Stable pseudonym of an identifier within this study
+    #[wasm_bindgen]
+    pub fn pseudonymize_id(&self, identifier: &str) -> String {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(self.pseudonym_key.as_ref()).expect("HMAC accepts any key length");
+        mac.update(identifier.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        format!("p_{}", to_hex(&digest[..PSEUDONYM_LENGTH]))
+    }
+
+    /// Timestamp of `subject_id` as it appears in exports
+    #[wasm_bindgen]
+    pub fn coarsen_timestamp(&self, subject_id: &str, timestamp_ms: u64) -> u64 {
+        let shifted = (timestamp_ms as i64).saturating_add(self.date_shift_days(subject_id) * DAY_MS).max(0);
+        floor_to(shifted, self.granularity) as u64
+    }
+
+    /// Transform one JSON record of `subject_id`; requires research sharing consent
+    #[wasm_bindgen]
+    pub fn pseudonymize_record(&self, subject_id: &str, record_json: &str) -> Result<String, JsValue> {
+        if !consent_granted(ConsentFeature::ResearchSharing) {
+            return Err(JsValue::from_str("Research sharing has not been consented to"));
+        }
+        self.transform_record(subject_id, record_json).map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl ResearchPseudonymizer {
+    pub(crate) fn with_key(research_key: &[u8], study_id: &str) -> Result<Self, String> {
+        if research_key.len() < MIN_RESEARCH_KEY_LENGTH {
+            return Err(format!("Research key must be at least {} bytes", MIN_RESEARCH_KEY_LENGTH));
+        }
+        if study_id.is_empty() {
+            return Err("Study id must not be empty".to_string());
+        }
+        // The study id salts both keys, so subjects cannot be linked across studies
+        let hkdf = Hkdf::<Sha256>::new(Some(study_id.as_bytes()), research_key);
+        let mut pseudonym_key = Zeroizing::new([0u8; 32]);
+        let mut date_shift_key = Zeroizing::new([0u8; 32]);
+        hkdf.expand(PSEUDONYM_KEY_INFO, pseudonym_key.as_mut())
+            .and_then(|_| hkdf.expand(DATE_SHIFT_KEY_INFO, date_shift_key.as_mut()))
+            .map_err(|_| "Research key derivation failed".to_string())?;
+        Ok(ResearchPseudonymizer {
+            pseudonym_key,
+            date_shift_key,
+            granularity: TimeGranularity::Day,
+            max_date_shift_days: 0,
+            fields: BTreeMap::new(),
+        })
+    }
+
+    /// Output carries the subject pseudonym plus the fields that have a rule
+    pub(crate) fn transform_record(&self, subject_id: &str, record_json: &str) -> Result<String, String> {
+        let record: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(record_json).map_err(|e| format!("Record must be a JSON object: {}", e))?;
+
+        let mut exported = serde_json::Map::new();
+        exported.insert("subject".to_string(), self.pseudonymize_id(subject_id).into());
+        for (field, value) in record {
+            let Some(rule) = self.fields.get(&field) else { continue };
+            if value.is_null() {
+                exported.insert(field, value);
+                continue;
+            }
+            let transformed = match rule {
+                FieldRule::Keep => value,
+                FieldRule::Pseudonymize => match &value {
+                    serde_json::Value::String(identifier) => self.pseudonymize_id(identifier).into(),
+                    serde_json::Value::Number(identifier) => self.pseudonymize_id(&identifier.to_string()).into(),
+                    _ => return Err(format!("Field {} is not an identifier", field)),
+                },
+                FieldRule::Timestamp => {
+                    let timestamp = value.as_u64().ok_or_else(|| format!("Field {} is not a millisecond timestamp", field))?;
+                    self.coarsen_timestamp(subject_id, timestamp).into()
+                }
+                FieldRule::Bucket(width) => {
+                    let number = value.as_f64().ok_or_else(|| format!("Field {} is not a number", field))?;
+                    serde_json::json!((number / width).floor() * width)
+                }
+                FieldRule::Truncate(keep_chars) => {
+                    let text = value.as_str().ok_or_else(|| format!("Field {} is not a string", field))?;
+                    text.chars().take(*keep_chars).collect::<String>().into()
+                }
+            };
+            exported.insert(field, transformed);
+        }
+        serde_json::to_string(&exported).map_err(|e| format!("Failed to serialize record: {}", e))
+    }
+
+    fn date_shift_days(&self, subject_id: &str) -> i64 {
+        if self.max_date_shift_days == 0 {
+            return 0;
+        }
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(self.date_shift_key.as_ref()).expect("HMAC accepts any key length");
+        mac.update(subject_id.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        let mut draw = [0u8; 8];
+        draw.copy_from_slice(&digest[..8]);
+        let span = 2 * self.max_date_shift_days as u64 + 1;
+        (u64::from_be_bytes(draw) % span) as i64 - self.max_date_shift_days as i64
+    }
+}
+
+fn floor_to(timestamp_ms: i64, granularity: TimeGranularity) -> i64 {
+    match granularity {
+        TimeGranularity::Hour => timestamp_ms - timestamp_ms % (DAY_MS / 24),
+        TimeGranularity::Day => timestamp_ms - timestamp_ms % DAY_MS,
+        TimeGranularity::Week => timestamp_ms - timestamp_ms % (7 * DAY_MS),
+        TimeGranularity::Month => DateTime::<Utc>::from_timestamp_millis(timestamp_ms)
+            .and_then(|time| NaiveDate::from_ymd_opt(time.year(), time.month(), 1))
+            .and_then(|first| first.and_hms_opt(0, 0, 0))
+            .map_or(timestamp_ms, |midnight| midnight.and_utc().timestamp_millis()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARCH_14_2024_NOON: u64 = 1_710_417_600_000;
+
+    fn pseudonymizer(study_id: &str) -> ResearchPseudonymizer {
+        let mut pseudonymizer = ResearchPseudonymizer::with_key(&[7u8; 32], study_id).unwrap();
+        pseudonymizer.pseudonymize_field("entry_id".into());
+        pseudonymizer.timestamp_field("recorded_at".into());
+        pseudonymizer.fields.insert("age".into(), FieldRule::Bucket(5.0));
+        pseudonymizer.truncate_field("postcode".into(), 3);
+        pseudonymizer.keep_field("flow".into());
+        pseudonymizer.set_max_date_shift_days(30);
+        pseudonymizer
+    }
+
+    #[test]
+    fn test_exports_are_consistent_per_study_and_drop_unlisted_fields() {
+        let record = serde_json::json!({
+            "entry_id": "entry-42", "recorded_at": MARCH_14_2024_NOON, "age": 34, "postcode": "SW1A 1AA",
+            "flow": "light", "name": "Jane Doe", "email": "jane@example.org",
+        })
+        .to_string();
+
+        let first = pseudonymizer("study-a").transform_record("user-1", &record).unwrap();
+        let again = pseudonymizer("study-a").transform_record("user-1", &record).unwrap();
+        assert_eq!(first, again);
+        let exported: serde_json::Value = serde_json::from_str(&first).unwrap();
+        assert!(exported.get("name").is_none() && exported.get("email").is_none() && !first.contains("user-1"));
+        assert_eq!((exported["age"].as_f64(), exported["postcode"].as_str(), exported["flow"].as_str()), (Some(30.0), Some("SW1"), Some("light")));
+        assert!(exported["entry_id"].as_str().unwrap().starts_with("p_"));
+
+        // Shift is fixed per subject, so intervals between a subject's entries survive
+        let study = pseudonymizer("study-a");
+        let shifted = study.coarsen_timestamp("user-1", MARCH_14_2024_NOON);
+        assert_eq!(exported["recorded_at"].as_u64(), Some(shifted));
+        assert_eq!(study.coarsen_timestamp("user-1", MARCH_14_2024_NOON + 3 * DAY_MS as u64), shifted + 3 * DAY_MS as u64);
+        assert!(shifted.abs_diff(MARCH_14_2024_NOON) <= 30 * DAY_MS as u64 + DAY_MS as u64);
+        assert_eq!(shifted % DAY_MS as u64, 0);
+
+        let other_study = pseudonymizer("study-b").transform_record("user-1", &record).unwrap();
+        let other: serde_json::Value = serde_json::from_str(&other_study).unwrap();
+        assert_ne!(other["subject"], exported["subject"]);
+        assert_ne!(other["entry_id"], exported["entry_id"]);
+
+        assert_eq!(floor_to(MARCH_14_2024_NOON as i64, TimeGranularity::Month), 1_709_251_200_000);
+        assert!(study.transform_record("user-1", r#"{"age":"old"}"#).is_err());
+        assert!(ResearchPseudonymizer::with_key(&[7u8; 16], "study-a").is_err());
+    }
+}
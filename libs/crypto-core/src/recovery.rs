@@ -1,8 +1,14 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use crate::memory::{track_secret_allocation, track_secret_zeroization};
 use crate::keys::CryptoKey;
+use crate::envelope::KDFParams;
+use crate::integration::{require_owner_js, RestrictedOperation};
+use crate::scope::{enter_scope, ScopeContext};
+use crate::platform;
+use crate::recovery_rehearsal::seal_rehearsal_target;
+use crate::threshold::{ShamirScheme, ThresholdScheme};
 // use crate::derivation::HierarchicalKeyDerivation; // Unused import removed
 
 /// BIP39 wordlist languages supported for recovery phrases
@@ -166,6 +172,8 @@ pub struct KeyBackup {
     backup_timestamp: u64,
     version: u32,
     metadata: String, // JSON metadata
+    #[serde(default)]
+    rehearsal_target: Option<Vec<u8>>, // Throwaway ciphertext for recovery rehearsals
 }
 
 #[wasm_bindgen]
@@ -191,6 +199,7 @@ impl KeyBackup {
             backup_timestamp,
             version,
             metadata,
+            rehearsal_target: None,
         }
     }
 
@@ -233,6 +242,29 @@ impl KeyBackup {
     pub fn metadata(&self) -> String {
         self.metadata.clone()
     }
+
+    /// Opened by `RecoverySystem::rehearse_recovery` instead of the master key; None for older backups
+    #[wasm_bindgen(getter)]
+    pub fn rehearsal_target(&self) -> Option<Vec<u8>> {
+        self.rehearsal_target.clone()
+    }
+
+    /// KDF recorded at backup time, so restore can re-derive on any device
+    #[wasm_bindgen(getter)]
+    pub fn kdf_params(&self) -> Option<KDFParams> {
+        let metadata: serde_json::Value = serde_json::from_str(&self.metadata).ok()?;
+        KDFParams::from_json_value(&metadata["kdf"])
+    }
+}
+
+impl KeyBackup {
+    pub(crate) fn matches_phrase(&self, recovery_phrase: &RecoveryPhrase) -> bool {
+        recovery_phrase.validate() && simple_hash(recovery_phrase.phrase_string().as_bytes()) == self.recovery_phrase_hash
+    }
+
+    pub(crate) fn accepts_passkey_response(&self, passkey_response: &[u8]) -> bool {
+        validate_passkey_response(&self.passkey_challenge, passkey_response)
+    }
 }
 
 /// Recovery validation levels for emergency procedures
@@ -245,6 +277,93 @@ pub enum RecoveryValidationLevel {
     Emergency = 3,  // Multi-factor with time delay
 }
 
+/// Step-by-step construction of a `RecoverySystem`, validated at `build()`
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct RecoverySystemBuilder {
+    device_id: String,
+    validation_level: u8,
+    max_attempts: u32,
+    lockout_duration_ms: u64,
+    kdf_params: Option<KDFParams>,
+}
+
+#[wasm_bindgen]
+impl RecoverySystemBuilder {
+    /// Start from standard validation, 5 attempts and a 15 minute lockout
+    #[wasm_bindgen(constructor)]
+    pub fn new(device_id: String) -> Self {
+        Self {
+            device_id,
+            validation_level: RecoveryValidationLevel::Standard as u8,
+            max_attempts: 5,
+            lockout_duration_ms: 15 * 60 * 1000,
+            kdf_params: None,
+        }
+    }
+
+    #[wasm_bindgen(js_name = validationLevel)]
+    pub fn validation_level(mut self, level: RecoveryValidationLevel) -> Self {
+        self.validation_level = level as u8;
+        self
+    }
+
+    #[wasm_bindgen(js_name = maxAttempts)]
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    #[wasm_bindgen(js_name = lockoutDurationMs)]
+    pub fn lockout_duration_ms(mut self, duration_ms: u64) -> Self {
+        self.lockout_duration_ms = duration_ms;
+        self
+    }
+
+    #[wasm_bindgen(js_name = kdfParams)]
+    pub fn kdf_params(mut self, params: KDFParams) -> Self {
+        self.kdf_params = Some(params);
+        self
+    }
+
+    #[wasm_bindgen]
+    pub fn build(self) -> Result<RecoverySystem, JsValue> {
+        self.validate().map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.finish())
+    }
+}
+
+impl RecoverySystemBuilder {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.device_id.is_empty() {
+            return Err("Device id must not be empty".to_string());
+        }
+        if self.validation_level > RecoveryValidationLevel::Emergency as u8 {
+            return Err("Unknown recovery validation level".to_string());
+        }
+        if self.max_attempts == 0 {
+            return Err("Max attempts must be greater than 0".to_string());
+        }
+        if self.lockout_duration_ms == 0 {
+            return Err("Lockout duration must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Unvalidated result, used by the legacy constructor
+    pub(crate) fn finish(self) -> RecoverySystem {
+        RecoverySystem {
+            device_id: self.device_id,
+            key_backups: HashMap::new(),
+            recovery_attempts: HashMap::new(),
+            validation_level: self.validation_level,
+            max_attempts: self.max_attempts,
+            lockout_duration_ms: self.lockout_duration_ms,
+            kdf_params: self.kdf_params,
+        }
+    }
+}
+
 /// Recovery system manager integrating with Passkeys authentication
 #[wasm_bindgen]
 pub struct RecoverySystem {
@@ -254,6 +373,7 @@ pub struct RecoverySystem {
     validation_level: u8, // RecoveryValidationLevel as u8
     max_attempts: u32,
     lockout_duration_ms: u64,
+    kdf_params: Option<KDFParams>, // Negotiated KDF recorded in backup metadata
 }
 
 #[wasm_bindgen]
@@ -266,14 +386,20 @@ impl RecoverySystem {
         max_attempts: u32,
         lockout_duration_ms: u64,
     ) -> Self {
-        Self {
+        RecoverySystemBuilder {
             device_id,
-            key_backups: HashMap::new(),
-            recovery_attempts: HashMap::new(),
             validation_level,
             max_attempts,
             lockout_duration_ms,
+            kdf_params: None,
         }
+        .finish()
+    }
+
+    /// Set the KDF negotiated for this device (see `DeviceCapabilityDetector::select_kdf_params`)
+    #[wasm_bindgen]
+    pub fn set_kdf_params(&mut self, params: KDFParams) {
+        self.kdf_params = Some(params);
     }
 
     /// Create key backup with recovery phrase and passkey integration
@@ -284,6 +410,8 @@ impl RecoverySystem {
         recovery_phrase: &RecoveryPhrase,
         passkey_challenge: Vec<u8>,
     ) -> Result<KeyBackup, JsValue> {
+        require_owner_js(RestrictedOperation::Recovery)?;
+        let _scope = enter_scope(ScopeContext::for_device(self.device_id.clone()));
         if !recovery_phrase.validate() {
             return Err(JsValue::from_str("Invalid recovery phrase"));
         }
@@ -291,7 +419,7 @@ impl RecoverySystem {
         let backup_id = format!(
             "backup_{}_{}", 
             self.device_id, 
-            js_sys::Date::now() as u64
+            platform::now_ms()
         );
 
         // Hash the recovery phrase for verification
@@ -302,25 +430,28 @@ impl RecoverySystem {
         // Encrypt master key with recovery phrase seed
         let seed = recovery_phrase.to_seed("")?;
         let encrypted_master_key = encrypt_with_seed(&seed, hierarchical_key)?;
+        let rehearsal_target = seal_rehearsal_target(&seed, &backup_id).map_err(|e| JsValue::from_str(&e))?;
 
         let metadata = serde_json::json!({
             "device_id": self.device_id,
-            "created_at": js_sys::Date::now(),
+            "created_at": platform::now_ms() as f64,
             "validation_level": self.validation_level,
             "word_count": recovery_phrase.word_count(),
             "language": recovery_phrase.language(),
+            "kdf": self.kdf_params.as_ref().map(|params| params.to_json_value()),
         }).to_string();
 
-        let backup = KeyBackup::new(
+        let mut backup = KeyBackup::new(
             backup_id.clone(),
             self.device_id.clone(),
             encrypted_master_key,
             recovery_phrase_hash,
             passkey_challenge,
-            js_sys::Date::now() as u64,
+            platform::now_ms(),
             1, // Version 1
             metadata,
         );
+        backup.rehearsal_target = Some(rehearsal_target);
 
         self.key_backups.insert(backup_id, backup.clone());
         track_secret_allocation();
@@ -336,52 +467,10 @@ impl RecoverySystem {
         recovery_phrase: &RecoveryPhrase,
         passkey_response: Vec<u8>,
     ) -> Result<String, JsValue> {
-        // Check attempt limits
-        let attempt_count = self.recovery_attempts.get(&backup_id).unwrap_or(&0);
-        if *attempt_count >= self.max_attempts {
-            return Err(JsValue::from_str("Recovery attempts exceeded - account locked"));
-        }
-
-        let backup = self.key_backups.get(&backup_id)
-            .ok_or_else(|| JsValue::from_str("Backup not found"))?;
-
-        // Validate recovery phrase
-        if !recovery_phrase.validate() {
-            self.increment_attempt_count(&backup_id);
-            return Err(JsValue::from_str("Invalid recovery phrase"));
-        }
-
-        // Verify recovery phrase matches backup
-        let phrase_string = recovery_phrase.phrase_string();
-        let phrase_bytes = phrase_string.as_bytes();
-        let phrase_hash = simple_hash(phrase_bytes);
-        
-        if phrase_hash != backup.recovery_phrase_hash() {
-            self.increment_attempt_count(&backup_id);
-            return Err(JsValue::from_str("Recovery phrase does not match backup"));
-        }
-
-        // Validate passkey response (simplified)
-        if self.validation_level >= RecoveryValidationLevel::Standard as u8 {
-            if !validate_passkey_response(&backup.passkey_challenge(), &passkey_response) {
-                self.increment_attempt_count(&backup_id);
-                return Err(JsValue::from_str("Passkey authentication failed"));
-            }
-        }
-
-        // Generate recovery token
-        let recovery_token = format!(
-            "recovery_{}_{}_{}",
-            backup_id,
-            self.device_id,
-            js_sys::Date::now() as u64
-        );
-
-        // Reset attempt count on successful initiation
-        self.recovery_attempts.remove(&backup_id);
-        track_secret_allocation();
-
-        Ok(recovery_token)
+        require_owner_js(RestrictedOperation::Recovery)?;
+        let _scope = enter_scope(ScopeContext::for_device(self.device_id.clone()));
+        self.initiate_recovery_at(&backup_id, recovery_phrase, &passkey_response, platform::now_ms())
+            .map_err(|e| JsValue::from_str(&e))
     }
 
     /// Complete recovery and restore hierarchical key
@@ -392,6 +481,8 @@ impl RecoverySystem {
         recovery_token: String,
         recovery_phrase: &RecoveryPhrase,
     ) -> Result<Vec<u8>, JsValue> {
+        require_owner_js(RestrictedOperation::Recovery)?;
+        let _scope = enter_scope(ScopeContext::for_device(self.device_id.clone()));
         // Validate recovery token format
         if !recovery_token.starts_with("recovery_") {
             return Err(JsValue::from_str("Invalid recovery token"));
@@ -417,6 +508,8 @@ impl RecoverySystem {
         emergency_code: String,
         passkey_response: Vec<u8>,
     ) -> Result<String, JsValue> {
+        require_owner_js(RestrictedOperation::Recovery)?;
+        let _scope = enter_scope(ScopeContext::for_device(self.device_id.clone()));
         if self.validation_level != RecoveryValidationLevel::Emergency as u8 {
             return Err(JsValue::from_str("Emergency recovery not enabled"));
         }
@@ -431,7 +524,7 @@ impl RecoverySystem {
             "emergency_delay_{}_{}_{}",
             backup_id,
             self.device_id,
-            js_sys::Date::now() as u64 + self.lockout_duration_ms
+            platform::now_ms() + self.lockout_duration_ms
         );
 
         track_secret_allocation();
@@ -448,7 +541,7 @@ impl RecoverySystem {
         // Extract timestamp from token (simplified parsing)
         if let Some(timestamp_str) = delay_token.split('_').last() {
             if let Ok(unlock_time) = timestamp_str.parse::<u64>() {
-                return js_sys::Date::now() as u64 >= unlock_time;
+                return platform::now_ms() >= unlock_time;
             }
         }
 
@@ -491,6 +584,7 @@ impl RecoverySystem {
     /// Remove old backup
     #[wasm_bindgen]
     pub fn remove_backup(&mut self, backup_id: String) -> Result<(), JsValue> {
+        require_owner_js(RestrictedOperation::Recovery)?;
         if self.key_backups.remove(&backup_id).is_some() {
             track_secret_zeroization();
             Ok(())
@@ -540,6 +634,87 @@ impl RecoverySystem {
     }
 }
 
+impl RecoverySystem {
+    pub(crate) fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    pub(crate) fn backup(&self, backup_id: &str) -> Option<&KeyBackup> {
+        self.key_backups.get(backup_id)
+    }
+
+    pub(crate) fn requires_passkey(&self) -> bool {
+        self.validation_level >= RecoveryValidationLevel::Standard as u8
+    }
+
+    pub(crate) fn attempts_exhausted(&self, backup_id: &str) -> bool {
+        self.recovery_attempts.get(backup_id).is_some_and(|count| *count >= self.max_attempts)
+    }
+
+    pub(crate) fn count_failed_attempt(&mut self, backup_id: &str) {
+        self.increment_attempt_count(backup_id);
+    }
+
+    pub(crate) fn insert_backup(&mut self, backup: KeyBackup) {
+        self.key_backups.insert(backup.backup_id.clone(), backup);
+    }
+
+    pub(crate) fn initiate_recovery_at(
+        &mut self,
+        backup_id: &str,
+        recovery_phrase: &RecoveryPhrase,
+        passkey_response: &[u8],
+        now: u64,
+    ) -> Result<String, String> {
+        // Check attempt limits
+        let attempt_count = self.recovery_attempts.get(backup_id).unwrap_or(&0);
+        if *attempt_count >= self.max_attempts {
+            return Err("Recovery attempts exceeded - account locked".to_string());
+        }
+
+        let backup = self.key_backups.get(backup_id)
+            .ok_or_else(|| "Backup not found".to_string())?;
+
+        // Validate recovery phrase
+        if !recovery_phrase.validate() {
+            self.increment_attempt_count(backup_id);
+            return Err("Invalid recovery phrase".to_string());
+        }
+
+        // Verify recovery phrase matches backup
+        let phrase_string = recovery_phrase.phrase_string();
+        let phrase_bytes = phrase_string.as_bytes();
+        let phrase_hash = simple_hash(phrase_bytes);
+        
+        if phrase_hash != backup.recovery_phrase_hash() {
+            self.increment_attempt_count(backup_id);
+            return Err("Recovery phrase does not match backup".to_string());
+        }
+
+        // Validate passkey response (simplified)
+        if self.validation_level >= RecoveryValidationLevel::Standard as u8 {
+            if !validate_passkey_response(&backup.passkey_challenge(), passkey_response) {
+                self.increment_attempt_count(backup_id);
+                return Err("Passkey authentication failed".to_string());
+            }
+        }
+
+        // Generate recovery token
+        let recovery_token = format!(
+            "recovery_{}_{}_{}",
+            backup_id,
+            self.device_id,
+            now
+        );
+
+        // Reset attempt count on successful initiation
+        self.recovery_attempts.remove(backup_id);
+        track_secret_allocation();
+
+        Ok(recovery_token)
+    }
+}
+
 impl Drop for RecoverySystem {
     fn drop(&mut self) {
         // Clear sensitive data when dropping
@@ -549,6 +724,519 @@ impl Drop for RecoverySystem {
     }
 }
 
+/// Independent ways of recovering the master key
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecoveryMethodKind {
+    Phrase = 0,
+    PasskeyPrf = 1,
+    ShamirShares = 2,
+}
+
+impl RecoveryMethodKind {
+    // Rough chance the method is still usable when needed, used only for the combined score
+    fn availability(&self, threshold: u8, share_count: u8) -> f64 {
+        match self {
+            RecoveryMethodKind::Phrase => 0.6,      // Paper copies get lost
+            RecoveryMethodKind::PasskeyPrf => 0.7,  // Tied to a synced passkey provider
+            RecoveryMethodKind::ShamirShares if threshold < share_count => 0.8,
+            RecoveryMethodKind::ShamirShares => 0.5, // Every share required
+        }
+    }
+}
+
+/// One enrolled recovery method and the key versions it can restore
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryMethod {
+    method_id: String,
+    kind: RecoveryMethodKind,
+    label: String,
+    enrolled_at: u64,
+    revoked_at: Option<u64>,
+    threshold: u8,   // Shamir only
+    share_count: u8, // Shamir only
+    covered_key_versions: BTreeSet<String>,
+}
+
+#[wasm_bindgen]
+impl RecoveryMethod {
+    #[wasm_bindgen(getter)]
+    pub fn method_id(&self) -> String {
+        self.method_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> RecoveryMethodKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn enrolled_at(&self) -> u64 {
+        self.enrolled_at
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn revoked_at(&self) -> Option<u64> {
+        self.revoked_at
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    #[wasm_bindgen]
+    pub fn covered_key_versions(&self) -> Vec<String> {
+        self.covered_key_versions.iter().cloned().collect()
+    }
+
+    #[wasm_bindgen]
+    pub fn covers(&self, key_version: &str) -> bool {
+        self.covered_key_versions.contains(key_version)
+    }
+}
+
+/// Combined recovery posture across all active methods
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveryStrength {
+    score: u8,
+    active_methods: usize,
+    uncovered_key_versions: Vec<String>,
+    single_method_key_versions: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl RecoveryStrength {
+    /// 0-100: chance at least one method still restores the worst-covered key version
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> u8 {
+        self.score
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn active_methods(&self) -> usize {
+        self.active_methods
+    }
+
+    /// Key versions no active method can restore
+    #[wasm_bindgen]
+    pub fn uncovered_key_versions(&self) -> Vec<String> {
+        self.uncovered_key_versions.clone()
+    }
+
+    /// Key versions that depend on a single method
+    #[wasm_bindgen]
+    pub fn single_method_key_versions(&self) -> Vec<String> {
+        self.single_method_key_versions.clone()
+    }
+}
+
+/// Registry of enrolled recovery methods; each can be revoked on its own
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryMethodRegistry {
+    methods: Vec<RecoveryMethod>,
+}
+
+#[wasm_bindgen]
+impl RecoveryMethodRegistry {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> RecoveryMethodRegistry {
+        Self::default()
+    }
+
+    /// Enroll a recovery phrase or passkey-PRF backup; returns the method id
+    #[wasm_bindgen]
+    pub fn enroll(&mut self, kind: RecoveryMethodKind, label: String) -> Result<String, JsValue> {
+        if kind == RecoveryMethodKind::ShamirShares {
+            return Err(JsValue::from_str("Use enroll_shamir for Shamir shares"));
+        }
+        Ok(self.enroll_at(kind, label, 0, 0, platform::now_ms()))
+    }
+
+    /// Enroll a `threshold`-of-`share_count` Shamir split; returns the method id
+    #[wasm_bindgen]
+    pub fn enroll_shamir(&mut self, label: String, threshold: u8, share_count: u8) -> Result<String, JsValue> {
+        ShamirScheme.validate_parameters(threshold, share_count).map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.enroll_at(RecoveryMethodKind::ShamirShares, label, threshold, share_count, platform::now_ms()))
+    }
+
+    /// Record that a method can restore `key_version` (e.g. after re-wrapping on rotation)
+    #[wasm_bindgen]
+    pub fn add_coverage(&mut self, method_id: &str, key_version: String) -> Result<(), JsValue> {
+        let method = self.active_method_mut(method_id).map_err(|e| JsValue::from_str(&e))?;
+        method.covered_key_versions.insert(key_version);
+        Ok(())
+    }
+
+    /// Revoke one method; the others keep their coverage. False if unknown or already revoked
+    #[wasm_bindgen]
+    pub fn revoke(&mut self, method_id: &str) -> bool {
+        self.revoke_at(method_id, platform::now_ms())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_method(&self, method_id: &str) -> Option<RecoveryMethod> {
+        self.methods.iter().find(|method| method.method_id == method_id).cloned()
+    }
+
+    #[wasm_bindgen]
+    pub fn active_method_ids(&self) -> Vec<String> {
+        self.active().map(|method| method.method_id.clone()).collect()
+    }
+
+    /// Combined strength over the key versions that must stay recoverable
+    #[wasm_bindgen]
+    pub fn recovery_strength(&self, key_versions: Vec<String>) -> RecoveryStrength {
+        let mut uncovered_key_versions = Vec::new();
+        let mut single_method_key_versions = Vec::new();
+        let mut worst = if key_versions.is_empty() { self.coverage_probability(None) } else { 1.0 };
+
+        for key_version in &key_versions {
+            match self.active().filter(|method| method.covers(key_version)).count() {
+                0 => uncovered_key_versions.push(key_version.clone()),
+                1 => single_method_key_versions.push(key_version.clone()),
+                _ => {}
+            }
+            worst = worst.min(self.coverage_probability(Some(key_version)));
+        }
+
+        RecoveryStrength {
+            score: (worst * 100.0).round() as u8,
+            active_methods: self.active().count(),
+            uncovered_key_versions,
+            single_method_key_versions,
+        }
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize recovery methods: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<RecoveryMethodRegistry, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid recovery method registry: {}", e)))
+    }
+}
+
+impl RecoveryMethodRegistry {
+    pub(crate) fn enroll_at(
+        &mut self,
+        kind: RecoveryMethodKind,
+        label: String,
+        threshold: u8,
+        share_count: u8,
+        now: u64,
+    ) -> String {
+        let method_id = platform::new_uuid();
+        self.methods.push(RecoveryMethod {
+            method_id: method_id.clone(),
+            kind,
+            label,
+            enrolled_at: now,
+            revoked_at: None,
+            threshold,
+            share_count,
+            covered_key_versions: BTreeSet::new(),
+        });
+        method_id
+    }
+
+    pub(crate) fn revoke_at(&mut self, method_id: &str, now: u64) -> bool {
+        match self.active_method_mut(method_id) {
+            Ok(method) => {
+                method.revoked_at = Some(now);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn active(&self) -> impl Iterator<Item = &RecoveryMethod> {
+        self.methods.iter().filter(|method| method.is_active())
+    }
+
+    fn active_method_mut(&mut self, method_id: &str) -> Result<&mut RecoveryMethod, String> {
+        self.methods
+            .iter_mut()
+            .find(|method| method.method_id == method_id && method.is_active())
+            .ok_or_else(|| "Recovery method not found or revoked".to_string())
+    }
+
+    // Methods fail independently, so recovery fails only if every covering method does
+    fn coverage_probability(&self, key_version: Option<&str>) -> f64 {
+        let all_fail: f64 = self
+            .active()
+            .filter(|method| key_version.is_none_or(|version| method.covers(version)))
+            .map(|method| 1.0 - method.kind.availability(method.threshold, method.share_count))
+            .product();
+        1.0 - all_fail
+    }
+}
+
+/// Estimated time to crack a passphrase offline against a slow KDF (~10^4 guesses/s)
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CrackTimeClass {
+    Instant = 0,   // Under a minute
+    Minutes = 1,
+    Hours = 2,
+    Days = 3,
+    Months = 4,
+    Years = 5,
+    Centuries = 6,
+}
+
+/// Stable feedback codes so every platform can localize the same hints
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassphraseFeedback {
+    TooShort = 0,
+    CommonPassword = 1,
+    RepeatedCharacters = 2,
+    SequentialCharacters = 3,
+    RepeatedPattern = 4,
+    SingleCharacterClass = 5,
+    AddMoreWords = 6,
+}
+
+impl PassphraseFeedback {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PassphraseFeedback::TooShort => "too_short",
+            PassphraseFeedback::CommonPassword => "common_password",
+            PassphraseFeedback::RepeatedCharacters => "repeated_characters",
+            PassphraseFeedback::SequentialCharacters => "sequential_characters",
+            PassphraseFeedback::RepeatedPattern => "repeated_pattern",
+            PassphraseFeedback::SingleCharacterClass => "single_character_class",
+            PassphraseFeedback::AddMoreWords => "add_more_words",
+        }
+    }
+}
+
+/// Minimum score (0-4) recommended for BIP39 and backup passphrases
+pub const MIN_PASSPHRASE_SCORE: u8 = 3;
+
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+const OFFLINE_GUESSES_PER_SECOND: f64 = 1e4;
+
+// Small built-in dictionary; matches are scored as dictionary guesses instead of characters
+const COMMON_PASSWORDS: &[&str] = &[
+    "password", "123456", "12345678", "qwerty", "letmein", "iloveyou", "admin",
+    "welcome", "monkey", "dragon", "abc123", "111111", "sunshine", "princess",
+    "football", "baseball", "master", "shadow", "trustno1", "passw0rd",
+];
+
+/// zxcvbn-style passphrase strength estimate
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct PassphraseStrength {
+    entropy_bits: f64,
+    score: u8,
+    crack_time_class: CrackTimeClass,
+    feedback: Vec<PassphraseFeedback>,
+}
+
+#[wasm_bindgen]
+impl PassphraseStrength {
+    /// Estimate strength of an optional BIP39 or backup passphrase
+    #[wasm_bindgen]
+    pub fn estimate(passphrase: &str) -> PassphraseStrength {
+        let chars: Vec<char> = passphrase.chars().collect();
+        let mut feedback = Vec::new();
+
+        if chars.len() < MIN_PASSPHRASE_LENGTH {
+            feedback.push(PassphraseFeedback::TooShort);
+        }
+
+        let lowercase = passphrase.to_lowercase();
+        let common_match = COMMON_PASSWORDS.iter()
+            .filter(|word| lowercase.contains(*word))
+            .map(|word| word.chars().count())
+            .max();
+
+        let entropy_bits = match repeated_unit_length(&chars) {
+            // "abcabcabc" costs little more than guessing "abc" and the repeat count
+            Some(unit) => {
+                feedback.push(PassphraseFeedback::RepeatedPattern);
+                let repeats = (chars.len() / unit) as f64;
+                sequence_entropy(&chars[..unit], common_match, &mut feedback) + repeats.log2()
+            }
+            None => sequence_entropy(&chars, common_match, &mut feedback),
+        };
+
+        if !chars.is_empty() && character_class_count(&chars) == 1 {
+            feedback.push(PassphraseFeedback::SingleCharacterClass);
+        }
+
+        let guesses_log10 = entropy_bits * std::f64::consts::LOG10_2;
+        let score = match guesses_log10 {
+            g if g < 3.0 => 0,
+            g if g < 6.0 => 1,
+            g if g < 8.0 => 2,
+            g if g < 10.0 => 3,
+            _ => 4,
+        };
+
+        if score < MIN_PASSPHRASE_SCORE && !passphrase.contains(' ') {
+            feedback.push(PassphraseFeedback::AddMoreWords);
+        }
+
+        // Attacker finds the passphrase after searching half the space on average
+        let seconds = 2f64.powf(entropy_bits - 1.0) / OFFLINE_GUESSES_PER_SECOND;
+
+        PassphraseStrength {
+            entropy_bits,
+            score,
+            crack_time_class: crack_time_class(seconds),
+            feedback,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn entropy_bits(&self) -> f64 {
+        self.entropy_bits
+    }
+
+    /// 0 (trivially guessable) to 4 (very strong)
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> u8 {
+        self.score
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn crack_time_class(&self) -> CrackTimeClass {
+        self.crack_time_class
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn feedback_codes(&self) -> Vec<String> {
+        self.feedback.iter().map(|f| f.code().to_string()).collect()
+    }
+
+    #[wasm_bindgen]
+    pub fn meets_minimum(&self, min_score: u8) -> bool {
+        self.score >= min_score
+    }
+}
+
+impl PassphraseStrength {
+    pub fn feedback(&self) -> &[PassphraseFeedback] {
+        &self.feedback
+    }
+}
+
+// Per-character entropy with discounts for repeats, sequences and dictionary words
+fn sequence_entropy(chars: &[char], common_match: Option<usize>, feedback: &mut Vec<PassphraseFeedback>) -> f64 {
+    if chars.is_empty() {
+        return 0.0;
+    }
+
+    let per_char = (charset_size(chars) as f64).log2();
+    let mut entropy = 0.0;
+    let mut repeated = false;
+    let mut sequential = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        let previous = if i > 0 { Some(chars[i - 1]) } else { None };
+        entropy += match previous {
+            Some(p) if p == c => {
+                repeated = true;
+                1.0
+            }
+            Some(p) if (c as i64 - p as i64).abs() == 1 => {
+                sequential = true;
+                1.5
+            }
+            _ => per_char,
+        };
+    }
+
+    // Replace the characters of a dictionary match with a dictionary lookup
+    if let Some(matched_len) = common_match {
+        feedback.push(PassphraseFeedback::CommonPassword);
+        let dictionary_bits = (COMMON_PASSWORDS.len() as f64).log2() + 1.0; // +1 for capitalization
+        entropy = (entropy - matched_len as f64 * per_char).max(0.0) + dictionary_bits;
+    }
+
+    if repeated {
+        feedback.push(PassphraseFeedback::RepeatedCharacters);
+    }
+    if sequential {
+        feedback.push(PassphraseFeedback::SequentialCharacters);
+    }
+
+    entropy
+}
+
+fn character_class_count(chars: &[char]) -> usize {
+    [
+        chars.iter().any(|c| c.is_ascii_lowercase()),
+        chars.iter().any(|c| c.is_ascii_uppercase()),
+        chars.iter().any(|c| c.is_ascii_digit()),
+        chars.iter().any(|c| c.is_ascii_punctuation() || *c == ' '),
+        chars.iter().any(|c| !c.is_ascii()),
+    ]
+    .iter()
+    .filter(|present| **present)
+    .count()
+}
+
+fn charset_size(chars: &[char]) -> u32 {
+    let mut size = 0;
+    if chars.iter().any(|c| c.is_ascii_lowercase()) {
+        size += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_uppercase()) {
+        size += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_digit()) {
+        size += 10;
+    }
+    if chars.iter().any(|c| c.is_ascii_punctuation() || *c == ' ') {
+        size += 33;
+    }
+    if chars.iter().any(|c| !c.is_ascii()) {
+        size += 100;
+    }
+    size.max(1)
+}
+
+// Shortest unit that tiles the whole passphrase at least twice
+fn repeated_unit_length(chars: &[char]) -> Option<usize> {
+    (1..=chars.len() / 2)
+        .filter(|unit| chars.len().is_multiple_of(*unit))
+        .find(|&unit| chars.chunks(unit).all(|chunk| chunk == &chars[..unit]))
+}
+
+fn crack_time_class(seconds: f64) -> CrackTimeClass {
+    const MINUTE: f64 = 60.0;
+    const HOUR: f64 = MINUTE * 60.0;
+    const DAY: f64 = HOUR * 24.0;
+    const MONTH: f64 = DAY * 31.0;
+    const YEAR: f64 = MONTH * 12.0;
+
+    match seconds {
+        s if s < MINUTE => CrackTimeClass::Instant,
+        s if s < HOUR => CrackTimeClass::Minutes,
+        s if s < DAY => CrackTimeClass::Hours,
+        s if s < MONTH => CrackTimeClass::Days,
+        s if s < YEAR => CrackTimeClass::Months,
+        s if s < YEAR * 100.0 => CrackTimeClass::Years,
+        _ => CrackTimeClass::Centuries,
+    }
+}
+
 // Helper functions for BIP39 and cryptographic operations
 
 fn generate_bip39_words(entropy_bits: usize, language: u8, word_count: usize) -> Result<Vec<String>, JsValue> {
@@ -742,31 +1430,104 @@ mod tests {
         ).unwrap();
 
         // First failed attempt
-        let result1 = recovery_system.initiate_recovery(
-            backup.backup_id(),
+        let result1 = recovery_system.initiate_recovery_at(
+            &backup.backup_id(),
             &wrong_phrase,
-            vec![1, 2, 3, 4],
+            &[1, 2, 3, 4],
+            0,
         );
         assert!(result1.is_err());
         assert_eq!(recovery_system.get_attempt_count(backup.backup_id()), 1);
 
         // Second failed attempt
-        let result2 = recovery_system.initiate_recovery(
-            backup.backup_id(),
+        let result2 = recovery_system.initiate_recovery_at(
+            &backup.backup_id(),
             &wrong_phrase,
-            vec![1, 2, 3, 4],
+            &[1, 2, 3, 4],
+            0,
         );
         assert!(result2.is_err());
         assert_eq!(recovery_system.get_attempt_count(backup.backup_id()), 2);
         assert!(recovery_system.is_backup_locked(backup.backup_id()));
 
         // Third attempt should be blocked
-        let result3 = recovery_system.initiate_recovery(
-            backup.backup_id(),
+        let result3 = recovery_system.initiate_recovery_at(
+            &backup.backup_id(),
             &phrase, // Even with correct phrase
-            vec![1, 2, 3, 4],
+            &[1, 2, 3, 4],
+            0,
         );
         assert!(result3.is_err());
-        assert!(result3.unwrap_err().as_string().unwrap().contains("locked"));
+        assert!(result3.unwrap_err().contains("locked"));
+    }
+
+    #[test]
+    fn test_passphrase_strength_weak_inputs() {
+        let common = PassphraseStrength::estimate("Password1");
+        assert!(common.score() <= 1);
+        assert!(common.feedback().contains(&PassphraseFeedback::CommonPassword));
+
+        let repeated = PassphraseStrength::estimate("abcabcabcabc");
+        assert!(repeated.feedback().contains(&PassphraseFeedback::RepeatedPattern));
+        assert!(!repeated.meets_minimum(MIN_PASSPHRASE_SCORE));
+
+        let empty = PassphraseStrength::estimate("");
+        assert_eq!(empty.score(), 0);
+        assert_eq!(empty.crack_time_class(), CrackTimeClass::Instant);
+        assert!(empty.feedback_codes().contains(&"too_short".to_string()));
+    }
+
+    #[test]
+    fn test_passphrase_strength_strong_input() {
+        let strong = PassphraseStrength::estimate("orbit Lantern 7 velvet-quartz");
+        assert_eq!(strong.score(), 4);
+        assert!(strong.meets_minimum(MIN_PASSPHRASE_SCORE));
+        assert_eq!(strong.crack_time_class(), CrackTimeClass::Centuries);
+        assert!(strong.feedback().is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_recovery_builder_matches_legacy_constructor() {
+        let built = RecoverySystemBuilder::new("device1".to_string())
+            .validation_level(RecoveryValidationLevel::Enhanced)
+            .max_attempts(3)
+            .lockout_duration_ms(60_000)
+            .finish();
+        let legacy = RecoverySystem::new("device1".to_string(), RecoveryValidationLevel::Enhanced as u8, 3, 60_000);
+        assert_eq!(
+            (built.validation_level, built.max_attempts, built.lockout_duration_ms),
+            (legacy.validation_level, legacy.max_attempts, legacy.lockout_duration_ms)
+        );
+
+        assert!(RecoverySystemBuilder::new("device1".to_string()).max_attempts(0).validate().is_err());
+        assert!(RecoverySystemBuilder::new(String::new()).validate().is_err());
+    }
+
+    #[test]
+    fn test_recovery_methods_revoke_independently() {
+        let mut registry = RecoveryMethodRegistry::new();
+        let phrase = registry.enroll_at(RecoveryMethodKind::Phrase, "Paper".to_string(), 0, 0, 1_000);
+        let passkey = registry.enroll_at(RecoveryMethodKind::PasskeyPrf, "Phone passkey".to_string(), 0, 0, 1_000);
+        let shamir = registry.enroll_at(RecoveryMethodKind::ShamirShares, "Family".to_string(), 2, 3, 1_000);
+        for method_id in [&phrase, &passkey, &shamir] {
+            registry.active_method_mut(method_id).unwrap().covered_key_versions.insert("v1".to_string());
+        }
+        registry.active_method_mut(&passkey).unwrap().covered_key_versions.insert("v2".to_string());
+
+        let versions = vec!["v1".to_string(), "v2".to_string()];
+        let strength = registry.recovery_strength(versions.clone());
+        assert_eq!(strength.score(), 70); // Limited by v2, which only the passkey covers
+        assert_eq!(strength.single_method_key_versions(), vec!["v2".to_string()]);
+
+        assert!(registry.revoke_at(&passkey, 2_000));
+        assert!(!registry.revoke_at(&passkey, 3_000));
+        let strength = registry.recovery_strength(versions);
+        assert_eq!((strength.score(), strength.active_methods()), (0, 2));
+        assert_eq!(strength.uncovered_key_versions(), vec!["v2".to_string()]);
+
+        // Revoking the passkey leaves the other methods' coverage intact
+        assert_eq!(registry.recovery_strength(vec!["v1".to_string()]).score(), 92);
+        assert_eq!(registry.get_method(&passkey).unwrap().revoked_at(), Some(2_000));
+        assert!(registry.get_method(&phrase).unwrap().covers("v1"));
+    }
+}
@@ -0,0 +1,301 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+use crate::integration::{require_owner, RestrictedOperation};
+use crate::key_rotation::AuditTrailManager;
+use crate::platform;
+use crate::recovery::{RecoveryPhrase, RecoverySystem};
+use crate::scope::{enter_scope, ScopeContext};
+
+// Recovery rehearsal
+// Users rarely try their recovery path until they depend on it. A rehearsal walks the
+// steps of a real recovery (phrase entry, passkey verification, backup decryption), but
+// the decryption step opens a throwaway target sealed next to the backup when it was
+// created: random bytes under a key derived from the same phrase seed as the master key
+// ciphertext. The master key is never decrypted and the backup is not modified. Every
+// step runs even after an earlier one failed, so the user sees all problems at once.
+// Since a rehearsal tests the same secrets as a real recovery, it shares its limit: a
+// rehearsal with a failed step counts as one recovery attempt, and a locked backup
+// cannot be rehearsed. Each step and the overall result go to the backup's trail in
+// the `AuditTrailManager`, recording outcomes only, never phrase words, seeds or
+// passkey responses.
+
+pub(crate) const REHEARSAL_KEY_INFO: &[u8] = b"aura-recovery-rehearsal-v1";
+const TARGET_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RehearsalStep {
+    PhraseEntry = 0,
+    PasskeyVerification = 1,
+    BackupDecryption = 2,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RehearsalOutcome {
+    Passed = 0,
+    Failed = 1,
+    Skipped = 2, // Not required at the system's validation level
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RehearsalStepResult {
+    step: RehearsalStep,
+    outcome: RehearsalOutcome,
+    detail: String,
+}
+
+#[wasm_bindgen]
+impl RehearsalStepResult {
+    #[wasm_bindgen(getter)]
+    pub fn step(&self) -> RehearsalStep {
+        self.step
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn outcome(&self) -> RehearsalOutcome {
+        self.outcome
+    }
+
+    /// What the user should do about a failure, or what was checked
+    #[wasm_bindgen(getter)]
+    pub fn detail(&self) -> String {
+        self.detail.clone()
+    }
+}
+
+/// Per-step results of one rehearsal plus the ids of the audit entries documenting it
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecoveryRehearsal {
+    rehearsal_id: String,
+    backup_id: String,
+    rehearsed_at: u64,
+    steps: Vec<RehearsalStepResult>,
+    audit_entry_ids: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl RecoveryRehearsal {
+    #[wasm_bindgen(getter)]
+    pub fn rehearsal_id(&self) -> String {
+        self.rehearsal_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn backup_id(&self) -> String {
+        self.backup_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rehearsed_at(&self) -> u64 {
+        self.rehearsed_at
+    }
+
+    /// True when no step failed; a real recovery with the same inputs would succeed
+    #[wasm_bindgen]
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(|result| result.outcome != RehearsalOutcome::Failed)
+    }
+
+    #[wasm_bindgen]
+    pub fn steps(&self) -> Vec<RehearsalStepResult> {
+        self.steps.clone()
+    }
+
+    /// Audit trail entries, one per step and one for the overall result
+    #[wasm_bindgen]
+    pub fn audit_entry_ids(&self) -> Vec<String> {
+        self.audit_entry_ids.clone()
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl RecoveryRehearsal {
+    fn audit(&mut self, audit: &mut AuditTrailManager, device_id: &str, step: Option<RehearsalStep>, outcome: &str) {
+        let step_name = step.and_then(|step| serde_json::to_value(step).ok()).and_then(|value| value.as_str().map(str::to_string));
+        let entry_id = audit.record_recovery_rehearsal(
+            &self.backup_id, &self.rehearsal_id, device_id, step_name.as_deref(), outcome, self.rehearsed_at,
+        );
+        self.audit_entry_ids.push(entry_id);
+    }
+
+    fn record(&mut self, audit: &mut AuditTrailManager, device_id: &str, step: RehearsalStep, outcome: RehearsalOutcome, detail: &str) {
+        let outcome_name = serde_json::to_value(outcome).ok().and_then(|value| value.as_str().map(str::to_string));
+        self.audit(audit, device_id, Some(step), outcome_name.as_deref().unwrap_or_default());
+        self.steps.push(RehearsalStepResult { step, outcome, detail: detail.to_string() });
+    }
+}
+
+#[wasm_bindgen]
+impl RecoverySystem {
+    /// Rehearse recovering a backup without decrypting its master key; a failed
+    /// rehearsal counts as a recovery attempt
+    #[wasm_bindgen]
+    pub fn rehearse_recovery(
+        &mut self,
+        backup_id: String,
+        recovery_phrase: &RecoveryPhrase,
+        passkey_response: Vec<u8>,
+        audit: &mut AuditTrailManager,
+    ) -> Result<RecoveryRehearsal, JsValue> {
+        self.rehearse_recovery_at(&backup_id, recovery_phrase, &passkey_response, audit, platform::now_ms())
+            .map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl RecoverySystem {
+    pub(crate) fn rehearse_recovery_at(
+        &mut self,
+        backup_id: &str,
+        recovery_phrase: &RecoveryPhrase,
+        passkey_response: &[u8],
+        audit: &mut AuditTrailManager,
+        now: u64,
+    ) -> Result<RecoveryRehearsal, String> {
+        require_owner(RestrictedOperation::Recovery).map_err(|denied| denied.to_string())?;
+        let device_id = self.device_id().to_string();
+        let _scope = enter_scope(ScopeContext::for_device(device_id.as_str()));
+        let backup = self.backup(backup_id).ok_or_else(|| "Backup not found".to_string())?;
+        if self.attempts_exhausted(backup_id) {
+            return Err("Recovery attempts exceeded - account locked".to_string());
+        }
+
+        let mut rehearsal = RecoveryRehearsal {
+            rehearsal_id: platform::new_uuid(),
+            backup_id: backup_id.to_string(),
+            rehearsed_at: now,
+            steps: Vec::new(),
+            audit_entry_ids: Vec::new(),
+        };
+
+        let (outcome, detail) = if backup.matches_phrase(recovery_phrase) {
+            (RehearsalOutcome::Passed, "Recovery phrase matches the backup")
+        } else {
+            (RehearsalOutcome::Failed, "Recovery phrase does not match the backup; check the written words and their order")
+        };
+        rehearsal.record(audit, &device_id, RehearsalStep::PhraseEntry, outcome, detail);
+
+        let (outcome, detail) = if !self.requires_passkey() {
+            (RehearsalOutcome::Skipped, "Validation level does not require a passkey")
+        } else if backup.accepts_passkey_response(passkey_response) {
+            (RehearsalOutcome::Passed, "Passkey response verified")
+        } else {
+            (RehearsalOutcome::Failed, "Passkey verification failed; make sure the passkey is still available")
+        };
+        rehearsal.record(audit, &device_id, RehearsalStep::PasskeyVerification, outcome, detail);
+
+        let (outcome, detail) = match backup.rehearsal_target() {
+            None => (RehearsalOutcome::Failed, "Backup predates recovery rehearsals; create a new backup to rehearse decryption"),
+            Some(_) if !recovery_phrase.validate() => (RehearsalOutcome::Failed, "Recovery phrase is not a valid phrase"),
+            Some(target) => {
+                // validate() passed, so seed derivation cannot fail
+                let seed = Zeroizing::new(recovery_phrase.to_seed("").unwrap_or_default());
+                if open_rehearsal_target(&seed, backup_id, &target) {
+                    (RehearsalOutcome::Passed, "Backup decrypts with this recovery phrase")
+                } else {
+                    (RehearsalOutcome::Failed, "Backup does not decrypt with this recovery phrase")
+                }
+            }
+        };
+        rehearsal.record(audit, &device_id, RehearsalStep::BackupDecryption, outcome, detail);
+
+        let overall = if rehearsal.passed() { "passed" } else { "failed" };
+        rehearsal.audit(audit, &device_id, None, overall);
+        if !rehearsal.passed() {
+            self.count_failed_attempt(backup_id);
+        }
+        Ok(rehearsal)
+    }
+}
+
+/// Throwaway target sealed under the phrase seed at backup time: nonce || ciphertext
+pub(crate) fn seal_rehearsal_target(seed: &[u8], backup_id: &str) -> Result<Vec<u8>, String> {
+    let cipher = rehearsal_cipher(seed)?;
+    let mut target = Zeroizing::new(vec![0u8; TARGET_LENGTH]);
+    let mut nonce = [0u8; NONCE_LENGTH];
+    platform::fill_random(&mut target);
+    platform::fill_random(&mut nonce);
+
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: target.as_slice(), aad: backup_id.as_bytes() })
+        .map_err(|_| "Rehearsal target encryption failed".to_string())?;
+    Ok([nonce.as_slice(), &ciphertext].concat())
+}
+
+fn open_rehearsal_target(seed: &[u8], backup_id: &str, target: &[u8]) -> bool {
+    let (Ok(cipher), Some((nonce, ciphertext))) = (rehearsal_cipher(seed), target.split_at_checked(NONCE_LENGTH)) else {
+        return false;
+    };
+    cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: backup_id.as_bytes() })
+        .map(Zeroizing::new)
+        .is_ok()
+}
+
+fn rehearsal_cipher(seed: &[u8]) -> Result<Aes256Gcm, String> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(None, seed)
+        .expand(REHEARSAL_KEY_INFO, key.as_mut())
+        .map_err(|_| "Rehearsal key derivation failed".to_string())?;
+    Aes256Gcm::new_from_slice(key.as_ref()).map_err(|_| "Invalid rehearsal key".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::CryptoKey;
+    use crate::recovery::{RecoveryValidationLevel, WordlistLanguage};
+
+    #[test]
+    fn test_rehearsal_reports_each_step_and_counts_failures_as_attempts() {
+        let mut system = RecoverySystem::new("device-a".to_string(), RecoveryValidationLevel::Standard as u8, 2, 60_000);
+        let mut audit = AuditTrailManager::new();
+        let phrase = RecoveryPhrase::generate(128, WordlistLanguage::English as u8).unwrap();
+        let backup = system.create_backup(&CryptoKey::new("encryption".to_string()), &phrase, vec![5, 6, 7, 8]).unwrap();
+        let backup_id = backup.backup_id();
+
+        let rehearsal = system.rehearse_recovery_at(&backup_id, &phrase, &[9, 9, 9, 9], &mut audit, 1_000).unwrap();
+        assert!(rehearsal.passed());
+        assert!(rehearsal.steps().iter().all(|result| result.outcome() == RehearsalOutcome::Passed));
+        assert_eq!(rehearsal.audit_entry_ids().len(), 4);
+        assert_eq!(system.get_attempt_count(backup_id.clone()), 0);
+
+        let exported = serde_json::to_string(&audit.unified_export()).unwrap();
+        assert!(phrase.words().iter().all(|word| !exported.contains(word.as_str())));
+        assert!(audit.integrity_issues(&format!("recovery_rehearsal:{}", backup_id)).is_empty());
+
+        // A wrong phrase and passkey fail every step and count against the recovery limit
+        let wrong = RecoveryPhrase::generate(160, WordlistLanguage::English as u8).unwrap();
+        for attempt in 1..=2 {
+            let rehearsal = system.rehearse_recovery_at(&backup_id, &wrong, &[1], &mut audit, 2_000).unwrap();
+            let outcomes: Vec<RehearsalOutcome> = rehearsal.steps().iter().map(|result| result.outcome()).collect();
+            assert_eq!(outcomes, [RehearsalOutcome::Failed; 3]);
+            assert_eq!(system.get_attempt_count(backup_id.clone()), attempt);
+        }
+        assert!(system.is_backup_locked(backup_id.clone()));
+        assert!(system.rehearse_recovery_at(&backup_id, &phrase, &[9, 9, 9, 9], &mut audit, 3_000).unwrap_err().contains("locked"));
+        assert_eq!(system.backup(&backup_id).unwrap().encrypted_master_key(), backup.encrypted_master_key());
+        assert!(exported.contains("RecoveryRehearsal"));
+
+        let mut basic = RecoverySystem::new("device-b".to_string(), RecoveryValidationLevel::Basic as u8, 2, 60_000);
+        assert!(basic.rehearse_recovery_at(&backup_id, &phrase, &[], &mut audit, 3_000).is_err());
+        let basic_backup = basic.create_backup(&CryptoKey::new("encryption".to_string()), &phrase, vec![]).unwrap();
+        let rehearsal = basic.rehearse_recovery_at(&basic_backup.backup_id(), &phrase, &[], &mut audit, 3_000).unwrap();
+        assert_eq!(rehearsal.steps()[1].outcome(), RehearsalOutcome::Skipped);
+        assert!(rehearsal.passed());
+    }
+}
@@ -0,0 +1,251 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+
+// Operation scope labels
+// Envelope, rotation and recovery operations run inside a `ScopeContext` naming the
+// profile, purpose, key version and device they act on. Scopes nest: an inner scope
+// fills in the fields it knows and inherits the rest. Audit entries, decrypt audit
+// records, crash snapshots and the crate's typed errors capture the current scope
+// when they are created, so all of them carry the same scope fields. The host sets
+// the outer scope (usually the active profile and device) with `set_scope_context`.
+// Typed errors hold it as an `ErrorScope` field and get their `scope` and `message`
+// getters from `scoped_error!`.
+
+thread_local! {
+    static SCOPE_STACK: RefCell<Vec<ScopeContext>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Profile, purpose, key version and device an operation acts on; unset fields are omitted
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopeContext {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    purpose: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    key_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    device_id: Option<String>,
+}
+
+#[wasm_bindgen]
+impl ScopeContext {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ScopeContext {
+        Self::default()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn profile(&self) -> Option<String> {
+        self.profile.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_profile(&mut self, profile: Option<String>) {
+        self.profile = profile;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn purpose(&self) -> Option<String> {
+        self.purpose.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_purpose(&mut self, purpose: Option<String>) {
+        self.purpose = purpose;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn key_version(&self) -> Option<String> {
+        self.key_version.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_key_version(&mut self, key_version: Option<String>) {
+        self.key_version = key_version;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn device_id(&self) -> Option<String> {
+        self.device_id.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_device_id(&mut self, device_id: Option<String>) {
+        self.device_id = device_id;
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl ScopeContext {
+    pub(crate) fn for_key(purpose: impl Into<String>, key_version: Option<String>) -> ScopeContext {
+        ScopeContext { purpose: Some(purpose.into()), key_version, ..Self::default() }
+    }
+
+    pub(crate) fn for_device(device_id: impl Into<String>) -> ScopeContext {
+        ScopeContext { device_id: Some(device_id.into()), ..Self::default() }
+    }
+
+    /// Scope in effect on this thread
+    pub(crate) fn current() -> ScopeContext {
+        SCOPE_STACK.with(|stack| stack.borrow().last().cloned().unwrap_or_default())
+    }
+
+    /// Fields of `self`, falling back to `outer` for the unset ones
+    pub(crate) fn within(&self, outer: &ScopeContext) -> ScopeContext {
+        ScopeContext {
+            profile: self.profile.clone().or_else(|| outer.profile.clone()),
+            purpose: self.purpose.clone().or_else(|| outer.purpose.clone()),
+            key_version: self.key_version.clone().or_else(|| outer.key_version.clone()),
+            device_id: self.device_id.clone().or_else(|| outer.device_id.clone()),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == ScopeContext::default()
+    }
+
+    pub(crate) fn to_json_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for ScopeContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let fields = [
+            ("profile", &self.profile),
+            ("purpose", &self.purpose),
+            ("key_version", &self.key_version),
+            ("device", &self.device_id),
+        ];
+        let labels: Vec<String> = fields
+            .iter()
+            .filter_map(|(name, value)| value.as_ref().map(|value| format!("{}={}", name, value)))
+            .collect();
+        write!(f, "{}", labels.join(" "))
+    }
+}
+
+/// Scope captured when a typed error is raised. Displays as the ` [profile=.. purpose=..]`
+/// message suffix, empty when no scope was set; boxed to keep `Result<_, E>` small
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ErrorScope(Box<ScopeContext>);
+
+impl ErrorScope {
+    pub(crate) fn capture() -> ErrorScope {
+        ErrorScope(Box::new(ScopeContext::current()))
+    }
+
+    pub(crate) fn context(&self) -> ScopeContext {
+        (*self.0).clone()
+    }
+}
+
+impl std::fmt::Display for ErrorScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            write!(f, " [{}]", self.0)
+        }
+    }
+}
+
+/// Adds the `scope` and `message` getters and `std::error::Error` to a typed error with
+/// a `scope: ErrorScope` field and a `Display` impl that ends with that field
+macro_rules! scoped_error {
+    ($error:ident) => {
+        #[wasm_bindgen]
+        impl $error {
+            /// Scope in effect when the error was raised
+            #[wasm_bindgen(getter)]
+            pub fn scope(&self) -> $crate::scope::ScopeContext {
+                self.scope.context()
+            }
+
+            #[wasm_bindgen(getter)]
+            pub fn message(&self) -> String {
+                self.to_string()
+            }
+        }
+
+        impl std::error::Error for $error {}
+    };
+}
+pub(crate) use scoped_error;
+
+/// Leaves the scope it was created for when dropped, including on unwind
+pub(crate) struct ScopeGuard(());
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        SCOPE_STACK.with(|stack| stack.borrow_mut().pop());
+    }
+}
+
+/// Enter `scope`, nested in the current one, until the guard is dropped
+#[must_use = "the scope is left as soon as the guard is dropped"]
+pub(crate) fn enter_scope(scope: ScopeContext) -> ScopeGuard {
+    let nested = scope.within(&ScopeContext::current());
+    SCOPE_STACK.with(|stack| stack.borrow_mut().push(nested));
+    ScopeGuard(())
+}
+
+/// Set the outermost scope, e.g. the active profile and device; replaces any previous one
+#[wasm_bindgen]
+pub fn set_scope_context(scope: &ScopeContext) {
+    SCOPE_STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        stack.clear();
+        stack.push(scope.clone());
+    });
+}
+
+#[wasm_bindgen]
+pub fn clear_scope_context() {
+    SCOPE_STACK.with(|stack| stack.borrow_mut().clear());
+}
+
+#[wasm_bindgen]
+pub fn current_scope_context() -> ScopeContext {
+    ScopeContext::current()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_scopes_inherit_and_unwind() {
+        let mut profile = ScopeContext::new();
+        profile.set_profile(Some("profile-a".to_string()));
+        profile.set_device_id(Some("device-1".to_string()));
+        set_scope_context(&profile);
+
+        {
+            let _key = enter_scope(ScopeContext::for_key("cycle_data", Some("1.1.0".to_string())));
+            let _device = enter_scope(ScopeContext::for_device("device-2"));
+            let inner = ScopeContext::current();
+            assert_eq!(inner.to_string(), "profile=profile-a purpose=cycle_data key_version=1.1.0 device=device-2");
+        }
+        assert_eq!(ScopeContext::current(), profile);
+
+        let result = std::panic::catch_unwind(|| {
+            let _device = enter_scope(ScopeContext::for_device("device-3"));
+            panic!("boom");
+        });
+        assert!(result.is_err());
+        assert_eq!(ScopeContext::current(), profile);
+        assert_eq!(ErrorScope::capture().to_string(), " [profile=profile-a device=device-1]");
+
+        clear_scope_context();
+        assert_eq!(ErrorScope::capture().to_string(), "");
+        assert_eq!(ScopeContext::for_device("d").to_json_value(), serde_json::json!({ "device_id": "d" }));
+    }
+}
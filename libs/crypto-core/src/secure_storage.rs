@@ -1,6 +1,7 @@
 use wasm_bindgen::prelude::*;
 use std::collections::HashMap;
 use crate::memory::SecureBuffer;
+use crate::platform;
 
 // Platform-specific secure storage interface
 #[wasm_bindgen]
@@ -334,8 +335,8 @@ impl PlatformSecureStorage {
             key_id,
             self.get_device_id(),
             storage_location,
-            js_sys::Date::now(),
-            js_sys::Date::now(),
+            platform::now_ms() as f64,
+            platform::now_ms() as f64,
             0,
             self.config.platform(),
             self.is_hardware_backed(),
@@ -571,7 +572,7 @@ impl PlatformSecureStorage {
                     32,
                     1.0, // High quality
                     true,
-                    js_sys::Date::now(),
+                    platform::now_ms() as f64,
                 ));
             }
             SecureStoragePlatform::AndroidKeystore | SecureStoragePlatform::AndroidStrongBox => {
@@ -580,7 +581,7 @@ impl PlatformSecureStorage {
                     32,
                     1.0, // High quality
                     true,
-                    js_sys::Date::now(),
+                    platform::now_ms() as f64,
                 ));
             }
             SecureStoragePlatform::WebCryptoAPI => {
@@ -589,7 +590,7 @@ impl PlatformSecureStorage {
                     32,
                     0.9, // Good quality
                     false,
-                    js_sys::Date::now(),
+                    platform::now_ms() as f64,
                 ));
             }
             SecureStoragePlatform::WebIndexedDB => {
@@ -598,7 +599,7 @@ impl PlatformSecureStorage {
                     32,
                     0.3, // Poor quality - should be supplemented
                     false,
-                    js_sys::Date::now(),
+                    platform::now_ms() as f64,
                 ));
             }
         }
@@ -12,7 +12,8 @@ extern "C" {
 macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
-use rand::RngCore;
+use crate::envelope::KDFParams;
+use crate::platform;
 
 /// Security hardening and attack mitigation module
 /// Implements constant-time operations, side-channel attack prevention,
@@ -52,8 +53,7 @@ impl SecureRandom {
         }
         
         let mut buffer = vec![0u8; size];
-        let mut rng = rand::thread_rng();
-        rng.fill_bytes(&mut buffer);
+        platform::fill_random(&mut buffer);
         
         Ok(buffer)
     }
@@ -94,9 +94,8 @@ impl MemoryProtection {
     #[wasm_bindgen(constructor)]
     #[must_use]
     pub fn new() -> MemoryProtection {
-        let mut rng = rand::thread_rng();
         MemoryProtection {
-            canary_value: rng.next_u64(),
+            canary_value: platform::random_u64(),
         }
     }
     
@@ -169,8 +168,7 @@ impl SideChannelProtection {
     /// Add timing noise to prevent timing analysis
     #[wasm_bindgen]
     pub fn add_timing_noise() {
-        let mut rng = rand::thread_rng();
-        let noise_cycles = (rng.next_u32() % 100) + 50; // 50-149 cycles
+        let noise_cycles = (platform::random_u32() % 100) + 50; // 50-149 cycles
         
         // Perform dummy operations for timing noise
         let mut dummy = 0u64;
@@ -206,7 +204,7 @@ impl AuditTrail {
     /// Log a cryptographic operation (privacy-safe)
     #[wasm_bindgen]
     pub fn log_operation(&mut self, operation_type: &str, algorithm: &str) {
-        let timestamp = js_sys::Date::now() as u64;
+        let timestamp = platform::now_ms();
         let entry = format!("{}|{}|{}", timestamp, operation_type, algorithm);
         
         self.operations.push(entry);
@@ -265,6 +263,46 @@ impl Drop for AuditTrail {
     }
 }
 
+/// Password KDF identifiers recorded alongside backups and envelopes
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KdfAlgorithm {
+    Argon2id = 1,
+    Scrypt = 2, // Fallback for devices that cannot afford Argon2 memory
+}
+
+impl KdfAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KdfAlgorithm::Argon2id => "argon2id",
+            KdfAlgorithm::Scrypt => "scrypt",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "argon2id" => Some(KdfAlgorithm::Argon2id),
+            "scrypt" => Some(KdfAlgorithm::Scrypt),
+            _ => None,
+        }
+    }
+}
+
+/// scrypt cost bounds (log2 N); 2^14 with r = 8 needs 16 MB. The upper bound is only
+/// reachable with r = p = 1, since total memory is capped at `KDF_MAX_MEMORY_BYTES`
+pub const SCRYPT_MIN_LOG_N: u8 = 14;
+pub const SCRYPT_MAX_LOG_N: u8 = 19;
+pub const SCRYPT_DEFAULT_BLOCK_SIZE: u32 = 8;
+
+/// Most memory any stored KDF parameters may demand; the Argon2 memory cost limit
+pub const KDF_MAX_MEMORY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Memory scrypt needs for the given parameters: 128 * r * N * p bytes
+#[must_use]
+pub fn scrypt_memory_bytes(log_n: u8, block_size: u32, parallelism: u32) -> u64 {
+    (128 * u64::from(block_size) * u64::from(parallelism)) << log_n
+}
+
 /// Secure key derivation with timing attack protection
 #[wasm_bindgen]
 pub struct SecureKDF;
@@ -282,36 +320,154 @@ impl SecureKDF {
         parallelism: u32,
         output_length: usize
     ) -> Result<Vec<u8>, JsValue> {
+        Self::argon2id(password, salt, iterations, memory_cost, parallelism, output_length)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Derive key using scrypt for memory-constrained platforms
+    #[wasm_bindgen]
+    pub fn derive_key_scrypt(
+        password: &[u8],
+        salt: &[u8],
+        log_n: u8,
+        block_size: u32,
+        parallelism: u32,
+        output_length: usize
+    ) -> Result<Vec<u8>, JsValue> {
+        Self::scrypt(password, salt, log_n, block_size, parallelism, output_length)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Derive key with whichever KDF the stored parameters name, so data
+    /// protected with Argon2id or scrypt can be opened on any device
+    #[wasm_bindgen]
+    pub fn derive_key_with_params(
+        password: &[u8],
+        salt: &[u8],
+        params: &KDFParams,
+        output_length: usize
+    ) -> Result<Vec<u8>, JsValue> {
+        Self::derive_with_params(password, salt, params, output_length)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+impl SecureKDF {
+    pub(crate) fn argon2id(
+        password: &[u8],
+        salt: &[u8],
+        iterations: u32,
+        memory_cost: u32,
+        parallelism: u32,
+        output_length: usize
+    ) -> Result<Vec<u8>, String> {
         use argon2::{Argon2, Algorithm, Version, Params};
         
         // Validate parameters to prevent DoS
         if iterations < 1 || iterations > 10 {
-            return Err(JsValue::from_str("Invalid iterations: must be 1-10"));
+            return Err("Invalid iterations: must be 1-10".to_string());
         }
         
         if memory_cost < 1024 || memory_cost > 65536 {
-            return Err(JsValue::from_str("Invalid memory cost: must be 1024-65536 KB"));
+            return Err("Invalid memory cost: must be 1024-65536 KB".to_string());
         }
         
         if parallelism < 1 || parallelism > 4 {
-            return Err(JsValue::from_str("Invalid parallelism: must be 1-4"));
+            return Err("Invalid parallelism: must be 1-4".to_string());
         }
         
         if output_length < 16 || output_length > 64 {
-            return Err(JsValue::from_str("Invalid output length: must be 16-64 bytes"));
+            return Err("Invalid output length: must be 16-64 bytes".to_string());
         }
         
         let params = Params::new(memory_cost, iterations, parallelism, Some(output_length))
-            .map_err(|e| JsValue::from_str(&format!("Invalid Argon2 params: {}", e)))?;
+            .map_err(|e| format!("Invalid Argon2 params: {}", e))?;
         
         let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
         let mut output = vec![0u8; output_length];
         
         argon2.hash_password_into(password, salt, &mut output)
-            .map_err(|e| JsValue::from_str(&format!("Key derivation failed: {}", e)))?;
+            .map_err(|e| format!("Key derivation failed: {}", e))?;
         
         Ok(output)
     }
+
+    pub(crate) fn scrypt(
+        password: &[u8],
+        salt: &[u8],
+        log_n: u8,
+        block_size: u32,
+        parallelism: u32,
+        output_length: usize
+    ) -> Result<Vec<u8>, String> {
+        // Same DoS bound as Argon2: no more than 64 MiB in total, however the cost is split
+        if !(SCRYPT_MIN_LOG_N..=SCRYPT_MAX_LOG_N).contains(&log_n) {
+            return Err(format!(
+                "Invalid scrypt cost: log_n must be {}-{}",
+                SCRYPT_MIN_LOG_N, SCRYPT_MAX_LOG_N
+            ));
+        }
+
+        if !(1..=16).contains(&block_size) {
+            return Err("Invalid scrypt block size: must be 1-16".to_string());
+        }
+
+        if !(1..=4).contains(&parallelism) {
+            return Err("Invalid parallelism: must be 1-4".to_string());
+        }
+
+        if scrypt_memory_bytes(log_n, block_size, parallelism) > KDF_MAX_MEMORY_BYTES {
+            return Err("Invalid scrypt cost: 128 * r * N * p must not exceed 64 MiB".to_string());
+        }
+
+        if !(16..=64).contains(&output_length) {
+            return Err("Invalid output length: must be 16-64 bytes".to_string());
+        }
+
+        let params = scrypt::Params::new(log_n, block_size, parallelism, output_length)
+            .map_err(|e| format!("Invalid scrypt params: {}", e))?;
+
+        let mut output = vec![0u8; output_length];
+        scrypt::scrypt(password, salt, &params, &mut output)
+            .map_err(|e| format!("Key derivation failed: {}", e))?;
+
+        Ok(output)
+    }
+
+    pub(crate) fn derive_with_params(
+        password: &[u8],
+        salt: &[u8],
+        params: &KDFParams,
+        output_length: usize
+    ) -> Result<Vec<u8>, String> {
+        match params.kdf_algorithm() {
+            Some(KdfAlgorithm::Argon2id) => {
+                let memory_cost = params.memory_cost()
+                    .ok_or_else(|| "Missing Argon2 memory cost".to_string())?;
+                Self::argon2id(
+                    password,
+                    salt,
+                    params.iterations(),
+                    memory_cost,
+                    params.parallelism().unwrap_or(1),
+                    output_length,
+                )
+            }
+            Some(KdfAlgorithm::Scrypt) => {
+                let log_n = u8::try_from(params.iterations())
+                    .map_err(|_| "Invalid scrypt cost".to_string())?;
+                Self::scrypt(
+                    password,
+                    salt,
+                    log_n,
+                    params.block_size().unwrap_or(SCRYPT_DEFAULT_BLOCK_SIZE),
+                    params.parallelism().unwrap_or(1),
+                    output_length,
+                )
+            }
+            None => Err(format!("Unsupported KDF algorithm: {}", params.algorithm())),
+        }
+    }
 }
 
 /// Platform-specific entropy collection
@@ -327,23 +483,24 @@ impl PlatformEntropy {
         let mut entropy = Vec::new();
         
         // Timestamp entropy
-        let timestamp = js_sys::Date::now();
+        let timestamp = platform::now_ms() as f64;
         entropy.extend_from_slice(&timestamp.to_bits().to_le_bytes());
         
-        // Performance timing entropy
+        // Performance timing entropy (omitted in deterministic test builds)
+        #[cfg(not(feature = "deterministic-test"))]
         let performance_now = web_sys::window()
             .and_then(|win| win.performance())
             .map(|perf| perf.now())
             .unwrap_or(0.0);
+        #[cfg(not(feature = "deterministic-test"))]
         entropy.extend_from_slice(&performance_now.to_bits().to_le_bytes());
         
         // Memory usage entropy would be available in Node.js context
         // but is not accessible in WASM/browser context for security reasons
         
-        // Add some randomness from thread_rng as well
-        let mut rng = rand::thread_rng();
+        // Add some randomness from the crate RNG as well
         let mut random_bytes = [0u8; 16];
-        rng.fill_bytes(&mut random_bytes);
+        platform::fill_random(&mut random_bytes);
         entropy.extend_from_slice(&random_bytes);
         
         entropy
@@ -425,6 +582,37 @@ mod tests {
         assert_eq!(SideChannelProtection::conditional_select(false, 0xFF, 0x00), 0x00);
     }
 
+    #[test]
+    fn test_kdf_params_select_matching_algorithm() {
+        let password = b"correct horse battery staple";
+        let salt = [7u8; 16];
+
+        let scrypt_params = KDFParams::scrypt(SCRYPT_MIN_LOG_N, 8, 1);
+        let via_params = SecureKDF::derive_with_params(password, &salt, &scrypt_params, 32).unwrap();
+        let direct = SecureKDF::scrypt(password, &salt, SCRYPT_MIN_LOG_N, 8, 1, 32).unwrap();
+        assert_eq!(via_params, direct);
+
+        let argon_params = KDFParams::argon2id(1, 1024, 1);
+        let argon_key = SecureKDF::derive_with_params(password, &salt, &argon_params, 32).unwrap();
+        assert_ne!(argon_key, via_params);
+
+        let unknown = KDFParams::new("pbkdf2".to_string(), 100_000);
+        assert!(SecureKDF::derive_with_params(password, &salt, &unknown, 32).is_err());
+        assert!(SecureKDF::scrypt(password, &salt, 30, 8, 1, 32).is_err());
+    }
+
+    #[test]
+    fn test_scrypt_total_memory_is_capped() {
+        assert_eq!(scrypt_memory_bytes(SCRYPT_MAX_LOG_N, 1, 1), KDF_MAX_MEMORY_BYTES);
+        assert_eq!(scrypt_memory_bytes(16, 8, 1), KDF_MAX_MEMORY_BYTES);
+
+        // Each parameter is within its own range, but together they need 2 GiB
+        let err = SecureKDF::scrypt(b"pw", &[7u8; 16], SCRYPT_MAX_LOG_N, 16, 4, 32).unwrap_err();
+        assert!(err.contains("64 MiB"));
+        let untrusted = KDFParams::scrypt(17, 8, 1);
+        assert!(SecureKDF::derive_with_params(b"pw", &[7u8; 16], &untrusted, 32).is_err());
+    }
+
     #[cfg(target_arch = "wasm32")]
     #[test]
     fn test_audit_trail() {
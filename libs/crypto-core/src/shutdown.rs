@@ -0,0 +1,235 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use crate::aad::{active_user_scope_tag, clear_active_user_scope, drain_scope_violation_events, requeue_scope_violation_events};
+use crate::auth_freshness::clear_authentication;
+use crate::consent::close_consent_registry;
+use crate::decrypt_audit::{drain_decrypt_audit_records, requeue_decrypt_audit_records};
+use crate::host_calls::{call_host, HostCallbackKind};
+use crate::memory::{zeroize_live_secrets, SecretsWiped};
+use crate::platform;
+use crate::scope::clear_scope_context;
+
+// Graceful shutdown
+// `shutdown` runs from app-termination hooks and test teardown. It advances the
+// shutdown generation, which
+//   - cancels operations started before it at their next deadline check; they roll
+//     back exactly as on a timeout and return a `Timeout` with `cancelled` set,
+//   - retires every `SecureBuffer` allocated before it: retired buffers read as
+//     zeroized and are never reused by a pool.
+// Pending decrypt audit records, scope violation events and migration checkpoints are
+// then written through the host storage callback; anything the host does not accept
+// is put back and counted in the report. Every live `SecureBuffer` (key material,
+// pooled and temporary buffers) is then zeroized in place through the memory
+// registry, and the consent registry's keys are dropped. Finally the session
+// (authentication, active user scope, operation scope) is cleared. The report counts
+// what was actually wiped. Companion mode is left in place so a restricted session
+// cannot come back with owner rights. Work started after shutdown runs normally, so
+// tests can tear down and start over in one process.
+
+/// Storage kind, drain and requeue for each process-wide audit queue
+type AuditQueue = (&'static str, fn() -> Vec<String>, fn(Vec<String>));
+
+const AUDIT_QUEUES: [AuditQueue; 2] = [
+    ("decrypt_audit", drain_decrypt_audit_records, requeue_decrypt_audit_records),
+    ("scope_violations", drain_scope_violation_events, requeue_scope_violation_events),
+];
+
+static SHUTDOWN_GENERATION: AtomicU64 = AtomicU64::new(0);
+static PENDING_CHECKPOINTS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+pub(crate) fn shutdown_generation() -> u64 {
+    SHUTDOWN_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Record the latest state of a resumable operation for the next flush
+pub(crate) fn stage_checkpoint(key: &str, json: String) {
+    if let Ok(mut pending) = PENDING_CHECKPOINTS.lock() {
+        pending.insert(key.to_string(), json);
+    }
+}
+
+pub(crate) fn unstage_checkpoint(key: &str) {
+    if let Ok(mut pending) = PENDING_CHECKPOINTS.lock() {
+        pending.remove(key);
+    }
+}
+
+/// What `shutdown` flushed and retired
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ShutdownReport {
+    generation: u64,
+    completed_at: u64,
+    audit_records_flushed: usize,
+    checkpoints_flushed: usize,
+    items_unflushed: usize,
+    buffers_zeroized: usize,
+    bytes_zeroized: usize,
+    caches_cleared: Vec<String>,
+    flush_errors: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl ShutdownReport {
+    #[wasm_bindgen(getter)]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn completed_at(&self) -> u64 {
+        self.completed_at
+    }
+
+    /// Decrypt audit records and scope violation events written to storage
+    #[wasm_bindgen(getter)]
+    pub fn audit_records_flushed(&self) -> usize {
+        self.audit_records_flushed
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn checkpoints_flushed(&self) -> usize {
+        self.checkpoints_flushed
+    }
+
+    /// Records and checkpoints still queued because storage refused them
+    #[wasm_bindgen(getter)]
+    pub fn items_unflushed(&self) -> usize {
+        self.items_unflushed
+    }
+
+    /// Live secure buffers zeroized in place
+    #[wasm_bindgen(getter)]
+    pub fn buffers_zeroized(&self) -> usize {
+        self.buffers_zeroized
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn bytes_zeroized(&self) -> usize {
+        self.bytes_zeroized
+    }
+
+    /// Process-wide caches that held secrets or session state and were emptied
+    #[wasm_bindgen(getter)]
+    pub fn caches_cleared(&self) -> Vec<String> {
+        self.caches_cleared.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn flush_errors(&self) -> Vec<String> {
+        self.flush_errors.clone()
+    }
+
+    /// Everything pending reached storage
+    #[wasm_bindgen]
+    pub fn is_clean(&self) -> bool {
+        self.items_unflushed == 0
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Shut the crate down; `storage(kind, json)` persists each pending item, where `kind`
+/// is "decrypt_audit", "scope_violations" (JSON arrays) or "checkpoint" (one object)
+#[wasm_bindgen]
+pub fn shutdown(storage: &js_sys::Function) -> ShutdownReport {
+    shutdown_with(|kind, json| {
+        call_host(HostCallbackKind::Storage, || {
+            storage
+                .call2(&JsValue::NULL, &JsValue::from_str(kind), &JsValue::from_str(json))
+                .map(|_| ())
+                .map_err(|_| format!("Storage callback threw while writing {}", kind))
+        })
+    })
+}
+
+pub(crate) fn shutdown_with(mut write: impl FnMut(&str, &str) -> Result<(), String>) -> ShutdownReport {
+    let generation = SHUTDOWN_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let mut report = ShutdownReport { generation, ..ShutdownReport::default() };
+
+    flush_pending(&mut write, &mut report);
+    wipe_secrets(zeroize_live_secrets(), &mut report);
+
+    clear_authentication();
+    clear_active_user_scope();
+    clear_scope_context();
+    report.completed_at = platform::now_ms();
+    report
+}
+
+fn wipe_secrets(wiped: SecretsWiped, report: &mut ShutdownReport) {
+    report.buffers_zeroized = wiped.buffers;
+    report.bytes_zeroized = wiped.bytes;
+    if close_consent_registry() {
+        report.caches_cleared.push("consent_registry".to_string());
+    }
+    if active_user_scope_tag().is_some() {
+        report.caches_cleared.push("user_scope".to_string());
+    }
+}
+
+fn flush_pending(write: &mut impl FnMut(&str, &str) -> Result<(), String>, report: &mut ShutdownReport) {
+    for (kind, drain, requeue) in AUDIT_QUEUES {
+        let records = drain();
+        if records.is_empty() {
+            continue;
+        }
+        match write(kind, &format!("[{}]", records.join(","))) {
+            Ok(()) => report.audit_records_flushed += records.len(),
+            Err(e) => {
+                report.items_unflushed += records.len();
+                report.flush_errors.push(e);
+                requeue(records);
+            }
+        }
+    }
+
+    let checkpoints = PENDING_CHECKPOINTS.lock().map(|pending| pending.clone()).unwrap_or_default();
+    for (key, json) in checkpoints {
+        match write("checkpoint", &json) {
+            Ok(()) => {
+                report.checkpoints_flushed += 1;
+                // Keep a checkpoint that moved on while it was being written
+                if let Ok(mut pending) = PENDING_CHECKPOINTS.lock() {
+                    if pending.get(&key) == Some(&json) {
+                        pending.remove(&key);
+                    }
+                }
+            }
+            Err(e) => {
+                report.items_unflushed += 1;
+                report.flush_errors.push(e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flush_requeues_what_storage_refuses() {
+        let key = "test:flush-requeue";
+        stage_checkpoint(key, r#"{"batch":1}"#.to_string());
+
+        let mut refused = ShutdownReport::default();
+        flush_pending(&mut |_, _| Err("disk full".to_string()), &mut refused);
+        assert!(!refused.is_clean());
+        assert!(refused.flush_errors.iter().all(|e| e == "disk full"));
+        assert!(PENDING_CHECKPOINTS.lock().unwrap().contains_key(key));
+
+        let mut written = Vec::new();
+        let mut accepted = ShutdownReport::default();
+        flush_pending(&mut |kind, json| { written.push((kind.to_string(), json.to_string())); Ok(()) }, &mut accepted);
+        assert!(accepted.is_clean());
+        assert!(written.contains(&("checkpoint".to_string(), r#"{"batch":1}"#.to_string())));
+        assert!(!PENDING_CHECKPOINTS.lock().unwrap().contains_key(key));
+    }
+}
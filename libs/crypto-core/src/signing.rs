@@ -0,0 +1,164 @@
+use wasm_bindgen::prelude::*;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use zeroize::Zeroizing;
+use crate::inbox::device_fingerprint;
+use crate::integration::DeviceKeyStorage;
+use crate::memory::{track_secret_allocation, track_secret_zeroization};
+use crate::platform;
+use crate::SecureBuffer;
+
+// Ed25519 signatures (RFC 8032)
+// Attestations, evidence bundles and lifecycle events are signed with a per-device
+// Ed25519 key, so a verifier needs only the published public key and cannot forge
+// what it checks. Signing and verification come from ed25519-dalek; verification is
+// `verify_strict`, which also rejects small-order keys and non-canonical encodings.
+// The seed stays inside wasm and is persisted through `DeviceKeyStorage`, like
+// `RecipientKeyPair`.
+
+pub(crate) const PUBLIC_KEY_LENGTH: usize = ed25519_dalek::PUBLIC_KEY_LENGTH;
+pub(crate) const SIGNATURE_LENGTH: usize = ed25519_dalek::SIGNATURE_LENGTH;
+const SEED_LENGTH: usize = ed25519_dalek::SECRET_KEY_LENGTH;
+
+/// Ed25519 signing key; JS only ever sees the public key
+#[wasm_bindgen]
+pub struct SigningKeyPair {
+    key: SigningKey, // zeroized on drop
+}
+
+#[wasm_bindgen]
+impl SigningKeyPair {
+    #[wasm_bindgen]
+    pub fn generate() -> SigningKeyPair {
+        let mut seed = Zeroizing::new([0u8; SEED_LENGTH]);
+        platform::fill_random(seed.as_mut());
+        Self::from_seed(&seed)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.key.verifying_key().to_bytes().to_vec()
+    }
+
+    /// Fingerprint of the public key, used as the signer id in signed artifacts
+    #[wasm_bindgen(getter)]
+    pub fn key_id(&self) -> String {
+        device_fingerprint(self.key.verifying_key().as_bytes())
+    }
+}
+
+impl SigningKeyPair {
+    pub(crate) fn from_seed(seed: &[u8; SEED_LENGTH]) -> SigningKeyPair {
+        track_secret_allocation();
+        SigningKeyPair { key: SigningKey::from_bytes(seed) }
+    }
+
+    pub(crate) fn sign(&self, message: &[u8]) -> [u8; SIGNATURE_LENGTH] {
+        self.key.sign(message).to_bytes()
+    }
+
+    /// Keep the signing seed in device key storage under `key_id`
+    pub fn store_in(&self, storage: &dyn DeviceKeyStorage, key_id: &str) -> Result<(), String> {
+        let seed = Zeroizing::new(self.key.to_bytes());
+        storage.store_key(key_id, &SecureBuffer::from_bytes(seed.to_vec()))
+    }
+
+    pub fn load_from(storage: &dyn DeviceKeyStorage, key_id: &str) -> Result<SigningKeyPair, String> {
+        let stored = storage.retrieve_key(key_id)?;
+        let seed: [u8; SEED_LENGTH] = stored
+            .as_slice()
+            .map_err(|e| e.to_string())?
+            .try_into()
+            .map_err(|_| "Stored signing key must be 32 bytes".to_string())?;
+        Ok(Self::from_seed(&Zeroizing::new(seed)))
+    }
+}
+
+impl Drop for SigningKeyPair {
+    fn drop(&mut self) {
+        track_secret_zeroization();
+    }
+}
+
+/// Check an Ed25519 signature against a 32-byte public key
+#[wasm_bindgen]
+pub fn verify_signature(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    verify(public_key, message, signature)
+}
+
+pub(crate) fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let (Ok(public_key), Ok(signature)) = (
+        <[u8; PUBLIC_KEY_LENGTH]>::try_from(public_key),
+        <[u8; SIGNATURE_LENGTH]>::try_from(signature),
+    ) else {
+        return false;
+    };
+    let Ok(public_key) = VerifyingKey::from_bytes(&public_key) else {
+        return false;
+    };
+    public_key.verify_strict(message, &Signature::from_bytes(&signature)).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::envelope::decode_hex;
+    use crate::test_support::InMemoryKeystore;
+
+    fn unhex<const N: usize>(hex: &str) -> [u8; N] {
+        decode_hex(hex).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_rfc8032_vectors() {
+        let vectors = [
+            (
+                "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+                &[][..],
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+            ),
+            (
+                "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+                "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+                &[0x72][..],
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+            ),
+        ];
+        for (seed, public_key, message, signature) in vectors {
+            let pair = SigningKeyPair::from_seed(&unhex::<32>(seed));
+            assert_eq!(pair.public_key(), unhex::<32>(public_key));
+            assert_eq!(pair.sign(message), unhex::<64>(signature));
+            assert!(verify(&pair.public_key(), message, &unhex::<64>(signature)));
+        }
+    }
+
+    #[test]
+    fn test_forged_or_mismatched_signatures_are_rejected() {
+        let pair = SigningKeyPair::generate();
+        let other = SigningKeyPair::generate();
+        let signature = pair.sign(b"rotation completed");
+
+        assert!(verify(&pair.public_key(), b"rotation completed", &signature));
+        assert!(!verify(&pair.public_key(), b"device revoked", &signature));
+        assert!(!verify(&other.public_key(), b"rotation completed", &signature));
+        assert!(!verify(&pair.public_key(), b"rotation completed", &signature[..63]));
+
+        let mut tampered = signature;
+        tampered[40] ^= 1;
+        assert!(!verify(&pair.public_key(), b"rotation completed", &tampered));
+
+        // Strict verification refuses small-order public keys, which verify anything
+        // under the plain cofactorless equation for a suitably crafted signature
+        let mut small_order = [0u8; 32];
+        small_order[0] = 1;
+        let mut weak_signature = [0u8; 64];
+        weak_signature[0] = 1;
+        assert!(!verify(&small_order, b"rotation completed", &weak_signature));
+
+        let keystore = InMemoryKeystore::new(vec![3u8; 16]);
+        pair.store_in(&keystore, "signing").unwrap();
+        let restored = SigningKeyPair::load_from(&keystore, "signing").unwrap();
+        assert_eq!(restored.public_key(), pair.public_key());
+        assert_eq!(restored.key_id(), pair.key_id());
+    }
+}
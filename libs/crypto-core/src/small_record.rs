@@ -0,0 +1,154 @@
+use wasm_bindgen::prelude::*;
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce, Tag};
+use zeroize::Zeroize;
+use crate::memory::{MemoryPool, SecureBuffer};
+use crate::platform;
+
+// Small-record fast path
+// Records under 1 KB (daily entries, preference flips) spent most of their encryption
+// time in allocator traffic: growing output vectors and cloning envelopes. This path
+// encrypts AES-256-GCM in place into a buffer the caller owns, or one taken from a
+// `MemoryPool`, and performs no heap allocation per record once the pool is warm.
+// Wire layout: nonce (12) || ciphertext || tag (16). Errors are static strings so the
+// failure path does not allocate either.
+
+pub const MAX_SMALL_RECORD_LENGTH: usize = 1024;
+pub const SMALL_RECORD_OVERHEAD: usize = NONCE_LENGTH + TAG_LENGTH;
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+
+/// AES-256-GCM for records up to `MAX_SMALL_RECORD_LENGTH` bytes, written into caller buffers
+#[wasm_bindgen]
+pub struct SmallRecordCipher {
+    cipher: Aes256Gcm,
+}
+
+#[wasm_bindgen]
+impl SmallRecordCipher {
+    #[wasm_bindgen(constructor)]
+    pub fn new(key: &[u8]) -> Result<SmallRecordCipher, JsValue> {
+        Self::with_key(key).map_err(JsValue::from_str)
+    }
+
+    /// Encrypt into `out` (at least plaintext length + 28 bytes); returns the bytes written
+    #[wasm_bindgen]
+    pub fn encrypt_into(&self, plaintext: &[u8], aad: &[u8], out: &mut [u8]) -> Result<usize, JsValue> {
+        self.seal_into(plaintext, aad, out).map_err(JsValue::from_str)
+    }
+
+    /// Decrypt a record into `out` (at least record length - 28 bytes); returns the plaintext length
+    #[wasm_bindgen]
+    pub fn decrypt_into(&self, record: &[u8], aad: &[u8], out: &mut [u8]) -> Result<usize, JsValue> {
+        self.open_into(record, aad, out).map_err(JsValue::from_str)
+    }
+}
+
+impl SmallRecordCipher {
+    pub(crate) fn with_key(key: &[u8]) -> Result<Self, &'static str> {
+        if key.len() != KEY_LENGTH {
+            return Err("Small record key must be 32 bytes");
+        }
+        Aes256Gcm::new_from_slice(key).map(|cipher| SmallRecordCipher { cipher }).map_err(|_| "Invalid small record key")
+    }
+
+    pub(crate) fn seal_into(&self, plaintext: &[u8], aad: &[u8], out: &mut [u8]) -> Result<usize, &'static str> {
+        if plaintext.len() > MAX_SMALL_RECORD_LENGTH {
+            return Err("Record exceeds the small record limit");
+        }
+        let record_length = plaintext.len() + SMALL_RECORD_OVERHEAD;
+        let out = out.get_mut(..record_length).ok_or("Output buffer too small for record")?;
+
+        let (nonce, rest) = out.split_at_mut(NONCE_LENGTH);
+        let (body, tag) = rest.split_at_mut(plaintext.len());
+        platform::fill_random(nonce);
+        body.copy_from_slice(plaintext);
+        let computed = self.cipher
+            .encrypt_in_place_detached(Nonce::from_slice(nonce), aad, body)
+            .map_err(|_| "Small record encryption failed")?;
+        tag.copy_from_slice(&computed);
+        Ok(record_length)
+    }
+
+    pub(crate) fn open_into(&self, record: &[u8], aad: &[u8], out: &mut [u8]) -> Result<usize, &'static str> {
+        let plaintext_length = record
+            .len()
+            .checked_sub(SMALL_RECORD_OVERHEAD)
+            .filter(|length| *length <= MAX_SMALL_RECORD_LENGTH)
+            .ok_or("Invalid small record length")?;
+        let body = out.get_mut(..plaintext_length).ok_or("Output buffer too small for plaintext")?;
+
+        let (nonce, rest) = record.split_at(NONCE_LENGTH);
+        let (ciphertext, tag) = rest.split_at(plaintext_length);
+        body.copy_from_slice(ciphertext);
+        // GCM decrypts before it checks the tag; never leave unauthenticated plaintext behind
+        self.cipher
+            .decrypt_in_place_detached(Nonce::from_slice(nonce), aad, body, Tag::from_slice(tag))
+            .map_err(|_| {
+                body.zeroize();
+                "Small record authentication failed"
+            })?;
+        Ok(plaintext_length)
+    }
+
+    /// Encrypt into a buffer from `pool`; hand it back with `PooledRecord::release`
+    pub fn seal_pooled(&self, pool: &mut MemoryPool, plaintext: &[u8], aad: &[u8]) -> Result<PooledRecord, &'static str> {
+        let mut buffer = pool.get_encryption_buffer(MAX_SMALL_RECORD_LENGTH + SMALL_RECORD_OVERHEAD);
+        let length = self.seal_into(plaintext, aad, buffer.as_mut_slice()?)?;
+        Ok(PooledRecord { buffer, length })
+    }
+}
+
+/// Encrypted record living in a pooled buffer
+pub struct PooledRecord {
+    buffer: SecureBuffer,
+    length: usize,
+}
+
+impl PooledRecord {
+    pub fn as_bytes(&self) -> Result<&[u8], &'static str> {
+        self.buffer.as_slice().map(|bytes| &bytes[..self.length])
+    }
+
+    /// Return the buffer to `pool` (it is zeroized there) for the next record
+    pub fn release(self, pool: &mut MemoryPool) {
+        pool.return_encryption_buffer(self.buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::thread_heap_allocations;
+
+    #[test]
+    fn test_small_records_round_trip_without_heap_allocations() {
+        let cipher = SmallRecordCipher::with_key(&[5u8; 32]).unwrap();
+        let plaintext = [0x42u8; 700];
+        let mut record = [0u8; MAX_SMALL_RECORD_LENGTH + SMALL_RECORD_OVERHEAD];
+        let mut opened = [0u8; MAX_SMALL_RECORD_LENGTH];
+        let mut pool = MemoryPool::new(1);
+        // Warm up the thread RNG and the pool
+        let warm = cipher.seal_pooled(&mut pool, &plaintext, b"aad").unwrap();
+        warm.release(&mut pool);
+
+        let before = thread_heap_allocations();
+        for _ in 0..100 {
+            let length = cipher.seal_into(&plaintext, b"aad", &mut record).unwrap();
+            assert_eq!(cipher.open_into(&record[..length], b"aad", &mut opened), Ok(plaintext.len()));
+            let pooled = cipher.seal_pooled(&mut pool, &plaintext, b"aad").unwrap();
+            assert_eq!(pooled.as_bytes().unwrap().len(), length);
+            pooled.release(&mut pool);
+        }
+        assert_eq!(thread_heap_allocations(), before);
+        assert_eq!(&opened[..plaintext.len()], &plaintext[..]);
+
+        let length = cipher.seal_into(&plaintext, b"aad", &mut record).unwrap();
+        assert!(cipher.open_into(&record[..length], b"other", &mut opened).is_err());
+        assert!(opened.iter().all(|&b| b == 0));
+        assert!(cipher.seal_into(&[0u8; MAX_SMALL_RECORD_LENGTH + 1], b"", &mut record).is_err());
+        assert!(cipher.seal_into(&plaintext, b"", &mut [0u8; 700]).is_err());
+    }
+}
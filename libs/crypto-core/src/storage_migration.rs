@@ -0,0 +1,485 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+use zeroize::Zeroizing;
+use crate::host_calls::{call_host, HostCallbackKind};
+use crate::platform;
+use crate::secure_storage::SecureStoragePlatform;
+use crate::security::constant_time_compare;
+
+// Storage backend migration with double writes
+// Moving to another backend (typically IndexedDB to the native keystore) starts in
+// `DualWrite`: every write goes to both backends and every read consults both. The
+// source stays authoritative; a target that is missing the value, holds different
+// bytes, has a value the source lacks, or fails, is recorded as a divergence and
+// restarts the verification window. Divergent keys are resolved by a later matching
+// read, a successful double write or `backfill`. Keys that are never touched would
+// never be compared, so `verify_all` backfills and checks every key either backend
+// lists. `cut_over` is refused until such a full pass has found both backends in
+// agreement and a full window has passed with no divergence since; it then switches
+// to the target in a single step. `abort` goes back to the source alone and leaves the target as is.
+// The host persists `export_state` after every phase change and reopens with `resume`.
+
+const MAX_DIVERGENCE_LOG: usize = 100;
+
+/// Backend a migration reads from and writes to
+pub trait StorageBackend {
+    fn read(&mut self, key: &str) -> Result<Option<Vec<u8>>, String>;
+    fn write(&mut self, key: &str, value: &[u8]) -> Result<(), String>;
+    fn delete(&mut self, key: &str) -> Result<(), String>;
+    /// Every key currently stored
+    fn keys(&mut self) -> Result<Vec<String>, String>;
+}
+
+/// Host backend: `read(key) => Uint8Array | null`, `write(key, value)`, `delete(key)`,
+/// `keys() => string[]`
+#[wasm_bindgen]
+pub struct HostStorageBackend {
+    platform: SecureStoragePlatform,
+    read: js_sys::Function,
+    write: js_sys::Function,
+    delete: js_sys::Function,
+    keys: js_sys::Function,
+}
+
+#[wasm_bindgen]
+impl HostStorageBackend {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        platform: SecureStoragePlatform,
+        read: js_sys::Function,
+        write: js_sys::Function,
+        delete: js_sys::Function,
+        keys: js_sys::Function,
+    ) -> HostStorageBackend {
+        HostStorageBackend { platform, read, write, delete, keys }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn platform(&self) -> SecureStoragePlatform {
+        self.platform.clone()
+    }
+}
+
+impl StorageBackend for HostStorageBackend {
+    fn read(&mut self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let value = call_host(HostCallbackKind::Storage, || {
+            self.read
+                .call1(&JsValue::NULL, &JsValue::from_str(key))
+                .map_err(|_| format!("Storage backend failed reading {}", key))
+        })?;
+        if value.is_null() || value.is_undefined() {
+            return Ok(None);
+        }
+        if !value.is_instance_of::<js_sys::Uint8Array>() {
+            return Err(format!("Storage backend returned a non-Uint8Array for {}", key));
+        }
+        Ok(Some(js_sys::Uint8Array::from(value).to_vec()))
+    }
+
+    fn write(&mut self, key: &str, value: &[u8]) -> Result<(), String> {
+        call_host(HostCallbackKind::Storage, || {
+            self.write
+                .call2(&JsValue::NULL, &JsValue::from_str(key), &js_sys::Uint8Array::from(value))
+                .map(|_| ())
+                .map_err(|_| format!("Storage backend failed writing {}", key))
+        })
+    }
+
+    fn delete(&mut self, key: &str) -> Result<(), String> {
+        call_host(HostCallbackKind::Storage, || {
+            self.delete
+                .call1(&JsValue::NULL, &JsValue::from_str(key))
+                .map(|_| ())
+                .map_err(|_| format!("Storage backend failed deleting {}", key))
+        })
+    }
+
+    fn keys(&mut self) -> Result<Vec<String>, String> {
+        let value = call_host(HostCallbackKind::Storage, || {
+            self.keys.call0(&JsValue::NULL).map_err(|_| "Storage backend failed listing keys".to_string())
+        })?;
+        if !js_sys::Array::is_array(&value) {
+            return Err("Storage backend returned a non-array key list".to_string());
+        }
+        js_sys::Array::from(&value)
+            .iter()
+            .map(|key| key.as_string().ok_or_else(|| "Storage backend listed a non-string key".to_string()))
+            .collect()
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackendMigrationPhase {
+    DualWrite = 0,
+    CutOver = 1,    // Target only
+    RolledBack = 2, // Source only
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum DivergenceKind {
+    MissingOnTarget,
+    UnexpectedOnTarget,
+    ValueMismatch,
+    TargetError,
+}
+
+/// One disagreement between the backends; never contains stored values
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct StorageDivergence {
+    key: String,
+    kind: DivergenceKind,
+    detected_at: u64,
+}
+
+/// What the host persists across restarts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct BackendMigrationState {
+    phase: BackendMigrationPhase,
+    verification_period_ms: u64,
+    window_started_at: u64,
+    unresolved: BTreeSet<String>,
+    /// When the last full pass found both backends in agreement; cleared by any divergence
+    #[serde(default)]
+    full_pass_at: Option<u64>,
+}
+
+/// Double-write migration from one storage backend to another
+#[wasm_bindgen]
+pub struct StorageBackendMigration {
+    source: Box<dyn StorageBackend>,
+    target: Box<dyn StorageBackend>,
+    state: BackendMigrationState,
+    divergences: VecDeque<StorageDivergence>,
+}
+
+#[wasm_bindgen]
+impl StorageBackendMigration {
+    /// Start double writing; cut-over needs `verification_period_ms` without divergence
+    #[wasm_bindgen(constructor)]
+    pub fn new(source: HostStorageBackend, target: HostStorageBackend, verification_period_ms: u64) -> StorageBackendMigration {
+        Self::with_backends(Box::new(source), Box::new(target), verification_period_ms, platform::now_ms())
+    }
+
+    /// Reopen a migration from `export_state`
+    #[wasm_bindgen]
+    pub fn resume(source: HostStorageBackend, target: HostStorageBackend, state_json: &str) -> Result<StorageBackendMigration, JsValue> {
+        let state: BackendMigrationState = serde_json::from_str(state_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid backend migration state: {}", e)))?;
+        Ok(StorageBackendMigration { source: Box::new(source), target: Box::new(target), state, divergences: VecDeque::new() })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn phase(&self) -> BackendMigrationPhase {
+        self.state.phase
+    }
+
+    /// Keys whose backends still disagree
+    #[wasm_bindgen(getter)]
+    pub fn unresolved_keys(&self) -> Vec<String> {
+        self.state.unresolved.iter().cloned().collect()
+    }
+
+    /// Time left before `cut_over` is allowed, assuming no further divergence
+    #[wasm_bindgen]
+    pub fn verification_remaining_ms(&self) -> u64 {
+        self.remaining_at(platform::now_ms())
+    }
+
+    #[wasm_bindgen]
+    pub fn write(&mut self, key: &str, value: &[u8]) -> Result<(), JsValue> {
+        self.write_at(key, value, platform::now_ms()).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Read from the authoritative backend, verifying against the target while double writing
+    #[wasm_bindgen]
+    pub fn read(&mut self, key: &str) -> Result<Option<Vec<u8>>, JsValue> {
+        self.read_at(key, platform::now_ms())
+            .map(|value| value.map(|value| value.to_vec()))
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn delete(&mut self, key: &str) -> Result<(), JsValue> {
+        self.delete_at(key, platform::now_ms()).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Copy a key written before the migration to the target; true if the backends now agree
+    #[wasm_bindgen]
+    pub fn backfill(&mut self, key: &str) -> Result<bool, JsValue> {
+        self.backfill_at(key, platform::now_ms()).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Backfill and verify every key of both backends; returns how many keys still diverge
+    #[wasm_bindgen]
+    pub fn verify_all(&mut self) -> Result<u32, JsValue> {
+        self.verify_all_at(platform::now_ms()).map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn cut_over(&mut self) -> Result<(), JsValue> {
+        self.cut_over_at(platform::now_ms()).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Return to the source backend alone; refused after cut-over, when the source is stale
+    #[wasm_bindgen]
+    pub fn abort(&mut self) -> Result<(), JsValue> {
+        self.roll_back().map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Drain the divergence log as a JSON array, oldest first
+    #[wasm_bindgen]
+    pub fn take_divergence_report(&mut self) -> String {
+        let divergences: Vec<StorageDivergence> = self.divergences.drain(..).collect();
+        serde_json::to_string(&divergences).unwrap_or_default()
+    }
+
+    #[wasm_bindgen]
+    pub fn export_state(&self) -> String {
+        serde_json::to_string(&self.state).unwrap_or_default()
+    }
+}
+
+impl StorageBackendMigration {
+    pub(crate) fn with_backends(
+        source: Box<dyn StorageBackend>,
+        target: Box<dyn StorageBackend>,
+        verification_period_ms: u64,
+        now: u64,
+    ) -> StorageBackendMigration {
+        let state = BackendMigrationState {
+            phase: BackendMigrationPhase::DualWrite,
+            verification_period_ms,
+            window_started_at: now,
+            unresolved: BTreeSet::new(),
+            full_pass_at: None,
+        };
+        StorageBackendMigration { source, target, state, divergences: VecDeque::new() }
+    }
+
+    fn remaining_at(&self, now: u64) -> u64 {
+        let elapsed = now.saturating_sub(self.state.window_started_at);
+        self.state.verification_period_ms.saturating_sub(elapsed)
+    }
+
+    pub(crate) fn write_at(&mut self, key: &str, value: &[u8], now: u64) -> Result<(), String> {
+        match self.state.phase {
+            BackendMigrationPhase::CutOver => self.target.write(key, value),
+            BackendMigrationPhase::RolledBack => self.source.write(key, value),
+            BackendMigrationPhase::DualWrite => {
+                self.source.write(key, value)?;
+                match self.target.write(key, value) {
+                    Ok(()) => self.resolve(key),
+                    Err(_) => self.diverge(key, DivergenceKind::TargetError, now),
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn read_at(&mut self, key: &str, now: u64) -> Result<Option<Zeroizing<Vec<u8>>>, String> {
+        match self.state.phase {
+            BackendMigrationPhase::CutOver => Ok(self.target.read(key)?.map(Zeroizing::new)),
+            BackendMigrationPhase::RolledBack => Ok(self.source.read(key)?.map(Zeroizing::new)),
+            BackendMigrationPhase::DualWrite => {
+                let authoritative = self.source.read(key)?.map(Zeroizing::new);
+                match self.target.read(key).map(|value| value.map(Zeroizing::new)) {
+                    Ok(mirrored) => match Self::compare(authoritative.as_deref().map(Vec::as_slice), mirrored.as_deref().map(Vec::as_slice)) {
+                        Some(kind) => self.diverge(key, kind, now),
+                        None => self.resolve(key),
+                    },
+                    Err(_) => self.diverge(key, DivergenceKind::TargetError, now),
+                }
+                Ok(authoritative)
+            }
+        }
+    }
+
+    pub(crate) fn delete_at(&mut self, key: &str, now: u64) -> Result<(), String> {
+        match self.state.phase {
+            BackendMigrationPhase::CutOver => self.target.delete(key),
+            BackendMigrationPhase::RolledBack => self.source.delete(key),
+            BackendMigrationPhase::DualWrite => {
+                self.source.delete(key)?;
+                match self.target.delete(key) {
+                    Ok(()) => self.resolve(key),
+                    Err(_) => self.diverge(key, DivergenceKind::TargetError, now),
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn backfill_at(&mut self, key: &str, now: u64) -> Result<bool, String> {
+        if self.state.phase != BackendMigrationPhase::DualWrite {
+            return Err("Backfill is only possible while double writing".to_string());
+        }
+        match self.source.read(key)?.map(Zeroizing::new) {
+            Some(value) => self.write_at(key, &value, now)?,
+            None => self.delete_at(key, now)?,
+        }
+        self.read_at(key, now)?;
+        Ok(!self.state.unresolved.contains(key))
+    }
+
+    pub(crate) fn verify_all_at(&mut self, now: u64) -> Result<u32, String> {
+        if self.state.phase != BackendMigrationPhase::DualWrite {
+            return Err("Verification is only possible while double writing".to_string());
+        }
+        let mut keys: BTreeSet<String> = self.source.keys()?.into_iter().collect();
+        keys.extend(self.target.keys().map_err(|e| format!("Full pass aborted: {}", e))?);
+        // Keys that diverged earlier but are gone from both backends are settled by the pass too
+        keys.extend(self.state.unresolved.iter().cloned());
+        for key in &keys {
+            self.backfill_at(key, now)?;
+        }
+        if self.state.unresolved.is_empty() {
+            self.state.full_pass_at = Some(now);
+        }
+        Ok(self.state.unresolved.len() as u32)
+    }
+
+    pub(crate) fn cut_over_at(&mut self, now: u64) -> Result<(), String> {
+        if self.state.phase != BackendMigrationPhase::DualWrite {
+            return Err("Cut-over is only possible while double writing".to_string());
+        }
+        if !self.state.unresolved.is_empty() {
+            return Err(format!("Cut-over refused: {} keys still diverge", self.state.unresolved.len()));
+        }
+        if self.state.full_pass_at.is_none() {
+            return Err("Cut-over refused: no full verification pass since the last divergence".to_string());
+        }
+        let remaining = self.remaining_at(now);
+        if remaining > 0 {
+            return Err(format!("Cut-over refused: verification window has {} ms left", remaining));
+        }
+        self.state.phase = BackendMigrationPhase::CutOver;
+        Ok(())
+    }
+
+    pub(crate) fn roll_back(&mut self) -> Result<(), String> {
+        if self.state.phase == BackendMigrationPhase::CutOver {
+            return Err("Cannot abort after cut-over".to_string());
+        }
+        self.state.phase = BackendMigrationPhase::RolledBack;
+        Ok(())
+    }
+
+    fn compare(source: Option<&[u8]>, target: Option<&[u8]>) -> Option<DivergenceKind> {
+        match (source, target) {
+            (Some(source), Some(target)) if !constant_time_compare(source, target) => Some(DivergenceKind::ValueMismatch),
+            (Some(_), None) => Some(DivergenceKind::MissingOnTarget),
+            (None, Some(_)) => Some(DivergenceKind::UnexpectedOnTarget),
+            _ => None,
+        }
+    }
+
+    fn diverge(&mut self, key: &str, kind: DivergenceKind, now: u64) {
+        self.state.unresolved.insert(key.to_string());
+        self.state.window_started_at = now;
+        self.state.full_pass_at = None;
+        if self.divergences.len() == MAX_DIVERGENCE_LOG {
+            self.divergences.pop_front();
+        }
+        self.divergences.push_back(StorageDivergence { key: key.to_string(), kind, detected_at: now });
+    }
+
+    fn resolve(&mut self, key: &str) {
+        self.state.unresolved.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::InMemoryStorageBackend;
+
+    const PERIOD: u64 = 1_000;
+
+    fn migration() -> (StorageBackendMigration, InMemoryStorageBackend, InMemoryStorageBackend) {
+        let (source, target) = (InMemoryStorageBackend::new(), InMemoryStorageBackend::new());
+        let migration = StorageBackendMigration::with_backends(Box::new(source.clone()), Box::new(target.clone()), PERIOD, 0);
+        (migration, source, target)
+    }
+
+    #[test]
+    fn test_divergence_blocks_cut_over_until_resolved_and_window_passes() {
+        let (mut migration, source, target) = migration();
+        migration.write_at("master", b"v1", 10).unwrap();
+        assert_eq!(target.get("master"), Some(b"v1".to_vec()));
+
+        // A key written before the migration and a target that drifted
+        source.insert("legacy", b"old");
+        target.insert("master", b"v0");
+        assert_eq!(migration.read_at("master", 500).unwrap().as_deref().map(Vec::as_slice), Some(&b"v1"[..]));
+        assert!(migration.read_at("legacy", 600).unwrap().is_some());
+        assert_eq!(migration.unresolved_keys(), ["legacy", "master"]);
+        assert!(migration.cut_over_at(2_000).unwrap_err().contains("2 keys"));
+
+        assert!(migration.backfill_at("legacy", 700).unwrap());
+        migration.write_at("master", b"v2", 800).unwrap();
+        assert!(migration.unresolved_keys().is_empty());
+        assert!(migration.cut_over_at(1_600).unwrap_err().contains("no full verification pass"));
+        assert_eq!(migration.verify_all_at(900).unwrap(), 0);
+        assert!(migration.cut_over_at(1_500).unwrap_err().contains("100 ms left"));
+        migration.cut_over_at(1_600).unwrap();
+
+        // Reads now come from the target alone
+        source.clear();
+        assert_eq!(migration.read_at("legacy", 1_700).unwrap().as_deref().map(Vec::as_slice), Some(&b"old"[..]));
+        let report: Vec<serde_json::Value> = serde_json::from_str(&migration.take_divergence_report()).unwrap();
+        let kinds: Vec<&str> = report.iter().map(|d| d["kind"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["value_mismatch", "missing_on_target"]);
+    }
+
+    #[test]
+    fn test_cut_over_needs_a_full_pass_that_copies_never_accessed_keys() {
+        let (mut migration, source, target) = migration();
+        source.insert("untouched-a", b"a");
+        source.insert("untouched-b", b"b");
+        target.insert("stale", b"left over");
+        migration.write_at("master", b"v1", 10).unwrap();
+
+        // Nothing read the old keys, so no divergence was ever seen for them
+        assert!(migration.unresolved_keys().is_empty());
+        assert!(migration.cut_over_at(5_000).unwrap_err().contains("no full verification pass"));
+
+        target.set_failing(true);
+        assert_eq!(migration.verify_all_at(100).unwrap_err(), "Full pass aborted: Storage backend unavailable");
+        target.set_failing(false);
+
+        assert_eq!(migration.verify_all_at(200).unwrap(), 0);
+        assert_eq!(target.get("untouched-a"), Some(b"a".to_vec()));
+        assert_eq!(target.get("untouched-b"), Some(b"b".to_vec()));
+        assert_eq!(target.get("stale"), None);
+        migration.cut_over_at(1_200).unwrap();
+        assert_eq!(migration.phase(), BackendMigrationPhase::CutOver);
+
+        // A divergence after the pass demands a new one
+        let (mut migration, source, _) = self::migration();
+        assert_eq!(migration.verify_all_at(0).unwrap(), 0);
+        source.insert("late", b"x");
+        migration.read_at("late", 10).unwrap();
+        migration.backfill_at("late", 20).unwrap();
+        assert!(migration.cut_over_at(5_000).unwrap_err().contains("no full verification pass"));
+    }
+
+    #[test]
+    fn test_target_failures_never_fail_the_caller_and_abort_keeps_source() {
+        let (mut migration, source, target) = migration();
+        target.set_failing(true);
+        migration.write_at("master", b"v1", 10).unwrap();
+        assert_eq!(migration.read_at("master", 20).unwrap().as_deref().map(Vec::as_slice), Some(&b"v1"[..]));
+        assert_eq!(migration.unresolved_keys(), ["master"]);
+
+        let state: BackendMigrationState = serde_json::from_str(&migration.export_state()).unwrap();
+        assert_eq!((state.window_started_at, state.unresolved.len()), (20, 1));
+
+        migration.roll_back().unwrap();
+        assert!(migration.cut_over_at(u64::MAX).is_err());
+        migration.write_at("master", b"v2", 30).unwrap();
+        assert_eq!(source.get("master"), Some(b"v2".to_vec()));
+    }
+}
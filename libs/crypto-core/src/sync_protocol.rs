@@ -0,0 +1,608 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::platform;
+use crate::scope::{scoped_error, ErrorScope};
+use crate::trusted_time::{check_message_time, record_peer_clock, TimedMessageKind};
+
+// Sync protocol state machine
+// Two devices reconcile a change set in one session. The initiator proposes the set by
+// digest, the responder either acknowledges it or first asks for the deltas it is
+// missing, and the initiator commits. Either side may abort any open session.
+// Every message travels in a versioned frame and is parsed into a typed schema that
+// rejects unknown fields. Allowed moves are listed once in `TRANSITIONS`; a message
+// that matches no row is refused with a typed error and leaves the session unchanged.
+// `sync_protocol_schema` renders the message schemas and that table as documentation.
+// Frames carry the sender's clock as `sent_at` (required since v2); received frames are
+// checked against the sync window in `trusted_time`. Only a session bound to a peer the
+// transport authenticated corrects for that peer's skew and records accepted frames.
+// `send_over` and `receive_from` drive a session over any `SyncTransport`; a frame the
+// transport refuses to carry leaves the session where it was.
+
+pub const SYNC_PROTOCOL_VERSION: u32 = 2;
+
+/// Where a session stands; which states occur depends on the device's role
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncSessionState {
+    Idle = 0,
+    AwaitingAck = 1,    // Initiator: proposal sent
+    ServingDelta = 2,   // Initiator: responder asked for missing deltas
+    ReadyToCommit = 3,  // Initiator: proposal acknowledged
+    Proposed = 4,       // Responder: proposal received
+    AwaitingDelta = 5,  // Responder: deltas requested
+    AwaitingCommit = 6, // Responder: proposal acknowledged
+    Committed = 7,
+    Aborted = 8,
+}
+
+impl SyncSessionState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SyncSessionState::Idle => "idle",
+            SyncSessionState::AwaitingAck => "awaiting_ack",
+            SyncSessionState::ServingDelta => "serving_delta",
+            SyncSessionState::ReadyToCommit => "ready_to_commit",
+            SyncSessionState::Proposed => "proposed",
+            SyncSessionState::AwaitingDelta => "awaiting_delta",
+            SyncSessionState::AwaitingCommit => "awaiting_commit",
+            SyncSessionState::Committed => "committed",
+            SyncSessionState::Aborted => "aborted",
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        matches!(self, SyncSessionState::Committed | SyncSessionState::Aborted)
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMessageKind {
+    Propose = 0,
+    Ack = 1,
+    Commit = 2,
+    Abort = 3,
+    DeltaRequest = 4,
+    DeltaResponse = 5,
+}
+
+impl SyncMessageKind {
+    const ALL: [SyncMessageKind; 6] = [
+        SyncMessageKind::Propose,
+        SyncMessageKind::Ack,
+        SyncMessageKind::Commit,
+        SyncMessageKind::Abort,
+        SyncMessageKind::DeltaRequest,
+        SyncMessageKind::DeltaResponse,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SyncMessageKind::Propose => "propose",
+            SyncMessageKind::Ack => "ack",
+            SyncMessageKind::Commit => "commit",
+            SyncMessageKind::Abort => "abort",
+            SyncMessageKind::DeltaRequest => "delta_request",
+            SyncMessageKind::DeltaResponse => "delta_response",
+        }
+    }
+
+    // Field name, JSON type and meaning, in serialization order after `type`
+    fn fields(&self) -> &'static [(&'static str, &'static str, &'static str)] {
+        const SESSION: (&str, &str, &str) = ("session_id", "string", "Session the message belongs to");
+        const SENDER: (&str, &str, &str) = ("sender", "string", "Fingerprint of the sending device");
+        const DIGEST: (&str, &str, &str) = ("digest", "string", "Hex SHA-256 of the proposed change set");
+        match self {
+            SyncMessageKind::Propose => &[
+                SESSION,
+                SENDER,
+                ("base_sequence", "u64", "Last change sequence the proposal builds on"),
+                ("change_count", "u32", "Number of changes in the set"),
+                DIGEST,
+            ],
+            SyncMessageKind::Ack | SyncMessageKind::Commit => &[SESSION, SENDER, DIGEST],
+            SyncMessageKind::Abort => &[SESSION, SENDER, ("reason", "string", "Why the session was abandoned")],
+            SyncMessageKind::DeltaRequest => &[
+                SESSION,
+                SENDER,
+                ("since_sequence", "u64", "First change sequence the responder is missing"),
+            ],
+            SyncMessageKind::DeltaResponse => &[
+                SESSION,
+                SENDER,
+                ("since_sequence", "u64", "Echo of the request"),
+                ("up_to_sequence", "u64", "Last change sequence included"),
+                ("deltas", "string[]", "Sealed change records, oldest first"),
+            ],
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncDirection {
+    Sent = 0,
+    Received = 1,
+}
+
+impl SyncDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SyncDirection::Sent => "sent",
+            SyncDirection::Received => "received",
+        }
+    }
+}
+
+use SyncDirection::{Received, Sent};
+use SyncMessageKind::{Ack, Commit, DeltaRequest, DeltaResponse, Propose};
+use SyncSessionState::{AwaitingAck, AwaitingCommit, AwaitingDelta, Committed, Idle, Proposed, ReadyToCommit, ServingDelta};
+
+/// Every allowed move except aborts, which any open (non-idle, non-terminal) state accepts
+const TRANSITIONS: &[(SyncSessionState, SyncDirection, SyncMessageKind, SyncSessionState)] = &[
+    (Idle, Sent, Propose, AwaitingAck),
+    (AwaitingAck, Received, Ack, ReadyToCommit),
+    (AwaitingAck, Received, DeltaRequest, ServingDelta),
+    (ServingDelta, Sent, DeltaResponse, AwaitingAck),
+    (ReadyToCommit, Sent, Commit, Committed),
+    (Idle, Received, Propose, Proposed),
+    (Proposed, Sent, Ack, AwaitingCommit),
+    (Proposed, Sent, DeltaRequest, AwaitingDelta),
+    (AwaitingDelta, Received, DeltaResponse, Proposed),
+    (AwaitingCommit, Received, Commit, Committed),
+];
+
+/// Typed body of a sync message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum SyncMessage {
+    Propose { session_id: String, sender: String, base_sequence: u64, change_count: u32, digest: String },
+    Ack { session_id: String, sender: String, digest: String },
+    Commit { session_id: String, sender: String, digest: String },
+    Abort { session_id: String, sender: String, reason: String },
+    DeltaRequest { session_id: String, sender: String, since_sequence: u64 },
+    DeltaResponse { session_id: String, sender: String, since_sequence: u64, up_to_sequence: u64, deltas: Vec<String> },
+}
+
+impl SyncMessage {
+    pub(crate) fn kind(&self) -> SyncMessageKind {
+        match self {
+            SyncMessage::Propose { .. } => SyncMessageKind::Propose,
+            SyncMessage::Ack { .. } => SyncMessageKind::Ack,
+            SyncMessage::Commit { .. } => SyncMessageKind::Commit,
+            SyncMessage::Abort { .. } => SyncMessageKind::Abort,
+            SyncMessage::DeltaRequest { .. } => SyncMessageKind::DeltaRequest,
+            SyncMessage::DeltaResponse { .. } => SyncMessageKind::DeltaResponse,
+        }
+    }
+
+    fn session_id(&self) -> &str {
+        match self {
+            SyncMessage::Propose { session_id, .. }
+            | SyncMessage::Ack { session_id, .. }
+            | SyncMessage::Commit { session_id, .. }
+            | SyncMessage::Abort { session_id, .. }
+            | SyncMessage::DeltaRequest { session_id, .. }
+            | SyncMessage::DeltaResponse { session_id, .. } => session_id,
+        }
+    }
+
+    fn sender(&self) -> &str {
+        match self {
+            SyncMessage::Propose { sender, .. }
+            | SyncMessage::Ack { sender, .. }
+            | SyncMessage::Commit { sender, .. }
+            | SyncMessage::Abort { sender, .. }
+            | SyncMessage::DeltaRequest { sender, .. }
+            | SyncMessage::DeltaResponse { sender, .. } => sender,
+        }
+    }
+}
+
+/// Versioned wire frame around one message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SyncFrame {
+    version: u32,
+    message: SyncMessage,
+    sent_at: u64, // Sender's clock in ms
+
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncErrorKind {
+    Malformed = 0,          // Not valid JSON or not a known schema
+    UnsupportedVersion = 1,
+    InvalidTransition = 2,  // Message not allowed in the current state
+    SessionMismatch = 3,
+    DigestMismatch = 4,     // Ack or commit for a different change set
+    DeltaMismatch = 5,      // Delta response does not answer the request
+    Expired = 6,            // Sent outside the sync time window, after skew correction
+}
+
+// Typed error for a sync message that was refused; the session is left unchanged
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncProtocolError {
+    kind: SyncErrorKind,
+    state: SyncSessionState,
+    direction: SyncDirection,
+    message_kind: Option<SyncMessageKind>,
+    detail: String,
+    scope: ErrorScope,
+}
+
+#[wasm_bindgen]
+impl SyncProtocolError {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> SyncErrorKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> SyncSessionState {
+        self.state
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn direction(&self) -> SyncDirection {
+        self.direction
+    }
+
+    // None when the message could not be parsed
+    #[wasm_bindgen(getter)]
+    pub fn message_kind(&self) -> Option<SyncMessageKind> {
+        self.message_kind
+    }
+}
+
+impl std::fmt::Display for SyncProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let message = self.message_kind.map_or("message", |kind| kind.as_str());
+        write!(
+            f,
+            "Sync {} {} refused in state {}: {}{}",
+            message, self.direction.as_str(), self.state.as_str(), self.detail, self.scope
+        )
+    }
+}
+
+scoped_error!(SyncProtocolError);
+
+/// Carries wire frames between two devices; delivery order must be preserved
+pub trait SyncTransport {
+    fn send_frame(&mut self, frame: &str) -> Result<(), String>;
+    /// Next frame from the peer, `None` when nothing is waiting
+    fn receive_frame(&mut self) -> Result<Option<String>, String>;
+}
+
+/// One side of one sync session
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct SyncSession {
+    session_id: String,
+    peer_id: Option<String>,
+    state: SyncSessionState,
+    proposal_digest: Option<String>,
+    requested_since: Option<u64>,
+    abort_reason: Option<String>,
+}
+
+#[wasm_bindgen]
+impl SyncSession {
+    #[wasm_bindgen(constructor)]
+    pub fn new(session_id: String) -> SyncSession {
+        SyncSession { session_id, peer_id: None, state: SyncSessionState::Idle, proposal_digest: None, requested_since: None, abort_reason: None }
+    }
+
+    /// Session with a peer whose device id the transport has authenticated. Frames from
+    /// any other sender are refused, and the peer's clock skew is learned and corrected.
+    #[wasm_bindgen]
+    pub fn for_peer(session_id: String, peer_id: String) -> SyncSession {
+        SyncSession { peer_id: Some(peer_id), ..SyncSession::new(session_id) }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn session_id(&self) -> String {
+        self.session_id.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> SyncSessionState {
+        self.state
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn abort_reason(&self) -> Option<String> {
+        self.abort_reason.clone()
+    }
+
+    /// Validate an outgoing message (JSON of the message body) and return its wire frame
+    #[wasm_bindgen]
+    pub fn send(&mut self, message_json: &str) -> Result<String, SyncProtocolError> {
+        let message: SyncMessage = serde_json::from_str(message_json)
+            .map_err(|e| self.error(SyncErrorKind::Malformed, Sent, None, format!("invalid message: {}", e)))?;
+        self.apply(Sent, &message)?;
+        let frame = SyncFrame { version: SYNC_PROTOCOL_VERSION, message, sent_at: platform::now_ms() };
+        Ok(serde_json::to_string(&frame).unwrap_or_default())
+    }
+
+    /// Apply an incoming wire frame; returns the new state
+    #[wasm_bindgen]
+    pub fn receive(&mut self, frame_json: &str) -> Result<SyncSessionState, SyncProtocolError> {
+        self.receive_at(frame_json, platform::now_ms())
+    }
+}
+
+impl SyncSession {
+    /// Send a message over `transport`; the session only advances once the frame is handed off
+    pub fn send_over(&mut self, transport: &mut dyn SyncTransport, message_json: &str) -> Result<(), String> {
+        let mut next = self.clone();
+        let frame = next.send(message_json).map_err(|e| e.to_string())?;
+        transport.send_frame(&frame)?;
+        *self = next;
+        Ok(())
+    }
+
+    /// Apply the next waiting frame from `transport`; `None` when nothing is waiting
+    pub fn receive_from(&mut self, transport: &mut dyn SyncTransport) -> Result<Option<SyncSessionState>, String> {
+        self.receive_from_at(transport, platform::now_ms())
+    }
+
+    pub(crate) fn receive_from_at(&mut self, transport: &mut dyn SyncTransport, now: u64) -> Result<Option<SyncSessionState>, String> {
+        match transport.receive_frame()? {
+            Some(frame) => self.receive_at(&frame, now).map(Some).map_err(|e| e.to_string()),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn receive_at(&mut self, frame_json: &str, now: u64) -> Result<SyncSessionState, SyncProtocolError> {
+        let frame = parse_frame(frame_json).map_err(|(kind, detail)| self.error(kind, Received, None, detail))?;
+        let message_kind = Some(frame.message.kind());
+        if let Some(peer_id) = self.peer_id.as_deref().filter(|peer_id| *peer_id != frame.message.sender()) {
+            let detail = format!("sender {} is not the session peer {}", frame.message.sender(), peer_id);
+            return Err(self.error(SyncErrorKind::SessionMismatch, Received, message_kind, detail));
+        }
+        check_message_time(TimedMessageKind::SyncFrame, self.peer_id.as_deref(), frame.sent_at, now)
+            .map_err(|detail| self.error(SyncErrorKind::Expired, Received, message_kind, detail))?;
+        let state = self.apply(Received, &frame.message)?;
+        if let Some(peer_id) = &self.peer_id {
+            record_peer_clock(peer_id, frame.sent_at, now);
+        }
+        Ok(state)
+    }
+
+    pub(crate) fn apply(&mut self, direction: SyncDirection, message: &SyncMessage) -> Result<SyncSessionState, SyncProtocolError> {
+        let kind = message.kind();
+        let refuse = |error_kind, detail: String| self.error(error_kind, direction, Some(kind), detail);
+
+        if message.session_id() != self.session_id {
+            return Err(refuse(SyncErrorKind::SessionMismatch, format!("message is for session {}", message.session_id())));
+        }
+        let next = match message {
+            SyncMessage::Abort { .. } if self.state.is_terminal() || self.state == Idle => None,
+            SyncMessage::Abort { .. } => Some(SyncSessionState::Aborted),
+            _ => TRANSITIONS
+                .iter()
+                .find(|(from, dir, msg, _)| *from == self.state && *dir == direction && *msg == kind)
+                .map(|(_, _, _, to)| *to),
+        };
+        let Some(next) = next else {
+            return Err(refuse(SyncErrorKind::InvalidTransition, "not allowed by the protocol".to_string()));
+        };
+
+        match message {
+            SyncMessage::Ack { digest, .. } | SyncMessage::Commit { digest, .. }
+                if self.proposal_digest.as_deref() != Some(digest.as_str()) =>
+            {
+                return Err(refuse(SyncErrorKind::DigestMismatch, "digest does not match the proposal".to_string()));
+            }
+            SyncMessage::DeltaResponse { since_sequence, up_to_sequence, .. }
+                if self.requested_since != Some(*since_sequence) || up_to_sequence < since_sequence =>
+            {
+                return Err(refuse(SyncErrorKind::DeltaMismatch, format!("deltas {}..={} do not answer the request", since_sequence, up_to_sequence)));
+            }
+            _ => {}
+        }
+
+        match message {
+            SyncMessage::Propose { digest, .. } => self.proposal_digest = Some(digest.clone()),
+            SyncMessage::DeltaRequest { since_sequence, .. } => self.requested_since = Some(*since_sequence),
+            SyncMessage::DeltaResponse { .. } => self.requested_since = None,
+            SyncMessage::Abort { reason, .. } => self.abort_reason = Some(reason.clone()),
+            SyncMessage::Ack { .. } | SyncMessage::Commit { .. } => {}
+        }
+        self.state = next;
+        Ok(next)
+    }
+
+    fn error(&self, kind: SyncErrorKind, direction: SyncDirection, message_kind: Option<SyncMessageKind>, detail: String) -> SyncProtocolError {
+        SyncProtocolError { kind, state: self.state, direction, message_kind, detail, scope: ErrorScope::capture() }
+    }
+}
+
+fn parse_frame(frame_json: &str) -> Result<SyncFrame, (SyncErrorKind, String)> {
+    let value: serde_json::Value = serde_json::from_str(frame_json)
+        .map_err(|e| (SyncErrorKind::Malformed, format!("invalid frame: {}", e)))?;
+    // Check the version first so a newer peer gets a version error, not a schema error
+    match value.get("version").and_then(|version| version.as_u64()) {
+        Some(version) if version == SYNC_PROTOCOL_VERSION as u64 => {}
+        Some(version) => return Err((SyncErrorKind::UnsupportedVersion, format!("protocol version {} (supported: {})", version, SYNC_PROTOCOL_VERSION))),
+        None => return Err((SyncErrorKind::Malformed, "frame has no version".to_string())),
+    }
+    serde_json::from_value(value).map_err(|e| (SyncErrorKind::Malformed, format!("invalid frame: {}", e)))
+}
+
+/// Markdown reference of the wire format, message schemas and state transitions
+#[wasm_bindgen]
+pub fn sync_protocol_schema() -> String {
+    let mut doc = format!(
+        "# Sync protocol v{}\n\nEach message is sent as `{{\"version\": {}, \"message\": {{\"type\": ..., ...}}, \"sent_at\": <ms>}}`. Unknown fields are rejected; frames outside the sync time window (after clock-skew correction) are refused.\n",
+        SYNC_PROTOCOL_VERSION, SYNC_PROTOCOL_VERSION
+    );
+    for kind in SyncMessageKind::ALL {
+        doc.push_str(&format!("\n## `{}`\n\n| Field | Type | Description |\n|---|---|---|\n", kind.as_str()));
+        for (name, json_type, description) in kind.fields() {
+            doc.push_str(&format!("| `{}` | {} | {} |\n", name, json_type, description));
+        }
+    }
+    doc.push_str("\n## Transitions\n\n| From | Direction | Message | To |\n|---|---|---|---|\n");
+    for (from, direction, kind, to) in TRANSITIONS {
+        doc.push_str(&format!("| {} | {} | `{}` | {} |\n", from.as_str(), direction.as_str(), kind.as_str(), to.as_str()));
+    }
+    doc.push_str("| any open state | sent or received | `abort` | aborted |\n\nAny other message is refused and leaves the session unchanged.\n");
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::InMemorySyncTransport;
+
+    fn frame(message: serde_json::Value) -> String {
+        serde_json::json!({ "version": SYNC_PROTOCOL_VERSION, "message": message, "sent_at": platform::now_ms() }).to_string()
+    }
+
+    #[test]
+    fn test_session_runs_through_delta_exchange_to_commit() {
+        let propose = r#"{"type":"propose","session_id":"s1","sender":"a","base_sequence":7,"change_count":2,"digest":"d1"}"#;
+        let mut initiator = SyncSession::new("s1".to_string());
+        let mut responder = SyncSession::new("s1".to_string());
+
+        let wire = initiator.send(propose).unwrap();
+        assert_eq!(responder.receive(&wire), Ok(SyncSessionState::Proposed));
+        let wire = responder.send(r#"{"type":"delta_request","session_id":"s1","sender":"b","since_sequence":5}"#).unwrap();
+        assert_eq!(initiator.receive(&wire), Ok(SyncSessionState::ServingDelta));
+
+        let mismatched = r#"{"type":"delta_response","session_id":"s1","sender":"a","since_sequence":6,"up_to_sequence":7,"deltas":[]}"#;
+        assert_eq!(initiator.send(mismatched).unwrap_err().kind(), SyncErrorKind::DeltaMismatch);
+        let wire = initiator.send(&mismatched.replace(":6,", ":5,")).unwrap();
+        assert_eq!(responder.receive(&wire), Ok(SyncSessionState::Proposed));
+
+        let wire = responder.send(r#"{"type":"ack","session_id":"s1","sender":"b","digest":"d1"}"#).unwrap();
+        assert_eq!(initiator.receive(&wire), Ok(SyncSessionState::ReadyToCommit));
+        assert_eq!(initiator.send(r#"{"type":"commit","session_id":"s1","sender":"a","digest":"d2"}"#).unwrap_err().kind(), SyncErrorKind::DigestMismatch);
+        let wire = initiator.send(r#"{"type":"commit","session_id":"s1","sender":"a","digest":"d1"}"#).unwrap();
+        assert_eq!(responder.receive(&wire), Ok(SyncSessionState::Committed));
+
+        // Terminal sessions refuse everything, including aborts
+        let abort = serde_json::json!({ "type": "abort", "session_id": "s1", "sender": "b", "reason": "late" });
+        let refused = initiator.receive(&frame(abort)).unwrap_err();
+        assert_eq!((refused.kind(), refused.state()), (SyncErrorKind::InvalidTransition, SyncSessionState::Committed));
+    }
+
+    #[test]
+    fn test_every_unlisted_transition_is_refused() {
+        let states = [Idle, AwaitingAck, ServingDelta, ReadyToCommit, Proposed, AwaitingDelta, AwaitingCommit, Committed, SyncSessionState::Aborted];
+        let sample = |kind: SyncMessageKind| {
+            let (session_id, sender, digest) = ("s1".to_string(), "a".to_string(), "d1".to_string());
+            match kind {
+                SyncMessageKind::Propose => SyncMessage::Propose { session_id, sender, base_sequence: 0, change_count: 1, digest },
+                SyncMessageKind::Ack => SyncMessage::Ack { session_id, sender, digest },
+                SyncMessageKind::Commit => SyncMessage::Commit { session_id, sender, digest },
+                SyncMessageKind::Abort => SyncMessage::Abort { session_id, sender, reason: "user".to_string() },
+                SyncMessageKind::DeltaRequest => SyncMessage::DeltaRequest { session_id, sender, since_sequence: 0 },
+                SyncMessageKind::DeltaResponse => SyncMessage::DeltaResponse { session_id, sender, since_sequence: 0, up_to_sequence: 0, deltas: Vec::new() },
+            }
+        };
+
+        for state in states {
+            for direction in [Sent, Received] {
+                for kind in SyncMessageKind::ALL {
+                    let mut session = SyncSession { state, proposal_digest: Some("d1".to_string()), requested_since: Some(0), ..SyncSession::new("s1".to_string()) };
+                    let listed = TRANSITIONS.iter().any(|(from, dir, msg, _)| (*from, *dir, *msg) == (state, direction, kind))
+                        || (kind == SyncMessageKind::Abort && state != Idle && !state.is_terminal());
+                    let result = session.apply(direction, &sample(kind));
+                    assert_eq!(result.is_ok(), listed, "{:?} {:?} {:?}", state, direction, kind);
+                    if !listed {
+                        assert_eq!(result.unwrap_err().kind(), SyncErrorKind::InvalidTransition);
+                        assert_eq!(session.state(), state);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_schemas_match_documentation_and_reject_unknown_input() {
+        for kind in SyncMessageKind::ALL {
+            // Build a sample from the documented fields alone
+            let mut sample = serde_json::json!({ "type": kind.as_str() });
+            for (name, json_type, _) in kind.fields() {
+                sample[*name] = match *json_type {
+                    "string" => serde_json::json!("x"),
+                    "string[]" => serde_json::json!([]),
+                    _ => serde_json::json!(1),
+                };
+            }
+            let parsed: SyncMessage = serde_json::from_value(sample).unwrap();
+            assert_eq!(parsed.kind(), kind);
+            let serialized = serde_json::to_value(&parsed).unwrap();
+            let keys: Vec<&str> = serialized.as_object().unwrap().keys().map(|key| key.as_str()).filter(|key| *key != "type").collect();
+            let mut documented: Vec<&str> = kind.fields().iter().map(|(name, _, _)| *name).collect();
+            documented.sort_unstable();
+            assert_eq!(keys, documented);
+            assert!(sync_protocol_schema().contains(&format!("## `{}`", kind.as_str())));
+        }
+
+        let mut session = SyncSession::new("s1".to_string());
+        let ack_from = |sender: &str| serde_json::json!({ "type": "ack", "session_id": "s1", "sender": sender, "digest": "d1" });
+        let ack = ack_from("a");
+        let newer = serde_json::json!({ "version": SYNC_PROTOCOL_VERSION + 1, "message": { "type": "hello" } }).to_string();
+        assert_eq!(session.receive(&newer).unwrap_err().kind(), SyncErrorKind::UnsupportedVersion);
+        let mut extra = ack.clone();
+        extra["priority"] = serde_json::json!(1);
+        assert_eq!(session.receive(&frame(extra)).unwrap_err().kind(), SyncErrorKind::Malformed);
+        let mut other_session = ack;
+        other_session["session_id"] = serde_json::json!("s2");
+        assert_eq!(session.receive(&frame(other_session)).unwrap_err().kind(), SyncErrorKind::SessionMismatch);
+        let stale = serde_json::json!({ "version": SYNC_PROTOCOL_VERSION, "message": ack_from("stale-peer"), "sent_at": 1_000 }).to_string();
+        assert_eq!(session.receive_at(&stale, 1_000 + 3_600_000).unwrap_err().kind(), SyncErrorKind::Expired);
+        let untimed = serde_json::json!({ "version": SYNC_PROTOCOL_VERSION, "message": ack_from("a") }).to_string();
+        assert_eq!(session.receive(&untimed).unwrap_err().kind(), SyncErrorKind::Malformed);
+
+        // A session bound to its authenticated peer refuses frames claiming another sender
+        let mut bound = SyncSession::for_peer("s1".to_string(), "b".to_string());
+        let propose = serde_json::json!({ "type": "propose", "session_id": "s1", "sender": "mallory", "base_sequence": 0, "change_count": 1, "digest": "d1" });
+        assert_eq!(bound.receive(&frame(propose)).unwrap_err().kind(), SyncErrorKind::SessionMismatch);
+    }
+
+    #[test]
+    fn test_end_to_end_session_over_transport_survives_a_partition() {
+        let (mut link_a, mut link_b) = InMemorySyncTransport::pair();
+        let mut initiator = SyncSession::for_peer("s9".to_string(), "b".to_string());
+        let mut responder = SyncSession::for_peer("s9".to_string(), "a".to_string());
+
+        initiator.send_over(&mut link_a, r#"{"type":"propose","session_id":"s9","sender":"a","base_sequence":3,"change_count":1,"digest":"d9"}"#).unwrap();
+        assert_eq!(responder.receive_from(&mut link_b), Ok(Some(SyncSessionState::Proposed)));
+        assert_eq!(responder.receive_from(&mut link_b), Ok(None));
+
+        responder.send_over(&mut link_b, r#"{"type":"delta_request","session_id":"s9","sender":"b","since_sequence":2}"#).unwrap();
+        assert_eq!(initiator.receive_from(&mut link_a), Ok(Some(SyncSessionState::ServingDelta)));
+
+        // A send that the partitioned link refuses leaves the session where it was
+        link_a.set_connected(false);
+        let response = r#"{"type":"delta_response","session_id":"s9","sender":"a","since_sequence":2,"up_to_sequence":3,"deltas":["x"]}"#;
+        assert!(initiator.send_over(&mut link_a, response).is_err());
+        assert_eq!(initiator.state(), SyncSessionState::ServingDelta);
+        link_a.set_connected(true);
+        initiator.send_over(&mut link_a, response).unwrap();
+        assert_eq!(responder.receive_from(&mut link_b), Ok(Some(SyncSessionState::Proposed)));
+
+        responder.send_over(&mut link_b, r#"{"type":"ack","session_id":"s9","sender":"b","digest":"d9"}"#).unwrap();
+        assert_eq!(initiator.receive_from(&mut link_a), Ok(Some(SyncSessionState::ReadyToCommit)));
+        initiator.send_over(&mut link_a, r#"{"type":"commit","session_id":"s9","sender":"a","digest":"d9"}"#).unwrap();
+        assert_eq!(responder.receive_from(&mut link_b), Ok(Some(SyncSessionState::Committed)));
+        assert_eq!(initiator.state(), SyncSessionState::Committed);
+
+        // A refused frame is reported and consumed without moving the session
+        let (mut rogue, mut link_c) = InMemorySyncTransport::pair();
+        let mut session = SyncSession::for_peer("s10".to_string(), "a".to_string());
+        SyncSession::new("s10".to_string())
+            .send_over(&mut rogue, r#"{"type":"propose","session_id":"s10","sender":"mallory","base_sequence":1,"change_count":1,"digest":"d"}"#)
+            .unwrap();
+        assert!(session.receive_from(&mut link_c).unwrap_err().contains("not the session peer"));
+        assert_eq!(session.state(), SyncSessionState::Idle);
+    }
+}
@@ -0,0 +1,240 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::rc::Rc;
+use zeroize::Zeroizing;
+use crate::integration::{DeviceKeyManagementConfig, DeviceKeyStorage};
+use crate::memory::SecureBuffer;
+use crate::storage_migration::StorageBackend;
+use crate::sync_protocol::SyncTransport;
+
+// Reference test doubles
+// In-memory implementations of the traits the host normally backs, built for the
+// crate's own tests and, behind the `test_support` feature, for downstream apps that
+// want to run end-to-end scenarios without a device. They behave like a healthy
+// platform (ordered delivery, read-your-writes, nothing persisted across processes)
+// and each has a switch to make calls fail, so failure paths can be exercised too.
+// Clones share state, so a test can keep a handle to a double it boxed into the crate.
+
+type Queue = Rc<RefCell<VecDeque<String>>>;
+
+/// One end of an in-memory link between two devices
+#[derive(Clone)]
+pub struct InMemorySyncTransport {
+    outbox: Queue,
+    inbox: Queue,
+    connected: Rc<Cell<bool>>,
+}
+
+impl InMemorySyncTransport {
+    /// Two connected ends; frames sent on one arrive at the other in order
+    pub fn pair() -> (Self, Self) {
+        let (a_to_b, b_to_a) = (Queue::default(), Queue::default());
+        let connected = Rc::new(Cell::new(true));
+        (
+            InMemorySyncTransport { outbox: a_to_b.clone(), inbox: b_to_a.clone(), connected: connected.clone() },
+            InMemorySyncTransport { outbox: b_to_a, inbox: a_to_b, connected },
+        )
+    }
+
+    /// Partition or heal the link for both ends; frames already in flight are kept
+    pub fn set_connected(&self, connected: bool) {
+        self.connected.set(connected);
+    }
+
+    /// Frames waiting to be received on this end
+    pub fn pending(&self) -> usize {
+        self.inbox.borrow().len()
+    }
+}
+
+impl SyncTransport for InMemorySyncTransport {
+    fn send_frame(&mut self, frame: &str) -> Result<(), String> {
+        if !self.connected.get() {
+            return Err("Transport disconnected".to_string());
+        }
+        self.outbox.borrow_mut().push_back(frame.to_string());
+        Ok(())
+    }
+
+    fn receive_frame(&mut self) -> Result<Option<String>, String> {
+        if !self.connected.get() {
+            return Err("Transport disconnected".to_string());
+        }
+        Ok(self.inbox.borrow_mut().pop_front())
+    }
+}
+
+/// Secure storage backend over a map
+#[derive(Default, Clone)]
+pub struct InMemoryStorageBackend {
+    values: Rc<RefCell<BTreeMap<String, Vec<u8>>>>,
+    failing: Rc<Cell<bool>>,
+}
+
+impl InMemoryStorageBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make every following call fail until reset, as an unavailable backend would
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.set(failing);
+    }
+
+    /// Read a value directly, bypassing the failure switch
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.values.borrow().get(key).cloned()
+    }
+
+    /// Seed or overwrite a value directly, e.g. to simulate drift or pre-existing data
+    pub fn insert(&self, key: &str, value: &[u8]) {
+        self.values.borrow_mut().insert(key.to_string(), value.to_vec());
+    }
+
+    pub fn clear(&self) {
+        self.values.borrow_mut().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.borrow().is_empty()
+    }
+
+    fn check_available(&self) -> Result<(), String> {
+        if self.failing.get() { Err("Storage backend unavailable".to_string()) } else { Ok(()) }
+    }
+}
+
+impl StorageBackend for InMemoryStorageBackend {
+    fn read(&mut self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        self.check_available()?;
+        Ok(self.get(key))
+    }
+
+    fn write(&mut self, key: &str, value: &[u8]) -> Result<(), String> {
+        self.check_available()?;
+        self.insert(key, value);
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &str) -> Result<(), String> {
+        self.check_available()?;
+        self.values.borrow_mut().remove(key);
+        Ok(())
+    }
+
+    fn keys(&mut self) -> Result<Vec<String>, String> {
+        self.check_available()?;
+        Ok(self.values.borrow().keys().cloned().collect())
+    }
+}
+
+/// Platform keystore over a map; key material is zeroized when removed or dropped
+#[derive(Clone)]
+pub struct InMemoryKeystore {
+    keys: Rc<RefCell<BTreeMap<String, Zeroizing<Vec<u8>>>>>,
+    config: DeviceKeyManagementConfig,
+    locked: Rc<Cell<bool>>,
+}
+
+impl InMemoryKeystore {
+    /// Software keystore: no HSM, biometrics or secure enclave
+    pub fn new(device_salt: Vec<u8>) -> Self {
+        Self::with_capabilities(DeviceKeyManagementConfig::new(device_salt, false, false, false))
+    }
+
+    pub fn with_capabilities(config: DeviceKeyManagementConfig) -> Self {
+        InMemoryKeystore { keys: Rc::default(), config, locked: Rc::new(Cell::new(false)) }
+    }
+
+    /// A locked keystore refuses every access, like a device before first unlock
+    pub fn set_locked(&self, locked: bool) {
+        self.locked.set(locked);
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.borrow().is_empty()
+    }
+
+    fn check_unlocked(&self) -> Result<(), String> {
+        if self.locked.get() { Err("Keystore is locked".to_string()) } else { Ok(()) }
+    }
+}
+
+impl DeviceKeyStorage for InMemoryKeystore {
+    fn store_key(&self, key_id: &str, key_data: &SecureBuffer) -> Result<(), String> {
+        self.check_unlocked()?;
+        let material = key_data.as_slice().map_err(|e| e.to_string())?;
+        self.keys.borrow_mut().insert(key_id.to_string(), Zeroizing::new(material.to_vec()));
+        Ok(())
+    }
+
+    fn retrieve_key(&self, key_id: &str) -> Result<SecureBuffer, String> {
+        self.check_unlocked()?;
+        self.keys
+            .borrow()
+            .get(key_id)
+            .map(|material| SecureBuffer::from_bytes(material.to_vec()))
+            .ok_or_else(|| format!("Key not found: {}", key_id))
+    }
+
+    fn delete_key(&self, key_id: &str) -> Result<(), String> {
+        self.check_unlocked()?;
+        self.keys
+            .borrow_mut()
+            .remove(key_id)
+            .map(|_| ())
+            .ok_or_else(|| format!("Key not found: {}", key_id))
+    }
+
+    fn key_exists(&self, key_id: &str) -> bool {
+        !self.locked.get() && self.keys.borrow().contains_key(key_id)
+    }
+
+    fn get_capabilities(&self) -> DeviceKeyManagementConfig {
+        self.config.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync_protocol::{SyncSession, SyncSessionState};
+
+    #[test]
+    fn test_doubles_carry_a_sync_session_and_store_device_keys() {
+        let (mut link_a, mut link_b) = InMemorySyncTransport::pair();
+        let mut initiator = SyncSession::new("s1".to_string());
+        let mut responder = SyncSession::new("s1".to_string());
+
+        let propose = r#"{"type":"propose","session_id":"s1","sender":"a","base_sequence":1,"change_count":1,"digest":"d1"}"#;
+        link_a.send_frame(&initiator.send(propose).unwrap()).unwrap();
+        assert_eq!(link_b.pending(), 1);
+        let frame = link_b.receive_frame().unwrap().unwrap();
+        assert_eq!(responder.receive(&frame), Ok(SyncSessionState::Proposed));
+
+        link_b.send_frame(&responder.send(r#"{"type":"ack","session_id":"s1","sender":"b","digest":"d1"}"#).unwrap()).unwrap();
+        link_a.set_connected(false);
+        assert!(link_a.receive_frame().is_err());
+        link_b.set_connected(true);
+        let frame = link_a.receive_frame().unwrap().unwrap();
+        assert_eq!(initiator.receive(&frame), Ok(SyncSessionState::ReadyToCommit));
+        assert_eq!(link_a.receive_frame(), Ok(None));
+
+        let keystore = InMemoryKeystore::new(vec![1; 16]);
+        keystore.store_key("device-a", &SecureBuffer::from_bytes(vec![9; 32])).unwrap();
+        assert_eq!(keystore.retrieve_key("device-a").unwrap().as_slice().unwrap(), &[9; 32]);
+        keystore.set_locked(true);
+        assert!(!keystore.key_exists("device-a") && keystore.retrieve_key("device-a").is_err());
+        keystore.set_locked(false);
+        keystore.delete_key("device-a").unwrap();
+        assert!(keystore.is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
+use crate::memory::{track_secret_allocation, track_secret_zeroization};
+use crate::platform;
+
+// Threshold secret sharing
+// Recovery shares are produced and recombined through the `ThresholdScheme` trait so
+// that deployments can pick a scheme without the recovery code knowing which one is
+// in use. Shamir over GF(256) is the default. Schemes that publish verification data
+// (e.g. Feldman/Pedersen VSS) carry it in `SecretShare::public_data` and override
+// `verify_share`; new schemes are registered in `threshold_scheme`.
+
+pub const DEFAULT_THRESHOLD_SCHEME: &str = ShamirScheme::ID;
+
+/// One share of a split secret, tagged with the scheme that produced it
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretShare {
+    scheme: String,
+    index: u8,
+    threshold: u8,
+    value: Vec<u8>,
+    #[serde(default)]
+    public_data: Vec<u8>, // Scheme-specific verification data; empty for Shamir
+}
+
+#[wasm_bindgen]
+impl SecretShare {
+    #[wasm_bindgen(getter)]
+    pub fn scheme(&self) -> String {
+        self.scheme.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize share: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<SecretShare, JsValue> {
+        serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid secret share: {}", e)))
+    }
+}
+
+impl Drop for SecretShare {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+/// A k-of-n secret sharing scheme
+pub trait ThresholdScheme {
+    /// Stable identifier stored in every share
+    fn scheme_id(&self) -> &'static str;
+
+    fn split(&self, secret: &[u8], threshold: u8, share_count: u8) -> Result<Vec<SecretShare>, String>;
+
+    fn combine(&self, shares: &[SecretShare]) -> Result<Vec<u8>, String>;
+
+    fn validate_parameters(&self, threshold: u8, share_count: u8) -> Result<(), String> {
+        if threshold < 2 || threshold > share_count {
+            return Err("Threshold must be between 2 and the share count".to_string());
+        }
+        Ok(())
+    }
+
+    /// Check a single share before combining; schemes without verification data accept any
+    fn verify_share(&self, _share: &SecretShare) -> bool {
+        true
+    }
+}
+
+/// Look up a scheme by the id stored in its shares
+pub fn threshold_scheme(scheme_id: &str) -> Option<Box<dyn ThresholdScheme>> {
+    match scheme_id {
+        ShamirScheme::ID => Some(Box::new(ShamirScheme)),
+        _ => None,
+    }
+}
+
+/// Shamir's secret sharing, byte-wise over GF(2^8) with the AES polynomial
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShamirScheme;
+
+impl ShamirScheme {
+    pub const ID: &'static str = "shamir-gf256-v1";
+}
+
+impl ThresholdScheme for ShamirScheme {
+    fn scheme_id(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn split(&self, secret: &[u8], threshold: u8, share_count: u8) -> Result<Vec<SecretShare>, String> {
+        self.validate_parameters(threshold, share_count)?;
+        if secret.is_empty() {
+            return Err("Cannot split an empty secret".to_string());
+        }
+
+        let mut shares: Vec<SecretShare> = (1..=share_count)
+            .map(|index| SecretShare {
+                scheme: Self::ID.to_string(),
+                index,
+                threshold,
+                value: Vec::with_capacity(secret.len()),
+                public_data: Vec::new(),
+            })
+            .collect();
+
+        // One random polynomial of degree threshold - 1 per secret byte
+        let mut coefficients = Zeroizing::new(vec![0u8; threshold as usize]);
+        for &byte in secret {
+            coefficients[0] = byte;
+            platform::fill_random(&mut coefficients[1..]);
+            for share in shares.iter_mut() {
+                share.value.push(evaluate(&coefficients, share.index));
+            }
+        }
+        track_secret_allocation();
+        Ok(shares)
+    }
+
+    fn combine(&self, shares: &[SecretShare]) -> Result<Vec<u8>, String> {
+        let first = shares.first().ok_or_else(|| "No shares provided".to_string())?;
+        let threshold = first.threshold as usize;
+        if shares.iter().any(|share| share.scheme != Self::ID) {
+            return Err("Share was produced by a different scheme".to_string());
+        }
+        if shares.iter().any(|share| share.threshold != first.threshold || share.value.len() != first.value.len()) {
+            return Err("Shares belong to different splits".to_string());
+        }
+        if shares.iter().any(|share| share.index == 0) {
+            return Err("Invalid share index".to_string());
+        }
+        let mut indices: Vec<u8> = shares.iter().map(|share| share.index).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.len() != shares.len() {
+            return Err("Duplicate share index".to_string());
+        }
+        if shares.len() < threshold {
+            return Err(format!("Need {} shares, got {}", threshold, shares.len()));
+        }
+
+        let used = &shares[..threshold];
+        let secret = (0..first.value.len())
+            .map(|position| {
+                used.iter().fold(0u8, |acc, share| {
+                    acc ^ gf_mul(share.value[position], lagrange_at_zero(share.index, used))
+                })
+            })
+            .collect();
+        track_secret_zeroization();
+        Ok(secret)
+    }
+}
+
+/// Split with the named scheme; shares are returned as JSON for distribution
+#[wasm_bindgen]
+pub fn split_secret(scheme_id: &str, secret: &[u8], threshold: u8, share_count: u8) -> Result<Vec<String>, JsValue> {
+    let scheme = threshold_scheme(scheme_id)
+        .ok_or_else(|| JsValue::from_str(&format!("Unknown threshold scheme: {}", scheme_id)))?;
+    let shares = scheme.split(secret, threshold, share_count).map_err(|e| JsValue::from_str(&e))?;
+    shares.iter().map(|share| share.to_json()).collect()
+}
+
+/// Recombine JSON shares; the scheme is taken from the shares themselves
+#[wasm_bindgen]
+pub fn combine_secret_shares(shares_json: Vec<String>) -> Result<Vec<u8>, JsValue> {
+    let shares = shares_json
+        .iter()
+        .map(|json| SecretShare::from_json(json))
+        .collect::<Result<Vec<_>, _>>()?;
+    combine_shares(&shares).map_err(|e| JsValue::from_str(&e))
+}
+
+pub(crate) fn combine_shares(shares: &[SecretShare]) -> Result<Vec<u8>, String> {
+    let scheme_id = shares.first().map(|share| share.scheme.as_str()).unwrap_or(DEFAULT_THRESHOLD_SCHEME);
+    let scheme = threshold_scheme(scheme_id).ok_or_else(|| format!("Unknown threshold scheme: {}", scheme_id))?;
+    if let Some(share) = shares.iter().find(|share| !scheme.verify_share(share)) {
+        return Err(format!("Share {} failed verification", share.index));
+    }
+    scheme.combine(shares)
+}
+
+// Horner evaluation of the polynomial at x
+fn evaluate(coefficients: &[u8], x: u8) -> u8 {
+    coefficients.iter().rev().fold(0u8, |acc, &coefficient| gf_mul(acc, x) ^ coefficient)
+}
+
+// Lagrange basis polynomial for `index` evaluated at zero
+fn lagrange_at_zero(index: u8, shares: &[SecretShare]) -> u8 {
+    shares
+        .iter()
+        .filter(|other| other.index != index)
+        .fold(1u8, |acc, other| gf_mul(acc, gf_mul(other.index, gf_inverse(other.index ^ index))))
+}
+
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+// a^254 = a^-1 in GF(2^8)
+fn gf_inverse(a: u8) -> u8 {
+    let mut result = 1u8;
+    let mut base = a;
+    let mut exponent = 254u8;
+    while exponent != 0 {
+        if exponent & 1 != 0 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exponent >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shamir_any_threshold_subset_recovers_secret() {
+        let secret = b"0123456789abcdef0123456789abcdef";
+        let shares = ShamirScheme.split(secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|share| share.value != secret.to_vec()));
+
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let picked: Vec<SecretShare> = subset.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(combine_shares(&picked).unwrap(), secret.to_vec());
+        }
+
+        assert!(combine_shares(&shares[..2]).unwrap_err().contains("Need 3 shares"));
+        assert!(combine_shares(&[shares[0].clone(), shares[0].clone(), shares[1].clone()]).is_err());
+    }
+
+    #[test]
+    fn test_scheme_registry_and_parameter_validation() {
+        let scheme = threshold_scheme(DEFAULT_THRESHOLD_SCHEME).unwrap();
+        assert_eq!(scheme.scheme_id(), ShamirScheme::ID);
+        assert!(threshold_scheme("feldman-vss").is_none());
+        assert!(scheme.validate_parameters(1, 3).is_err());
+        assert!(scheme.validate_parameters(4, 3).is_err());
+
+        let mut foreign = ShamirScheme.split(&[42], 2, 2).unwrap();
+        foreign[1].scheme = "feldman-vss".to_string();
+        assert!(ShamirScheme.combine(&foreign).is_err());
+        assert_eq!(gf_mul(0x53, gf_inverse(0x53)), 1);
+    }
+}
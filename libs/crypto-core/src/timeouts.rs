@@ -0,0 +1,199 @@
+use wasm_bindgen::prelude::*;
+use crate::platform;
+use crate::scope::{scoped_error, ErrorScope};
+use crate::shutdown::shutdown_generation;
+
+// Per-operation timeouts
+// Pairing handshakes, sync (inbox) application and migration batches each run
+// against a configurable deadline. Callers check the deadline between steps; on
+// expiry they roll back to their last consistent point, drop (and thereby zeroize)
+// intermediate buffers, and return a typed `Timeout` naming the phase reached.
+// A `shutdown` cancels every deadline started before it the same way, at the next check.
+
+/// Operations that run against a deadline
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedOperation {
+    PairingHandshake = 0,
+    SyncApplication = 1,
+    MigrationBatch = 2,
+}
+
+impl TimedOperation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TimedOperation::PairingHandshake => "pairing handshake",
+            TimedOperation::SyncApplication => "sync application",
+            TimedOperation::MigrationBatch => "migration batch",
+        }
+    }
+}
+
+/// Timeout per operation in milliseconds; `None` disables the deadline
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationTimeouts {
+    pairing_handshake_ms: Option<u64>,
+    sync_application_ms: Option<u64>,
+    migration_batch_ms: Option<u64>,
+}
+
+impl Default for OperationTimeouts {
+    fn default() -> Self {
+        Self {
+            pairing_handshake_ms: Some(5 * 60 * 1000),
+            sync_application_ms: Some(10 * 1000),
+            migration_batch_ms: Some(5 * 60 * 1000),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl OperationTimeouts {
+    /// Defaults: 5 minute handshakes, 10 second sync application, 5 minute migration batches
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> OperationTimeouts {
+        Self::default()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_timeout(&mut self, operation: TimedOperation, timeout_ms: Option<u64>) {
+        match operation {
+            TimedOperation::PairingHandshake => self.pairing_handshake_ms = timeout_ms,
+            TimedOperation::SyncApplication => self.sync_application_ms = timeout_ms,
+            TimedOperation::MigrationBatch => self.migration_batch_ms = timeout_ms,
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn timeout_ms(&self, operation: TimedOperation) -> Option<u64> {
+        match operation {
+            TimedOperation::PairingHandshake => self.pairing_handshake_ms,
+            TimedOperation::SyncApplication => self.sync_application_ms,
+            TimedOperation::MigrationBatch => self.migration_batch_ms,
+        }
+    }
+}
+
+/// Running deadline for one operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Deadline {
+    operation: TimedOperation,
+    started_at: u64,
+    limit_ms: Option<u64>,
+    generation: u64, // Shutdown generation the operation started in
+}
+
+impl Deadline {
+    pub fn start(operation: TimedOperation, timeouts: &OperationTimeouts) -> Self {
+        Self::starting_at(operation, timeouts, platform::now_ms())
+    }
+
+    pub fn starting_at(operation: TimedOperation, timeouts: &OperationTimeouts, started_at: u64) -> Self {
+        Deadline { operation, started_at, limit_ms: timeouts.timeout_ms(operation), generation: shutdown_generation() }
+    }
+
+    pub fn check(&self, phase: &str) -> Result<(), Timeout> {
+        self.check_at(phase, platform::now_ms())
+    }
+
+    /// Expired once the elapsed time reaches the limit, so a zero limit always expires.
+    /// Always expired after a shutdown, whatever the limit
+    pub fn check_at(&self, phase: &str, now: u64) -> Result<(), Timeout> {
+        let elapsed_ms = now.saturating_sub(self.started_at);
+        let cancelled = self.generation != shutdown_generation();
+        let limit_ms = match self.limit_ms {
+            _ if cancelled => self.limit_ms.unwrap_or(0),
+            Some(limit_ms) if elapsed_ms >= limit_ms => limit_ms,
+            _ => return Ok(()),
+        };
+        Err(Timeout {
+            operation: self.operation,
+            phase: phase.to_string(),
+            elapsed_ms,
+            limit_ms,
+            cancelled,
+            scope: ErrorScope::capture(),
+        })
+    }
+}
+
+// Typed error returned after an operation overran its deadline and was rolled back
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeout {
+    operation: TimedOperation,
+    phase: String,
+    elapsed_ms: u64,
+    limit_ms: u64,
+    cancelled: bool,
+    scope: ErrorScope,
+}
+
+#[wasm_bindgen]
+impl Timeout {
+    #[wasm_bindgen(getter)]
+    pub fn operation(&self) -> TimedOperation {
+        self.operation
+    }
+
+    // Step that was running when the deadline passed
+    #[wasm_bindgen(getter)]
+    pub fn phase(&self) -> String {
+        self.phase.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn elapsed_ms(&self) -> u64 {
+        self.elapsed_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn limit_ms(&self) -> u64 {
+        self.limit_ms
+    }
+
+    // Cancelled by `shutdown` rather than overrunning; `limit_ms` is 0 when uncapped
+    #[wasm_bindgen(getter)]
+    pub fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+}
+
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.cancelled {
+            return write!(
+                f,
+                "{} cancelled by shutdown during {} after {} ms{}",
+                self.operation.as_str(), self.phase, self.elapsed_ms, self.scope
+            );
+        }
+        write!(
+            f,
+            "{} timed out during {} after {} ms (limit {} ms){}",
+            self.operation.as_str(), self.phase, self.elapsed_ms, self.limit_ms, self.scope
+        )
+    }
+}
+
+scoped_error!(Timeout);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline_from_before_shutdown_is_cancelled() {
+        let mut timeouts = OperationTimeouts::new();
+        timeouts.set_timeout(TimedOperation::SyncApplication, None);
+        let mut deadline = Deadline::starting_at(TimedOperation::SyncApplication, &timeouts, 100);
+        assert!(deadline.check_at("message 1", u64::MAX).is_ok());
+
+        deadline.generation = deadline.generation.wrapping_sub(1);
+        let cancelled = deadline.check_at("message 2", 150).unwrap_err();
+        assert!(cancelled.cancelled());
+        assert_eq!((cancelled.elapsed_ms(), cancelled.limit_ms()), (50, 0));
+        assert_eq!(cancelled.message(), "sync application cancelled by shutdown during message 2 after 50 ms");
+    }
+}
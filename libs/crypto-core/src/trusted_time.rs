@@ -0,0 +1,219 @@
+use wasm_bindgen::prelude::*;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+// Trusted time and clock-skew tolerance
+// Cross-device messages carry the sender's wall-clock time, and device clocks drift
+// or are set by hand. The acceptance windows live here, one per message type: how old
+// a message may be and how far ahead of the local clock it may claim to be. Once a
+// message from an authenticated peer has been accepted, the caller records the peer's
+// offset from the local clock (last 16 per peer). After enough accepted samples, the
+// median offset, capped at the maximum skew correction, is taken as the peer's skew
+// and removed from its later timestamps before the window applies. The message being
+// checked never contributes to its own correction, rejected or unauthenticated
+// messages record nothing, and first contact gets the plain window. The cap bounds
+// how far a peer that lies about time consistently can stretch a window: the
+// effective limit is never more than window + correction.
+
+const MAX_SKEW_SAMPLES: usize = 16;
+const MIN_SKEW_SAMPLES: usize = 4;
+const DEFAULT_MAX_SKEW_CORRECTION_MS: u64 = 15 * 60 * 1000;
+
+static TRUSTED_TIME: Mutex<TrustedTimeState> = Mutex::new(TrustedTimeState::new());
+
+/// Cross-device message types whose sender timestamps are checked
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedMessageKind {
+    PairingRequest = 0,
+    SyncFrame = 1,
+}
+
+impl TimedMessageKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TimedMessageKind::PairingRequest => "pairing request",
+            TimedMessageKind::SyncFrame => "sync frame",
+        }
+    }
+}
+
+/// How old, and how far in the future, a message may be after skew correction
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageTimeWindow {
+    max_age_ms: u64,
+    max_future_ms: u64,
+}
+
+#[wasm_bindgen]
+impl MessageTimeWindow {
+    #[wasm_bindgen(constructor)]
+    pub fn new(max_age_ms: u64, max_future_ms: u64) -> MessageTimeWindow {
+        MessageTimeWindow { max_age_ms, max_future_ms }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_age_ms(&self) -> u64 {
+        self.max_age_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_future_ms(&self) -> u64 {
+        self.max_future_ms
+    }
+}
+
+pub(crate) struct TrustedTimeState {
+    windows: [MessageTimeWindow; 2],
+    max_skew_correction_ms: u64,
+    peer_offsets: BTreeMap<String, VecDeque<i64>>,
+}
+
+impl TrustedTimeState {
+    pub(crate) const fn new() -> Self {
+        TrustedTimeState {
+            windows: [
+                MessageTimeWindow { max_age_ms: 5 * 60 * 1000, max_future_ms: 60 * 1000 },
+                MessageTimeWindow { max_age_ms: 10 * 60 * 1000, max_future_ms: 60 * 1000 },
+            ],
+            max_skew_correction_ms: DEFAULT_MAX_SKEW_CORRECTION_MS,
+            peer_offsets: BTreeMap::new(),
+        }
+    }
+
+    /// Check a timestamp against the window, corrected for the skew of `peer_id` when
+    /// one is given and enough of its accepted messages have been recorded
+    pub(crate) fn check(&self, kind: TimedMessageKind, peer_id: Option<&str>, peer_timestamp_ms: u64, now: u64) -> Result<(), String> {
+        let skew = peer_id.and_then(|peer_id| self.peer_skew(peer_id)).unwrap_or(0);
+        let corrected_offset = peer_timestamp_ms as i64 - now as i64 - skew;
+        let window = self.windows[kind as usize];
+        if corrected_offset < 0 && corrected_offset.unsigned_abs() > window.max_age_ms {
+            return Err(format!(
+                "{} is {} ms old after skew correction (limit {} ms)",
+                kind.as_str(), corrected_offset.unsigned_abs(), window.max_age_ms
+            ));
+        }
+        if corrected_offset > 0 && corrected_offset as u64 > window.max_future_ms {
+            return Err(format!(
+                "{} is {} ms in the future after skew correction (limit {} ms)",
+                kind.as_str(), corrected_offset, window.max_future_ms
+            ));
+        }
+        Ok(())
+    }
+
+    /// Record the offset of a message that was accepted from an authenticated peer
+    pub(crate) fn record(&mut self, peer_id: &str, peer_timestamp_ms: u64, now: u64) {
+        let samples = self.peer_offsets.entry(peer_id.to_string()).or_default();
+        samples.push_back(peer_timestamp_ms as i64 - now as i64);
+        if samples.len() > MAX_SKEW_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    /// Median recorded offset, capped at the maximum correction; None until enough samples exist
+    pub(crate) fn peer_skew(&self, peer_id: &str) -> Option<i64> {
+        let mut samples: Vec<i64> = self.peer_offsets.get(peer_id)?.iter().copied().collect();
+        if samples.len() < MIN_SKEW_SAMPLES {
+            return None;
+        }
+        samples.sort_unstable();
+        let cap = self.max_skew_correction_ms as i64;
+        Some(samples[(samples.len() - 1) / 2].clamp(-cap, cap))
+    }
+}
+
+#[wasm_bindgen]
+pub fn set_message_time_window(kind: TimedMessageKind, window: &MessageTimeWindow) {
+    if let Ok(mut state) = TRUSTED_TIME.lock() {
+        state.windows[kind as usize] = *window;
+    }
+}
+
+#[wasm_bindgen]
+pub fn get_message_time_window(kind: TimedMessageKind) -> MessageTimeWindow {
+    let state = TRUSTED_TIME.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    state.windows[kind as usize]
+}
+
+/// Largest peer clock skew that is corrected for; 0 turns skew correction off
+#[wasm_bindgen]
+pub fn set_max_skew_correction_ms(max_skew_correction_ms: u64) {
+    if let Ok(mut state) = TRUSTED_TIME.lock() {
+        state.max_skew_correction_ms = max_skew_correction_ms;
+    }
+}
+
+/// Estimated offset of the peer's clock from ours (positive: peer is ahead); None until
+/// enough of its messages have been accepted
+#[wasm_bindgen]
+pub fn estimated_peer_skew_ms(peer_id: &str) -> Option<i64> {
+    TRUSTED_TIME.lock().ok()?.peer_skew(peer_id)
+}
+
+/// Drop the skew samples of a peer, e.g. after it was revoked or its clock was corrected
+#[wasm_bindgen]
+pub fn forget_peer_clock(peer_id: &str) {
+    if let Ok(mut state) = TRUSTED_TIME.lock() {
+        state.peer_offsets.remove(peer_id);
+    }
+}
+
+/// `peer_id` is the authenticated sender, or None when the sender is not yet known
+pub(crate) fn check_message_time(kind: TimedMessageKind, peer_id: Option<&str>, peer_timestamp_ms: u64, now: u64) -> Result<(), String> {
+    TRUSTED_TIME
+        .lock()
+        .map_err(|_| "Trusted time state unavailable".to_string())?
+        .check(kind, peer_id, peer_timestamp_ms, now)
+}
+
+/// Learn from a message of `peer_id` that was authenticated and accepted
+pub(crate) fn record_peer_clock(peer_id: &str, peer_timestamp_ms: u64, now: u64) {
+    if let Ok(mut state) = TRUSTED_TIME.lock() {
+        state.record(peer_id, peer_timestamp_ms, now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60 * 1000;
+
+    #[test]
+    fn test_windows_apply_after_bounded_skew_correction() {
+        let mut state = TrustedTimeState::new();
+        let now = 100 * MINUTE;
+        let pairing = TimedMessageKind::PairingRequest;
+
+        // First contact gets the plain window, however stale the message
+        assert!(state.check(pairing, Some("slow"), now - 19 * MINUTE, now).is_err());
+        assert!(state.check(pairing, Some("slow"), now - 4 * MINUTE, now).is_ok());
+        assert_eq!(state.peer_skew("slow"), None);
+
+        // A peer running 8 minutes slow is accepted once enough of its messages were recorded
+        for _ in 0..MIN_SKEW_SAMPLES - 1 {
+            state.record("slow", now - 8 * MINUTE, now);
+        }
+        assert!(state.check(pairing, Some("slow"), now - 12 * MINUTE, now).is_err());
+        state.record("slow", now - 8 * MINUTE, now);
+        assert_eq!(state.peer_skew("slow"), Some(-8 * MINUTE as i64));
+        assert!(state.check(pairing, Some("slow"), now - 12 * MINUTE, now).is_ok());
+        assert!(state.check(pairing, Some("slow"), now - 30 * MINUTE, now).unwrap_err().contains("old after skew correction"));
+        // Unauthenticated senders get no correction
+        assert!(state.check(pairing, None, now - 12 * MINUTE, now).is_err());
+
+        // Correction never exceeds the cap, so a replay an hour old stays expired
+        for _ in 0..MIN_SKEW_SAMPLES {
+            state.record("replay", now - 60 * MINUTE, now);
+        }
+        assert!(state.check(TimedMessageKind::SyncFrame, Some("replay"), now - 60 * MINUTE, now).is_err());
+        assert_eq!(state.peer_skew("replay"), Some(-15 * MINUTE as i64));
+
+        // Without correction the plain windows apply, including the future limit
+        state.max_skew_correction_ms = 0;
+        assert!(state.check(TimedMessageKind::SyncFrame, Some("slow"), now + 2 * MINUTE, now).unwrap_err().contains("future"));
+        assert!(state.check(TimedMessageKind::SyncFrame, Some("slow"), now - 9 * MINUTE, now).is_ok());
+    }
+}
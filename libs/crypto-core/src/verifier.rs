@@ -0,0 +1,237 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+use crate::derivation::{DataCategory, HierarchicalKeyDerivation};
+use crate::envelope::to_hex;
+use crate::memory::{track_secret_allocation, track_secret_zeroization};
+use crate::security::constant_time_compare;
+
+// Salted verifier export
+// Lets a server check whether a value (email, device identifier, ...) is already
+// enrolled without ever seeing it. Every device of the same user derives the same
+// per-purpose verifier key from the category branch of the HD tree, so verifiers
+// are comparable across devices, and the key version is mixed in so verifiers
+// rotate together with the category key.
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub(crate) const VERIFIER_BRANCH: u32 = 1; // m / purpose' / 1' / 0' is reserved for verifier keys
+pub(crate) const VERIFIER_HKDF_SALT: &[u8] = b"aura-verifier-v1";
+const MIN_SALT_LENGTH: usize = 16;
+const MAX_SALT_LENGTH: usize = 64;
+
+/// Salted keyed hash suitable for server-side equality checks
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaltedVerifier {
+    category: String,
+    purpose: String,
+    key_version: String,
+    salt: Vec<u8>,
+    digest: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl SaltedVerifier {
+    #[wasm_bindgen(getter)]
+    pub fn category(&self) -> String {
+        self.category.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn purpose(&self) -> String {
+        self.purpose.clone()
+    }
+
+    /// Category key version the verifier was computed under
+    #[wasm_bindgen(getter)]
+    pub fn key_version(&self) -> String {
+        self.key_version.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn salt(&self) -> Vec<u8> {
+        self.salt.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn digest(&self) -> Vec<u8> {
+        self.digest.clone()
+    }
+
+    /// Lowercase hex digest for transport
+    #[wasm_bindgen]
+    pub fn digest_hex(&self) -> String {
+        to_hex(&self.digest)
+    }
+
+    /// Constant-time comparison of two verifiers from the same purpose, version and salt
+    #[wasm_bindgen]
+    pub fn matches(&self, other: &SaltedVerifier) -> bool {
+        self.category == other.category
+            && self.purpose == other.purpose
+            && self.key_version == other.key_version
+            && self.salt == other.salt
+            && constant_time_compare(&self.digest, &other.digest)
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize verifier: {}", e)))
+    }
+}
+
+/// Per-purpose verifier key bound to one category key version
+#[wasm_bindgen]
+pub struct VerifierKey {
+    key: Zeroizing<[u8; 32]>,
+    category: DataCategory,
+    purpose: String,
+    key_version: String,
+}
+
+#[wasm_bindgen]
+impl VerifierKey {
+    /// Derive the verifier key for `purpose` under `category` at `key_version`
+    #[wasm_bindgen]
+    pub fn derive(
+        hd: &mut HierarchicalKeyDerivation,
+        category: DataCategory,
+        purpose: String,
+        key_version: String,
+    ) -> Result<VerifierKey, JsValue> {
+        let path = format!("m/{}'/{}'/0'", category.purpose_index(), VERIFIER_BRANCH);
+        let branch_key = Zeroizing::new(hd.derive_key_at_path(&path)?);
+        Self::from_branch_key(&branch_key, category, purpose, key_version)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn category(&self) -> DataCategory {
+        self.category.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn purpose(&self) -> String {
+        self.purpose.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn key_version(&self) -> String {
+        self.key_version.clone()
+    }
+
+    /// Verifier over raw bytes; `salt` is the server-issued salt for this purpose
+    #[wasm_bindgen]
+    pub fn export(&self, value: &[u8], salt: &[u8]) -> Result<SaltedVerifier, JsValue> {
+        self.compute(value, salt).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Verifier over an identifier, trimmed and lowercased so formatting does not matter
+    #[wasm_bindgen]
+    pub fn export_identifier(&self, identifier: &str, salt: &[u8]) -> Result<SaltedVerifier, JsValue> {
+        let normalized = Zeroizing::new(identifier.trim().to_lowercase());
+        self.export(normalized.as_bytes(), salt)
+    }
+}
+
+impl VerifierKey {
+    pub(crate) fn from_branch_key(
+        branch_key: &[u8],
+        category: DataCategory,
+        purpose: String,
+        key_version: String,
+    ) -> Result<VerifierKey, String> {
+        if purpose.is_empty() || purpose.contains('|') {
+            return Err("Verifier purpose must be non-empty and must not contain '|'".to_string());
+        }
+
+        let info = format!("{}|{}|{}", category.to_string(), purpose, key_version);
+        let mut key = Zeroizing::new([0u8; 32]);
+        Hkdf::<Sha256>::new(Some(VERIFIER_HKDF_SALT), branch_key)
+            .expand(info.as_bytes(), key.as_mut())
+            .map_err(|_| "Verifier key derivation failed".to_string())?;
+
+        track_secret_allocation();
+        Ok(VerifierKey { key, category, purpose, key_version })
+    }
+
+    // Rebuild a key stored elsewhere (see `KdfRehashRegistry`)
+    pub(crate) fn from_stored(key: [u8; 32], category: DataCategory, purpose: String, key_version: String) -> VerifierKey {
+        track_secret_allocation();
+        VerifierKey { key: Zeroizing::new(key), category, purpose, key_version }
+    }
+
+    pub(crate) fn key_bytes(&self) -> &[u8; 32] {
+        &self.key
+    }
+
+    pub(crate) fn compute(&self, value: &[u8], salt: &[u8]) -> Result<SaltedVerifier, String> {
+        if !(MIN_SALT_LENGTH..=MAX_SALT_LENGTH).contains(&salt.len()) {
+            return Err(format!(
+                "Verifier salt must be between {} and {} bytes",
+                MIN_SALT_LENGTH, MAX_SALT_LENGTH
+            ));
+        }
+
+        let mut mac = HmacSha256::new_from_slice(self.key.as_ref())
+            .map_err(|_| "Verifier key is invalid".to_string())?;
+        // Length-prefix the salt so salt/value boundaries cannot be shifted
+        mac.update(&(salt.len() as u32).to_be_bytes());
+        mac.update(salt);
+        mac.update(value);
+
+        Ok(SaltedVerifier {
+            category: self.category.to_string(),
+            purpose: self.purpose.clone(),
+            key_version: self.key_version.clone(),
+            salt: salt.to_vec(),
+            digest: mac.finalize().into_bytes().to_vec(),
+        })
+    }
+}
+
+impl Drop for VerifierKey {
+    fn drop(&mut self) {
+        track_secret_zeroization();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SALT: [u8; 16] = [9u8; 16];
+
+    fn key(purpose: &str, version: &str) -> VerifierKey {
+        VerifierKey::from_branch_key(&[1u8; 32], DataCategory::DeviceSync, purpose.to_string(), version.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_verifiers_match_across_formatting_and_devices() {
+        let phone = key("email_enrollment", "1.0.0");
+        let laptop = key("email_enrollment", "1.0.0");
+
+        let a = phone.compute(b"user@example.com", &SALT).unwrap();
+        let b = laptop.compute("  User@Example.com ".trim().to_lowercase().as_bytes(), &SALT).unwrap();
+        assert!(a.matches(&b));
+        assert_eq!(a.digest_hex().len(), 64);
+    }
+
+    #[test]
+    fn test_verifiers_separate_purposes_versions_and_salts() {
+        let base = key("email_enrollment", "1.0.0").compute(b"user@example.com", &SALT).unwrap();
+        let other_purpose = key("device_enrollment", "1.0.0").compute(b"user@example.com", &SALT).unwrap();
+        let rotated = key("email_enrollment", "1.1.0").compute(b"user@example.com", &SALT).unwrap();
+        let resalted = key("email_enrollment", "1.0.0").compute(b"user@example.com", &[8u8; 16]).unwrap();
+
+        for other in [&other_purpose, &rotated, &resalted] {
+            assert_ne!(base.digest(), other.digest());
+        }
+        assert!(key("email_enrollment", "1.0.0").compute(b"x", &[0u8; 8]).is_err());
+    }
+}
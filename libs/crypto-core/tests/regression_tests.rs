@@ -4,7 +4,7 @@ use crypto_core::{
     aad::AADValidator,
     memory::{SecureBuffer, get_memory_stats, reset_memory_stats},
     EncryptionResult,
-    encrypt_data, decrypt_data, derive_key_from_password
+    encrypt_data, derive_key_from_password
 };
 
 // Comprehensive regression tests for crypto operations